@@ -1,13 +1,22 @@
 use anyhow::Result;
-use std::path::PathBuf;
-use std::process::{Command, Stdio};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Instant;
 
-use crate::models::{AppMode, Host, HostField, SortBy, Container, DeploymentScript, Project, DockerPendingAction, ScriptSection};
+use crate::models::{AppMode, FilePane, FilePrompt, Host, HostField, DockerSettingsField, RsyncChange, RsyncChangeKind, RsyncOptions, RsyncProfile, RsyncProfileRun, RsyncProgress, RsyncTransfer, TransferTool, SortBy, Container, DeploymentScript, Project, DockerPendingAction, ScriptSection, ImageUpdateStatus, DockerResourceKind, FileEntry, FileSort, ComposeAction, ComposeView, LintIssue, DriftItem, EnvStorage, SecretWrite};
+use crate::models::host::DEFAULT_SEARCH_ROOT;
 use crate::ssh::{
     config::{default_ssh_config_path, parse_ssh_config, write_ssh_config, SshConfig},
-    metadata::{default_metadata_path, load_metadata, save_metadata, Metadata},
+    executor::StreamingCommand,
+    metadata::{default_metadata_path, load_metadata, save_metadata, save_run_logs, Metadata, RunLogLine, ScriptRun, MAX_RUN_LOG_LINES},
+    rsync::{CopyLine, RsyncLine},
 };
 use crate::docker;
+use crate::utils::diff::unified_diff;
+
+/// Log of secret reveals and moves, kept next to the metadata file
+const AUDIT_LOG_FILE: &str = "sshing-audit.log";
 
 /// Types of SSH commands we can execute (for handling responses)
 #[derive(Clone, Debug)]
@@ -22,13 +31,38 @@ pub enum SshCommandType {
     ContainerTop { container_index: usize },
     ContainerInspect { container_index: usize },
     InspectContainerEnv { container_index: usize },
+    InspectForScript { container_index: usize },
+    /// Drift of one container, or of every container with a script
+    CheckDrift { container: Option<String>, show: bool },
     ViewScriptContent { script_path: String, container_index: usize },
     ListDirectory { path: String },
     ReadScriptForContainer { script_path: String, container_index: usize },
     WriteScript { script_path: String },
-    RunScript,
+    ListScriptBackups,
+    /// Remote script checks; `issues` holds what the local checks already found
+    LintScript { script: Box<DeploymentScript>, issues: Vec<LintIssue> },
+    ReadScriptBackup { backup_path: String },
+    ExecCommand { container_index: usize, command: String },
+    ListImages,
+    ResourceOperation { operation: String },
+    CheckImageUpdate { image_id: String },
+    ListVolumes,
+    ListNetworks,
+    VolumeListDirectory { path: String },
+    VolumeReadFile { path: String },
+    ListComposeProjects,
+    ComposeOutput { project: String },
+    ComposeImport { project: String, dir: String },
+    WriteComposeFile { path: String },
     // Rsync file browser
     RsyncListDirectory { path: String },
+    // File manager
+    FileManagerList { path: String },
+    FileManagerRead { path: String },
+    /// A file viewed from the script or rsync file browser
+    PreviewFile { path: String },
+    /// A rename, mkdir or delete in the remote pane, described for the status line
+    FileManagerOperation { operation: String },
 }
 
 pub struct PendingSshCommand {
@@ -37,6 +71,69 @@ pub struct PendingSshCommand {
     pub command_type: SshCommandType,
 }
 
+/// A remote command that needs the terminal (run with the TUI suspended)
+#[derive(Clone, Debug)]
+pub struct PendingInteractiveCommand {
+    pub host: Host,
+    pub command: String,
+    /// What the command is, for the status line once it returns
+    pub label: String,
+}
+
+/// A host's file to edit locally, from the file browsers
+#[derive(Clone, Debug)]
+pub struct PendingFileEdit {
+    pub host: Host,
+    pub path: String,
+    /// Read and written with `sudo -i`, like the script browser's listings
+    pub sudo: bool,
+}
+
+/// A deployment script that is running, with its output so far
+pub struct ActiveScriptRun {
+    pub process: StreamingCommand,
+    pub host_index: usize,
+    pub container_name: String,
+    pub started: Instant,
+    /// Where the host keeps the script's process id, for stopping it
+    pub pid_file: String,
+    /// Stopped from the console, so its exit is not reported as a failure
+    pub stopped: bool,
+    /// The run as it will be recorded (exit code and duration are set at the end)
+    pub run: ScriptRun,
+}
+
+/// An rsync transfer running in the background, and its result once it ends
+pub struct RsyncRun {
+    process: StreamingCommand,
+    pub transfer: RsyncTransfer,
+    pub started: Instant,
+    pub progress: Option<RsyncProgress>,
+    /// The path rsync is working on
+    pub current_file: Option<String>,
+    /// Files created or updated so far, and their sizes
+    pub files: usize,
+    pub file_bytes: u64,
+    pub errors: Vec<String>,
+    /// The files sftp or scp copies, in order, to work out its progress
+    /// (empty for rsync, which reports its own)
+    pub copy_files: Vec<RsyncChange>,
+    /// Set once rsync has exited (the inner value is None when a signal stopped it)
+    pub exit_code: Option<Option<i32>>,
+    pub duration_secs: u64,
+    pub cancelled: bool,
+    /// Where the source host keeps rsync's process id, for stopping a
+    /// transfer between two hosts (see `rsync::runs_on_source`)
+    pid_file: String,
+}
+
+impl RsyncRun {
+    /// Bytes sent or received, as far as rsync has reported
+    pub fn bytes(&self) -> u64 {
+        self.progress.as_ref().map_or(self.file_bytes, |p| p.bytes)
+    }
+}
+
 /// Main application state
 pub struct App {
     /// Current application mode
@@ -78,12 +175,36 @@ pub struct App {
     /// Pending SSH connection (host to connect to)
     pub pending_connection: Option<Host>,
 
-    /// Pending rsync execution (host, source, dest, to_host, compress)
-    pub pending_rsync: Option<(Host, String, String, bool, bool)>,
+    /// Pending interactive command (e.g. a shell inside a container)
+    pub pending_interactive: Option<PendingInteractiveCommand>,
+
+    /// A host's file to open in the local editor (with the TUI suspended)
+    pub pending_file_edit: Option<PendingFileEdit>,
+
+    /// Deployment script currently streaming its output (one at a time)
+    pub active_script_run: Option<ActiveScriptRun>,
+
+    /// The rsync transfer running in the background (kept after it ends for its summary)
+    pub rsync_run: Option<RsyncRun>,
 
-    /// Whether rsync is available on this system
+    /// Copies queued by the file manager, started one after another
+    pub file_copies: Vec<RsyncTransfer>,
+
+    /// Pending rsync dry run, whose changes are reviewed before the real run
+    pub pending_rsync_dry_run: Option<(usize, RsyncTransfer)>,
+
+    /// Source host picked in the table for a transfer between two hosts,
+    /// waiting for the destination host
+    pub rsync_source_host: Option<String>,
+
+    /// Whether rsync is available on this system (without it, transfers use sftp or scp)
     pub rsync_available: bool,
 
+    /// Order of entries in the file browsers and the file manager
+    pub file_sort: FileSort,
+    /// Whether the file browsers list dotfiles
+    pub show_hidden_files: bool,
+
     // ==================== Docker Mode Fields ====================
 
     /// Docker containers for connected host
@@ -95,6 +216,13 @@ pub struct App {
     /// Docker scripts discovered on remote server
     pub scripts: Vec<DeploymentScript>,
 
+    /// Differences between each container and its script, by container name.
+    /// Containers that were not checked (or have no script) are missing
+    pub container_drift: HashMap<String, Vec<DriftItem>>,
+
+    /// Secret env vars shown in clear in the script editor, as (script path, key)
+    pub revealed_secrets: HashSet<(String, String)>,
+
     /// Index of currently selected container in docker mode
     pub docker_selected_index: usize,
 
@@ -110,17 +238,19 @@ pub struct App {
     /// Whether to use sudo for docker commands
     pub use_sudo: bool,
 
-    /// Default path for docker clients
+    /// Where new scripts go on the current docker host: its first search root
     pub clients_path: String,
 }
 
 impl App {
     /// Create a new application instance
     pub fn new() -> Result<Self> {
-        let metadata_path = default_metadata_path();
-        let config_path = default_ssh_config_path();
+        Self::load(&default_ssh_config_path(), default_metadata_path())
+    }
 
-        let mut ssh_config = parse_ssh_config(&config_path)?;
+    /// Create an application instance on the given SSH config and metadata files
+    fn load(config_path: &Path, metadata_path: PathBuf) -> Result<Self> {
+        let mut ssh_config = parse_ssh_config(config_path)?;
         let mut metadata = load_metadata(&metadata_path)?;
 
         // Merge metadata into hosts
@@ -160,18 +290,28 @@ impl App {
             status_message: None,
             error_message: None,
             pending_connection: None,
-            pending_rsync: None,
+            pending_interactive: None,
+            pending_file_edit: None,
+            active_script_run: None,
+            rsync_run: None,
+            file_copies: Vec::new(),
+            pending_rsync_dry_run: None,
+            rsync_source_host: None,
             rsync_available: crate::ssh::rsync::is_rsync_available(),
+            file_sort: FileSort::default(),
+            show_hidden_files: false,
             // Docker mode fields
             containers: Vec::new(),
             projects: Vec::new(),
             scripts: Vec::new(),
+            container_drift: HashMap::new(),
+            revealed_secrets: HashSet::new(),
             docker_selected_index: 0,
             pending_ssh_command: None,
             pending_docker_commands: Vec::new(),
             current_docker_host_index: None,
             use_sudo: false,
-            clients_path: "~/clients".to_string(),
+            clients_path: DEFAULT_SEARCH_ROOT.to_string(),
         })
     }
 
@@ -446,11 +586,77 @@ impl App {
                     focused_field: crate::models::app_state::RsyncField::SourcePath,
                     editing_mode: false,
                     compress: false, // Default to no compression
+                    options: RsyncOptions::default(),
+                    field_buffer: String::new(),
+                    profile: None,
+                    profile_name: None,
+                    dest_host: None,
                 };
             }
         }
     }
 
+    /// Pick the selected host as the source of a transfer between two hosts,
+    /// or, once a source is picked, as its destination
+    pub fn pick_rsync_host(&mut self) {
+        let Some(selected) = self.selected_host().map(|h| h.host.clone()) else {
+            return;
+        };
+        let Some(source) = self.rsync_source_host.take() else {
+            self.set_status(format!("Transfer from '{}': select the destination host and press R (Esc to cancel)", selected));
+            self.rsync_source_host = Some(selected);
+            return;
+        };
+        if source == selected {
+            self.set_error("Pick a different host as the destination");
+            self.rsync_source_host = Some(source);
+            return;
+        }
+        let (Some(host_index), Some(dest_host)) = (
+            self.hosts.iter().position(|h| h.host == source),
+            self.hosts.iter().find(|h| h.host == selected).cloned(),
+        ) else {
+            return;
+        };
+        self.mode = AppMode::Rsync {
+            host_index,
+            editing_host: self.hosts[host_index].clone(),
+            source_path: String::new(),
+            dest_path: String::new(),
+            sync_to_host: true,
+            focused_field: crate::models::app_state::RsyncField::SourcePath,
+            editing_mode: false,
+            compress: false,
+            options: RsyncOptions::default(),
+            field_buffer: String::new(),
+            profile: None,
+            profile_name: None,
+            dest_host: Some(dest_host),
+        };
+    }
+
+    /// Forget the source host picked for a transfer between two hosts
+    pub fn cancel_rsync_host_pick(&mut self) {
+        self.rsync_source_host = None;
+        self.set_status("Transfer between hosts cancelled".to_string());
+    }
+
+    /// Swap the direction of the rsync form: pushing and pulling, or the two
+    /// hosts of a transfer between hosts
+    pub fn toggle_rsync_direction(&mut self) {
+        if let AppMode::Rsync { host_index, editing_host, sync_to_host, dest_host, .. } = &mut self.mode {
+            match dest_host {
+                Some(other) => {
+                    std::mem::swap(editing_host, other);
+                    if let Some(index) = self.hosts.iter().position(|h| h.host == editing_host.host) {
+                        *host_index = index;
+                    }
+                }
+                None => *sync_to_host = !*sync_to_host,
+            }
+        }
+    }
+
     /// Start file browser for rsync path selection
     pub fn start_rsync_browse(&mut self) {
         use crate::models::app_state::RsyncField;
@@ -463,6 +669,9 @@ impl App {
             sync_to_host,
             focused_field,
             compress,
+            options,
+            profile,
+            dest_host,
             ..
         } = &self.mode
         {
@@ -473,11 +682,19 @@ impl App {
             let sync_to_host = *sync_to_host;
             let focused_field = *focused_field;
             let compress = *compress;
+            let options = options.clone();
+            let profile = profile.clone();
+            let dest_host = dest_host.clone();
 
             // Determine if we're browsing local or remote based on field and direction
+            // (both sides are remote in a transfer between two hosts)
             let is_remote = match focused_field {
-                RsyncField::SourcePath => !sync_to_host,  // Source is remote when pulling from host
-                RsyncField::DestPath => sync_to_host,     // Dest is remote when pushing to host
+                RsyncField::SourcePath => !sync_to_host || dest_host.is_some(),  // Source is remote when pulling from host
+                RsyncField::DestPath => sync_to_host || dest_host.is_some(),     // Dest is remote when pushing to host
+                _ => {
+                    self.set_error("Only the source and destination can be browsed");
+                    return;
+                }
             };
 
             // Get starting path - use home directory as default
@@ -491,9 +708,13 @@ impl App {
 
             if is_remote {
                 // For remote, queue SSH command to list directory
-                let cmd = format!("ls -la {} 2>/dev/null | tail -n +2", start_path);
+                let cmd = docker::list_directory_command(&start_path);
+                let browse_host = match (&dest_host, focused_field) {
+                    (Some(dest_host), RsyncField::DestPath) => dest_host.clone(),
+                    _ => editing_host.clone(),
+                };
                 self.pending_ssh_command = Some(PendingSshCommand {
-                    host: editing_host.clone(),
+                    host: browse_host,
                     command: cmd,
                     command_type: SshCommandType::RsyncListDirectory { path: start_path.clone() },
                 });
@@ -511,6 +732,9 @@ impl App {
                     dest_path,
                     sync_to_host,
                     compress,
+                    options,
+                    profile,
+                    dest_host,
                 };
             } else {
                 // For local, list directory directly
@@ -529,61 +753,105 @@ impl App {
                     dest_path,
                     sync_to_host,
                     compress,
+                    options,
+                    profile,
+                    dest_host,
                 };
             }
         }
     }
 
-    /// List entries in a local directory
-    pub fn list_local_directory(&self, path: &str) -> Vec<crate::models::docker::FileEntry> {
-        use crate::models::docker::FileEntry;
+    /// List entries in a local directory, arranged like remote listings
+    pub fn list_local_directory(&self, path: &str) -> Vec<FileEntry> {
         use std::fs;
 
         let mut entries = Vec::new();
 
         // Add parent directory entry (unless at root)
         if path != "/" {
-            entries.push(FileEntry {
-                name: "..".to_string(),
-                is_dir: true,
-                is_script: false,
-            });
+            entries.push(FileEntry::parent());
         }
 
-        // Read directory contents
         if let Ok(read_dir) = fs::read_dir(path) {
-            let mut items: Vec<_> = read_dir
-                .filter_map(|entry| entry.ok())
-                .filter_map(|entry| {
-                    let name = entry.file_name().to_string_lossy().to_string();
-                    // Skip hidden files
-                    if name.starts_with('.') {
-                        return None;
-                    }
-                    let metadata = entry.metadata().ok()?;
-                    let is_dir = metadata.is_dir();
+            #[cfg(unix)]
+            let owners = local_user_names();
+            entries.extend(read_dir.filter_map(|entry| entry.ok()).filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                // Not followed, so a symlink shows as one
+                let metadata = entry.metadata().ok()?;
+                let is_dir = fs::metadata(entry.path()).is_ok_and(|m| m.is_dir());
+
+                let mut file = FileEntry::new(name, is_dir);
+                file.size = metadata.len();
+                file.modified = metadata
+                    .modified()
+                    .ok()
+                    .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|age| age.as_secs() as i64);
+                if metadata.file_type().is_symlink() {
+                    file.link_target = fs::read_link(entry.path()).ok().map(|target| target.to_string_lossy().to_string());
+                }
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::MetadataExt;
+                    file.mode = Some(metadata.mode() & 0o7777);
+                    file.owner = owners.get(&metadata.uid()).cloned().unwrap_or_else(|| metadata.uid().to_string());
+                }
+                Some(file)
+            }));
+        }
 
-                    Some(FileEntry {
-                        name,
-                        is_dir,
-                        is_script: false,
-                    })
-                })
-                .collect();
+        self.arrange_entries(entries)
+    }
 
-            // Sort: directories first, then alphabetically
-            items.sort_by(|a, b| {
-                match (a.is_dir, b.is_dir) {
-                    (true, false) => std::cmp::Ordering::Less,
-                    (false, true) => std::cmp::Ordering::Greater,
-                    _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-                }
-            });
+    /// Drop hidden entries unless they are shown, and sort the rest
+    fn arrange_entries(&self, mut entries: Vec<FileEntry>) -> Vec<FileEntry> {
+        if !self.show_hidden_files {
+            entries.retain(|e| !e.is_hidden());
+        }
+        self.file_sort.sort(&mut entries);
+        entries
+    }
 
-            entries.extend(items);
+    /// Sort the file browsers by the next column, keeping the selection
+    pub fn cycle_file_sort(&mut self) {
+        self.file_sort = self.file_sort.next();
+        let sort = self.file_sort;
+        let resort = |entries: &mut Vec<FileEntry>, selected_index: &mut usize| {
+            let selected = entries.get(*selected_index).map(|e| e.name.clone());
+            sort.sort(entries);
+            *selected_index = selected
+                .and_then(|name| entries.iter().position(|e| e.name == name))
+                .unwrap_or(0);
+        };
+        match &mut self.mode {
+            AppMode::FileBrowser { entries, selected_index, .. }
+            | AppMode::RsyncFileBrowser { entries, selected_index, .. } => resort(entries, selected_index),
+            AppMode::FileManager { local, remote, .. } => {
+                resort(&mut local.entries, &mut local.selected_index);
+                resort(&mut remote.entries, &mut remote.selected_index);
+            }
+            _ => {}
         }
+        self.set_status(format!("Sorted by {}", sort.label()));
+    }
 
-        entries
+    /// Show or hide dotfiles, listing the open directories again
+    pub fn toggle_hidden_files(&mut self) {
+        self.show_hidden_files = !self.show_hidden_files;
+        match &self.mode {
+            AppMode::FileBrowser { current_path, .. } => {
+                let path = current_path.clone();
+                self.file_browser_list(path);
+            }
+            AppMode::RsyncFileBrowser { current_path, .. } => {
+                let path = current_path.clone();
+                self.rsync_navigate_to(path);
+            }
+            AppMode::FileManager { .. } => self.file_manager_refresh_all(),
+            _ => {}
+        }
+        self.set_status(if self.show_hidden_files { "Showing hidden files" } else { "Hiding hidden files" }.to_string());
     }
 
     /// Navigate to a directory in rsync file browser
@@ -592,16 +860,22 @@ impl App {
             host_index,
             editing_host,
             is_remote,
+            target_field,
+            dest_host,
             ..
         } = &self.mode
         {
             let _host_index = *host_index;
-            let editing_host = editing_host.clone();
+            // The destination of a transfer between two hosts is browsed on that host
+            let editing_host = match (dest_host, target_field) {
+                (Some(dest_host), crate::models::app_state::RsyncField::DestPath) => dest_host.clone(),
+                _ => editing_host.clone(),
+            };
             let is_remote = *is_remote;
 
             if is_remote {
                 // Queue SSH command to list directory
-                let cmd = format!("ls -la {} 2>/dev/null | tail -n +2", path);
+                let cmd = docker::list_directory_command(&path);
                 self.pending_ssh_command = Some(PendingSshCommand {
                     host: editing_host,
                     command: cmd,
@@ -658,6 +932,9 @@ impl App {
             dest_path,
             sync_to_host,
             compress,
+            options,
+            profile,
+            dest_host,
             ..
         } = &self.mode
         {
@@ -685,8 +962,8 @@ impl App {
 
             // Update the appropriate field
             let (new_source, new_dest) = match target_field {
-                RsyncField::SourcePath => (final_path, dest_path.clone()),
                 RsyncField::DestPath => (source_path.clone(), final_path),
+                _ => (final_path, dest_path.clone()),
             };
 
             self.mode = AppMode::Rsync {
@@ -698,6 +975,11 @@ impl App {
                 focused_field: *target_field,
                 editing_mode: false,
                 compress: *compress,
+                options: options.clone(),
+                field_buffer: String::new(),
+                profile: profile.clone(),
+                profile_name: None,
+                dest_host: dest_host.clone(),
             };
         }
     }
@@ -712,6 +994,9 @@ impl App {
             dest_path,
             sync_to_host,
             compress,
+            options,
+            profile,
+            dest_host,
             ..
         } = &self.mode
         {
@@ -724,796 +1009,2772 @@ impl App {
                 focused_field: *target_field,
                 editing_mode: false,
                 compress: *compress,
+                options: options.clone(),
+                field_buffer: String::new(),
+                profile: profile.clone(),
+                profile_name: None,
+                dest_host: dest_host.clone(),
             };
         }
     }
 
-    /// Delete the confirmed host
-    pub fn delete_host(&mut self, index: usize) -> Result<()> {
-        if index < self.hosts.len() {
-            let host = self.hosts.remove(index);
-            self.ssh_config.remove_host(index)?;
-            self.metadata.remove(&host.host);
-
-            self.save_all()?;
-
-            self.status_message = Some(format!("Deleted host '{}'", host.host));
-
-            // Adjust selection if needed
-            if self.selected_index >= self.hosts.len() && self.selected_index > 0 {
-                self.selected_index -= 1;
+    /// Check the transfer set up in the rsync form with a dry run first
+    pub fn start_rsync_review(&mut self) {
+        if let AppMode::Rsync { host_index, editing_host, source_path, dest_path, sync_to_host, compress, options, dest_host, .. } = &self.mode {
+            if source_path.is_empty() || dest_path.is_empty() {
+                self.set_error("Both source and destination paths required");
+                return;
+            }
+            if let Err(e) = options.validate(dest_path) {
+                self.set_error(e);
+                return;
             }
+            let transfer = RsyncTransfer {
+                host: editing_host.clone(),
+                source: source_path.clone(),
+                dest: dest_path.clone(),
+                to_host: *sync_to_host,
+                compress: *compress,
+                options: options.clone(),
+                skipped: Vec::new(),
+                profile: None,
+                dest_host: dest_host.clone(),
+                relay: false,
+                tool: TransferTool::Rsync,
+            };
+            // Run by the main loop once the status is drawn
+            self.pending_rsync_dry_run = Some((*host_index, transfer));
+            self.set_status("Running dry run...".to_string());
         }
-
-        self.mode = AppMode::Table;
-        Ok(())
     }
 
-    /// Connect to the selected host
-    pub fn connect_to_selected(&mut self) -> Result<()> {
-        // Clone the host to avoid borrow checker issues
-        let host_to_connect = match self.selected_host() {
-            Some(host) => host.clone(),
-            None => {
-                self.error_message = Some("No host selected".to_string());
-                return Ok(());
+    /// Add the rules of the source's `.gitignore` to the excludes and includes
+    pub fn import_rsync_gitignore(&mut self) {
+        let AppMode::Rsync { editing_host, source_path, sync_to_host, dest_host, .. } = &self.mode else {
+            return;
+        };
+        if source_path.is_empty() {
+            self.set_error("Set the source path first");
+            return;
+        }
+        let path = format!("{}/.gitignore", source_path.trim_end_matches('/'));
+        let content = if *sync_to_host && dest_host.is_none() {
+            std::fs::read_to_string(expand_tilde(&path)).map_err(|e| e.to_string())
+        } else {
+            let host = editing_host.clone();
+            let command = format!("cat -- {}", crate::utils::shell::quote_path(&path));
+            self.execute_ssh_command(&host, &command).map_err(|e| e.to_string())
+        };
+        let content = match content {
+            Ok(content) => content,
+            Err(e) => {
+                self.set_error(format!("Could not read {}: {}", path, e.trim()));
+                return;
             }
         };
 
-        let host_name = host_to_connect.host.clone();
-
-        // Update the host in our list and mark as used
-        if let Some(actual_index) = self.hosts.iter().position(|h| h.host == host_name) {
-            self.hosts[actual_index].mark_used();
+        let (includes, excludes) = crate::ssh::rsync::parse_gitignore(&content);
+        if let AppMode::Rsync { options, .. } = &mut self.mode {
+            let mut added = 0;
+            for (list, patterns) in [(&mut options.includes, includes), (&mut options.excludes, excludes)] {
+                for pattern in patterns {
+                    if !list.contains(&pattern) {
+                        list.push(pattern);
+                        added += 1;
+                    }
+                }
+            }
+            self.set_status(format!("Imported {} rules from {}", added, path));
         }
+    }
 
-        // Save metadata with updated last_used
-        self.save_metadata_only()?;
-
-        // Store pending connection instead of executing immediately
-        // The main loop will handle terminal cleanup and SSH execution
-        self.pending_connection = Some(host_to_connect);
+    /// Show the changes a dry run found, or why it failed
+    pub fn show_rsync_review(&mut self, host_index: usize, transfer: RsyncTransfer, result: Result<Vec<RsyncChange>, String>) {
+        match result {
+            Ok(changes) if changes.is_empty() => {
+                self.set_status("Dry run: nothing to transfer, destination is up to date".to_string());
+            }
+            Ok(changes) => {
+                self.set_status(format!("Dry run: {} changes", changes.len()));
+                self.mode = AppMode::RsyncReview { host_index, transfer, changes, selected_index: 0 };
+            }
+            Err(e) => self.set_error(format!("Rsync dry run failed:\n{}", e)),
+        }
+    }
 
-        Ok(())
+    /// Leave the selected change (and everything under it) out of the run, or put it back
+    pub fn toggle_rsync_exclude(&mut self) {
+        if let AppMode::RsyncReview { changes, selected_index, .. } = &mut self.mode {
+            let Some(change) = changes.get(*selected_index) else {
+                return;
+            };
+            let (dir, excluded) = (change.path.clone(), !change.excluded);
+            for change in changes.iter_mut().filter(|c| c.path == dir || c.is_under(&dir)) {
+                change.excluded = excluded;
+            }
+        }
     }
 
-    /// Clear the pending connection and update status
-    pub fn complete_connection(&mut self, success: bool, error: Option<String>) {
-        if let Some(ref host) = self.pending_connection {
-            if success {
-                self.status_message = Some(format!("Connected to '{}'", host.host));
-            } else {
-                self.error_message = error;
+    /// Run the reviewed transfer without the excluded paths
+    pub fn confirm_rsync_review(&mut self) {
+        if let AppMode::RsyncReview { transfer, changes, .. } = &self.mode {
+            if changes.iter().all(|c| c.excluded) {
+                self.set_error("Every change is excluded, nothing to transfer");
+                return;
+            }
+            // Paths inside an excluded directory are left out with it
+            let excluded_dirs: Vec<&str> = changes.iter().filter(|c| c.excluded && c.is_dir()).map(|c| c.path.as_str()).collect();
+            let skipped = changes
+                .iter()
+                .filter(|c| c.excluded && !excluded_dirs.iter().any(|dir| c.is_under(dir)))
+                .map(|c| c.path.clone())
+                .collect();
+            let transfer = RsyncTransfer { skipped, ..transfer.clone() };
+            if let AppMode::RsyncReview { host_index, .. } = self.mode {
+                self.start_rsync_run(host_index, transfer);
             }
         }
-        self.pending_connection = None;
     }
 
-    /// Enter search mode
-    pub fn start_search(&mut self) {
-        self.mode = AppMode::Search {
-            query: self.search_query.clone(),
-        };
+    /// Start the transfer in the background and show its progress
+    pub fn start_rsync_run(&mut self, host_index: usize, transfer: RsyncTransfer) {
+        if self.spawn_rsync_run(transfer) {
+            self.mode = AppMode::RsyncProgress { host_index };
+        }
     }
 
-    /// Apply search query
-    pub fn apply_search(&mut self, query: String) {
-        self.search_query = query;
-        self.selected_index = 0; // Reset selection when search changes
-        self.mode = AppMode::Table;
+    /// Start the transfer in the background. False (with the error shown) when it could not start
+    fn spawn_rsync_run(&mut self, transfer: RsyncTransfer) -> bool {
+        let pid_file = remote_pid_file();
+        match crate::ssh::rsync::spawn_rsync(&transfer, &pid_file) {
+            Ok((process, copy_files)) => {
+                self.rsync_run = Some(RsyncRun {
+                    process,
+                    transfer,
+                    started: Instant::now(),
+                    progress: None,
+                    current_file: None,
+                    files: 0,
+                    file_bytes: 0,
+                    errors: Vec::new(),
+                    copy_files,
+                    exit_code: None,
+                    duration_secs: 0,
+                    cancelled: false,
+                    pid_file,
+                });
+                true
+            }
+            Err(e) => {
+                self.set_error(format!("{} failed: {}", transfer.tool.name(), e));
+                false
+            }
+        }
     }
 
-    /// Cycle to next sort option
-    pub fn cycle_sort(&mut self) {
-        self.sort_by = self.sort_by.next();
-        self.selected_index = 0; // Reset selection when sort changes
-        self.status_message = Some(format!("Sorting by: {}", self.sort_by.label()));
+    /// Collect progress from the running transfer
+    pub fn poll_rsync_run(&mut self) {
+        let Some(run) = self.rsync_run.as_mut().filter(|run| run.exit_code.is_none()) else {
+            return;
+        };
 
-        // Save sort preference to metadata
-        self.metadata.sort_by = self.sort_by.as_config_str();
-        let _ = save_metadata(&self.metadata_path, &self.metadata);
-    }
+        for line in run.process.read_lines() {
+            if run.transfer.tool != TransferTool::Rsync {
+                match crate::ssh::rsync::parse_copy_line(run.transfer.tool, &line) {
+                    CopyLine::File { path, size } => {
+                        // sftp only names the file; its size is in the listing, in the same order
+                        let size = size.or_else(|| run.copy_files.get(run.files).map(|c| c.size)).unwrap_or(0);
+                        run.files += 1;
+                        run.file_bytes += size;
+                        let total = run.copy_files.iter().map(|c| c.size).sum();
+                        run.progress = Some(crate::ssh::rsync::copy_progress(run.file_bytes, total, run.started.elapsed().as_secs()));
+                        run.current_file = Some(path);
+                    }
+                    CopyLine::Chatter => {}
+                    CopyLine::Other => run.errors.push(line.text),
+                }
+            } else if line.stderr {
+                run.errors.push(line.text);
+            } else if run.transfer.relay {
+                // The extracting tar lists each path it writes
+                if !line.text.ends_with('/') {
+                    run.files += 1;
+                }
+                run.current_file = Some(line.text.trim_start_matches("./").to_string());
+            } else {
+                match crate::ssh::rsync::parse_rsync_line(&line.text) {
+                    RsyncLine::Progress(progress) => run.progress = Some(progress),
+                    RsyncLine::Change(change) => {
+                        if !change.is_dir() && change.kind != RsyncChangeKind::Delete {
+                            run.files += 1;
+                            run.file_bytes += change.size;
+                        }
+                        run.current_file = Some(change.path);
+                    }
+                    RsyncLine::Other => {}
+                }
+            }
+        }
 
-    /// Start tag filter mode
-    pub fn start_tag_filter(&mut self) {
-        self.mode = AppMode::TagFilter {
-            selected_tags: self.active_tag_filters.clone(),
+        let Some(exit_code) = run.process.try_finish() else {
+            return;
+        };
+        run.exit_code = Some(exit_code);
+        run.duration_secs = run.started.elapsed().as_secs();
+        run.current_file = None;
+
+        let profile_run = run.transfer.profile.clone().map(|profile| {
+            let result = RsyncProfileRun {
+                started: chrono::Utc::now() - chrono::Duration::seconds(run.duration_secs as i64),
+                duration_secs: run.duration_secs,
+                exit_code: exit_code.filter(|_| !run.cancelled),
+                files: run.files,
+                bytes: run.bytes(),
+            };
+            (run.transfer.host.host.clone(), profile, result)
+        });
+
+        let succeeded = exit_code == Some(0) && !run.cancelled;
+        let tool = run.transfer.tool.name();
+        let status = match exit_code {
+            _ if run.cancelled => format!("{} was cancelled", tool),
+            Some(0) => format!("{} completed: {} files, {}", tool, run.files, crate::ssh::rsync::format_size(run.bytes())),
+            Some(code) => match run.errors.iter().find_map(|error| crate::ssh::rsync::host_key_hint(error)) {
+                Some(hint) => format!("{} failed (exit {}): {}", tool, code, hint),
+                None => format!("{} failed (exit {})", tool, code),
+            },
+            None => format!("{} was stopped", tool),
         };
+        if exit_code == Some(0) {
+            self.set_status(status);
+        } else {
+            self.set_error(status);
+        }
+        if let Some((host_alias, profile, result)) = profile_run {
+            self.record_rsync_profile_run(&host_alias, &profile, result);
+        }
+        self.continue_file_copies(succeeded);
     }
 
-    /// Apply tag filters
-    pub fn apply_tag_filter(&mut self, tags: Vec<String>) {
-        self.active_tag_filters = tags;
-        self.selected_index = 0; // Reset selection when filter changes
-        self.mode = AppMode::Table;
+    /// Stop the running transfer. Rsync running on the source host is
+    /// stopped there; if the host cannot be told, only the ssh session is
+    pub fn cancel_rsync_run(&mut self) {
+        let Some(run) = self.rsync_run.as_mut().filter(|run| run.exit_code.is_none()) else {
+            return;
+        };
+        run.cancelled = true;
+        if !crate::ssh::rsync::runs_on_source(&run.transfer) {
+            run.process.terminate();
+            self.set_status("Stopping rsync...".to_string());
+            return;
+        }
+        let host = run.transfer.host.clone();
+        let stop = docker::stop_script_command(&run.pid_file);
+        let result = self.execute_ssh_command(&host, &stop);
+        match result {
+            Ok(_) => self.set_status("Stopping rsync...".to_string()),
+            Err(e) => {
+                if let Some(run) = self.rsync_run.as_mut() {
+                    run.process.terminate();
+                }
+                self.set_error(format!("Could not stop rsync on {}, it may still be running: {}", host.host, e));
+            }
+        }
     }
 
-    /// Show help overlay
-    pub fn show_help(&mut self) {
-        self.mode = AppMode::Help;
+    /// Leave the progress view once the transfer has ended
+    pub fn close_rsync_run(&mut self) {
+        if self.rsync_run.as_ref().is_some_and(|run| run.exit_code.is_some()) {
+            self.rsync_run = None;
+            self.return_to_table();
+        }
     }
 
-    /// Return to table view
-    pub fn return_to_table(&mut self) {
-        self.mode = AppMode::Table;
+    /// Go back from the review to the rsync form
+    pub fn cancel_rsync_review(&mut self) {
+        if let AppMode::RsyncReview { host_index, transfer, .. } = &self.mode {
+            self.mode = AppMode::Rsync {
+                host_index: *host_index,
+                editing_host: transfer.host.clone(),
+                source_path: transfer.source.clone(),
+                dest_path: transfer.dest.clone(),
+                sync_to_host: transfer.to_host,
+                focused_field: crate::models::app_state::RsyncField::SourcePath,
+                editing_mode: false,
+                compress: transfer.compress,
+                options: transfer.options.clone(),
+                field_buffer: String::new(),
+                profile: transfer.profile.clone(),
+                profile_name: None,
+                dest_host: transfer.dest_host.clone(),
+            };
+        }
     }
 
-    /// Set status message
-    pub fn set_status(&mut self, message: String) {
-        self.status_message = Some(message);
+    /// Show the selected host's saved rsync profiles
+    pub fn start_rsync_profiles(&mut self) {
+        if let Some(host) = self.selected_host() {
+            if let Some(host_index) = self.hosts.iter().position(|h| h.host == host.host) {
+                self.mode = AppMode::RsyncProfiles { host_index, selected_index: 0, confirm_delete: false };
+            }
+        }
     }
 
-    /// Set error message
-    pub fn set_error(&mut self, message: impl Into<String>) {
-        self.error_message = Some(message.into());
+    /// Start typing a name to save the rsync form under
+    pub fn start_rsync_profile_save(&mut self) {
+        if let AppMode::Rsync { profile, profile_name, dest_host, .. } = &mut self.mode {
+            if dest_host.is_some() {
+                self.error_message = Some("Transfers between two hosts cannot be saved as profiles".to_string());
+                return;
+            }
+            *profile_name = Some(profile.clone().unwrap_or_default());
+        }
     }
 
-    /// Quit the application
-    pub fn quit(&mut self) {
-        self.should_quit = true;
+    /// Save the rsync form as a profile, replacing one with the same name
+    pub fn save_rsync_profile(&mut self) {
+        let AppMode::Rsync { host_index, source_path, dest_path, sync_to_host, compress, options, profile_name, .. } = &self.mode else {
+            return;
+        };
+        let name = profile_name.as_deref().unwrap_or_default().trim().to_string();
+        if name.is_empty() {
+            self.set_error("Profile name cannot be empty");
+            return;
+        }
+        if source_path.is_empty() || dest_path.is_empty() {
+            self.set_error("Both source and destination paths required");
+            return;
+        }
+        if let Err(e) = options.validate(dest_path) {
+            self.set_error(e);
+            return;
+        }
+        let saved = RsyncProfile {
+            name: name.clone(),
+            source: source_path.clone(),
+            dest: dest_path.clone(),
+            to_host: *sync_to_host,
+            compress: *compress,
+            options: options.clone(),
+            last_run: None,
+        };
+        let Some(host) = self.hosts.get_mut(*host_index) else {
+            return;
+        };
+        let index = host.rsync_profiles.iter().position(|p| p.name == name);
+        upsert(&mut host.rsync_profiles, index, saved);
+        if let Err(e) = self.save_metadata_only() {
+            self.set_error(format!("Failed to save profile: {}", e));
+            return;
+        }
+        if let AppMode::Rsync { profile, profile_name, .. } = &mut self.mode {
+            *profile = Some(name.clone());
+            *profile_name = None;
+        }
+        self.set_status(format!("Saved rsync profile '{}'", name));
     }
 
-    /// Clear status and error messages
-    pub fn clear_messages(&mut self) {
-        self.status_message = None;
-        self.error_message = None;
+    fn selected_rsync_profile(&self) -> Option<(usize, &Host, &RsyncProfile)> {
+        let AppMode::RsyncProfiles { host_index, selected_index, .. } = &self.mode else {
+            return None;
+        };
+        let host = self.hosts.get(*host_index)?;
+        Some((*host_index, host, host.rsync_profiles.get(*selected_index)?))
     }
 
-    /// Save both SSH config and metadata
-    fn save_all(&mut self) -> Result<()> {
-        // Extract metadata from hosts
-        self.metadata.extract_from_hosts(&self.hosts);
-
-        // Save SSH config
-        write_ssh_config(&self.ssh_config)?;
-
-        // Save metadata
-        save_metadata(&self.metadata_path, &self.metadata)?;
+    /// Run the selected profile, straight away or after a dry run review
+    pub fn run_rsync_profile(&mut self, review: bool) {
+        let Some((host_index, host, profile)) = self.selected_rsync_profile() else {
+            return;
+        };
+        let mut transfer = profile.transfer(host);
+        if let Err(e) = transfer.options.validate(&transfer.dest) {
+            self.set_error(e);
+            return;
+        }
+        if review {
+            self.pending_rsync_dry_run = Some((host_index, transfer));
+            self.set_status("Running dry run...".to_string());
+        } else {
+            transfer.tool = crate::ssh::rsync::pick_transfer_tool(&transfer, self.rsync_available);
+            self.start_rsync_run(host_index, transfer);
+        }
+    }
 
-        Ok(())
+    /// Open the selected profile in the rsync form, to change it or save a copy
+    pub fn edit_rsync_profile(&mut self) {
+        let Some((host_index, host, profile)) = self.selected_rsync_profile() else {
+            return;
+        };
+        self.mode = AppMode::Rsync {
+            host_index,
+            editing_host: host.clone(),
+            source_path: profile.source.clone(),
+            dest_path: profile.dest.clone(),
+            sync_to_host: profile.to_host,
+            focused_field: crate::models::app_state::RsyncField::SourcePath,
+            editing_mode: false,
+            compress: profile.compress,
+            options: profile.options.clone(),
+            field_buffer: String::new(),
+            profile: Some(profile.name.clone()),
+            profile_name: None,
+            dest_host: None,
+        };
     }
 
-    /// Save only metadata (for last_used updates)
-    fn save_metadata_only(&mut self) -> Result<()> {
-        self.metadata.extract_from_hosts(&self.hosts);
-        save_metadata(&self.metadata_path, &self.metadata)?;
-        Ok(())
+    /// Delete the selected profile
+    pub fn delete_rsync_profile(&mut self) {
+        let AppMode::RsyncProfiles { host_index, selected_index, confirm_delete } = &mut self.mode else {
+            return;
+        };
+        *confirm_delete = false;
+        let Some(host) = self.hosts.get_mut(*host_index) else {
+            return;
+        };
+        if *selected_index >= host.rsync_profiles.len() {
+            return;
+        }
+        let removed = host.rsync_profiles.remove(*selected_index);
+        *selected_index = (*selected_index).min(host.rsync_profiles.len().saturating_sub(1));
+        match self.save_metadata_only() {
+            Ok(()) => self.set_status(format!("Deleted rsync profile '{}'", removed.name)),
+            Err(e) => self.set_error(format!("Failed to save metadata: {}", e)),
+        }
     }
 
-    // ==================== Docker Mode Methods ====================
+    /// Remember how a profile's last run went
+    pub fn record_rsync_profile_run(&mut self, host_alias: &str, profile: &str, result: RsyncProfileRun) {
+        let Some(host) = self.hosts.iter_mut().find(|h| h.host == host_alias) else {
+            return;
+        };
+        if let Some(saved) = host.rsync_profiles.iter_mut().find(|p| p.name == profile) {
+            saved.last_run = Some(result);
+            let _ = self.save_metadata_only();
+        }
+    }
 
-    /// Start Docker mode for the selected host
-    pub fn start_docker_mode(&mut self) {
-        if let Some(host) = self.selected_host() {
-            if let Some(actual_index) = self.hosts.iter().position(|h| h.host == host.host) {
-                let host = host.clone();
+    /// Open the file manager between this machine's home directory and the
+    /// selected host's
+    pub fn start_file_manager(&mut self) {
+        let Some(host) = self.selected_host() else {
+            return;
+        };
+        let Some(host_index) = self.hosts.iter().position(|h| h.host == host.host) else {
+            return;
+        };
+        let home = dirs::home_dir()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| "/".to_string());
+        let mut local = FilePane::new(home.clone());
+        local.show(home.clone(), self.list_local_directory(&home));
+        self.mode = AppMode::FileManager {
+            host_index,
+            local,
+            remote: FilePane::new("~"),
+            remote_focused: false,
+            prompt: None,
+            preview: None,
+        };
+        self.file_manager_list_remote("~".to_string());
+    }
 
-                // Reset sudo flag for new connection
-                self.use_sudo = false;
+    fn file_manager_list_remote(&mut self, path: String) {
+        if let AppMode::FileManager { host_index, ref mut remote, .. } = self.mode {
+            remote.loading = true;
+            if let Some(host) = self.hosts.get(host_index).cloned() {
+                self.pending_ssh_command = Some(PendingSshCommand {
+                    host,
+                    command: crate::ssh::files::list_command(&path),
+                    command_type: SshCommandType::FileManagerList { path },
+                });
+            }
+        }
+    }
 
-                // If user is not root, ask about sudo
-                if host.user.as_ref().map(|u| u != "root").unwrap_or(true) {
-                    self.mode = AppMode::ConfirmDockerAction {
-                        action: DockerPendingAction::EnableSudo { host_index: actual_index },
-                        return_mode: Box::new(AppMode::Table),
-                    };
-                } else {
-                    // Root user, connect directly
-                    self.start_fetching_containers(actual_index);
-                }
+    /// The focused pane, and whether it is the remote one
+    fn focused_file_pane(&self) -> Option<(&FilePane, bool)> {
+        match &self.mode {
+            AppMode::FileManager { local, remote, remote_focused, .. } => {
+                Some((if *remote_focused { remote } else { local }, *remote_focused))
             }
+            _ => None,
         }
     }
 
-    /// Start fetching containers after connection setup
-    pub fn start_fetching_containers(&mut self, host_index: usize) {
-        if host_index < self.hosts.len() {
-            let host = self.hosts[host_index].clone();
-            self.current_docker_host_index = Some(host_index);
+    /// Show a directory in one of the panes
+    fn file_manager_navigate(&mut self, remote_pane: bool, path: String) {
+        if remote_pane {
+            self.file_manager_list_remote(path);
+            return;
+        }
+        if let Err(e) = std::fs::read_dir(&path) {
+            self.set_error(format!("Could not open {}: {}", path, e));
+            return;
+        }
+        let entries = self.list_local_directory(&path);
+        if let AppMode::FileManager { ref mut local, .. } = self.mode {
+            local.show(path, entries);
+        }
+    }
 
-            // Clear previous data
-            self.containers.clear();
-            self.projects.clear();
-            self.scripts.clear();
+    /// Read a pane's directory again, after its files changed
+    fn file_manager_refresh(&mut self, remote_pane: bool) {
+        let path = match &self.mode {
+            AppMode::FileManager { remote, .. } if remote_pane => remote.path.clone(),
+            AppMode::FileManager { local, .. } => local.path.clone(),
+            _ => return,
+        };
+        self.file_manager_navigate(remote_pane, path);
+    }
 
-            // Build docker ps command (with sudo if needed)
-            let docker_ps = self.docker_cmd(&docker::docker_ps_command(true));
+    /// Read both panes' directories again
+    pub fn file_manager_refresh_all(&mut self) {
+        self.file_manager_refresh(false);
+        self.file_manager_refresh(true);
+    }
 
-            // Step 1: Fetch containers
-            self.pending_ssh_command = Some(PendingSshCommand {
-                host: host.clone(),
-                command: docker_ps,
-                command_type: SshCommandType::DockerPs,
-            });
+    /// Open the selected directory, or view the selected file
+    pub fn file_manager_open(&mut self) {
+        let Some((pane, remote_pane)) = self.focused_file_pane() else {
+            return;
+        };
+        let Some(entry) = pane.entries.get(pane.selected_index) else {
+            return;
+        };
+        if !entry.is_dir {
+            self.file_manager_view();
+            return;
+        }
+        let path = if entry.name == ".." { pane.parent_path() } else { pane.child_path(&entry.name) };
+        self.file_manager_navigate(remote_pane, path);
+    }
 
-            // Queue step 2: List projects
-            self.pending_docker_commands.push(PendingSshCommand {
-                host: host.clone(),
-                command: self.sudo_cmd(&docker::list_projects_command(&self.clients_path)),
-                command_type: SshCommandType::ListProjects,
-            });
+    /// Go up to the focused pane's parent directory
+    pub fn file_manager_parent(&mut self) {
+        if let Some((pane, remote_pane)) = self.focused_file_pane() {
+            let path = pane.parent_path();
+            self.file_manager_navigate(remote_pane, path);
+        }
+    }
 
-            self.mode = AppMode::ContainerList { host_index };
-            self.docker_selected_index = 0;
-            self.set_status(if self.use_sudo { "Connecting (sudo)..." } else { "Connecting..." }.to_string());
+    /// View the selected file in the pager (at most `PREVIEW_LIMIT` bytes of it)
+    pub fn file_manager_view(&mut self) {
+        use std::io::Read;
+
+        let Some((pane, remote_pane)) = self.focused_file_pane() else {
+            return;
+        };
+        let Some(entry) = pane.selected().filter(|e| !e.is_dir) else {
+            self.set_error("Select a file to view");
+            return;
+        };
+        let path = pane.child_path(&entry.name);
+        if remote_pane {
+            if let AppMode::FileManager { host_index, .. } = self.mode {
+                if let Some(host) = self.hosts.get(host_index).cloned() {
+                    self.pending_ssh_command = Some(PendingSshCommand {
+                        host,
+                        command: crate::ssh::files::read_command(&path),
+                        command_type: SshCommandType::FileManagerRead { path },
+                    });
+                }
+            }
+            return;
+        }
+        let mut content = Vec::new();
+        let limit = crate::ssh::files::PREVIEW_LIMIT as u64 + 1;
+        match std::fs::File::open(&path).and_then(|file| file.take(limit).read_to_end(&mut content)) {
+            Ok(_) => self.show_file_preview(path, &content),
+            Err(e) => self.set_error(format!("Could not read {}: {}", path, e)),
         }
     }
 
-    /// Refresh containers
-    pub fn refresh_containers(&mut self) {
-        if let Some(host_index) = self.current_docker_host_index {
-            if host_index < self.hosts.len() {
-                let host = self.hosts[host_index].clone();
+    /// Show the start of a file in the pager, over the file manager or browser it was picked in
+    fn show_file_preview(&mut self, path: String, content: &[u8]) {
+        let shown = match crate::ssh::files::preview(path, content) {
+            Ok(shown) => shown,
+            Err(e) => {
+                self.set_error(e);
+                return;
+            }
+        };
+        match self.mode {
+            AppMode::FileManager { ref mut preview, .. } => *preview = Some(shown),
+            AppMode::FileBrowser { .. } | AppMode::RsyncFileBrowser { .. } => {
+                let return_mode = Box::new(std::mem::replace(&mut self.mode, AppMode::Table));
+                self.mode = AppMode::FilePreview { preview: shown, return_mode };
+            }
+            _ => {}
+        }
+    }
 
-                // Clear and refetch
-                self.containers.clear();
-                self.projects.clear();
-                self.scripts.clear();
+    /// The file selected in the script or rsync file browser: its path, and
+    /// for a host's file, the host and whether it is read with sudo
+    fn browser_selected_file(&self) -> Option<(String, Option<(Host, bool)>)> {
+        let (current_path, entries, selected_index, remote) = match &self.mode {
+            AppMode::FileBrowser { host_index, current_path, entries, selected_index, .. } => {
+                let host = self.hosts.get(*host_index)?.clone();
+                (current_path, entries, *selected_index, Some((host, self.use_sudo)))
+            }
+            AppMode::RsyncFileBrowser { editing_host, current_path, entries, selected_index, is_remote, target_field, dest_host, .. } => {
+                // The destination of a transfer between two hosts is browsed on that host
+                let host = match (dest_host, target_field) {
+                    (Some(dest_host), crate::models::app_state::RsyncField::DestPath) => dest_host.clone(),
+                    _ => editing_host.clone(),
+                };
+                (current_path, entries, *selected_index, is_remote.then_some((host, false)))
+            }
+            _ => return None,
+        };
+        let entry = entries.get(selected_index).filter(|e| !e.is_dir)?;
+        Some((format!("{}/{}", current_path.trim_end_matches('/'), entry.name), remote))
+    }
 
-                let docker_ps = self.docker_cmd(&docker::docker_ps_command(true));
+    /// View the selected file in the pager (at most `PREVIEW_LIMIT` bytes of it)
+    pub fn browser_preview_file(&mut self) {
+        use std::io::Read;
 
+        match self.browser_selected_file() {
+            Some((path, Some((host, sudo)))) => {
+                let command = crate::ssh::files::read_command(&path);
                 self.pending_ssh_command = Some(PendingSshCommand {
-                    host: host.clone(),
-                    command: docker_ps,
-                    command_type: SshCommandType::DockerPs,
+                    host,
+                    command: if sudo { format!("sudo -i {}", command) } else { command },
+                    command_type: SshCommandType::PreviewFile { path },
                 });
+            }
+            Some((path, None)) => {
+                let mut content = Vec::new();
+                let limit = crate::ssh::files::PREVIEW_LIMIT as u64 + 1;
+                match std::fs::File::open(&path).and_then(|file| file.take(limit).read_to_end(&mut content)) {
+                    Ok(_) => self.show_file_preview(path, &content),
+                    Err(e) => self.set_error(format!("Could not read {}: {}", path, e)),
+                }
+            }
+            None => self.set_error("Select a file to view"),
+        }
+    }
 
-                self.pending_docker_commands.push(PendingSshCommand {
-                    host: host.clone(),
-                    command: self.sudo_cmd(&docker::list_projects_command(&self.clients_path)),
-                    command_type: SshCommandType::ListProjects,
-                });
+    /// Open the selected host file in the local editor (with the TUI suspended)
+    pub fn browser_edit_file(&mut self) {
+        match self.browser_selected_file() {
+            Some((path, Some((host, sudo)))) => self.pending_file_edit = Some(PendingFileEdit { host, path, sudo }),
+            Some((_, None)) => self.set_error("Only files on the host can be edited from here"),
+            None => self.set_error("Select a file to edit"),
+        }
+    }
 
-                self.set_status(if self.use_sudo { "Refreshing (sudo)..." } else { "Refreshing..." }.to_string());
-            }
+    /// Go back from the pager to the browser
+    pub fn close_file_preview(&mut self) {
+        if let AppMode::FilePreview { return_mode, .. } = std::mem::replace(&mut self.mode, AppMode::Table) {
+            self.mode = *return_mode;
         }
     }
 
-    /// Handle SSH command output
-    pub fn handle_ssh_output(&mut self, output: String, command_type: SshCommandType) {
-        match command_type {
-            SshCommandType::DockerPs => {
-                let server_name = self.current_docker_host_index
-                    .and_then(|i| self.hosts.get(i))
-                    .map(|s| s.host.as_str())
-                    .unwrap_or("unknown");
-                self.containers = docker::parse_docker_ps(&output, server_name);
+    /// Edit the previewed file, from the browser it was picked in
+    pub fn edit_previewed_file(&mut self) {
+        self.close_file_preview();
+        self.browser_edit_file();
+    }
 
-                // Associate containers with scripts
-                self.associate_containers_with_scripts();
+    /// Record how a quick edit went, and list the browser's directory again
+    /// so sizes and dates are current
+    pub fn complete_file_edit(&mut self, result: Result<String>) {
+        self.pending_file_edit = None;
+        match result {
+            Ok(status) => self.set_status(status),
+            Err(e) => self.set_error(e.to_string()),
+        }
+        match &self.mode {
+            AppMode::FileBrowser { current_path, .. } => {
+                let path = current_path.clone();
+                self.file_browser_list(path);
+            }
+            AppMode::RsyncFileBrowser { current_path, .. } => {
+                let path = current_path.clone();
+                self.rsync_navigate_to(path);
+            }
+            AppMode::FileManager { .. } => self.file_manager_refresh(true),
+            _ => {}
+        }
+    }
 
-                self.set_status(format!("Found {} containers", self.containers.len()));
+    /// Open the selected host file in the local editor (with the TUI suspended)
+    pub fn file_manager_edit(&mut self) {
+        let Some((pane, remote_pane)) = self.focused_file_pane() else {
+            return;
+        };
+        if !remote_pane {
+            self.set_error("Only files on the host can be edited from here");
+            return;
+        }
+        let Some(entry) = pane.selected().filter(|e| !e.is_dir) else {
+            self.set_error("Select a file to edit");
+            return;
+        };
+        let path = pane.child_path(&entry.name);
+        if let AppMode::FileManager { host_index, .. } = self.mode {
+            if let Some(host) = self.hosts.get(host_index).cloned() {
+                self.pending_file_edit = Some(PendingFileEdit { host, path, sudo: false });
             }
-            SshCommandType::ListProjects => {
-                if let Some(host_index) = self.current_docker_host_index {
-                    if let Some(host) = self.hosts.get(host_index).cloned() {
-                        self.projects = docker::parse_project_listing(&output, &self.clients_path);
+        }
+    }
 
-                        // Queue script discovery for each project
-                        for project in &self.projects {
-                            self.pending_docker_commands.push(PendingSshCommand {
-                                host: host.clone(),
-                                command: self.sudo_cmd(&docker::find_scripts_command(&project.path)),
-                                command_type: SshCommandType::FindScripts {
-                                    project_name: project.name.clone(),
-                                    project_path: project.path.clone(),
-                                },
-                            });
-                        }
+    /// Copy the focused pane's marked entries (or the selected one) into the
+    /// other pane's directory
+    pub fn file_manager_copy(&mut self) {
+        let Some((pane, remote_pane)) = self.focused_file_pane() else {
+            return;
+        };
+        let names = pane.targets();
+        self.queue_file_copies(remote_pane, names);
+    }
 
-                        self.set_status(format!("Found {} projects, scanning for scripts...", self.projects.len()));
-                    }
+    /// Copy the selected remote file into the local pane's directory
+    pub fn file_manager_download(&mut self) {
+        match self.focused_file_pane() {
+            Some((pane, true)) => match pane.selected().filter(|e| !e.is_dir) {
+                Some(entry) => {
+                    let name = entry.name.clone();
+                    self.queue_file_copies(true, vec![name]);
                 }
-            }
-            SshCommandType::FindScripts { project_name, project_path: _ } => {
-                let script_paths = docker::parse_script_paths(&output);
+                None => self.set_error("Select a file to download"),
+            },
+            Some((_, false)) => self.set_error("Downloads copy from the host pane, press Tab to switch"),
+            None => {}
+        }
+    }
 
-                if let Some(host_index) = self.current_docker_host_index {
-                    if let Some(host) = self.hosts.get(host_index).cloned() {
-                        // Queue reading each script
-                        for script_path in script_paths {
-                            self.pending_docker_commands.push(PendingSshCommand {
-                                host: host.clone(),
-                                command: self.sudo_cmd(&docker::read_script_command(&script_path)),
-                                command_type: SshCommandType::ReadScript {
-                                    project_name: project_name.clone(),
-                                    script_path,
-                                },
-                            });
-                        }
-                    }
-                }
-            }
-            SshCommandType::ReadScript { project_name, script_path } => {
-                if let Some(script) = docker::create_script_from_content(&script_path, &output, &project_name) {
-                    // Add script to project
-                    if let Some(project) = self.projects.iter_mut().find(|p| p.name == project_name) {
-                        project.scripts.push(script.clone());
-                    }
-                    self.scripts.push(script);
+    /// Copy `names` from one pane to the other, one transfer per name, with
+    /// rsync (or sftp or scp where it is missing)
+    fn queue_file_copies(&mut self, from_remote: bool, names: Vec<String>) {
+        if self.rsync_run.as_ref().is_some_and(|run| run.exit_code.is_none()) {
+            self.set_error("A copy is already running");
+            return;
+        }
+        let AppMode::FileManager { host_index, ref local, ref remote, .. } = self.mode else {
+            return;
+        };
+        if names.is_empty() {
+            self.set_error("Nothing selected to copy");
+            return;
+        }
+        let Some(host) = self.hosts.get(host_index) else {
+            return;
+        };
+        let (from, to) = if from_remote { (remote, local) } else { (local, remote) };
+        let dest = to.child_path("");
+        let mut transfers: Vec<RsyncTransfer> = names
+            .iter()
+            .map(|name| RsyncTransfer {
+                host: host.clone(),
+                source: from.child_path(name),
+                dest: dest.clone(),
+                to_host: !from_remote,
+                compress: false,
+                options: RsyncOptions::default(),
+                skipped: Vec::new(),
+                profile: None,
+                dest_host: None,
+                relay: false,
+                tool: TransferTool::Rsync,
+            })
+            .collect();
+        let tool = crate::ssh::rsync::pick_transfer_tool(&transfers[0], self.rsync_available);
+        for transfer in &mut transfers {
+            transfer.tool = tool;
+        }
+        let first = transfers.remove(0);
+        self.file_copies = transfers;
+        self.spawn_rsync_run(first);
+    }
 
-                    // Re-associate containers with scripts
-                    self.associate_containers_with_scripts();
-                }
+    /// Start the file manager's next queued copy, or refresh the panes once
+    /// they are all done. A failed or cancelled copy drops the rest
+    fn continue_file_copies(&mut self, succeeded: bool) {
+        if !matches!(self.mode, AppMode::FileManager { .. }) {
+            self.file_copies.clear();
+            return;
+        }
+        if succeeded && !self.file_copies.is_empty() {
+            let next = self.file_copies.remove(0);
+            self.spawn_rsync_run(next);
+            return;
+        }
+        self.file_copies.clear();
+        self.file_manager_refresh_all();
+    }
 
-                // Update status with script count
-                let total_scripts: usize = self.projects.iter().map(|p| p.scripts.len()).sum();
-                if self.pending_docker_commands.is_empty() {
+    /// Leave the file manager, stopping a running copy first
+    pub fn close_file_manager(&mut self) {
+        if self.rsync_run.as_ref().is_some_and(|run| run.exit_code.is_none()) {
+            self.file_copies.clear();
+            self.cancel_rsync_run();
+            return;
+        }
+        self.rsync_run = None;
+        self.return_to_table();
+    }
+
+    /// Ask for a new name for the selected entry
+    pub fn file_manager_start_rename(&mut self) {
+        if let AppMode::FileManager { ref local, ref remote, remote_focused, ref mut prompt, .. } = self.mode {
+            let pane = if remote_focused { remote } else { local };
+            if let Some(entry) = pane.selected() {
+                *prompt = Some(FilePrompt::Rename { name: entry.name.clone(), buffer: entry.name.clone() });
+            }
+        }
+    }
+
+    /// Ask for the name of a directory to create
+    pub fn file_manager_start_mkdir(&mut self) {
+        if let AppMode::FileManager { ref mut prompt, .. } = self.mode {
+            *prompt = Some(FilePrompt::Mkdir { buffer: String::new() });
+        }
+    }
+
+    /// Ask to confirm deleting the marked entries (or the selected one)
+    pub fn file_manager_start_delete(&mut self) {
+        let Some((pane, _)) = self.focused_file_pane() else {
+            return;
+        };
+        let names = pane.targets();
+        if names.is_empty() {
+            return;
+        }
+        if let AppMode::FileManager { ref mut prompt, .. } = self.mode {
+            *prompt = Some(FilePrompt::ConfirmDelete { names });
+        }
+    }
+
+    /// Carry out the prompt's rename, mkdir or delete in the focused pane
+    pub fn file_manager_submit_prompt(&mut self) {
+        let AppMode::FileManager { host_index, ref local, ref remote, remote_focused, ref mut prompt, .. } = self.mode else {
+            return;
+        };
+        let Some(action) = prompt.take() else {
+            return;
+        };
+        let dir = if remote_focused { remote.path.clone() } else { local.path.clone() };
+
+        let checked = match &action {
+            FilePrompt::Rename { buffer, .. } | FilePrompt::Mkdir { buffer } => crate::ssh::files::validate_name(buffer.trim()),
+            FilePrompt::ConfirmDelete { .. } => Ok(()),
+        };
+        if let Err(e) = checked {
+            *prompt = Some(action);
+            self.set_error(e);
+            return;
+        }
+
+        let operation = match &action {
+            FilePrompt::Rename { name, buffer } => format!("Renamed {} to {}", name, buffer.trim()),
+            FilePrompt::Mkdir { buffer } => format!("Created {}", buffer.trim()),
+            FilePrompt::ConfirmDelete { names } if names.len() == 1 => format!("Deleted {}", names[0]),
+            FilePrompt::ConfirmDelete { names } => format!("Deleted {} items", names.len()),
+        };
+
+        if remote_focused {
+            let command = match &action {
+                FilePrompt::Rename { name, buffer } => crate::ssh::files::rename_command(&dir, name, buffer.trim()),
+                FilePrompt::Mkdir { buffer } => crate::ssh::files::mkdir_command(&dir, buffer.trim()),
+                FilePrompt::ConfirmDelete { names } => crate::ssh::files::delete_command(&dir, names),
+            };
+            if let Some(host) = self.hosts.get(host_index).cloned() {
+                self.pending_ssh_command = Some(PendingSshCommand {
+                    host,
+                    command,
+                    command_type: SshCommandType::FileManagerOperation { operation },
+                });
+            }
+            return;
+        }
+
+        let path = |name: &str| std::path::Path::new(&dir).join(name);
+        let result = match &action {
+            FilePrompt::Rename { buffer, .. } if path(buffer.trim()).exists() => {
+                Err(std::io::Error::new(std::io::ErrorKind::AlreadyExists, format!("{} already exists", buffer.trim())))
+            }
+            FilePrompt::Rename { name, buffer } => std::fs::rename(path(name), path(buffer.trim())),
+            FilePrompt::Mkdir { buffer } => std::fs::create_dir(path(buffer.trim())),
+            FilePrompt::ConfirmDelete { names } => names.iter().try_for_each(|name| {
+                let target = path(name);
+                // Symlinks to directories are removed, not followed
+                if std::fs::symlink_metadata(&target)?.is_dir() {
+                    std::fs::remove_dir_all(&target)
+                } else {
+                    std::fs::remove_file(&target)
+                }
+            }),
+        };
+        match result {
+            Ok(()) => self.set_status(operation),
+            Err(e) => self.set_error(format!("{} failed: {}", operation, e)),
+        }
+        self.file_manager_refresh(false);
+    }
+
+    /// Delete the confirmed host
+    pub fn delete_host(&mut self, index: usize) -> Result<()> {
+        if index < self.hosts.len() {
+            let host = self.hosts.remove(index);
+            self.ssh_config.remove_host(index)?;
+            self.metadata.remove(&host.host);
+
+            self.save_all()?;
+
+            self.status_message = Some(format!("Deleted host '{}'", host.host));
+
+            // Adjust selection if needed
+            if self.selected_index >= self.hosts.len() && self.selected_index > 0 {
+                self.selected_index -= 1;
+            }
+        }
+
+        self.mode = AppMode::Table;
+        Ok(())
+    }
+
+    /// Connect to the selected host
+    pub fn connect_to_selected(&mut self) -> Result<()> {
+        // Clone the host to avoid borrow checker issues
+        let host_to_connect = match self.selected_host() {
+            Some(host) => host.clone(),
+            None => {
+                self.error_message = Some("No host selected".to_string());
+                return Ok(());
+            }
+        };
+
+        let host_name = host_to_connect.host.clone();
+
+        // Update the host in our list and mark as used
+        if let Some(actual_index) = self.hosts.iter().position(|h| h.host == host_name) {
+            self.hosts[actual_index].mark_used();
+        }
+
+        // Save metadata with updated last_used
+        self.save_metadata_only()?;
+
+        // Store pending connection instead of executing immediately
+        // The main loop will handle terminal cleanup and SSH execution
+        self.pending_connection = Some(host_to_connect);
+
+        Ok(())
+    }
+
+    /// Clear the pending connection and update status
+    pub fn complete_connection(&mut self, success: bool, error: Option<String>) {
+        if let Some(ref host) = self.pending_connection {
+            if success {
+                self.status_message = Some(format!("Connected to '{}'", host.host));
+            } else {
+                self.error_message = error;
+            }
+        }
+        self.pending_connection = None;
+    }
+
+    /// Clear the pending interactive command and update status
+    pub fn complete_interactive_command(&mut self, success: bool, error: Option<String>) {
+        if let Some(ref pending) = self.pending_interactive {
+            if success {
+                self.status_message = Some(format!("Closed {}", pending.label));
+            } else {
+                self.error_message = error;
+            }
+        }
+        self.pending_interactive = None;
+    }
+
+    /// Enter search mode
+    pub fn start_search(&mut self) {
+        self.mode = AppMode::Search {
+            query: self.search_query.clone(),
+        };
+    }
+
+    /// Apply search query
+    pub fn apply_search(&mut self, query: String) {
+        self.search_query = query;
+        self.selected_index = 0; // Reset selection when search changes
+        self.mode = AppMode::Table;
+    }
+
+    /// Cycle to next sort option
+    pub fn cycle_sort(&mut self) {
+        self.sort_by = self.sort_by.next();
+        self.selected_index = 0; // Reset selection when sort changes
+        self.status_message = Some(format!("Sorting by: {}", self.sort_by.label()));
+
+        // Save sort preference to metadata
+        self.metadata.sort_by = self.sort_by.as_config_str();
+        let _ = save_metadata(&self.metadata_path, &self.metadata);
+    }
+
+    /// Start tag filter mode
+    pub fn start_tag_filter(&mut self) {
+        self.mode = AppMode::TagFilter {
+            selected_tags: self.active_tag_filters.clone(),
+        };
+    }
+
+    /// Apply tag filters
+    pub fn apply_tag_filter(&mut self, tags: Vec<String>) {
+        self.active_tag_filters = tags;
+        self.selected_index = 0; // Reset selection when filter changes
+        self.mode = AppMode::Table;
+    }
+
+    /// Show help overlay
+    pub fn show_help(&mut self) {
+        self.mode = AppMode::Help;
+    }
+
+    /// Return to table view
+    pub fn return_to_table(&mut self) {
+        self.mode = AppMode::Table;
+    }
+
+    /// Set status message
+    pub fn set_status(&mut self, message: String) {
+        self.status_message = Some(message);
+    }
+
+    /// Set error message
+    pub fn set_error(&mut self, message: impl Into<String>) {
+        self.error_message = Some(message.into());
+    }
+
+    /// Quit the application
+    pub fn quit(&mut self) {
+        self.should_quit = true;
+    }
+
+    /// Clear status and error messages
+    pub fn clear_messages(&mut self) {
+        self.status_message = None;
+        self.error_message = None;
+    }
+
+    /// Save both SSH config and metadata
+    fn save_all(&mut self) -> Result<()> {
+        // Extract metadata from hosts
+        self.metadata.extract_from_hosts(&self.hosts);
+
+        // Save SSH config
+        write_ssh_config(&self.ssh_config)?;
+
+        // Save metadata
+        save_metadata(&self.metadata_path, &self.metadata)?;
+
+        Ok(())
+    }
+
+    /// Save only metadata (for last_used updates)
+    fn save_metadata_only(&mut self) -> Result<()> {
+        self.metadata.extract_from_hosts(&self.hosts);
+        save_metadata(&self.metadata_path, &self.metadata)?;
+        Ok(())
+    }
+
+    // ==================== Docker Mode Methods ====================
+
+    /// Start Docker mode for the selected host
+    pub fn start_docker_mode(&mut self) {
+        if let Some(host) = self.selected_host() {
+            if let Some(actual_index) = self.hosts.iter().position(|h| h.host == host.host) {
+                let host = host.clone();
+
+                // Reset sudo flag for new connection, unless the host has a default
+                self.use_sudo = host.docker.use_sudo.unwrap_or(false);
+
+                // If user is not root and the host has no sudo default, ask about sudo
+                if host.docker.use_sudo.is_none() && host.user.as_ref().map(|u| u != "root").unwrap_or(true) {
+                    self.mode = AppMode::ConfirmDockerAction {
+                        action: DockerPendingAction::EnableSudo { host_index: actual_index },
+                        return_mode: Box::new(AppMode::Table),
+                    };
+                } else {
+                    // Root user, connect directly
+                    self.start_fetching_containers(actual_index);
+                }
+            }
+        }
+    }
+
+    /// Open the Docker settings form for the selected host
+    pub fn start_docker_settings(&mut self) {
+        if let Some(host) = self.selected_host() {
+            if let Some(actual_index) = self.hosts.iter().position(|h| h.host == host.host) {
+                let settings = host.docker.clone();
+                let focused_field = DockerSettingsField::SearchRoots;
+                self.mode = AppMode::DockerSettingsForm {
+                    host_index: actual_index,
+                    field_buffer: focused_field.value(&settings),
+                    settings,
+                    focused_field,
+                    editing_mode: false,
+                };
+            }
+        }
+    }
+
+    /// Store the edited Docker settings on the host and save them
+    pub fn save_docker_settings(&mut self) -> Result<()> {
+        if let AppMode::DockerSettingsForm { host_index, settings, focused_field, field_buffer, .. } = &mut self.mode {
+            if let Err(e) = focused_field.apply(settings, field_buffer) {
+                self.error_message = Some(e);
+                return Ok(());
+            }
+            let (host_index, settings) = (*host_index, settings.clone());
+            if let Some(host) = self.hosts.get_mut(host_index) {
+                host.docker = settings;
+                let name = host.host.clone();
+                self.save_metadata_only()?;
+                self.set_status(format!("Saved Docker settings for {}", name));
+            }
+            self.mode = AppMode::Table;
+        }
+        Ok(())
+    }
+
+    /// Start fetching containers after connection setup
+    pub fn start_fetching_containers(&mut self, host_index: usize) {
+        if host_index < self.hosts.len() {
+            let host = self.hosts[host_index].clone();
+            self.current_docker_host_index = Some(host_index);
+            self.clients_path = host.docker.primary_root();
+
+            // Clear previous data
+            self.containers.clear();
+            self.projects.clear();
+            self.scripts.clear();
+
+            // Build docker ps command (with sudo if needed)
+            let docker_ps = self.docker_cmd(&docker::docker_ps_command(true));
+
+            // Step 1: Fetch containers
+            self.pending_ssh_command = Some(PendingSshCommand {
+                host: host.clone(),
+                command: docker_ps,
+                command_type: SshCommandType::DockerPs,
+            });
+
+            // Queue step 2: List projects
+            self.pending_docker_commands.push(PendingSshCommand {
+                host: host.clone(),
+                command: self.sudo_cmd(&docker::list_projects_command(&host.docker)),
+                command_type: SshCommandType::ListProjects,
+            });
+
+            self.mode = AppMode::ContainerList { host_index };
+            self.docker_selected_index = 0;
+            self.set_status(if self.use_sudo { "Connecting (sudo)..." } else { "Connecting..." }.to_string());
+        }
+    }
+
+    /// Refresh containers
+    pub fn refresh_containers(&mut self) {
+        if let Some(host_index) = self.current_docker_host_index {
+            if host_index < self.hosts.len() {
+                let host = self.hosts[host_index].clone();
+
+                // Clear and refetch
+                self.containers.clear();
+                self.projects.clear();
+                self.scripts.clear();
+                self.container_drift.clear();
+
+                let docker_ps = self.docker_cmd(&docker::docker_ps_command(true));
+
+                self.pending_ssh_command = Some(PendingSshCommand {
+                    host: host.clone(),
+                    command: docker_ps,
+                    command_type: SshCommandType::DockerPs,
+                });
+
+                self.pending_docker_commands.push(PendingSshCommand {
+                    host: host.clone(),
+                    command: self.sudo_cmd(&docker::list_projects_command(&host.docker)),
+                    command_type: SshCommandType::ListProjects,
+                });
+
+                self.set_status(if self.use_sudo { "Refreshing (sudo)..." } else { "Refreshing..." }.to_string());
+            }
+        }
+    }
+
+    /// Handle SSH command output
+    pub fn handle_ssh_output(&mut self, output: String, command_type: SshCommandType) {
+        match command_type {
+            SshCommandType::DockerPs => {
+                let server_name = self.current_docker_host_index
+                    .and_then(|i| self.hosts.get(i))
+                    .map(|s| s.host.as_str())
+                    .unwrap_or("unknown");
+                self.containers = docker::parse_docker_ps(&output, server_name);
+
+                // Group compose containers by project (stable, so docker ps order is kept within a group)
+                self.containers.sort_by(|a, b| {
+                    (a.compose_project.is_none(), &a.compose_project).cmp(&(b.compose_project.is_none(), &b.compose_project))
+                });
+
+                // Associate containers with scripts
+                self.associate_containers_with_scripts();
+
+                self.set_status(format!("Found {} containers", self.containers.len()));
+            }
+            SshCommandType::ListProjects => {
+                if let Some(host_index) = self.current_docker_host_index {
+                    if let Some(host) = self.hosts.get(host_index).cloned() {
+                        self.projects = docker::parse_project_listing(&output);
+
+                        // Queue script discovery for each project
+                        for project in &self.projects {
+                            self.pending_docker_commands.push(PendingSshCommand {
+                                host: host.clone(),
+                                command: self.sudo_cmd(&docker::find_scripts_command(&project.path, &host.docker)),
+                                command_type: SshCommandType::FindScripts {
+                                    project_name: project.name.clone(),
+                                    project_path: project.path.clone(),
+                                },
+                            });
+                        }
+
+                        self.set_status(format!("Found {} projects, scanning for scripts...", self.projects.len()));
+                    }
+                }
+            }
+            SshCommandType::FindScripts { project_name, project_path: _ } => {
+                let script_paths = docker::parse_script_paths(&output);
+
+                if let Some(host_index) = self.current_docker_host_index {
+                    if let Some(host) = self.hosts.get(host_index).cloned() {
+                        // Queue reading each script
+                        for script_path in script_paths {
+                            self.pending_docker_commands.push(PendingSshCommand {
+                                host: host.clone(),
+                                command: self.sudo_cmd(&docker::read_script_command(&script_path)),
+                                command_type: SshCommandType::ReadScript {
+                                    project_name: project_name.clone(),
+                                    script_path,
+                                },
+                            });
+                        }
+                    }
+                }
+            }
+            SshCommandType::ReadScript { project_name, script_path } => {
+                if let Some(script) = docker::create_script_from_content(&script_path, &output, &project_name) {
+                    // Add script to project
+                    if let Some(project) = self.projects.iter_mut().find(|p| p.name == project_name) {
+                        project.scripts.push(script.clone());
+                    }
+                    self.scripts.push(script);
+
+                    // Re-associate containers with scripts
+                    self.associate_containers_with_scripts();
+                }
+
+                // Update status with script count
+                let total_scripts: usize = self.projects.iter().map(|p| p.scripts.len()).sum();
+                if self.pending_docker_commands.is_empty() {
                     self.set_status(format!(
                         "{} containers, {} projects, {} scripts",
                         self.containers.len(),
                         self.projects.len(),
                         total_scripts
                     ));
+                    // Every script is loaded: check which containers drifted from theirs
+                    self.queue_drift_check(None, false);
+                }
+            }
+            SshCommandType::ViewLogs => {
+                if let AppMode::LogsViewer { ref mut log_buffer, .. } = self.mode {
+                    log_buffer.extend(output.lines().map(String::from));
+                }
+            }
+            SshCommandType::DockerOperation { operation } => {
+                self.set_status(format!("{} completed", operation));
+                // Refresh container list after operation
+                self.refresh_containers();
+            }
+            SshCommandType::ContainerStats { container_index } => {
+                let stats = docker::parser::parse_docker_stats(&output);
+                if let Some(host_index) = self.current_docker_host_index {
+                    self.mode = AppMode::StatsViewer {
+                        host_index,
+                        container_index,
+                        stats,
+                    };
+                }
+            }
+            SshCommandType::ContainerTop { container_index } => {
+                let processes = docker::parser::parse_docker_top(&output);
+                if let Some(host_index) = self.current_docker_host_index {
+                    self.mode = AppMode::ProcessViewer {
+                        host_index,
+                        container_index,
+                        processes,
+                        selected_index: 0,
+                    };
+                }
+            }
+            SshCommandType::ContainerInspect { container_index } => {
+                let info = docker::parser::parse_docker_inspect(&output);
+                if let Some(host_index) = self.current_docker_host_index {
+                    self.mode = AppMode::InspectViewer {
+                        host_index,
+                        container_index,
+                        info,
+                        selected_section: 0,
+                    };
+                }
+            }
+            SshCommandType::InspectContainerEnv { container_index } => {
+                let container_vars: Vec<(String, String)> = output
+                    .lines()
+                    .filter_map(|line| {
+                        let line = line.trim();
+                        line.find('=').map(|pos| {
+                            let key = line[..pos].to_string();
+                            let value = line[pos + 1..].to_string();
+                            (key, value)
+                        })
+                    })
+                    .collect();
+
+                let var_count = container_vars.len();
+
+                // Get script vars if available
+                let script_vars: Vec<(String, String)> = if container_index < self.containers.len() {
+                    let container_name = &self.containers[container_index].name;
+                    self.get_script_for_container(container_name)
+                        .map(|s| s.env_vars.iter().map(|e| (e.key.clone(), e.value.clone())).collect())
+                        .unwrap_or_default()
+                } else {
+                    Vec::new()
+                };
+
+                if let Some(host_index) = self.current_docker_host_index {
+                    self.mode = AppMode::EnvInspector {
+                        host_index,
+                        container_index,
+                        script_vars,
+                        container_vars,
+                        selected_index: 0,
+                        scroll_offset: 0,
+                        search_query: String::new(),
+                    };
+                    self.set_status(format!("Found {} environment variables. Type to search.", var_count));
+                }
+            }
+            SshCommandType::ExecCommand { container_index, command } => {
+                let (lines, exit_code) = docker::parse_exec_output(&output);
+                if let AppMode::ExecOutput { container_index: shown_index, command: ref shown_command, .. } = self.mode {
+                    if shown_index == container_index && *shown_command == command {
+                        if let Some(host_index) = self.current_docker_host_index {
+                            self.mode = AppMode::ExecOutput {
+                                host_index,
+                                container_index,
+                                command,
+                                output: lines,
+                                exit_code,
+                                scroll_offset: 0,
+                            };
+                        }
+                    }
+                }
+                match exit_code {
+                    Some(0) => self.set_status("Command finished (exit 0)".to_string()),
+                    Some(code) => self.set_error(format!("Command exited with status {}", code)),
+                    None => self.set_error("Command finished without an exit status"),
+                }
+            }
+            SshCommandType::ListImages => {
+                let parsed = docker::parse_docker_images(&output, &self.containers);
+                let count = parsed.len();
+                let dangling = parsed.iter().filter(|i| i.is_dangling()).count();
+                if let AppMode::ImageList { ref mut images, ref mut selected_index, .. } = self.mode {
+                    *images = parsed;
+                    *selected_index = (*selected_index).min(images.len().saturating_sub(1));
+                }
+                self.set_status(format!("{} images ({} dangling)", count, dangling));
+            }
+            SshCommandType::ResourceOperation { operation } => {
+                self.set_status(format!("{} completed", operation));
+                self.refresh_resource_list();
+            }
+            SshCommandType::ListComposeProjects => {
+                let parsed = docker::parse_compose_projects(&output);
+                let count = parsed.len();
+                let running = parsed.iter().filter(|p| p.is_running()).count();
+                if let AppMode::ComposeProjects { ref mut projects, ref mut selected_index, .. } = self.mode {
+                    *projects = parsed;
+                    *selected_index = (*selected_index).min(projects.len().saturating_sub(1));
+                }
+                self.set_status(format!("{} compose projects ({} running)", count, running));
+            }
+            SshCommandType::ComposeOutput { project } => {
+                if let AppMode::ComposeOutput { project: ref shown, ref mut lines, ref mut scroll_offset, view, .. } = self.mode {
+                    if *shown == project {
+                        *lines = output.lines().map(String::from).collect();
+                        // Logs are most useful from the end, config from the top
+                        *scroll_offset = match view {
+                            ComposeView::Logs => lines.len().saturating_sub(1),
+                            ComposeView::Config => 0,
+                        };
+                    }
+                }
+                self.clear_messages();
+            }
+            SshCommandType::ListVolumes => {
+                let parsed = docker::parse_docker_volumes(&output);
+                let count = parsed.len();
+                let orphaned = parsed.iter().filter(|v| v.is_orphaned()).count();
+                if let AppMode::VolumeList { ref mut volumes, ref mut selected_index, .. } = self.mode {
+                    *volumes = parsed;
+                    *selected_index = (*selected_index).min(volumes.len().saturating_sub(1));
+                }
+                self.set_status(format!("{} volumes ({} orphaned)", count, orphaned));
+            }
+            SshCommandType::ListNetworks => {
+                let parsed = docker::parse_docker_networks(&output);
+                let count = parsed.len();
+                if let AppMode::NetworkList { ref mut networks, ref mut selected_index, .. } = self.mode {
+                    *networks = parsed;
+                    *selected_index = (*selected_index).min(networks.len().saturating_sub(1));
+                }
+                self.set_status(format!("{} networks", count));
+            }
+            SshCommandType::VolumeListDirectory { path } => {
+                if let AppMode::VolumeBrowser { ref current_path, ref mut entries, ref mut selected_index, ref mut loading, .. } = self.mode {
+                    if *current_path == path {
+                        *entries = docker::parser::parse_directory_listing(&output, &path);
+                        if path.is_empty() {
+                            // Already at the volume root, there is nothing above it
+                            entries.retain(|e| e.name != "..");
+                        }
+                        *selected_index = 0;
+                        *loading = false;
+                    }
+                }
+            }
+            SshCommandType::VolumeReadFile { path } => {
+                if let AppMode::VolumeBrowser { ref mut preview, ref mut preview_scroll, .. } = self.mode {
+                    *preview = Some(output.lines().map(String::from).collect());
+                    *preview_scroll = 0;
+                }
+                self.set_status(format!("/{} (read-only)", path));
+            }
+            SshCommandType::CheckImageUpdate { image_id } => {
+                let status = docker::parse_image_update_check(&output);
+                match status {
+                    ImageUpdateStatus::UpToDate => self.set_status("Image is up to date".to_string()),
+                    ImageUpdateStatus::UpdateAvailable => self.set_status("Newer image available - press [p] to pull".to_string()),
+                    ImageUpdateStatus::Unknown(ref reason) => self.set_error(format!("Could not check for updates: {}", reason)),
+                }
+                if let AppMode::ImageList { ref mut images, .. } = self.mode {
+                    if let Some(image) = images.iter_mut().find(|i| i.id == image_id) {
+                        image.update_status = Some(status);
+                    }
+                }
+            }
+            SshCommandType::ViewScriptContent { script_path, container_index } => {
+                let lines: Vec<String> = output.lines().map(|l| l.to_string()).collect();
+                if let Some(host_index) = self.current_docker_host_index {
+                    self.mode = AppMode::ScriptViewer {
+                        host_index,
+                        container_index,
+                        script_path,
+                        script_content: lines,
+                        scroll_offset: 0,
+                    };
+                }
+            }
+            SshCommandType::ListDirectory { path } => {
+                let entries = self.arrange_entries(docker::parser::parse_directory_listing(&output, &path));
+                if let AppMode::FileBrowser { entries: ref mut e, loading, current_path, .. } = &mut self.mode {
+                    *e = entries;
+                    *loading = false;
+                    *current_path = path;
+                }
+            }
+            SshCommandType::ReadScriptForContainer { script_path, container_index } => {
+                if let Some(script) = docker::create_script_from_content(&script_path, &output, "manual") {
+                    // Update the container's script_path
+                    if container_index < self.containers.len() {
+                        self.containers[container_index].script_path = Some(script_path.clone());
+
+                        // Save the association to metadata for persistence
+                        if let Some(host) = self.get_current_docker_host() {
+                            let host_name = host.host.clone();
+                            let container_name = self.containers[container_index].name.clone();
+                            self.metadata.set_script_path(&host_name, &container_name, script_path.clone());
+                            let _ = save_metadata(&self.metadata_path, &self.metadata);
+                        }
+                    }
+
+                    // Add to scripts list if not already present
+                    if !self.scripts.iter().any(|s| s.path == script_path) {
+                        self.scripts.push(script.clone());
+                    }
+
+                    // Switch to script edit mode
+                    if let Some(host_index) = self.current_docker_host_index {
+                        self.mode = AppMode::ScriptEdit {
+                            host_index,
+                            container_index,
+                            editing_script: script,
+                            focused_section: ScriptSection::EnvVars,
+                            selected_index: 0,
+                            editing_mode: false,
+                        };
+                        self.set_status("Script loaded and saved. Make changes and press Ctrl+S to save.".to_string());
+                    }
+                } else {
+                    self.set_error("Failed to parse script file".to_string());
+                    // Return to container list
+                    if let Some(host_index) = self.current_docker_host_index {
+                        self.mode = AppMode::ContainerList { host_index };
+                    }
                 }
             }
-            SshCommandType::ViewLogs => {
-                if let AppMode::LogsViewer { ref mut log_buffer, .. } = self.mode {
-                    log_buffer.extend(output.lines().map(String::from));
+            SshCommandType::WriteScript { script_path } => {
+                self.set_status(format!("Script saved: {} (previous version kept as a backup)", script_path));
+            }
+            SshCommandType::LintScript { script, mut issues } => {
+                issues.extend(docker::parse_lint_output(&output, &script));
+                docker::sort_issues(&mut issues);
+                match &mut self.mode {
+                    AppMode::ScriptDiff { script: reviewed, lint, .. } if reviewed.path == script.path => {
+                        *lint = Some(issues);
+                    }
+                    AppMode::ConfirmDockerAction {
+                        action: DockerPendingAction::RunScript { script_path, lint, .. },
+                        ..
+                    } if *script_path == script.path => {
+                        *lint = Some(issues);
+                    }
+                    _ => {}
+                }
+            }
+            SshCommandType::InspectForScript { container_index } => {
+                if let AppMode::ContainerList { host_index } = self.mode {
+                    let Some(container) = self.containers.get(container_index) else {
+                        return;
+                    };
+                    let script_name = format!("start{}.sh", capitalize_first(&container.name));
+                    let script_path = format!("{}/{}/{}", self.clients_path, container.name, script_name);
+                    match docker::script_from_inspect(&output, &script_path, &container.name) {
+                        Ok(script) => {
+                            self.mode = AppMode::ScriptEdit {
+                                host_index,
+                                container_index,
+                                editing_script: script,
+                                focused_section: ScriptSection::EnvVars,
+                                selected_index: 0,
+                                editing_mode: false,
+                            };
+                            self.set_status("Script generated from the running container. Press Ctrl+S to review and save.".to_string());
+                        }
+                        Err(e) => self.set_error(e),
+                    }
+                }
+            }
+            SshCommandType::CheckDrift { container, show } => {
+                let mut drifted = 0;
+                for (name, inspect) in docker::split_drift_output(&output) {
+                    let Some(script) = self.get_script_for_container(&name) else {
+                        continue;
+                    };
+                    match docker::detect_drift(script, &inspect) {
+                        Ok(drift) => {
+                            drifted += usize::from(!drift.is_empty());
+                            self.container_drift.insert(name, drift);
+                        }
+                        Err(_) => {
+                            self.container_drift.remove(&name);
+                        }
+                    }
+                }
+                if show {
+                    if let AppMode::ContainerList { host_index } | AppMode::DriftReport { host_index, .. } = self.mode {
+                        self.mode = AppMode::DriftReport { host_index, container, scroll_offset: 0 };
+                        self.clear_messages();
+                    }
+                } else if drifted > 0 {
+                    self.set_status(format!("{} containers differ from their scripts - press [F] for the drift report", drifted));
+                }
+            }
+            SshCommandType::ComposeImport { project, dir } => {
+                if let AppMode::ComposeProjects { host_index, .. } = self.mode {
+                    let client_name = dir.rsplit('/').next().unwrap_or(&project).to_string();
+                    match docker::compose_to_scripts(&output, &dir, &client_name) {
+                        Ok((scripts, _)) if scripts.is_empty() => self.set_error(format!("{} has no services", project)),
+                        Ok((scripts, skipped)) => {
+                            self.mode = AppMode::ComposeImport { host_index, project, scripts, selected_index: 0 };
+                            self.clear_messages();
+                            if !skipped.is_empty() {
+                                self.set_status(format!("Not carried over (no script equivalent): {}", skipped.join(", ")));
+                            }
+                        }
+                        Err(e) => self.set_error(e),
+                    }
+                }
+            }
+            SshCommandType::WriteComposeFile { path } => {
+                self.set_status(format!("Compose file written: {}", path));
+            }
+            SshCommandType::ListScriptBackups => {
+                if let AppMode::ScriptBackups { backups, loading, .. } = &mut self.mode {
+                    *backups = docker::parse_script_backups(&output);
+                    *loading = false;
+                }
+            }
+            SshCommandType::ReadScriptBackup { backup_path } => {
+                if let AppMode::ScriptBackups { host_index, container_index, script_path, .. } = &self.mode {
+                    match docker::split_script_and_backup(&output) {
+                        Some((current, backup)) => {
+                            let client_name = self
+                                .scripts
+                                .iter()
+                                .find(|s| &s.path == script_path)
+                                .map(|s| s.client_name.clone())
+                                .unwrap_or_else(|| "manual".to_string());
+                            let script = docker::script_parser::parse_script(backup, script_path, &client_name);
+                            let diff = unified_diff(current, backup, script_path, &backup_path);
+                            if diff.is_empty() {
+                                self.set_status("Backup is identical to the current script".to_string());
+                            } else {
+                                let host_index = *host_index;
+                                self.mode = AppMode::ScriptDiff {
+                                    host_index,
+                                    container_index: *container_index,
+                                    script: script.clone(),
+                                    diff,
+                                    scroll_offset: 0,
+                                    lint: None,
+                                    secrets: Vec::new(),
+                                    return_mode: Box::new(self.mode.clone()),
+                                };
+                                self.clear_messages();
+                                self.queue_script_lint(host_index, script, Vec::new());
+                            }
+                        }
+                        None => self.set_error("Could not read the backup"),
+                    }
+                }
+            }
+            SshCommandType::RsyncListDirectory { path } => {
+                let entries = self.arrange_entries(docker::parser::parse_directory_listing(&output, &path));
+                if let AppMode::RsyncFileBrowser { entries: ref mut e, loading, current_path, .. } = &mut self.mode {
+                    *e = entries;
+                    *loading = false;
+                    *current_path = path;
+                }
+            }
+            SshCommandType::FileManagerList { path } => {
+                let listing = crate::ssh::files::parse_listing(&output);
+                let listing = listing.map(|(resolved, entries)| (resolved, self.arrange_entries(entries)));
+                if let AppMode::FileManager { ref mut remote, .. } = self.mode {
+                    match listing {
+                        Some((resolved, entries)) => remote.show(resolved, entries),
+                        None => {
+                            remote.loading = false;
+                            self.set_error(format!("Could not open {}: {}", path, output.trim()));
+                        }
+                    }
+                }
+            }
+            SshCommandType::FileManagerRead { path } | SshCommandType::PreviewFile { path } => {
+                self.show_file_preview(path, output.as_bytes());
+            }
+            SshCommandType::FileManagerOperation { operation } => {
+                self.set_status(operation);
+                self.file_manager_refresh(true);
+            }
+        }
+
+        // Process next queued command
+        self.process_next_docker_command();
+    }
+
+    /// Process next queued docker command
+    fn process_next_docker_command(&mut self) {
+        if self.pending_ssh_command.is_none() && !self.pending_docker_commands.is_empty() {
+            self.pending_ssh_command = Some(self.pending_docker_commands.remove(0));
+        }
+    }
+
+    /// Associate containers with scripts
+    fn associate_containers_with_scripts(&mut self) {
+        let host_name = self.get_current_docker_host()
+            .map(|h| h.host.clone())
+            .unwrap_or_default();
+
+        // Associate containers with their scripts based on saved metadata or NAME variable
+        for container in &mut self.containers {
+            container.script_path = None;
+
+            // First check if there's a saved association in metadata
+            if let Some(saved_path) = self.metadata.get_script_path(&host_name, &container.name) {
+                container.script_path = Some(saved_path);
+                continue;
+            }
+
+            // Then check discovered scripts by matching NAME variable
+            for script in &self.scripts {
+                if script.container_name == container.name {
+                    container.script_path = Some(script.path.clone());
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Get script for a container
+    pub fn get_script_for_container(&self, container_name: &str) -> Option<&DeploymentScript> {
+        // First try to find by container's script_path
+        if let Some(container) = self.containers.iter().find(|c| c.name == container_name) {
+            if let Some(ref script_path) = container.script_path {
+                if let Some(script) = self.scripts.iter().find(|s| &s.path == script_path) {
+                    return Some(script);
                 }
             }
-            SshCommandType::DockerOperation { operation } => {
-                self.set_status(format!("{} completed", operation));
-                // Refresh container list after operation
-                self.refresh_containers();
+        }
+        // Fall back to matching by script's container_name
+        self.scripts.iter().find(|s| s.container_name == container_name)
+    }
+
+    /// Compare containers with their scripts. `None` checks every container
+    /// whose script is loaded; `show` opens the report when the result is in
+    fn queue_drift_check(&mut self, container: Option<String>, show: bool) {
+        let Some(host) = self.get_current_docker_host().cloned() else {
+            return;
+        };
+        let checks: Vec<(&str, &DeploymentScript)> = self
+            .containers
+            .iter()
+            .filter(|c| container.as_ref().is_none_or(|name| *name == c.name))
+            .filter_map(|c| Some((c.name.as_str(), self.get_script_for_container(&c.name)?)))
+            .collect();
+        if checks.is_empty() {
+            if show {
+                self.set_error("No loaded script to compare with - press [e] to load it");
+            }
+            return;
+        }
+        let cmd = self.sudo_cmd(&docker::drift_command(&checks));
+        self.pending_docker_commands.push(PendingSshCommand {
+            host,
+            command: cmd,
+            command_type: SshCommandType::CheckDrift { container, show },
+        });
+        if show {
+            self.set_status("Comparing with docker inspect...".to_string());
+        }
+    }
+
+    /// Drift report for the selected container
+    pub fn view_drift(&mut self) {
+        if matches!(self.mode, AppMode::ContainerList { .. }) {
+            if let Some(container) = self.containers.get(self.docker_selected_index) {
+                self.queue_drift_check(Some(container.name.clone()), true);
+            }
+        }
+    }
+
+    /// Drift report for every container on the host that has a script
+    pub fn view_host_drift(&mut self) {
+        if matches!(self.mode, AppMode::ContainerList { .. }) {
+            self.queue_drift_check(None, true);
+        }
+    }
+
+    /// Check the containers of the open drift report again
+    pub fn refresh_drift_report(&mut self) {
+        if let AppMode::DriftReport { container, .. } = &self.mode {
+            let container = container.clone();
+            self.queue_drift_check(container, true);
+        }
+    }
+
+    /// Containers in a drift report with their differences, `None` when the
+    /// container could not be compared
+    pub fn drift_report_rows(&self, container: Option<&str>) -> Vec<(&str, Option<&[DriftItem]>)> {
+        self.containers
+            .iter()
+            .filter(|c| container.is_none_or(|name| name == c.name))
+            .filter(|c| self.get_script_for_container(&c.name).is_some())
+            .map(|c| (c.name.as_str(), self.container_drift.get(&c.name).map(Vec::as_slice)))
+            .collect()
+    }
+
+    /// Get current docker host
+    pub fn get_current_docker_host(&self) -> Option<&Host> {
+        self.current_docker_host_index.and_then(|i| self.hosts.get(i))
+    }
+
+    /// Docker navigation
+    pub fn docker_select_next(&mut self) {
+        if !self.containers.is_empty() && self.docker_selected_index < self.containers.len() - 1 {
+            self.docker_selected_index += 1;
+        }
+    }
+
+    pub fn docker_select_previous(&mut self) {
+        if self.docker_selected_index > 0 {
+            self.docker_selected_index -= 1;
+        }
+    }
+
+    pub fn docker_select_first(&mut self) {
+        self.docker_selected_index = 0;
+    }
+
+    pub fn docker_select_last(&mut self) {
+        if !self.containers.is_empty() {
+            self.docker_selected_index = self.containers.len() - 1;
+        }
+    }
+
+    pub fn docker_page_down(&mut self) {
+        if !self.containers.is_empty() {
+            self.docker_selected_index = (self.docker_selected_index + 10).min(self.containers.len() - 1);
+        }
+    }
+
+    pub fn docker_page_up(&mut self) {
+        self.docker_selected_index = self.docker_selected_index.saturating_sub(10);
+    }
+
+    // Docker operations
+    pub fn docker_pull(&mut self) {
+        if let AppMode::ContainerList { host_index } = self.mode {
+            if self.docker_selected_index < self.containers.len() {
+                let container = &self.containers[self.docker_selected_index];
+                let current_mode = self.mode.clone();
+                self.mode = AppMode::ConfirmDockerAction {
+                    action: DockerPendingAction::DockerPull {
+                        host_index,
+                        container_id: container.id.clone(),
+                        container_name: container.name.clone(),
+                        image_name: container.image.clone(),
+                    },
+                    return_mode: Box::new(current_mode),
+                };
+            }
+        }
+    }
+
+    pub fn docker_restart(&mut self) {
+        if let AppMode::ContainerList { host_index } = self.mode {
+            if self.docker_selected_index < self.containers.len() {
+                let container = &self.containers[self.docker_selected_index];
+                let current_mode = self.mode.clone();
+                self.mode = AppMode::ConfirmDockerAction {
+                    action: DockerPendingAction::DockerRestart {
+                        host_index,
+                        container_id: container.id.clone(),
+                        container_name: container.name.clone(),
+                    },
+                    return_mode: Box::new(current_mode),
+                };
+            }
+        }
+    }
+
+    pub fn docker_stop(&mut self) {
+        if let AppMode::ContainerList { host_index } = self.mode {
+            if self.docker_selected_index < self.containers.len() {
+                let container = &self.containers[self.docker_selected_index];
+                let current_mode = self.mode.clone();
+                self.mode = AppMode::ConfirmDockerAction {
+                    action: DockerPendingAction::DockerStop {
+                        host_index,
+                        container_id: container.id.clone(),
+                        container_name: container.name.clone(),
+                    },
+                    return_mode: Box::new(current_mode),
+                };
+            }
+        }
+    }
+
+    pub fn docker_start(&mut self) {
+        if let AppMode::ContainerList { host_index } = self.mode {
+            if self.docker_selected_index < self.containers.len() {
+                let container = &self.containers[self.docker_selected_index];
+                let current_mode = self.mode.clone();
+                self.mode = AppMode::ConfirmDockerAction {
+                    action: DockerPendingAction::DockerStart {
+                        host_index,
+                        container_id: container.id.clone(),
+                        container_name: container.name.clone(),
+                    },
+                    return_mode: Box::new(current_mode),
+                };
+            }
+        }
+    }
+
+    pub fn docker_remove(&mut self, remove_volumes: bool, remove_image: bool) {
+        if let AppMode::ContainerList { host_index } = self.mode {
+            if self.docker_selected_index < self.containers.len() {
+                let container = &self.containers[self.docker_selected_index];
+                let current_mode = self.mode.clone();
+                self.mode = AppMode::ConfirmDockerAction {
+                    action: DockerPendingAction::DockerRemove {
+                        host_index,
+                        container_id: container.id.clone(),
+                        container_name: container.name.clone(),
+                        image_name: container.image.clone(),
+                        remove_volumes,
+                        remove_image,
+                    },
+                    return_mode: Box::new(current_mode),
+                };
             }
-            SshCommandType::ContainerStats { container_index } => {
-                let stats = docker::parser::parse_docker_stats(&output);
-                if let Some(host_index) = self.current_docker_host_index {
-                    self.mode = AppMode::StatsViewer {
+        }
+    }
+
+    pub fn view_logs(&mut self) {
+        if let AppMode::ContainerList { host_index } = self.mode {
+            if self.docker_selected_index < self.containers.len() {
+                let container = &self.containers[self.docker_selected_index];
+
+                if let Some(host) = self.hosts.get(host_index).cloned() {
+                    let cmd = self.docker_cmd(&docker::docker_logs_command(&container.name, Some(100), false));
+                    self.pending_ssh_command = Some(PendingSshCommand {
+                        host,
+                        command: cmd,
+                        command_type: SshCommandType::ViewLogs,
+                    });
+
+                    self.mode = AppMode::LogsViewer {
                         host_index,
-                        container_index,
-                        stats,
+                        container_index: self.docker_selected_index,
+                        log_buffer: Vec::new(),
+                        follow_mode: false,
+                        scroll_offset: 0,
+                        tail_count: 100,
                     };
                 }
             }
-            SshCommandType::ContainerTop { container_index } => {
-                let processes = docker::parser::parse_docker_top(&output);
-                if let Some(host_index) = self.current_docker_host_index {
-                    self.mode = AppMode::ProcessViewer {
+        }
+    }
+
+    /// Load more log lines (increase tail count)
+    pub fn load_more_logs(&mut self) {
+        if let AppMode::LogsViewer { host_index, container_index, tail_count, .. } = self.mode {
+            if container_index < self.containers.len() {
+                let container = &self.containers[container_index];
+
+                if let Some(host) = self.hosts.get(host_index).cloned() {
+                    // Progressive increase: 100 -> 500 -> 2000 -> 10000 -> 50000
+                    let new_tail_count = match tail_count {
+                        t if t < 500 => 500,
+                        t if t < 2000 => 2000,
+                        t if t < 10000 => 10000,
+                        _ => 50000,
+                    };
+
+                    let cmd = self.docker_cmd(&docker::docker_logs_command(&container.name, Some(new_tail_count), false));
+                    self.pending_ssh_command = Some(PendingSshCommand {
+                        host,
+                        command: cmd,
+                        command_type: SshCommandType::ViewLogs,
+                    });
+
+                    self.mode = AppMode::LogsViewer {
                         host_index,
                         container_index,
-                        processes,
-                        selected_index: 0,
+                        log_buffer: Vec::new(),  // Clear buffer, will be replaced
+                        follow_mode: false,
+                        scroll_offset: 0,
+                        tail_count: new_tail_count,
                     };
+
+                    self.set_status(format!("Loading last {} lines...", new_tail_count));
                 }
             }
-            SshCommandType::ContainerInspect { container_index } => {
-                let info = docker::parser::parse_docker_inspect(&output);
-                if let Some(host_index) = self.current_docker_host_index {
-                    self.mode = AppMode::InspectViewer {
-                        host_index,
-                        container_index,
-                        info,
-                        selected_section: 0,
-                    };
+        }
+    }
+
+    pub fn view_stats(&mut self) {
+        if let AppMode::ContainerList { host_index } = self.mode {
+            if self.docker_selected_index < self.containers.len() {
+                let container = &self.containers[self.docker_selected_index];
+
+                if let Some(host) = self.hosts.get(host_index).cloned() {
+                    let cmd = self.docker_cmd(&docker::docker_stats_command(&container.name));
+                    self.pending_ssh_command = Some(PendingSshCommand {
+                        host,
+                        command: cmd,
+                        command_type: SshCommandType::ContainerStats { container_index: self.docker_selected_index },
+                    });
                 }
             }
-            SshCommandType::InspectContainerEnv { container_index } => {
-                let container_vars: Vec<(String, String)> = output
-                    .lines()
-                    .filter_map(|line| {
-                        let line = line.trim();
-                        line.find('=').map(|pos| {
-                            let key = line[..pos].to_string();
-                            let value = line[pos + 1..].to_string();
-                            (key, value)
-                        })
-                    })
-                    .collect();
+        }
+    }
 
-                let var_count = container_vars.len();
+    pub fn view_processes(&mut self) {
+        if let AppMode::ContainerList { host_index } = self.mode {
+            if self.docker_selected_index < self.containers.len() {
+                let container = &self.containers[self.docker_selected_index];
 
-                // Get script vars if available
-                let script_vars: Vec<(String, String)> = if container_index < self.containers.len() {
-                    let container_name = &self.containers[container_index].name;
-                    self.get_script_for_container(container_name)
-                        .map(|s| s.env_vars.iter().map(|e| (e.key.clone(), e.value.clone())).collect())
-                        .unwrap_or_default()
-                } else {
-                    Vec::new()
-                };
+                if let Some(host) = self.hosts.get(host_index).cloned() {
+                    let cmd = self.docker_cmd(&docker::docker_top_command(&container.name));
+                    self.pending_ssh_command = Some(PendingSshCommand {
+                        host,
+                        command: cmd,
+                        command_type: SshCommandType::ContainerTop { container_index: self.docker_selected_index },
+                    });
+                }
+            }
+        }
+    }
 
-                if let Some(host_index) = self.current_docker_host_index {
-                    self.mode = AppMode::EnvInspector {
-                        host_index,
-                        container_index,
-                        script_vars,
-                        container_vars,
-                        selected_index: 0,
-                        scroll_offset: 0,
-                        search_query: String::new(),
-                    };
-                    self.set_status(format!("Found {} environment variables. Type to search.", var_count));
+    pub fn view_inspect(&mut self) {
+        if let AppMode::ContainerList { host_index } = self.mode {
+            if self.docker_selected_index < self.containers.len() {
+                let container = &self.containers[self.docker_selected_index];
+
+                if let Some(host) = self.hosts.get(host_index).cloned() {
+                    let cmd = self.docker_cmd(&docker::docker_inspect_command(&container.name));
+                    self.pending_ssh_command = Some(PendingSshCommand {
+                        host,
+                        command: cmd,
+                        command_type: SshCommandType::ContainerInspect { container_index: self.docker_selected_index },
+                    });
                 }
             }
-            SshCommandType::ViewScriptContent { script_path, container_index } => {
-                let lines: Vec<String> = output.lines().map(|l| l.to_string()).collect();
-                if let Some(host_index) = self.current_docker_host_index {
-                    self.mode = AppMode::ScriptViewer {
-                        host_index,
-                        container_index,
-                        script_path,
-                        script_content: lines,
-                        scroll_offset: 0,
-                    };
+        }
+    }
+
+    pub fn inspect_env(&mut self) {
+        if let AppMode::ContainerList { host_index } = self.mode {
+            if self.docker_selected_index < self.containers.len() {
+                let container = &self.containers[self.docker_selected_index];
+
+                if let Some(host) = self.hosts.get(host_index).cloned() {
+                    let cmd = self.docker_cmd(&docker::docker_exec_env_command(&container.name));
+                    self.pending_ssh_command = Some(PendingSshCommand {
+                        host,
+                        command: cmd,
+                        command_type: SshCommandType::InspectContainerEnv { container_index: self.docker_selected_index },
+                    });
                 }
             }
-            SshCommandType::ListDirectory { path } => {
-                let entries = docker::parser::parse_directory_listing(&output, &path);
-                if let AppMode::FileBrowser { entries: ref mut e, loading, current_path, .. } = &mut self.mode {
-                    *e = entries;
-                    *loading = false;
-                    *current_path = path;
-                }
+        }
+    }
+
+    /// Open an interactive shell in the selected container
+    pub fn open_container_shell(&mut self, user: Option<String>) {
+        let (host_index, container_index) = match self.mode {
+            AppMode::ContainerList { host_index } => (host_index, self.docker_selected_index),
+            AppMode::ExecUserPrompt { host_index, container_index, .. } => (host_index, container_index),
+            _ => return,
+        };
+
+        self.mode = AppMode::ContainerList { host_index };
+
+        if let Some(container) = self.containers.get(container_index) {
+            if !container.status.is_running() {
+                self.set_error(format!("Container '{}' is not running", container.name));
+                return;
+            }
+
+            if let Some(host) = self.hosts.get(host_index).cloned() {
+                let user = user.filter(|u| !u.is_empty());
+                let command = self.container_shell_command(&container.name, user.as_deref());
+                let label = match user {
+                    Some(ref user) => format!("shell in '{}' (as {})", container.name, user),
+                    None => format!("shell in '{}'", container.name),
+                };
+                self.pending_interactive = Some(PendingInteractiveCommand { host, command, label });
+            }
+        }
+    }
+
+    /// Ask which user to open the container shell as
+    pub fn start_exec_user_prompt(&mut self) {
+        if let AppMode::ContainerList { host_index } = self.mode {
+            if self.docker_selected_index < self.containers.len() {
+                self.mode = AppMode::ExecUserPrompt {
+                    host_index,
+                    container_index: self.docker_selected_index,
+                    user_buffer: String::new(),
+                };
+            }
+        }
+    }
+
+    /// Build the remote command that picks the first available shell (bash, sh, ash)
+    /// and replaces itself with an interactive `docker exec` into it
+    fn container_shell_command(&self, container: &str, user: Option<&str>) -> String {
+        let attempts: Vec<String> = docker::CONTAINER_SHELLS
+            .iter()
+            .map(|shell| {
+                format!(
+                    "{} && exec {}",
+                    self.docker_cmd(&docker::docker_exec_probe_command(container, user, shell)),
+                    self.docker_cmd(&docker::docker_exec_shell_command(container, user, shell))
+                )
+            })
+            .collect();
+
+        format!(
+            "{} || {{ echo 'No shell (bash, sh, ash) found in {}' >&2; exit 127; }}",
+            attempts.join(" || "),
+            container
+        )
+    }
+
+    /// Open the image list for the current docker host
+    pub fn view_images(&mut self) {
+        if let AppMode::ContainerList { host_index } = self.mode {
+            self.mode = AppMode::ImageList {
+                host_index,
+                images: Vec::new(),
+                selected_index: 0,
+            };
+            self.refresh_images();
+        }
+    }
+
+    /// Re-fetch `docker images` (container usage comes from the container list)
+    pub fn refresh_images(&mut self) {
+        if let AppMode::ImageList { host_index, .. } = self.mode {
+            if let Some(host) = self.hosts.get(host_index).cloned() {
+                let cmd = self.docker_cmd(&docker::docker_images_command());
+                self.pending_ssh_command = Some(PendingSshCommand {
+                    host,
+                    command: cmd,
+                    command_type: SshCommandType::ListImages,
+                });
+                self.set_status("Loading images...".to_string());
+            }
+        }
+    }
+
+    /// Ask to act on the selected image (pull, remove or prune)
+    fn confirm_image_action(&mut self, build: impl FnOnce(usize, &crate::models::DockerImage) -> Option<DockerPendingAction>) {
+        if let AppMode::ImageList { host_index, ref images, selected_index } = self.mode {
+            if let Some(action) = images.get(selected_index).and_then(|image| build(host_index, image)) {
+                let current_mode = self.mode.clone();
+                self.mode = AppMode::ConfirmDockerAction {
+                    action,
+                    return_mode: Box::new(current_mode),
+                };
+            }
+        }
+    }
+
+    pub fn image_pull(&mut self) {
+        if let AppMode::ImageList { ref images, selected_index, .. } = self.mode {
+            if images.get(selected_index).is_some_and(|i| i.repository == "<none>" || i.tag == "<none>") {
+                self.set_error("Image has no tag to pull");
+                return;
             }
-            SshCommandType::ReadScriptForContainer { script_path, container_index } => {
-                if let Some(script) = docker::create_script_from_content(&script_path, &output, "manual") {
-                    // Update the container's script_path
-                    if container_index < self.containers.len() {
-                        self.containers[container_index].script_path = Some(script_path.clone());
+        }
+        self.confirm_image_action(|host_index, image| {
+            Some(DockerPendingAction::ImagePull { host_index, reference: image.reference() })
+        });
+    }
 
-                        // Save the association to metadata for persistence
-                        if let Some(host) = self.get_current_docker_host() {
-                            let host_name = host.host.clone();
-                            let container_name = self.containers[container_index].name.clone();
-                            self.metadata.set_script_path(&host_name, &container_name, script_path.clone());
-                            let _ = save_metadata(&self.metadata_path, &self.metadata);
-                        }
-                    }
+    pub fn image_remove(&mut self) {
+        self.confirm_image_action(|host_index, image| {
+            Some(DockerPendingAction::ImageRemove {
+                host_index,
+                reference: image.reference(),
+                used_by: image.used_by.clone(),
+            })
+        });
+    }
 
-                    // Add to scripts list if not already present
-                    if !self.scripts.iter().any(|s| s.path == script_path) {
-                        self.scripts.push(script.clone());
-                    }
+    pub fn image_prune(&mut self) {
+        if let AppMode::ImageList { host_index, .. } = self.mode {
+            let current_mode = self.mode.clone();
+            self.mode = AppMode::ConfirmDockerAction {
+                action: DockerPendingAction::ImagePrune { host_index },
+                return_mode: Box::new(current_mode),
+            };
+        }
+    }
 
-                    // Switch to script edit mode
-                    if let Some(host_index) = self.current_docker_host_index {
-                        let original_env_vars = script.env_vars.clone();
-                        self.mode = AppMode::ScriptEdit {
-                            host_index,
-                            container_index,
-                            editing_script: script,
-                            focused_section: ScriptSection::EnvVars,
-                            selected_index: 0,
-                            editing_mode: false,
-                            original_env_vars,
-                        };
-                        self.set_status("Script loaded and saved. Make changes and press Ctrl+S to save.".to_string());
-                    }
-                } else {
-                    self.set_error("Failed to parse script file".to_string());
-                    // Return to container list
-                    if let Some(host_index) = self.current_docker_host_index {
-                        self.mode = AppMode::ContainerList { host_index };
-                    }
+    /// Compare the selected image's digest with the registry's copy of its tag
+    pub fn check_image_update(&mut self) {
+        if let AppMode::ImageList { host_index, ref images, selected_index } = self.mode {
+            if let Some(image) = images.get(selected_index) {
+                if image.repository == "<none>" || image.tag == "<none>" {
+                    self.set_error("Only tagged images can be checked for updates");
+                    return;
+                }
+
+                let reference = image.reference();
+                let image_id = image.id.clone();
+                if let Some(host) = self.hosts.get(host_index).cloned() {
+                    let cmd = format!(
+                        "{}; echo '{}'; {} || true",
+                        self.docker_cmd(&docker::docker_image_digests_command(&reference)),
+                        docker::IMAGE_DIGEST_SEPARATOR,
+                        self.docker_cmd(&docker::docker_remote_digest_command(&reference))
+                    );
+                    self.pending_ssh_command = Some(PendingSshCommand {
+                        host,
+                        command: cmd,
+                        command_type: SshCommandType::CheckImageUpdate { image_id },
+                    });
+                    self.set_status(format!("Checking registry for {}...", reference));
                 }
             }
-            SshCommandType::WriteScript { script_path } => {
-                self.set_status(format!("Script saved: {}", script_path));
-            }
-            SshCommandType::RunScript => {
-                self.set_status("Script executed".to_string());
-                // Refresh after script run
-                self.refresh_containers();
+        }
+    }
+
+    /// Open the compose project list for the current docker host
+    pub fn view_compose_projects(&mut self) {
+        if let Some(host_index) = self.current_docker_host_index {
+            self.mode = AppMode::ComposeProjects {
+                host_index,
+                projects: Vec::new(),
+                selected_index: 0,
+            };
+            self.refresh_compose_projects();
+        }
+    }
+
+    /// Re-fetch deployed compose projects and compose files under the clients path
+    pub fn refresh_compose_projects(&mut self) {
+        if let AppMode::ComposeProjects { host_index, .. } = self.mode {
+            if let Some(host) = self.hosts.get(host_index).cloned() {
+                let cmd = format!(
+                    "{}; echo '{}'; {}",
+                    self.docker_cmd(&docker::docker_compose_ls_command()),
+                    docker::SECTION_SEPARATOR,
+                    self.sudo_cmd(&docker::find_compose_files_command(&host.docker))
+                );
+                self.pending_ssh_command = Some(PendingSshCommand {
+                    host,
+                    command: cmd,
+                    command_type: SshCommandType::ListComposeProjects,
+                });
+                self.set_status("Loading compose projects...".to_string());
             }
-            SshCommandType::RsyncListDirectory { path } => {
-                let entries = docker::parser::parse_directory_listing(&output, &path);
-                if let AppMode::RsyncFileBrowser { entries: ref mut e, loading, current_path, .. } = &mut self.mode {
-                    *e = entries;
-                    *loading = false;
-                    *current_path = path;
+        }
+    }
+
+    /// Ask to run a project-level compose action on the selected project
+    pub fn compose_action(&mut self, action: ComposeAction) {
+        if let AppMode::ComposeProjects { host_index, ref projects, selected_index } = self.mode {
+            if let Some(project) = projects.get(selected_index) {
+                if !project.is_deployed() && matches!(action, ComposeAction::Down | ComposeAction::Restart) {
+                    self.set_error(format!("Compose project '{}' is not deployed", project.name));
+                    return;
                 }
+                let action = DockerPendingAction::Compose {
+                    host_index,
+                    project: project.name.clone(),
+                    config_files: project.config_files.clone(),
+                    action,
+                };
+                self.confirm_resource_action(action);
             }
         }
+    }
 
-        // Process next queued command
-        self.process_next_docker_command();
+    /// Show `docker compose logs` or the effective `docker compose config`
+    pub fn view_compose_output(&mut self, view: ComposeView) {
+        if let AppMode::ComposeProjects { host_index, ref projects, selected_index } = self.mode {
+            if let Some(project) = projects.get(selected_index).cloned() {
+                if let Some(host) = self.hosts.get(host_index).cloned() {
+                    let cmd = self.docker_cmd(&docker::docker_compose_command(&project.name, &project.config_files, view.subcommand()));
+                    self.pending_ssh_command = Some(PendingSshCommand {
+                        host,
+                        command: cmd,
+                        command_type: SshCommandType::ComposeOutput { project: project.name.clone() },
+                    });
+                    self.mode = AppMode::ComposeOutput {
+                        host_index,
+                        project: project.name,
+                        view,
+                        lines: Vec::new(),
+                        scroll_offset: 0,
+                    };
+                }
+            }
+        }
     }
 
-    /// Process next queued docker command
-    fn process_next_docker_command(&mut self) {
-        if self.pending_ssh_command.is_none() && !self.pending_docker_commands.is_empty() {
-            self.pending_ssh_command = Some(self.pending_docker_commands.remove(0));
+    /// Preview the selected container's script as a compose service
+    pub fn export_compose(&mut self) {
+        if let AppMode::ContainerList { host_index } = self.mode {
+            let Some(script_path) = self.containers.get(self.docker_selected_index).and_then(|c| c.script_path.clone()) else {
+                self.set_error("No script for this container");
+                return;
+            };
+            if !self.scripts.iter().any(|s| s.path == script_path) {
+                self.set_error("Script is not loaded yet - press [R] to refresh");
+                return;
+            }
+            self.mode = AppMode::ComposeExport {
+                host_index,
+                script_path,
+                whole_project: false,
+                target_path: String::new(),
+                lines: Vec::new(),
+                scroll_offset: 0,
+            };
+            self.update_compose_export();
         }
     }
 
-    /// Associate containers with scripts
-    fn associate_containers_with_scripts(&mut self) {
-        let host_name = self.get_current_docker_host()
-            .map(|h| h.host.clone())
-            .unwrap_or_default();
+    /// Switch the export between the selected script and its whole project
+    pub fn toggle_compose_export_scope(&mut self) {
+        if let AppMode::ComposeExport { whole_project, scroll_offset, .. } = &mut self.mode {
+            *whole_project = !*whole_project;
+            *scroll_offset = 0;
+        }
+        self.update_compose_export();
+    }
 
-        // Associate containers with their scripts based on saved metadata or NAME variable
-        for container in &mut self.containers {
-            container.script_path = None;
+    /// Regenerate the compose file shown by the export preview
+    fn update_compose_export(&mut self) {
+        if let AppMode::ComposeExport { script_path, whole_project, target_path, lines, .. } = &mut self.mode {
+            let Some(selected) = self.scripts.iter().find(|s| &s.path == script_path) else {
+                return;
+            };
+            let scripts: Vec<DeploymentScript> = if *whole_project {
+                self.scripts.iter().filter(|s| s.client_name == selected.client_name).cloned().collect()
+            } else {
+                vec![selected.clone()]
+            };
+            let dir = script_path.rsplit_once('/').map_or(".", |(dir, _)| dir);
+            *target_path = format!("{}/docker-compose.yml", dir);
+            *lines = docker::scripts_to_compose(&scripts).lines().map(str::to_string).collect();
+        }
+    }
 
-            // First check if there's a saved association in metadata
-            if let Some(saved_path) = self.metadata.get_script_path(&host_name, &container.name) {
-                container.script_path = Some(saved_path);
-                continue;
+    /// Write the previewed compose file next to the script
+    pub fn write_compose_export(&mut self) {
+        if let AppMode::ComposeExport { host_index, target_path, lines, .. } = &self.mode {
+            let host_index = *host_index;
+            let path = target_path.clone();
+            let content = lines.join("\n");
+            if let Some(host) = self.hosts.get(host_index).cloned() {
+                let cmd = self.sudo_cmd(&docker::write_file_command(&path, &content, false));
+                self.pending_ssh_command = Some(PendingSshCommand {
+                    host,
+                    command: cmd,
+                    command_type: SshCommandType::WriteComposeFile { path: path.clone() },
+                });
+                self.mode = AppMode::ContainerList { host_index };
+                self.set_status(format!("Writing {}...", path));
             }
+        }
+    }
 
-            // Then check discovered scripts by matching NAME variable
-            for script in &self.scripts {
-                if script.container_name == container.name {
-                    container.script_path = Some(script.path.clone());
-                    break;
-                }
+    /// Read the selected compose project's services to turn them into scripts
+    pub fn import_compose_project(&mut self) {
+        if let AppMode::ComposeProjects { host_index, ref projects, selected_index } = self.mode {
+            let Some(project) = projects.get(selected_index).cloned() else {
+                return;
+            };
+            let dir = match project.config_files.first() {
+                Some(file) => file.rsplit_once('/').map_or(".", |(dir, _)| dir).to_string(),
+                None => format!("{}/{}", self.clients_path.trim_end_matches('/'), project.name),
+            };
+            if let Some(host) = self.hosts.get(host_index).cloned() {
+                let cmd = self.docker_cmd(&docker::docker_compose_command(&project.name, &project.config_files, "config --format json"));
+                self.pending_ssh_command = Some(PendingSshCommand {
+                    host,
+                    command: cmd,
+                    command_type: SshCommandType::ComposeImport { project: project.name.clone(), dir },
+                });
+                self.set_status(format!("Reading compose config of {}...", project.name));
             }
         }
     }
 
-    /// Get script for a container
-    pub fn get_script_for_container(&self, container_name: &str) -> Option<&DeploymentScript> {
-        // First try to find by container's script_path
-        if let Some(container) = self.containers.iter().find(|c| c.name == container_name) {
-            if let Some(ref script_path) = container.script_path {
-                if let Some(script) = self.scripts.iter().find(|s| &s.path == script_path) {
-                    return Some(script);
-                }
-            }
+    /// Open the script editor on the selected imported service. Saving writes
+    /// the generated script like any new one
+    pub fn edit_imported_script(&mut self) {
+        if let AppMode::ComposeImport { host_index, scripts, selected_index, .. } = &self.mode {
+            let Some(script) = scripts.get(*selected_index).cloned() else {
+                return;
+            };
+            // No container index when the service's container does not exist yet
+            let container_index = self
+                .containers
+                .iter()
+                .position(|c| c.name == script.container_name)
+                .unwrap_or(usize::MAX);
+            self.mode = AppMode::ScriptEdit {
+                host_index: *host_index,
+                container_index,
+                editing_script: script,
+                focused_section: ScriptSection::EnvVars,
+                selected_index: 0,
+                editing_mode: false,
+            };
+            self.set_status("Review the imported service. Press Ctrl+S to save the script.".to_string());
         }
-        // Fall back to matching by script's container_name
-        self.scripts.iter().find(|s| s.container_name == container_name)
     }
 
-    /// Get current docker host
-    pub fn get_current_docker_host(&self) -> Option<&Host> {
-        self.current_docker_host_index.and_then(|i| self.hosts.get(i))
+    /// Leave the compose views, refreshing containers since compose actions change them
+    pub fn compose_go_back(&mut self) {
+        if let Some(host_index) = self.current_docker_host_index {
+            self.mode = AppMode::ContainerList { host_index };
+            self.refresh_containers();
+        }
     }
 
-    /// Docker navigation
-    pub fn docker_select_next(&mut self) {
-        if !self.containers.is_empty() && self.docker_selected_index < self.containers.len() - 1 {
-            self.docker_selected_index += 1;
+    /// Open the volume list for the current docker host
+    pub fn view_volumes(&mut self) {
+        if let Some(host_index) = self.current_docker_host_index {
+            self.mode = AppMode::VolumeList {
+                host_index,
+                volumes: Vec::new(),
+                selected_index: 0,
+            };
+            self.refresh_volumes();
         }
     }
 
-    pub fn docker_select_previous(&mut self) {
-        if self.docker_selected_index > 0 {
-            self.docker_selected_index -= 1;
+    /// Open the network list for the current docker host
+    pub fn view_networks(&mut self) {
+        if let Some(host_index) = self.current_docker_host_index {
+            self.mode = AppMode::NetworkList {
+                host_index,
+                networks: Vec::new(),
+                selected_index: 0,
+            };
+            self.refresh_networks();
         }
     }
 
-    pub fn docker_select_first(&mut self) {
-        self.docker_selected_index = 0;
+    /// Re-fetch volumes with their users and disk usage
+    pub fn refresh_volumes(&mut self) {
+        if let AppMode::VolumeList { host_index, .. } = self.mode {
+            if let Some(host) = self.hosts.get(host_index).cloned() {
+                let cmd = format!(
+                    "{}; echo '{sep}'; {}; echo '{sep}'; {}",
+                    self.docker_cmd(&docker::docker_volume_ls_command()),
+                    self.docker_cmd(&docker::docker_ps_mounts_command()),
+                    self.docker_cmd(&docker::docker_system_df_command()),
+                    sep = docker::SECTION_SEPARATOR
+                );
+                self.pending_ssh_command = Some(PendingSshCommand {
+                    host,
+                    command: cmd,
+                    command_type: SshCommandType::ListVolumes,
+                });
+                self.set_status("Loading volumes...".to_string());
+            }
+        }
     }
 
-    pub fn docker_select_last(&mut self) {
-        if !self.containers.is_empty() {
-            self.docker_selected_index = self.containers.len() - 1;
+    /// Re-fetch networks with their subnets and attached containers
+    pub fn refresh_networks(&mut self) {
+        if let AppMode::NetworkList { host_index, .. } = self.mode {
+            if let Some(host) = self.hosts.get(host_index).cloned() {
+                let cmd = format!(
+                    "{} | {}",
+                    self.docker_cmd(&docker::docker_network_ids_command()),
+                    self.docker_cmd(&docker::docker_network_inspect_stdin_command())
+                );
+                self.pending_ssh_command = Some(PendingSshCommand {
+                    host,
+                    command: cmd,
+                    command_type: SshCommandType::ListNetworks,
+                });
+                self.set_status("Loading networks...".to_string());
+            }
         }
     }
 
-    pub fn docker_page_down(&mut self) {
-        if !self.containers.is_empty() {
-            self.docker_selected_index = (self.docker_selected_index + 10).min(self.containers.len() - 1);
+    /// Refresh whichever host-wide resource list is showing
+    fn refresh_resource_list(&mut self) {
+        match self.mode {
+            AppMode::ImageList { .. } => self.refresh_images(),
+            AppMode::VolumeList { .. } => self.refresh_volumes(),
+            AppMode::NetworkList { .. } => self.refresh_networks(),
+            AppMode::ComposeProjects { .. } => self.refresh_compose_projects(),
+            _ => {}
         }
     }
 
-    pub fn docker_page_up(&mut self) {
-        self.docker_selected_index = self.docker_selected_index.saturating_sub(10);
+    /// Ask for confirmation, coming back to the current view on cancel
+    fn confirm_resource_action(&mut self, action: DockerPendingAction) {
+        let current_mode = self.mode.clone();
+        self.mode = AppMode::ConfirmDockerAction {
+            action,
+            return_mode: Box::new(current_mode),
+        };
     }
 
-    // Docker operations
-    pub fn docker_pull(&mut self) {
-        if let AppMode::ContainerList { host_index } = self.mode {
-            if self.docker_selected_index < self.containers.len() {
-                let container = &self.containers[self.docker_selected_index];
-                let current_mode = self.mode.clone();
-                self.mode = AppMode::ConfirmDockerAction {
-                    action: DockerPendingAction::DockerPull {
-                        host_index,
-                        container_id: container.id.clone(),
-                        container_name: container.name.clone(),
-                        image_name: container.image.clone(),
-                    },
-                    return_mode: Box::new(current_mode),
+    pub fn volume_remove(&mut self) {
+        if let AppMode::VolumeList { host_index, ref volumes, selected_index } = self.mode {
+            if let Some(volume) = volumes.get(selected_index) {
+                let action = DockerPendingAction::VolumeRemove {
+                    host_index,
+                    name: volume.name.clone(),
+                    used_by: volume.used_by.clone(),
                 };
+                self.confirm_resource_action(action);
             }
         }
     }
 
-    pub fn docker_restart(&mut self) {
-        if let AppMode::ContainerList { host_index } = self.mode {
-            if self.docker_selected_index < self.containers.len() {
-                let container = &self.containers[self.docker_selected_index];
-                let current_mode = self.mode.clone();
-                self.mode = AppMode::ConfirmDockerAction {
-                    action: DockerPendingAction::DockerRestart {
-                        host_index,
-                        container_id: container.id.clone(),
-                        container_name: container.name.clone(),
-                    },
-                    return_mode: Box::new(current_mode),
+    pub fn network_remove(&mut self) {
+        if let AppMode::NetworkList { host_index, ref networks, selected_index } = self.mode {
+            if let Some(network) = networks.get(selected_index) {
+                if network.is_builtin() {
+                    self.set_error(format!("'{}' is a built-in network and cannot be removed", network.name));
+                    return;
+                }
+                let action = DockerPendingAction::NetworkRemove {
+                    host_index,
+                    name: network.name.clone(),
+                    containers: network.containers.clone(),
                 };
+                self.confirm_resource_action(action);
             }
         }
     }
 
-    pub fn docker_stop(&mut self) {
-        if let AppMode::ContainerList { host_index } = self.mode {
-            if self.docker_selected_index < self.containers.len() {
-                let container = &self.containers[self.docker_selected_index];
-                let current_mode = self.mode.clone();
-                self.mode = AppMode::ConfirmDockerAction {
-                    action: DockerPendingAction::DockerStop {
-                        host_index,
-                        container_id: container.id.clone(),
-                        container_name: container.name.clone(),
-                    },
-                    return_mode: Box::new(current_mode),
-                };
+    /// Prune unused volumes or networks, depending on the current list
+    pub fn resource_prune(&mut self) {
+        match self.mode {
+            AppMode::VolumeList { host_index, .. } => {
+                self.confirm_resource_action(DockerPendingAction::VolumePrune { host_index });
+            }
+            AppMode::NetworkList { host_index, .. } => {
+                self.confirm_resource_action(DockerPendingAction::NetworkPrune { host_index });
             }
+            _ => {}
         }
     }
 
-    pub fn docker_start(&mut self) {
-        if let AppMode::ContainerList { host_index } = self.mode {
-            if self.docker_selected_index < self.containers.len() {
-                let container = &self.containers[self.docker_selected_index];
-                let current_mode = self.mode.clone();
-                self.mode = AppMode::ConfirmDockerAction {
-                    action: DockerPendingAction::DockerStart {
-                        host_index,
-                        container_id: container.id.clone(),
-                        container_name: container.name.clone(),
-                    },
-                    return_mode: Box::new(current_mode),
-                };
+    /// Ask for the name of a new volume or network
+    pub fn start_create_resource(&mut self) {
+        let (host_index, kind) = match self.mode {
+            AppMode::VolumeList { host_index, .. } => (host_index, DockerResourceKind::Volume),
+            AppMode::NetworkList { host_index, .. } => (host_index, DockerResourceKind::Network),
+            _ => return,
+        };
+        let current_mode = self.mode.clone();
+        self.mode = AppMode::CreateResourcePrompt {
+            host_index,
+            kind,
+            name_buffer: String::new(),
+            return_mode: Box::new(current_mode),
+        };
+    }
+
+    /// Confirm creating the volume or network named in the prompt
+    pub fn submit_create_resource(&mut self) {
+        if let AppMode::CreateResourcePrompt { host_index, kind, ref name_buffer, ref return_mode } = self.mode {
+            let name = name_buffer.trim().to_string();
+            let return_mode = return_mode.clone();
+            if name.is_empty() {
+                self.mode = *return_mode;
+                return;
             }
+
+            let action = match kind {
+                DockerResourceKind::Volume => DockerPendingAction::VolumeCreate { host_index, name },
+                DockerResourceKind::Network => DockerPendingAction::NetworkCreate { host_index, name },
+            };
+            self.mode = AppMode::ConfirmDockerAction { action, return_mode };
         }
     }
 
-    pub fn docker_remove(&mut self, remove_volumes: bool, remove_image: bool) {
-        if let AppMode::ContainerList { host_index } = self.mode {
-            if self.docker_selected_index < self.containers.len() {
-                let container = &self.containers[self.docker_selected_index];
-                let current_mode = self.mode.clone();
-                self.mode = AppMode::ConfirmDockerAction {
-                    action: DockerPendingAction::DockerRemove {
-                        host_index,
-                        container_id: container.id.clone(),
-                        container_name: container.name.clone(),
-                        image_name: container.image.clone(),
-                        remove_volumes,
-                        remove_image,
-                    },
-                    return_mode: Box::new(current_mode),
+    /// Browse the selected volume's contents read-only
+    pub fn browse_volume(&mut self) {
+        if let AppMode::VolumeList { host_index, ref volumes, selected_index } = self.mode {
+            if let Some(volume) = volumes.get(selected_index) {
+                let volume = volume.name.clone();
+                self.mode = AppMode::VolumeBrowser {
+                    host_index,
+                    volume,
+                    current_path: String::new(),
+                    entries: Vec::new(),
+                    selected_index: 0,
+                    loading: true,
+                    preview: None,
+                    preview_scroll: 0,
                 };
+                self.list_volume_directory(String::new());
             }
         }
     }
 
-    pub fn view_logs(&mut self) {
-        if let AppMode::ContainerList { host_index } = self.mode {
-            if self.docker_selected_index < self.containers.len() {
-                let container = &self.containers[self.docker_selected_index];
-
-                if let Some(host) = self.hosts.get(host_index).cloned() {
-                    let cmd = self.docker_cmd(&docker::docker_logs_command(&container.name, Some(100), false));
-                    self.pending_ssh_command = Some(PendingSshCommand {
-                        host,
-                        command: cmd,
-                        command_type: SshCommandType::ViewLogs,
-                    });
-
-                    self.mode = AppMode::LogsViewer {
-                        host_index,
-                        container_index: self.docker_selected_index,
-                        log_buffer: Vec::new(),
-                        follow_mode: false,
-                        scroll_offset: 0,
-                        tail_count: 100,
+    /// Open the selected entry in the volume browser (directory or file preview)
+    pub fn volume_browser_open(&mut self) {
+        if let AppMode::VolumeBrowser { ref current_path, ref entries, selected_index, ref preview, .. } = self.mode {
+            if preview.is_some() {
+                return;
+            }
+            let entry: Option<FileEntry> = entries.get(selected_index).cloned();
+            let current_path = current_path.clone();
+            match entry {
+                Some(entry) if entry.name == ".." => {
+                    let parent = current_path
+                        .rsplit_once('/')
+                        .map(|(parent, _)| parent.to_string())
+                        .unwrap_or_default();
+                    self.list_volume_directory(parent);
+                }
+                Some(entry) => {
+                    let path = if current_path.is_empty() {
+                        entry.name.clone()
+                    } else {
+                        format!("{}/{}", current_path, entry.name)
                     };
+                    if entry.is_dir {
+                        self.list_volume_directory(path);
+                    } else {
+                        self.read_volume_file(path);
+                    }
                 }
+                None => {}
             }
         }
     }
 
-    /// Load more log lines (increase tail count)
-    pub fn load_more_logs(&mut self) {
-        if let AppMode::LogsViewer { host_index, container_index, tail_count, .. } = self.mode {
-            if container_index < self.containers.len() {
-                let container = &self.containers[container_index];
-
-                if let Some(host) = self.hosts.get(host_index).cloned() {
-                    // Progressive increase: 100 -> 500 -> 2000 -> 10000 -> 50000
-                    let new_tail_count = match tail_count {
-                        t if t < 500 => 500,
-                        t if t < 2000 => 2000,
-                        t if t < 10000 => 10000,
-                        _ => 50000,
-                    };
-
-                    let cmd = self.docker_cmd(&docker::docker_logs_command(&container.name, Some(new_tail_count), false));
-                    self.pending_ssh_command = Some(PendingSshCommand {
-                        host,
-                        command: cmd,
-                        command_type: SshCommandType::ViewLogs,
-                    });
-
-                    self.mode = AppMode::LogsViewer {
-                        host_index,
-                        container_index,
-                        log_buffer: Vec::new(),  // Clear buffer, will be replaced
-                        follow_mode: false,
-                        scroll_offset: 0,
-                        tail_count: new_tail_count,
-                    };
+    fn list_volume_directory(&mut self, path: String) {
+        if let AppMode::VolumeBrowser { host_index, ref volume, ref mut current_path, ref mut loading, .. } = self.mode {
+            let volume = volume.clone();
+            *current_path = path.clone();
+            *loading = true;
+            if let Some(host) = self.hosts.get(host_index).cloned() {
+                let cmd = self.docker_cmd(&docker::docker_volume_ls_dir_command(&volume, &path));
+                self.pending_ssh_command = Some(PendingSshCommand {
+                    host,
+                    command: cmd,
+                    command_type: SshCommandType::VolumeListDirectory { path },
+                });
+            }
+        }
+    }
 
-                    self.set_status(format!("Loading last {} lines...", new_tail_count));
-                }
+    fn read_volume_file(&mut self, path: String) {
+        if let AppMode::VolumeBrowser { host_index, ref volume, .. } = self.mode {
+            if let Some(host) = self.hosts.get(host_index).cloned() {
+                let cmd = self.docker_cmd(&docker::docker_volume_cat_command(volume, &path));
+                self.pending_ssh_command = Some(PendingSshCommand {
+                    host,
+                    command: cmd,
+                    command_type: SshCommandType::VolumeReadFile { path },
+                });
             }
         }
     }
 
-    pub fn view_stats(&mut self) {
-        if let AppMode::ContainerList { host_index } = self.mode {
-            if self.docker_selected_index < self.containers.len() {
-                let container = &self.containers[self.docker_selected_index];
+    /// Prompt for a one-off command to run in the selected container
+    pub fn start_exec_command_prompt(&mut self) {
+        let (host_index, container_index) = match self.mode {
+            AppMode::ContainerList { host_index } => (host_index, self.docker_selected_index),
+            AppMode::ExecOutput { host_index, container_index, .. } => (host_index, container_index),
+            _ => return,
+        };
 
-                if let Some(host) = self.hosts.get(host_index).cloned() {
-                    let cmd = self.docker_cmd(&docker::docker_stats_command(&container.name));
-                    self.pending_ssh_command = Some(PendingSshCommand {
-                        host,
-                        command: cmd,
-                        command_type: SshCommandType::ContainerStats { container_index: self.docker_selected_index },
-                    });
-                }
+        if let Some(container) = self.containers.get(container_index) {
+            if !container.status.is_running() {
+                self.set_error(format!("Container '{}' is not running", container.name));
+                return;
             }
+
+            self.mode = AppMode::ExecCommandPrompt {
+                host_index,
+                container_index,
+                command_buffer: String::new(),
+                history_index: None,
+            };
         }
     }
 
-    pub fn view_processes(&mut self) {
-        if let AppMode::ContainerList { host_index } = self.mode {
-            if self.docker_selected_index < self.containers.len() {
-                let container = &self.containers[self.docker_selected_index];
+    /// Command history for a container on the current docker host
+    pub fn exec_history(&self, host_index: usize, container_index: usize) -> Vec<String> {
+        match (self.hosts.get(host_index), self.containers.get(container_index)) {
+            (Some(host), Some(container)) => self.metadata.get_exec_history(&host.host, &container.name),
+            _ => Vec::new(),
+        }
+    }
 
-                if let Some(host) = self.hosts.get(host_index).cloned() {
-                    let cmd = self.docker_cmd(&docker::docker_top_command(&container.name));
-                    self.pending_ssh_command = Some(PendingSshCommand {
-                        host,
-                        command: cmd,
-                        command_type: SshCommandType::ContainerTop { container_index: self.docker_selected_index },
-                    });
+    /// Step through the command history in the exec prompt (older = true for Up)
+    pub fn exec_history_step(&mut self, older: bool) {
+        if let AppMode::ExecCommandPrompt { host_index, container_index, .. } = self.mode {
+            let history = self.exec_history(host_index, container_index);
+            if let AppMode::ExecCommandPrompt { ref mut command_buffer, ref mut history_index, .. } = self.mode {
+                if history.is_empty() {
+                    return;
                 }
+                *history_index = match (*history_index, older) {
+                    (None, true) => Some(history.len() - 1),
+                    (None, false) => None,
+                    (Some(i), true) => Some(i.saturating_sub(1)),
+                    (Some(i), false) if i + 1 < history.len() => Some(i + 1),
+                    (Some(_), false) => None,
+                };
+                *command_buffer = history_index.map(|i| history[i].clone()).unwrap_or_default();
             }
         }
     }
 
-    pub fn view_inspect(&mut self) {
-        if let AppMode::ContainerList { host_index } = self.mode {
-            if self.docker_selected_index < self.containers.len() {
-                let container = &self.containers[self.docker_selected_index];
+    /// Run the command typed in the exec prompt
+    pub fn submit_exec_command(&mut self) {
+        if let AppMode::ExecCommandPrompt { host_index, container_index, ref command_buffer, .. } = self.mode {
+            let command = command_buffer.trim().to_string();
+            if command.is_empty() {
+                self.mode = AppMode::ContainerList { host_index };
+                return;
+            }
 
-                if let Some(host) = self.hosts.get(host_index).cloned() {
-                    let cmd = self.docker_cmd(&docker::docker_inspect_command(&container.name));
-                    self.pending_ssh_command = Some(PendingSshCommand {
-                        host,
-                        command: cmd,
-                        command_type: SshCommandType::ContainerInspect { container_index: self.docker_selected_index },
-                    });
-                }
+            if let (Some(host), Some(container)) = (self.hosts.get(host_index), self.containers.get(container_index)) {
+                self.metadata.push_exec_history(&host.host, &container.name, command.clone());
+                let _ = save_metadata(&self.metadata_path, &self.metadata);
             }
+
+            self.run_exec_command(host_index, container_index, command);
         }
     }
 
-    pub fn inspect_env(&mut self) {
-        if let AppMode::ContainerList { host_index } = self.mode {
-            if self.docker_selected_index < self.containers.len() {
-                let container = &self.containers[self.docker_selected_index];
+    /// Run the command shown in the exec output view again
+    pub fn rerun_exec_command(&mut self) {
+        if let AppMode::ExecOutput { host_index, container_index, ref command, .. } = self.mode {
+            let command = command.clone();
+            self.run_exec_command(host_index, container_index, command);
+        }
+    }
 
-                if let Some(host) = self.hosts.get(host_index).cloned() {
-                    let cmd = self.docker_cmd(&docker::docker_exec_env_command(&container.name));
-                    self.pending_ssh_command = Some(PendingSshCommand {
-                        host,
-                        command: cmd,
-                        command_type: SshCommandType::InspectContainerEnv { container_index: self.docker_selected_index },
-                    });
-                }
-            }
+    fn run_exec_command(&mut self, host_index: usize, container_index: usize, command: String) {
+        if let (Some(host), Some(container)) = (self.hosts.get(host_index).cloned(), self.containers.get(container_index)) {
+            // The marker gets a line of its own even when the output has no final newline
+            let cmd = format!(
+                "{}; printf '\\n%s%d\\n' {} \"$?\"",
+                self.docker_cmd(&docker::docker_exec_oneoff_command(&container.name, &command)),
+                docker::EXEC_EXIT_MARKER
+            );
+            self.set_status(format!("Running in {}: {}", container.name, command));
+            self.pending_ssh_command = Some(PendingSshCommand {
+                host,
+                command: cmd,
+                command_type: SshCommandType::ExecCommand { container_index, command: command.clone() },
+            });
+
+            self.mode = AppMode::ExecOutput {
+                host_index,
+                container_index,
+                command,
+                output: Vec::new(),
+                exit_code: None,
+                scroll_offset: 0,
+            };
         }
     }
 
@@ -1546,7 +3807,6 @@ impl App {
 
                 // First check if script is already loaded in memory
                 if let Some(script) = self.get_script_for_container(&container_name).cloned() {
-                    let original_env_vars = script.env_vars.clone();
                     self.mode = AppMode::ScriptEdit {
                         host_index,
                         container_index: self.docker_selected_index,
@@ -1554,7 +3814,6 @@ impl App {
                         focused_section: ScriptSection::EnvVars,
                         selected_index: 0,
                         editing_mode: false,
-                        original_env_vars,
                     };
                 } else if let Some(script_path) = container.script_path.clone() {
                     // Script path exists but script not loaded - fetch it via SSH
@@ -1571,29 +3830,228 @@ impl App {
                             },
                         });
 
-                        self.set_status("Loading script...".to_string());
-                    }
-                } else {
-                    self.set_error("No script for this container. Press [n] to create one.".to_string());
+                        self.set_status("Loading script...".to_string());
+                    }
+                } else {
+                    self.set_error("No script for this container. Press [n] to create one.".to_string());
+                }
+            }
+        }
+    }
+
+    pub fn run_script(&mut self) {
+        if let AppMode::ContainerList { host_index } = self.mode {
+            if self.docker_selected_index < self.containers.len() {
+                let container = &self.containers[self.docker_selected_index];
+                if let Some(script_path) = container.script_path.clone() {
+                    let script = self.scripts.iter().find(|s| s.path == script_path).cloned();
+                    let lint = match &script {
+                        Some(_) => None,
+                        None => Some(vec![LintIssue::warning("The script is not loaded, so it was not checked")]),
+                    };
+                    let current_mode = self.mode.clone();
+                    self.mode = AppMode::ConfirmDockerAction {
+                        action: DockerPendingAction::RunScript { host_index, script_path, lint },
+                        return_mode: Box::new(current_mode),
+                    };
+                    if let Some(script) = script {
+                        self.queue_script_lint(host_index, script, Vec::new());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Start a deployment script and open the console streaming its output
+    fn start_script_run(&mut self, host_index: usize, script_path: String) {
+        self.mode = AppMode::ContainerList { host_index };
+        if self.active_script_run.is_some() {
+            self.set_error("Another deployment script is still running");
+            return;
+        }
+        let Some(host) = self.hosts.get(host_index).cloned() else {
+            return;
+        };
+
+        let container_name = self
+            .containers
+            .iter()
+            .find(|c| c.script_path.as_deref() == Some(script_path.as_str()))
+            .map(|c| c.name.clone())
+            .or_else(|| self.scripts.iter().find(|s| s.path == script_path).map(|s| s.container_name.clone()))
+            .unwrap_or_default();
+
+        let pid_file = remote_pid_file();
+        let cmd = self.sudo_cmd(&docker::run_script_command(&script_path, &pid_file));
+        match StreamingCommand::spawn(&host, &cmd) {
+            Ok(process) => {
+                self.active_script_run = Some(ActiveScriptRun {
+                    process,
+                    host_index,
+                    container_name: container_name.clone(),
+                    started: Instant::now(),
+                    pid_file,
+                    stopped: false,
+                    run: ScriptRun {
+                        script_path,
+                        started: chrono::Utc::now(),
+                        duration_secs: 0,
+                        exit_code: None,
+                        log: Vec::new(),
+                    },
+                });
+                self.mode = AppMode::ScriptRunConsole {
+                    host_index,
+                    container_name,
+                    run_index: None,
+                    scroll_offset: 0,
+                    follow: true,
+                };
+                self.clear_messages();
+            }
+            Err(e) => self.set_error(format!("Failed to run script: {}", e)),
+        }
+    }
+
+    /// Collect output from the running script; once it exits, record the run
+    /// in the container's history and refresh the container list
+    pub fn poll_script_run(&mut self) {
+        let Some(active) = self.active_script_run.as_mut() else {
+            return;
+        };
+
+        for line in active.process.read_lines() {
+            active.run.log.push(RunLogLine { stderr: line.stderr, text: line.text });
+        }
+        if active.run.log.len() > MAX_RUN_LOG_LINES * 2 {
+            let excess = active.run.log.len() - MAX_RUN_LOG_LINES;
+            active.run.log.drain(..excess);
+        }
+
+        let Some(exit_code) = active.process.try_finish() else {
+            return;
+        };
+        let Some(mut active) = self.active_script_run.take() else {
+            return;
+        };
+        active.run.exit_code = exit_code;
+        active.run.duration_secs = active.started.elapsed().as_secs();
+
+        let status = match exit_code {
+            Some(0) => format!("Script finished in {}s", active.run.duration_secs),
+            _ if active.stopped => "Script was stopped".to_string(),
+            Some(code) => format!("Script failed (exit {}) - see the run console", code),
+            None => "Script was stopped".to_string(),
+        };
+
+        if let Some(host) = self.hosts.get(active.host_index) {
+            let host_name = host.host.clone();
+            self.metadata.push_script_run(&host_name, &active.container_name, active.run);
+            let _ = save_metadata(&self.metadata_path, &self.metadata);
+            let _ = save_run_logs(&self.metadata_path, &self.metadata, &host_name, &active.container_name);
+        }
+
+        if exit_code == Some(0) {
+            self.set_status(status);
+        } else {
+            self.set_error(status);
+        }
+
+        if self.current_docker_host_index == Some(active.host_index) {
+            self.refresh_containers();
+        }
+    }
+
+    /// Stop the running script on the host. Its output keeps streaming until
+    /// it has exited; if the host cannot be told, only the ssh session is closed
+    pub fn cancel_script_run(&mut self) {
+        let Some((host_index, pid_file)) = self.active_script_run.as_ref().map(|a| (a.host_index, a.pid_file.clone())) else {
+            return;
+        };
+        let stop = self.sudo_cmd(&docker::stop_script_command(&pid_file));
+        let result = match self.hosts.get(host_index) {
+            Some(host) => self.execute_ssh_command(host, &stop).map(|_| ()),
+            None => Err(anyhow::anyhow!("host not found")),
+        };
+        if let Some(active) = self.active_script_run.as_mut() {
+            active.stopped = true;
+            match result {
+                Ok(()) => self.set_status("Stopping script...".to_string()),
+                Err(e) => {
+                    active.process.kill();
+                    self.set_error(format!("Could not stop the script on the host, it may still be running: {}", e));
                 }
             }
         }
     }
 
-    pub fn run_script(&mut self) {
+    /// The run shown by the console: an entry of the container's history, or
+    /// for `None` the live run (falling back to the latest finished one).
+    /// Also returns the elapsed seconds for a run still in progress
+    pub fn script_run_for(&self, host_index: usize, container_name: &str, run_index: Option<usize>) -> Option<(&ScriptRun, Option<u64>)> {
+        if run_index.is_none() {
+            if let Some(active) = self
+                .active_script_run
+                .as_ref()
+                .filter(|a| a.host_index == host_index && a.container_name == container_name)
+            {
+                return Some((&active.run, Some(active.started.elapsed().as_secs())));
+            }
+        }
+        let host_name = &self.hosts.get(host_index)?.host;
+        let runs = self.metadata.get_script_runs(host_name, container_name);
+        let run = match run_index {
+            Some(index) => runs.get(index)?,
+            None => runs.last()?,
+        };
+        Some((run, None))
+    }
+
+    /// Rows of the run history, newest first: the live run (None) if there is
+    /// one for this container, then indices into the recorded runs
+    pub fn script_run_rows(&self, host_index: usize, container_name: &str) -> Vec<Option<usize>> {
+        let mut rows = Vec::new();
+        if self
+            .active_script_run
+            .as_ref()
+            .is_some_and(|a| a.host_index == host_index && a.container_name == container_name)
+        {
+            rows.push(None);
+        }
+        if let Some(host) = self.hosts.get(host_index) {
+            let count = self.metadata.get_script_runs(&host.host, container_name).len();
+            rows.extend((0..count).rev().map(Some));
+        }
+        rows
+    }
+
+    /// List past runs of the selected container's deployment script
+    pub fn view_script_runs(&mut self) {
         if let AppMode::ContainerList { host_index } = self.mode {
-            if self.docker_selected_index < self.containers.len() {
-                let container = &self.containers[self.docker_selected_index];
-                if let Some(script_path) = &container.script_path {
-                    let current_mode = self.mode.clone();
-                    self.mode = AppMode::ConfirmDockerAction {
-                        action: DockerPendingAction::RunScript {
-                            host_index,
-                            script_path: script_path.clone(),
-                        },
-                        return_mode: Box::new(current_mode),
-                    };
-                }
+            let Some(container) = self.containers.get(self.docker_selected_index) else {
+                return;
+            };
+            let container_name = container.name.clone();
+            if self.script_run_rows(host_index, &container_name).is_empty() {
+                self.set_status(format!("No script runs recorded for {}", container_name));
+                return;
+            }
+            self.mode = AppMode::ScriptRunHistory { host_index, container_name, selected_index: 0 };
+        }
+    }
+
+    /// Open the console for the selected row of the run history
+    pub fn open_script_run(&mut self) {
+        if let AppMode::ScriptRunHistory { host_index, container_name, selected_index } = &self.mode {
+            let rows = self.script_run_rows(*host_index, container_name);
+            if let Some(run_index) = rows.get(*selected_index).copied() {
+                self.mode = AppMode::ScriptRunConsole {
+                    host_index: *host_index,
+                    container_name: container_name.clone(),
+                    run_index,
+                    scroll_offset: 0,
+                    follow: run_index.is_none(),
+                };
             }
         }
     }
@@ -1632,6 +4090,26 @@ impl App {
         }
     }
 
+    /// List a directory in the script file browser
+    pub fn file_browser_list(&mut self, path: String) {
+        let AppMode::FileBrowser { host_index, .. } = self.mode else {
+            return;
+        };
+        let Some(host) = self.hosts.get(host_index).cloned() else {
+            return;
+        };
+        self.pending_ssh_command = Some(PendingSshCommand {
+            host,
+            command: self.sudo_cmd(&docker::list_directory_command(&path)),
+            command_type: SshCommandType::ListDirectory { path: path.clone() },
+        });
+        if let AppMode::FileBrowser { ref mut current_path, ref mut loading, ref mut selected_index, .. } = self.mode {
+            *current_path = path;
+            *loading = true;
+            *selected_index = 0;
+        }
+    }
+
     /// Create a new script for a container that doesn't have one
     pub fn create_script(&mut self) {
         if let AppMode::ContainerList { host_index } = self.mode {
@@ -1662,7 +4140,6 @@ impl App {
                     focused_section: ScriptSection::EnvVars,
                     selected_index: 0,
                     editing_mode: false,
-                    original_env_vars: Vec::new(), // New script has no original env vars
                 };
 
                 self.set_status("Creating new script. Press Ctrl+S to save.".to_string());
@@ -1670,172 +4147,414 @@ impl App {
         }
     }
 
-    /// Start adding a new env var in script editor
-    pub fn start_add_env_var(&mut self) {
-        if let AppMode::ScriptEdit { host_index, container_index, editing_script, original_env_vars, .. } = &self.mode {
-            self.mode = AppMode::EnvVarEditor {
-                host_index: *host_index,
-                container_index: *container_index,
-                key_buffer: String::new(),
-                value_buffer: String::new(),
-                editing_key: true,
-                is_new: true,
-                editing_script: editing_script.clone(),
-                var_index: None,
-                original_env_vars: original_env_vars.clone(),
+    /// Generate a script for the selected container from `docker inspect`, so
+    /// containers started by hand can be managed like the others
+    pub fn generate_script_from_container(&mut self) {
+        if let AppMode::ContainerList { host_index } = self.mode {
+            let Some(container) = self.containers.get(self.docker_selected_index) else {
+                return;
             };
-        }
-    }
-
-    /// Start editing an existing env var
-    pub fn start_edit_env_var(&mut self) {
-        if let AppMode::ScriptEdit { host_index, container_index, editing_script, selected_index, focused_section, original_env_vars, .. } = &self.mode {
-            if *focused_section != ScriptSection::EnvVars {
+            if container.has_script() {
+                self.set_error("Container already has a script. Press [e] to edit.");
                 return;
             }
-            if *selected_index < editing_script.env_vars.len() {
-                let env = &editing_script.env_vars[*selected_index];
-                self.mode = AppMode::EnvVarEditor {
-                    host_index: *host_index,
-                    container_index: *container_index,
-                    key_buffer: env.key.clone(),
-                    value_buffer: env.value.clone(),
-                    editing_key: false,  // Start on value for editing
-                    is_new: false,
-                    editing_script: editing_script.clone(),
-                    var_index: Some(*selected_index),
-                    original_env_vars: original_env_vars.clone(),
-                };
+            if let Some(host) = self.hosts.get(host_index).cloned() {
+                let name = container.name.clone();
+                let cmd = self.sudo_cmd(&docker::inspect_for_script_command(&name));
+                self.pending_ssh_command = Some(PendingSshCommand {
+                    host,
+                    command: cmd,
+                    command_type: SshCommandType::InspectForScript { container_index: self.docker_selected_index },
+                });
+                self.set_status(format!("Inspecting {}...", name));
             }
         }
     }
 
-    /// Save env var from editor
-    pub fn save_env_var(&mut self) {
-        if let AppMode::EnvVarEditor {
+    /// Open the entry dialog for the focused script editor section. `edit`
+    /// loads the selected entry; the Network section always has exactly one
+    pub fn start_script_entry(&mut self, edit: bool) {
+        if let AppMode::ScriptEdit { host_index, container_index, editing_script, focused_section, selected_index, .. } = &self.mode {
+            let section = *focused_section;
+            let index = *selected_index;
+            let script = editing_script;
+
+            let entry = match section {
+                ScriptSection::EnvVars => script.env_vars.get(index).map(|e| (e.key.clone(), e.value.clone())),
+                ScriptSection::Volumes => script.volumes.get(index).map(|v| {
+                    let ro = if v.read_only { ":ro" } else { "" };
+                    (v.host_path.clone(), format!("{}{}", v.container_path, ro))
+                }),
+                ScriptSection::Ports => script.ports.get(index).map(|p| {
+                    let proto = if p.protocol == "tcp" { String::new() } else { format!("/{}", p.protocol) };
+                    (p.host_address(), format!("{}{}", p.container_port, proto))
+                }),
+                ScriptSection::Network => Some((
+                    script.network.clone().unwrap_or_default(),
+                    script.restart_policy.clone().unwrap_or_default(),
+                )),
+            };
+            let editing = edit || section == ScriptSection::Network;
+            let (key_buffer, value_buffer) = match (editing, entry) {
+                (true, Some(entry)) => entry,
+                (true, None) => return,
+                (false, _) => (String::new(), String::new()),
+            };
+
+            self.mode = AppMode::ScriptEntryEditor {
+                host_index: *host_index,
+                container_index: *container_index,
+                editing_script: script.clone(),
+                section,
+                entry_index: if editing { Some(index) } else { None },
+                key_buffer,
+                value_buffer,
+                // Start on the value when editing an env var, its key rarely changes
+                editing_key: !(editing && section == ScriptSection::EnvVars),
+                is_new: !editing,
+            };
+        }
+    }
+
+    /// Save the entry dialog back into the script being edited
+    pub fn save_script_entry(&mut self) {
+        let AppMode::ScriptEntryEditor {
             host_index,
             container_index,
             mut editing_script,
-            var_index,
+            section,
+            entry_index,
             key_buffer,
             value_buffer,
-            original_env_vars,
             ..
-        } = std::mem::replace(&mut self.mode, AppMode::Table) {
-            if key_buffer.is_empty() {
-                self.set_error("Key cannot be empty");
-                self.mode = AppMode::EnvVarEditor {
-                    host_index,
-                    container_index,
-                    editing_script,
-                    var_index,
-                    key_buffer,
-                    value_buffer,
-                    editing_key: true,
-                    is_new: var_index.is_none(),
-                    original_env_vars,
+        } = self.mode.clone() else {
+            return;
+        };
+
+        let key = key_buffer.trim();
+        let value = value_buffer.trim();
+        let result = match section {
+            ScriptSection::EnvVars if key.is_empty() => Err("Key cannot be empty"),
+            ScriptSection::EnvVars => {
+                let mut env = crate::models::EnvVar::new(key.to_string(), value_buffer.clone());
+                if let Some(existing) = entry_index.and_then(|i| editing_script.env_vars.get(i)) {
+                    env.storage = existing.storage;
+                }
+                Ok(upsert(&mut editing_script.env_vars, entry_index, env))
+            }
+            ScriptSection::Volumes => {
+                let (container_path, read_only) = match value.strip_suffix(":ro") {
+                    Some(path) => (path, true),
+                    None => (value.strip_suffix(":rw").unwrap_or(value), false),
                 };
-                return;
+                if key.is_empty() || !container_path.starts_with('/') {
+                    Err("Both paths are required; the container path must be absolute")
+                } else {
+                    let volume = crate::models::VolumeMount {
+                        host_path: key.to_string(),
+                        container_path: container_path.to_string(),
+                        read_only,
+                    };
+                    Ok(upsert(&mut editing_script.volumes, entry_index, volume))
+                }
             }
-
-            match var_index {
-                Some(idx) => {
-                    // Update existing
-                    if idx < editing_script.env_vars.len() {
-                        editing_script.env_vars[idx].key = key_buffer;
-                        editing_script.env_vars[idx].value = value_buffer;
+            ScriptSection::Ports => {
+                let (container, protocol) = value.split_once('/').unwrap_or((value, "tcp"));
+                // The host side may carry a bind address: `127.0.0.1:8080`
+                let (host_ip, host) = key.rsplit_once(':').unwrap_or(("", key));
+                match (host.parse::<u16>(), container.parse::<u16>()) {
+                    (Ok(host_port), Ok(container_port)) if host_port > 0 && container_port > 0 => {
+                        let port = crate::models::PortMapping {
+                            host_port,
+                            container_port,
+                            protocol: protocol.to_lowercase(),
+                            host_ip: crate::models::PortMapping::parse_host_ip(host_ip),
+                        };
+                        Ok(upsert(&mut editing_script.ports, entry_index, port))
                     }
-                }
-                None => {
-                    // Add new
-                    editing_script.env_vars.push(crate::models::EnvVar::new(key_buffer, value_buffer));
+                    _ => Err("Ports must be numbers between 1 and 65535"),
                 }
             }
+            ScriptSection::Network => {
+                editing_script.network = Some(key.to_string()).filter(|k| !k.is_empty());
+                editing_script.restart_policy = Some(value.to_string()).filter(|v| !v.is_empty());
+                Ok(0)
+            }
+        };
 
-            self.mode = AppMode::ScriptEdit {
-                host_index,
-                container_index,
-                editing_script,
-                focused_section: ScriptSection::EnvVars,
-                selected_index: 0,
-                editing_mode: false,
-                original_env_vars,
-            };
+        match result {
+            Ok(selected_index) => {
+                let inline_secret = match section {
+                    ScriptSection::EnvVars => editing_script
+                        .env_vars
+                        .get(selected_index)
+                        .filter(|e| e.is_secret && e.storage == EnvStorage::Inline && !e.value.is_empty() && !e.value.contains('$'))
+                        .map(|e| e.key.clone()),
+                    _ => None,
+                };
+                self.mode = AppMode::ScriptEdit {
+                    host_index,
+                    container_index,
+                    editing_script,
+                    focused_section: section,
+                    selected_index,
+                    editing_mode: false,
+                };
+                if let Some(key) = inline_secret {
+                    self.set_error(format!("{} looks like a secret and will be saved in plain text - press [m] to move it out of the script", key));
+                }
+            }
+            Err(e) => self.set_error(e),
         }
     }
 
-    /// Cancel env var editing and return to script edit
-    pub fn cancel_env_var_edit(&mut self) {
-        if let AppMode::EnvVarEditor { host_index, container_index, editing_script, original_env_vars, .. } = &self.mode {
+    /// Cancel the entry dialog and return to script edit
+    pub fn cancel_script_entry_edit(&mut self) {
+        if let AppMode::ScriptEntryEditor { host_index, container_index, editing_script, section, entry_index, .. } = &self.mode {
             self.mode = AppMode::ScriptEdit {
                 host_index: *host_index,
                 container_index: *container_index,
                 editing_script: editing_script.clone(),
-                focused_section: ScriptSection::EnvVars,
-                selected_index: 0,
+                focused_section: *section,
+                selected_index: entry_index.unwrap_or(0),
                 editing_mode: false,
-                original_env_vars: original_env_vars.clone(),
             };
         }
     }
 
-    /// Remove env var from current script
-    pub fn remove_env_var_from_current_script(&mut self, index: usize) {
-        if let AppMode::ScriptEdit { editing_script, selected_index, .. } = &mut self.mode {
-            if index < editing_script.env_vars.len() {
-                editing_script.env_vars.remove(index);
-                *selected_index = (*selected_index).min(editing_script.env_vars.len().saturating_sub(1));
+    /// Move the selected env var between the script, the env file and a secret file
+    pub fn cycle_env_storage(&mut self) {
+        if let AppMode::ScriptEdit { editing_script, focused_section: ScriptSection::EnvVars, selected_index, .. } = &mut self.mode {
+            if let Some(env) = editing_script.env_vars.get_mut(*selected_index) {
+                env.storage = env.storage.next();
+                let message = match env.storage {
+                    EnvStorage::Inline => format!("{} stays in the script", env.key),
+                    EnvStorage::EnvFile => format!("{} will move to the 0600 env file on save", env.key),
+                    EnvStorage::SecretFile => format!(
+                        "{} will move to /run/secrets/{} on save, passed as {}_FILE - the image must read *_FILE variables",
+                        env.key,
+                        env.key.to_lowercase(),
+                        env.key
+                    ),
+                };
+                self.set_status(message);
+            }
+        }
+    }
+
+    /// Show or hide the value of the selected secret env var. Every reveal is audited
+    pub fn toggle_secret_reveal(&mut self) {
+        let AppMode::ScriptEdit { host_index, editing_script, focused_section: ScriptSection::EnvVars, selected_index, .. } = &self.mode else {
+            return;
+        };
+        let Some(env) = editing_script.env_vars.get(*selected_index).filter(|e| e.is_secret) else {
+            return;
+        };
+        let entry = (editing_script.path.clone(), env.key.clone());
+        if self.revealed_secrets.remove(&entry) {
+            return;
+        }
+        let Some(host) = self.hosts.get(*host_index).cloned() else {
+            return;
+        };
+        match self.audit(&host, &format!("revealed {} of {}", entry.1, entry.0)) {
+            Ok(()) => {
+                self.revealed_secrets.insert(entry);
             }
+            Err(e) => self.set_error(format!("Not revealed: could not write the audit log ({})", e)),
+        }
+    }
+
+    /// Append a line to the audit log kept next to the metadata file
+    fn audit(&self, host: &Host, message: &str) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let line = format!("{} {} {}\n", chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ"), host.host, message);
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.metadata_path.with_file_name(AUDIT_LOG_FILE))?
+            .write_all(line.as_bytes())
+    }
+
+    /// Remove the selected entry of the focused script editor section
+    pub fn remove_script_entry(&mut self) {
+        if let AppMode::ScriptEdit { editing_script, focused_section, selected_index, .. } = &mut self.mode {
+            let len = match focused_section {
+                ScriptSection::EnvVars => remove_at(&mut editing_script.env_vars, *selected_index),
+                ScriptSection::Volumes => remove_at(&mut editing_script.volumes, *selected_index),
+                ScriptSection::Ports => remove_at(&mut editing_script.ports, *selected_index),
+                ScriptSection::Network => {
+                    editing_script.network = None;
+                    editing_script.restart_policy = None;
+                    1
+                }
+            };
+            *selected_index = (*selected_index).min(len.saturating_sub(1));
         }
     }
 
-    /// Save current script to remote server
+    /// Save current script: show what will change before anything is written
     pub fn save_current_script(&mut self) {
-        if let AppMode::ScriptEdit { host_index, editing_script, container_index, original_env_vars, .. } = &self.mode {
+        if let AppMode::ScriptEdit { host_index, editing_script, container_index, .. } = &self.mode {
+            // Env vars marked for an env file or a secret file leave the script here
+            let notes = docker::secret_file_warnings(editing_script);
+            let (mut script, secrets) = match docker::externalize_secrets(editing_script) {
+                Ok(moved) => moved,
+                Err(e) => {
+                    self.set_error(e);
+                    return;
+                }
+            };
+
+            // Use in-place modification for existing scripts, generate for new ones
+            let new_content = if script.raw_content.is_empty() {
+                // New script - generate from scratch
+                docker::script_parser::generate_script(&script)
+            } else {
+                // Existing script - modify in place to preserve structure
+                docker::apply_script_changes(&script)
+            };
+
+            let path = &script.path;
+            let diff = unified_diff(&script.raw_content, &new_content, path, &format!("{} (new)", path));
+            if diff.is_empty() {
+                self.set_status("No changes to save".to_string());
+                return;
+            }
+
             let host_index = *host_index;
-            let container_index = *container_index;
-            let original_env_vars = original_env_vars.clone();
-            if let Some(host) = self.hosts.get(host_index).cloned() {
-                // Use in-place modification for existing scripts, generate for new ones
-                let new_content = if editing_script.raw_content.is_empty() {
-                    // New script - generate from scratch
-                    docker::script_parser::generate_script(editing_script)
-                } else {
-                    // Existing script - modify in place to preserve structure
-                    docker::apply_script_changes(editing_script, &original_env_vars)
+            script.raw_content = new_content;
+            self.mode = AppMode::ScriptDiff {
+                host_index,
+                container_index: *container_index,
+                script: script.clone(),
+                diff,
+                scroll_offset: 0,
+                lint: None,
+                secrets,
+                return_mode: Box::new(self.mode.clone()),
+            };
+            self.queue_script_lint(host_index, script, notes);
+        }
+    }
+
+    /// Check a script before it is written or run. The local checks run now and
+    /// travel with the remote ones, whose results fill in the current dialog
+    fn queue_script_lint(&mut self, host_index: usize, script: DeploymentScript, notes: Vec<LintIssue>) {
+        let mut issues = docker::lint_script(&script, &self.containers, &self.scripts);
+        issues.extend(notes);
+        if let Some(host) = self.hosts.get(host_index).cloned() {
+            let cmd = self.sudo_cmd(&docker::lint_command(&script));
+            self.pending_ssh_command = Some(PendingSshCommand {
+                host,
+                command: cmd,
+                command_type: SshCommandType::LintScript { script: Box::new(script), issues },
+            });
+        }
+    }
+
+    /// Write the reviewed script (keeping a backup of the current version on the remote)
+    pub fn confirm_script_write(&mut self) {
+        let AppMode::ScriptDiff { host_index, container_index, script, secrets, .. } = &self.mode else {
+            return;
+        };
+        let (host_index, container_index, updated_script, secrets) = (*host_index, *container_index, script.clone(), secrets.clone());
+        let Some(host) = self.hosts.get(host_index).cloned() else {
+            return;
+        };
+
+        let script_path = updated_script.path.clone();
+        let cmd = if secrets.is_empty() {
+            self.sudo_cmd(&docker::write_script_command(&script_path, &updated_script.raw_content))
+        } else {
+            for secret in &secrets {
+                let message = match secret {
+                    SecretWrite::EnvFileEntry { path, key, .. } => format!("moved {} of {} to {}", key, script_path, path),
+                    SecretWrite::File { path, .. } => format!("moved a secret of {} to {}", script_path, path),
                 };
+                // The write itself matters more than its audit line
+                let _ = self.audit(&host, &message);
+            }
+            self.sudo_cmd(&docker::write_script_with_secrets_command(&script_path, &updated_script.raw_content, &secrets))
+        };
+        self.pending_ssh_command = Some(PendingSshCommand {
+            host,
+            command: cmd,
+            command_type: SshCommandType::WriteScript {
+                script_path: script_path.clone(),
+            },
+        });
 
-                let cmd = self.sudo_cmd(&docker::write_script_command(&editing_script.path, &new_content));
+        // Update local script copy
+        if let Some(script) = self.scripts.iter_mut().find(|s| s.path == script_path) {
+            *script = updated_script;
+        } else {
+            // New script - add to list and associate with container
+            self.scripts.push(updated_script);
+            if container_index < self.containers.len() {
+                self.containers[container_index].script_path = Some(script_path.clone());
+
+                // Save the association to metadata for persistence
+                let host_name = self.hosts[host_index].host.clone();
+                let container_name = self.containers[container_index].name.clone();
+                self.metadata.set_script_path(&host_name, &container_name, script_path);
+                let _ = save_metadata(&self.metadata_path, &self.metadata);
+            }
+        }
+
+        self.mode = AppMode::ContainerList { host_index };
+        self.set_status("Saving script...".to_string());
+    }
+
+    /// Leave the diff review without writing
+    pub fn cancel_script_diff(&mut self) {
+        if let AppMode::ScriptDiff { return_mode, .. } = &self.mode {
+            self.mode = (**return_mode).clone();
+        }
+    }
+
+    /// List the backups kept for the selected container's script
+    pub fn view_script_backups(&mut self) {
+        if let AppMode::ContainerList { host_index } = self.mode {
+            let container_index = self.docker_selected_index;
+            let Some(script_path) = self.containers.get(container_index).and_then(|c| c.script_path.clone()) else {
+                self.set_error("No script for this container");
+                return;
+            };
+            if let Some(host) = self.hosts.get(host_index).cloned() {
+                let cmd = self.sudo_cmd(&docker::list_script_backups_command(&script_path));
                 self.pending_ssh_command = Some(PendingSshCommand {
                     host,
                     command: cmd,
-                    command_type: SshCommandType::WriteScript {
-                        script_path: editing_script.path.clone(),
-                    },
+                    command_type: SshCommandType::ListScriptBackups,
                 });
+                self.mode = AppMode::ScriptBackups {
+                    host_index,
+                    container_index,
+                    script_path,
+                    backups: Vec::new(),
+                    selected_index: 0,
+                    loading: true,
+                };
+            }
+        }
+    }
 
-                // Update local script copy
-                let script_path = editing_script.path.clone();
-                let mut updated_script = editing_script.clone();
-                updated_script.raw_content = new_content.clone();
-                if let Some(script) = self.scripts.iter_mut().find(|s| s.path == script_path) {
-                    *script = updated_script.clone();
-                } else {
-                    // New script - add to list and associate with container
-                    self.scripts.push(updated_script);
-                    if container_index < self.containers.len() {
-                        self.containers[container_index].script_path = Some(script_path.clone());
-
-                        // Save the association to metadata for persistence
-                        let host_name = self.hosts[host_index].host.clone();
-                        let container_name = self.containers[container_index].name.clone();
-                        self.metadata.set_script_path(&host_name, &container_name, script_path);
-                        let _ = save_metadata(&self.metadata_path, &self.metadata);
-                    }
-                }
-
-                self.mode = AppMode::ContainerList { host_index };
-                self.set_status("Saving script...".to_string());
+    /// Fetch the selected backup and the current script to review the restore
+    pub fn preview_script_restore(&mut self) {
+        if let AppMode::ScriptBackups { host_index, script_path, backups, selected_index, loading: false, .. } = &self.mode {
+            let Some(backup_path) = backups.get(*selected_index).cloned() else {
+                return;
+            };
+            if let Some(host) = self.hosts.get(*host_index).cloned() {
+                let cmd = self.sudo_cmd(&docker::read_script_and_backup_command(script_path, &backup_path));
+                self.pending_ssh_command = Some(PendingSshCommand {
+                    host,
+                    command: cmd,
+                    command_type: SshCommandType::ReadScriptBackup { backup_path },
+                });
+                self.set_status("Loading backup...".to_string());
             }
         }
     }
@@ -1918,21 +4637,70 @@ impl App {
                     self.set_status(format!("Removing {}...", container_name));
                 }
             }
-            DockerPendingAction::RunScript { host_index, script_path } => {
-                if let Some(host) = self.hosts.get(host_index).cloned() {
-                    let cmd = self.sudo_cmd(&docker::run_script_command(&script_path));
-                    self.pending_ssh_command = Some(PendingSshCommand {
-                        host,
-                        command: cmd,
-                        command_type: SshCommandType::RunScript,
-                    });
-                    self.mode = AppMode::ContainerList { host_index };
-                    self.set_status(format!("Running script {}...", script_path));
-                }
+            DockerPendingAction::RunScript { host_index, script_path, .. } => {
+                self.start_script_run(host_index, script_path);
+            }
+            DockerPendingAction::ImagePull { host_index, reference } => {
+                let cmd = self.docker_cmd(&docker::docker_pull_command(&reference));
+                self.queue_resource_operation(host_index, cmd, format!("Pull {}", reference));
+                self.set_status(format!("Pulling {}...", reference));
+            }
+            DockerPendingAction::ImageRemove { host_index, reference, .. } => {
+                let cmd = self.docker_cmd(&docker::docker_rmi_image_command(&reference));
+                self.queue_resource_operation(host_index, cmd, format!("Remove {}", reference));
+                self.set_status(format!("Removing {}...", reference));
+            }
+            DockerPendingAction::ImagePrune { host_index } => {
+                let cmd = self.docker_cmd(&docker::docker_image_prune_command());
+                self.queue_resource_operation(host_index, cmd, "Prune dangling images".to_string());
+                self.set_status("Pruning dangling images...".to_string());
+            }
+            DockerPendingAction::VolumeCreate { host_index, name } => {
+                let cmd = self.docker_cmd(&docker::docker_volume_create_command(&name));
+                self.queue_resource_operation(host_index, cmd, format!("Create volume {}", name));
+            }
+            DockerPendingAction::VolumeRemove { host_index, name, .. } => {
+                let cmd = self.docker_cmd(&docker::docker_volume_rm_command(&name));
+                self.queue_resource_operation(host_index, cmd, format!("Remove volume {}", name));
+            }
+            DockerPendingAction::VolumePrune { host_index } => {
+                let cmd = self.docker_cmd(&docker::docker_volume_prune_command());
+                self.queue_resource_operation(host_index, cmd, "Prune volumes".to_string());
+            }
+            DockerPendingAction::NetworkCreate { host_index, name } => {
+                let cmd = self.docker_cmd(&docker::docker_network_create_command(&name));
+                self.queue_resource_operation(host_index, cmd, format!("Create network {}", name));
+            }
+            DockerPendingAction::NetworkRemove { host_index, name, .. } => {
+                let cmd = self.docker_cmd(&docker::docker_network_rm_command(&name));
+                self.queue_resource_operation(host_index, cmd, format!("Remove network {}", name));
+            }
+            DockerPendingAction::NetworkPrune { host_index } => {
+                let cmd = self.docker_cmd(&docker::docker_network_prune_command());
+                self.queue_resource_operation(host_index, cmd, "Prune networks".to_string());
+            }
+            DockerPendingAction::Compose { host_index, project, config_files, action } => {
+                let cmd = self.docker_cmd(&docker::docker_compose_command(&project, &config_files, action.subcommand()));
+                self.queue_resource_operation(host_index, cmd, format!("Compose {} {}", action.label().to_lowercase(), project));
+                self.set_status(format!("Compose {} {}...", action.label().to_lowercase(), project));
             }
         }
     }
 
+    /// Queue an image, volume or network command and go back to the list it was started from
+    fn queue_resource_operation(&mut self, host_index: usize, command: String, operation: String) {
+        if let AppMode::ConfirmDockerAction { ref return_mode, .. } = self.mode {
+            self.mode = *return_mode.clone();
+        }
+        if let Some(host) = self.hosts.get(host_index).cloned() {
+            self.pending_ssh_command = Some(PendingSshCommand {
+                host,
+                command,
+                command_type: SshCommandType::ResourceOperation { operation },
+            });
+        }
+    }
+
     /// Cancel a docker action
     pub fn cancel_docker_action(&mut self, return_mode: AppMode) {
         // For sudo dialog, if user says no, still connect without sudo
@@ -1950,6 +4718,7 @@ impl App {
         self.containers.clear();
         self.projects.clear();
         self.scripts.clear();
+        self.container_drift.clear();
         self.current_docker_host_index = None;
         self.docker_selected_index = 0;
         self.use_sudo = false;
@@ -1976,36 +4745,7 @@ impl App {
 
     /// Execute SSH command and return output
     pub fn execute_ssh_command(&self, host: &Host, command: &str) -> Result<String> {
-        let mut ssh_args = vec![
-            "-o".to_string(),
-            "BatchMode=yes".to_string(),
-            "-o".to_string(),
-            "StrictHostKeyChecking=accept-new".to_string(),
-        ];
-
-        // Add port if specified
-        if let Some(port) = host.port {
-            ssh_args.push("-p".to_string());
-            ssh_args.push(port.to_string());
-        }
-
-        // Add identity files if specified
-        if let Some(ref keys) = host.identity_file {
-            for key in keys {
-                ssh_args.push("-i".to_string());
-                ssh_args.push(expand_tilde(key));
-            }
-        }
-
-        // Add user@host
-        let user = host.user.clone().unwrap_or_else(|| "root".to_string());
-        ssh_args.push(format!("{}@{}", user, host.hostname));
-
-        // Add the command to execute
-        ssh_args.push(command.to_string());
-
-        let output = Command::new("ssh")
-            .args(&ssh_args)
+        let output = crate::ssh::executor::remote_command(host, command)
             .stdin(Stdio::null())
             .output()?;
 
@@ -2024,6 +4764,16 @@ impl Default for App {
     }
 }
 
+/// A file on a host to keep the process id of a command started there,
+/// unique to this run of the app
+fn remote_pid_file() -> String {
+    format!(
+        "/tmp/sshing-run-{}-{}.pid",
+        std::process::id(),
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    )
+}
+
 /// Get available SSH keys from ~/.ssh directory
 fn get_available_ssh_keys() -> Vec<String> {
     use std::fs;
@@ -2072,6 +4822,21 @@ fn expand_tilde(path: &str) -> String {
     path.to_string()
 }
 
+/// Local user names by uid, from `/etc/passwd`
+#[cfg(unix)]
+fn local_user_names() -> HashMap<u32, String> {
+    std::fs::read_to_string("/etc/passwd")
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?.parse().ok()?;
+            Some((uid, name.to_string()))
+        })
+        .collect()
+}
+
 /// Capitalize the first letter of a string
 fn capitalize_first(s: &str) -> String {
     let mut chars = s.chars();
@@ -2081,6 +4846,28 @@ fn capitalize_first(s: &str) -> String {
     }
 }
 
+/// Replace the item at `index` or append it; returns where it ended up
+fn upsert<T>(items: &mut Vec<T>, index: Option<usize>, item: T) -> usize {
+    match index.filter(|&i| i < items.len()) {
+        Some(i) => {
+            items[i] = item;
+            i
+        }
+        None => {
+            items.push(item);
+            items.len() - 1
+        }
+    }
+}
+
+/// Remove the item at `index` if present; returns the new length
+fn remove_at<T>(items: &mut Vec<T>, index: usize) -> usize {
+    if index < items.len() {
+        items.remove(index);
+    }
+    items.len()
+}
+
 /// Get the current value of a field as a string
 fn get_field_value(host: &Host, field: &HostField) -> String {
     match field {
@@ -2100,3 +4887,100 @@ fn get_field_value(host: &Host, field: &HostField) -> String {
         HostField::Note => host.note.clone().unwrap_or_default(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use std::time::Duration;
+
+    /// An app on an empty SSH config and metadata file of its own
+    fn test_app(name: &str) -> (App, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("sshing-app-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let app = App::load(&dir.join("config"), dir.join("sshing.json")).unwrap();
+        (app, dir)
+    }
+
+    /// Stand in for rsync with a shell script printing what rsync would
+    fn start_fake_rsync(app: &mut App, script: &str) {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(script);
+        app.rsync_run = Some(RsyncRun {
+            process: StreamingCommand::spawn_local(cmd).unwrap(),
+            transfer: RsyncTransfer {
+                host: Host::new("web".to_string(), "web.example.com".to_string()),
+                source: "./dist/".to_string(),
+                dest: "/srv/app".to_string(),
+                to_host: true,
+                compress: false,
+                options: RsyncOptions::default(),
+                skipped: Vec::new(),
+                profile: None,
+                dest_host: None,
+                relay: false,
+                tool: TransferTool::Rsync,
+            },
+            started: Instant::now(),
+            progress: None,
+            current_file: None,
+            files: 0,
+            file_bytes: 0,
+            errors: Vec::new(),
+            copy_files: Vec::new(),
+            exit_code: None,
+            duration_secs: 0,
+            cancelled: false,
+            pid_file: String::new(),
+        });
+    }
+
+    /// Poll the transfer until `done` holds for it, failing after a few seconds
+    fn poll_until(app: &mut App, done: impl Fn(&RsyncRun) -> bool) {
+        let started = Instant::now();
+        while !app.rsync_run.as_ref().is_some_and(&done) {
+            assert!(started.elapsed() < Duration::from_secs(5), "transfer did not get there");
+            std::thread::sleep(Duration::from_millis(20));
+            app.poll_rsync_run();
+        }
+    }
+
+    #[test]
+    fn test_rsync_run_completes() {
+        let (mut app, dir) = test_app("rsync-done");
+        start_fake_rsync(
+            &mut app,
+            "printf '>f+++++++++ 1536 dist/app.js\\n\\r  1,536 100%%  1.46MB/s  0:00:01 (xfr#1, to-chk=0/2)\\n'; \
+             echo 'rsync: some files vanished' >&2",
+        );
+
+        poll_until(&mut app, |run| run.exit_code.is_some());
+        let run = app.rsync_run.as_ref().unwrap();
+        assert_eq!(run.exit_code, Some(Some(0)));
+        assert_eq!((run.files, run.bytes()), (1, 1536));
+        assert_eq!(run.progress.as_ref().map(|p| p.percent), Some(100));
+        assert_eq!(run.errors, ["rsync: some files vanished"]);
+        assert_eq!(run.current_file, None);
+        assert!(app.status_message.as_deref().unwrap().starts_with("Rsync completed: 1 files"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_rsync_run_cancel() {
+        let (mut app, dir) = test_app("rsync-cancel");
+        start_fake_rsync(&mut app, "printf '>f+++++++++ 1536 dist/app.js\\n'; sleep 30");
+        poll_until(&mut app, |run| run.files == 1);
+        assert_eq!(app.rsync_run.as_ref().unwrap().current_file.as_deref(), Some("dist/app.js"));
+
+        app.cancel_rsync_run();
+        assert_eq!(app.status_message.as_deref(), Some("Stopping rsync..."));
+        poll_until(&mut app, |run| run.exit_code.is_some());
+        let run = app.rsync_run.as_ref().unwrap();
+        assert!(run.cancelled);
+        assert_eq!(run.exit_code, Some(None));
+        assert_eq!(app.error_message.as_deref(), Some("Rsync was cancelled"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
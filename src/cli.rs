@@ -0,0 +1,119 @@
+//! Commands run from the command line, without the TUI
+
+use anyhow::{anyhow, bail, Result};
+use std::time::Duration;
+
+use crate::app::App;
+use crate::models::TransferTool;
+use crate::ssh::rsync::{format_size, pick_transfer_tool, rsync_command_line, rsync_dry_run};
+
+const USAGE: &str = "Usage:
+  sshing                                     Start the TUI
+  sshing rsync <host>                        List the host's saved rsync profiles
+  sshing rsync <host> <profile> [--dry-run]  Run a saved rsync profile";
+
+/// Run the command in `args` (without the program name). Returns None when
+/// there is no command and the TUI should start, or the process exit code
+pub fn run(args: &[String]) -> Result<Option<i32>> {
+    match args.first().map(String::as_str) {
+        None => Ok(None),
+        Some("rsync") => {
+            let dry_run = args.iter().any(|a| a == "--dry-run");
+            let rest: Vec<&str> = args[1..].iter().map(String::as_str).filter(|a| *a != "--dry-run").collect();
+            match rest.as_slice() {
+                [host] => list_rsync_profiles(host).map(Some),
+                [host, profile] => run_rsync_profile(host, profile, dry_run).map(Some),
+                _ => usage(),
+            }
+        }
+        Some("-h" | "--help" | "help") => {
+            println!("{}", USAGE);
+            Ok(Some(0))
+        }
+        Some(_) => usage(),
+    }
+}
+
+fn usage() -> Result<Option<i32>> {
+    eprintln!("{}", USAGE);
+    Ok(Some(2))
+}
+
+fn host_index(app: &App, alias: &str) -> Result<usize> {
+    app.hosts
+        .iter()
+        .position(|h| h.host == alias)
+        .ok_or_else(|| anyhow!("No host named '{}'", alias))
+}
+
+fn list_rsync_profiles(alias: &str) -> Result<i32> {
+    let app = App::new()?;
+    let host = &app.hosts[host_index(&app, alias)?];
+    for profile in &host.rsync_profiles {
+        let last_run = match &profile.last_run {
+            None => "never run".to_string(),
+            Some(run) => {
+                let result = match run.exit_code {
+                    Some(0) => "ok".to_string(),
+                    Some(code) => format!("exit {}", code),
+                    None => "stopped".to_string(),
+                };
+                format!("{} {}", run.started.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"), result)
+            }
+        };
+        println!("{}\t{}\t{}", profile.name, rsync_command_line(&profile.transfer(host)), last_run);
+    }
+    Ok(0)
+}
+
+/// Run a profile to the end, printing rsync's errors and a summary.
+/// Exits with rsync's exit code
+fn run_rsync_profile(alias: &str, name: &str, dry_run: bool) -> Result<i32> {
+    let mut app = App::new()?;
+    let index = host_index(&app, alias)?;
+    let host = &app.hosts[index];
+    let mut transfer = host
+        .rsync_profiles
+        .iter()
+        .find(|p| p.name == name)
+        .map(|p| p.transfer(host))
+        .ok_or_else(|| anyhow!("Host '{}' has no rsync profile named '{}'", alias, name))?;
+    if let Err(e) = transfer.options.validate(&transfer.dest) {
+        bail!(e);
+    }
+    transfer.tool = pick_transfer_tool(&transfer, app.rsync_available);
+    if transfer.tool != TransferTool::Rsync {
+        eprintln!("rsync is not available on both ends, copying with {}", transfer.tool.label());
+    }
+
+    if dry_run {
+        let changes = rsync_dry_run(&transfer).map_err(|e| anyhow!("Rsync dry run failed: {}", e))?;
+        for change in &changes {
+            println!("{:<7} {}", change.kind.label(), change.path);
+        }
+        return Ok(0);
+    }
+
+    app.start_rsync_run(index, transfer);
+    if let Some(error) = app.error_message.take() {
+        bail!(error);
+    }
+    while app.rsync_run.as_ref().is_some_and(|run| run.exit_code.is_none()) {
+        std::thread::sleep(Duration::from_millis(100));
+        app.poll_rsync_run();
+    }
+
+    let Some(run) = app.rsync_run.take() else {
+        bail!("Rsync did not start");
+    };
+    for error in &run.errors {
+        eprintln!("{}", error);
+    }
+    let exit_code = run.exit_code.flatten().unwrap_or(1);
+    if exit_code == 0 {
+        println!("{} files, {} in {}s", run.files, format_size(run.bytes()), run.duration_secs);
+    } else if let Some(error) = app.error_message.take() {
+        eprintln!("{}", error);
+    }
+    Ok(exit_code)
+}
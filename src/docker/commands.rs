@@ -1,9 +1,11 @@
-/// Build docker commands for remote execution
+//! Build docker commands for remote execution
+
+use crate::utils::shell::{quote, quote_path};
 
 pub fn docker_ps_command(all: bool) -> String {
     let all_flag = if all { "-a " } else { "" };
     format!(
-        "docker ps {}--format '{{{{.ID}}}}|{{{{.Names}}}}|{{{{.Image}}}}|{{{{.Status}}}}|{{{{.Ports}}}}'",
+        "docker ps {}--format '{{{{.ID}}}}|{{{{.Names}}}}|{{{{.Image}}}}|{{{{.Status}}}}|{{{{.Ports}}}}|{{{{.Label \"com.docker.compose.project\"}}}}'",
         all_flag
     )
 }
@@ -56,6 +58,35 @@ pub fn docker_exec_env_command(container: &str) -> String {
     format!("docker exec {} env", container)
 }
 
+/// Shells tried, in order, when opening an interactive session in a container
+pub const CONTAINER_SHELLS: [&str; 3] = ["bash", "sh", "ash"];
+
+/// Check non-interactively whether a shell can be started in the container
+pub fn docker_exec_probe_command(container: &str, user: Option<&str>, shell: &str) -> String {
+    format!("docker exec {}{} {} -c true >/dev/null 2>&1", exec_user_flag(user), quote(container), shell)
+}
+
+/// Open an interactive shell in the container (needs a tty: `ssh -t`)
+pub fn docker_exec_shell_command(container: &str, user: Option<&str>, shell: &str) -> String {
+    format!("docker exec -it {}{} {}", exec_user_flag(user), quote(container), shell)
+}
+
+/// Marker echoed after an ad-hoc exec so the exit status survives a successful ssh
+pub const EXEC_EXIT_MARKER: &str = "__SSHING_EXIT__";
+
+/// Run a one-off command in the container through `sh -c` (so pipes and globs
+/// are evaluated inside it), without a tty, merging stderr into stdout
+pub fn docker_exec_oneoff_command(container: &str, command: &str) -> String {
+    format!("docker exec {} sh -c {} 2>&1", quote(container), quote(command))
+}
+
+fn exec_user_flag(user: Option<&str>) -> String {
+    match user {
+        Some(user) if !user.is_empty() => format!("-u {} ", quote(user)),
+        _ => String::new(),
+    }
+}
+
 pub fn docker_stats_command(container: &str) -> String {
     // Match dockering's custom format exactly
     format!("docker stats --no-stream --format '{{{{.CPUPerc}}}}|{{{{.MemUsage}}}}|{{{{.MemPerc}}}}|{{{{.NetIO}}}}|{{{{.BlockIO}}}}|{{{{.PIDs}}}}' {}", container)
@@ -70,8 +101,172 @@ pub fn docker_inspect_command(container: &str) -> String {
     format!("docker inspect {}", container)
 }
 
-/// List directory contents for file browser
-/// Match dockering's format: ls -la with tail to skip header
+pub fn docker_images_command() -> String {
+    "docker images --format '{{.ID}}|{{.Repository}}|{{.Tag}}|{{.Size}}|{{.CreatedAt}}'".to_string()
+}
+
+/// Remove an image by reference (`repo:tag` only untags if other tags remain)
+pub fn docker_rmi_image_command(reference: &str) -> String {
+    format!("docker rmi {}", reference)
+}
+
+/// Remove dangling (untagged) images
+pub fn docker_image_prune_command() -> String {
+    "docker image prune -f".to_string()
+}
+
+/// Print the image's local repo digests
+pub fn docker_image_digests_command(reference: &str) -> String {
+    format!("docker image inspect --format '{{{{range .RepoDigests}}}}{{{{println .}}}}{{{{end}}}}' {}", reference)
+}
+
+/// Print the registry's current digest for a tag (needs the buildx plugin)
+pub fn docker_remote_digest_command(reference: &str) -> String {
+    format!("docker buildx imagetools inspect {} 2>&1", reference)
+}
+
+/// Line separating the local and remote halves of the update check output
+pub const IMAGE_DIGEST_SEPARATOR: &str = "__SSHING_REMOTE__";
+
+/// Line separating the sections of multi-command listings
+pub const SECTION_SEPARATOR: &str = "__SSHING_SECTION__";
+
+pub fn docker_volume_ls_command() -> String {
+    "docker volume ls --format '{{.Name}}|{{.Driver}}|{{.Mountpoint}}'".to_string()
+}
+
+/// Container names with the volumes they mount (untruncated, for matching)
+pub fn docker_ps_mounts_command() -> String {
+    "docker ps -a --no-trunc --format '{{.Names}}|{{.Mounts}}'".to_string()
+}
+
+/// Disk usage per image, container and volume
+pub fn docker_system_df_command() -> String {
+    "docker system df -v".to_string()
+}
+
+pub fn docker_volume_create_command(name: &str) -> String {
+    format!("docker volume create {}", quote(name))
+}
+
+pub fn docker_volume_rm_command(name: &str) -> String {
+    format!("docker volume rm {}", quote(name))
+}
+
+/// Remove volumes not used by any container (anonymous ones only on Docker 23+)
+pub fn docker_volume_prune_command() -> String {
+    "docker volume prune -f".to_string()
+}
+
+pub fn docker_network_ids_command() -> String {
+    "docker network ls -q".to_string()
+}
+
+/// Inspect the networks whose IDs arrive on stdin (`... | xargs docker network inspect`)
+pub fn docker_network_inspect_stdin_command() -> String {
+    "xargs docker network inspect --format '{{.Id}}|{{.Name}}|{{.Driver}}|{{.Scope}}|{{range .IPAM.Config}}{{.Subnet}} {{end}}|{{range .Containers}}{{.Name}} {{end}}'".to_string()
+}
+
+pub fn docker_network_create_command(name: &str) -> String {
+    format!("docker network create {}", quote(name))
+}
+
+pub fn docker_network_rm_command(name: &str) -> String {
+    format!("docker network rm {}", quote(name))
+}
+
+pub fn docker_network_prune_command() -> String {
+    "docker network prune -f".to_string()
+}
+
+/// Image used for throwaway containers that read volume contents
+pub const VOLUME_BROWSER_IMAGE: &str = "busybox";
+
+/// Maximum number of bytes read when previewing a file inside a volume
+pub const VOLUME_PREVIEW_BYTES: usize = 256 * 1024;
+
+/// List a directory inside a volume, mounted read-only in a throwaway container
+pub fn docker_volume_ls_dir_command(volume: &str, path: &str) -> String {
+    format!(
+        "docker run --rm --network none -v {}:/volume:ro {} ls -la {} | tail -n +2",
+        quote(volume),
+        VOLUME_BROWSER_IMAGE,
+        quote(&volume_path(path))
+    )
+}
+
+/// Read (the start of) a file inside a volume, mounted read-only in a throwaway container
+pub fn docker_volume_cat_command(volume: &str, path: &str) -> String {
+    format!(
+        "docker run --rm --network none -v {}:/volume:ro {} head -c {} {}",
+        quote(volume),
+        VOLUME_BROWSER_IMAGE,
+        VOLUME_PREVIEW_BYTES,
+        quote(&volume_path(path))
+    )
+}
+
+/// Map a path relative to the volume root to where it is mounted
+fn volume_path(path: &str) -> String {
+    format!("/volume/{}", path.trim_start_matches('/'))
+}
+
+/// Deployed compose projects as JSON (including stopped ones)
+pub fn docker_compose_ls_command() -> String {
+    "docker compose ls -a --format json".to_string()
+}
+
+/// Run a compose subcommand against a project, passing its compose files when known
+pub fn docker_compose_command(project: &str, config_files: &[String], subcommand: &str) -> String {
+    let mut cmd = format!("docker compose -p {}", quote(project));
+    for file in config_files {
+        cmd.push_str(&format!(" -f {}", quote(file)));
+    }
+    cmd.push(' ');
+    cmd.push_str(subcommand);
+    cmd
+}
+
+/// `find -printf` fields for each directory entry, each ended by a NUL so
+/// any file name survives: type, type behind a symlink, size, mtime,
+/// permission bits, owner, symlink target and name
+const LISTING_FORMAT: &str = "%y\\0%Y\\0%s\\0%T@\\0%m\\0%u\\0%l\\0%f\\0";
+
+/// List directory contents for the file browsers. Systems without GNU find
+/// (busybox, BSD) fall back to `ls -la` with its header skipped
 pub fn list_directory_command(path: &str) -> String {
-    format!("ls -la {} 2>/dev/null | tail -n +2", path)
+    format!(
+        "find {}/ -mindepth 1 -maxdepth 1 -printf '{}' 2>/dev/null || ls -la {} 2>/dev/null | tail -n +2",
+        quote_path(path.trim_end_matches('/')),
+        LISTING_FORMAT,
+        quote_path(path)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ComposeAction, ComposeView};
+
+    #[test]
+    fn test_docker_compose_command() {
+        assert_eq!(
+            docker_compose_command("shop", &[], ComposeAction::Up.subcommand()),
+            "docker compose -p shop up -d"
+        );
+        let files = ["/srv/clients/shop/compose.yml".to_string(), "/srv/clients/my shop/compose.prod.yml".to_string()];
+        assert_eq!(
+            docker_compose_command("shop", &files, ComposeAction::Down.subcommand()),
+            "docker compose -p shop -f /srv/clients/shop/compose.yml -f '/srv/clients/my shop/compose.prod.yml' down"
+        );
+        assert_eq!(
+            docker_compose_command("it's", &[], ComposeView::Logs.subcommand()),
+            "docker compose -p 'it'\\''s' logs --no-color --tail 500 2>&1 || true"
+        );
+        assert_eq!(
+            docker_compose_command("wiki", &[], ComposeView::Config.subcommand()),
+            "docker compose -p wiki config 2>&1 || true"
+        );
+        assert_eq!(docker_compose_ls_command(), "docker compose ls -a --format json");
+    }
 }
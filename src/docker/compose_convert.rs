@@ -0,0 +1,440 @@
+//! Conversion between deployment scripts and docker compose services
+
+use serde_json::Value;
+
+use crate::models::{ComposeProject, DeploymentScript, EnvVar, HealthCheck, PortMapping, VolumeMount};
+use crate::utils::shell::quote;
+use super::script_parser::script_image;
+
+/// Networks that are a `network_mode` in compose rather than a network to join
+const NETWORK_MODES: &[&str] = &["host", "bridge", "none"];
+
+/// Render scripts as the services of a `docker-compose.yml`. Networks the
+/// scripts join are declared external, since the scripts expect them to exist
+pub fn scripts_to_compose(scripts: &[DeploymentScript]) -> String {
+    let mut out = String::from("services:\n");
+    let mut networks: Vec<&str> = Vec::new();
+
+    for script in scripts {
+        let name = service_name(script);
+        out.push_str(&format!("  {}:\n", name));
+        if let Some(image) = script_image(script) {
+            out.push_str(&format!("    image: {}\n", yaml_string(&image)));
+        }
+        if !script.container_name.is_empty() {
+            out.push_str(&format!("    container_name: {}\n", yaml_string(&script.container_name)));
+        }
+        out.push_str(&format!(
+            "    restart: {}\n",
+            yaml_string(script.restart_policy.as_deref().unwrap_or("unless-stopped"))
+        ));
+
+        match script.network.as_deref() {
+            Some(mode) if NETWORK_MODES.contains(&mode) || mode.starts_with("container:") => {
+                out.push_str(&format!("    network_mode: {}\n", yaml_string(mode)));
+            }
+            network => {
+                let joined: Vec<&str> = network.into_iter().chain(script.extra_networks.iter().map(String::as_str)).collect();
+                if !joined.is_empty() {
+                    out.push_str("    networks:\n");
+                }
+                for network in joined {
+                    out.push_str(&format!("      - {}\n", yaml_string(network)));
+                    if !networks.contains(&network) {
+                        networks.push(network);
+                    }
+                }
+            }
+        }
+
+        if !script.ports.is_empty() {
+            out.push_str("    ports:\n");
+            for port in &script.ports {
+                out.push_str(&format!("      - \"{}\"\n", port.publish_spec()));
+            }
+        }
+
+        if !script.volumes.is_empty() {
+            out.push_str("    volumes:\n");
+            for volume in &script.volumes {
+                let ro = if volume.read_only { ":ro" } else { "" };
+                let spec = format!("{}:{}{}", volume.host_path, volume.container_path, ro);
+                out.push_str(&format!("      - {}\n", yaml_string(&spec)));
+            }
+        }
+
+        if !script.env_files.is_empty() {
+            out.push_str("    env_file:\n");
+            for env_file in &script.env_files {
+                out.push_str(&format!("      - {}\n", yaml_string(env_file)));
+            }
+        }
+
+        if !script.env_vars.is_empty() {
+            out.push_str("    environment:\n");
+            for env in &script.env_vars {
+                out.push_str(&format!("      {}: {}\n", env.key, yaml_string(&env.value)));
+            }
+        }
+
+        for (key, value) in [
+            ("user", &script.user),
+            ("entrypoint", &script.entrypoint),
+            ("mem_limit", &script.memory),
+            ("cpus", &script.cpus),
+        ] {
+            if let Some(value) = value {
+                out.push_str(&format!("    {}: {}\n", key, yaml_string(value)));
+            }
+        }
+        if !script.command.is_empty() {
+            out.push_str("    command:\n");
+            for arg in &script.command {
+                out.push_str(&format!("      - {}\n", yaml_string(arg)));
+            }
+        }
+        if !script.labels.is_empty() {
+            out.push_str("    labels:\n");
+            for (key, value) in &script.labels {
+                out.push_str(&format!("      {}: {}\n", yaml_string(key), yaml_string(value)));
+            }
+        }
+        if let Some(health) = &script.healthcheck {
+            out.push_str(&format!("    healthcheck:\n      test: [\"CMD-SHELL\", {}]\n", yaml_string(&health.command)));
+            for (key, value) in [("interval", &health.interval), ("timeout", &health.timeout), ("start_period", &health.start_period)] {
+                if let Some(value) = value {
+                    out.push_str(&format!("      {}: {}\n", key, yaml_string(value)));
+                }
+            }
+            if let Some(retries) = health.retries {
+                out.push_str(&format!("      retries: {}\n", retries));
+            }
+        }
+    }
+
+    if !networks.is_empty() {
+        out.push_str("\nnetworks:\n");
+        for network in networks {
+            out.push_str(&format!("  {}:\n    external: true\n", yaml_string(network)));
+        }
+    }
+
+    out
+}
+
+/// Service settings `compose_to_scripts` carries over. `env_file` is already
+/// merged into `environment` by `docker compose config`
+const CONVERTED_KEYS: &[&str] = &[
+    "image", "container_name", "restart", "environment", "env_file", "ports", "volumes", "networks",
+    "network_mode", "command", "entrypoint", "labels", "user", "healthcheck", "mem_limit", "cpus",
+];
+
+/// Services of `docker compose config --format json` as new deployment scripts,
+/// named `start<Service>.sh` in `dir`, along with the settings that have no
+/// equivalent in a script (as `service.key`)
+pub fn compose_to_scripts(json: &str, dir: &str, client_name: &str) -> Result<(Vec<DeploymentScript>, Vec<String>), String> {
+    let config: Value = serde_json::from_str(json.trim()).map_err(|e| format!("Invalid compose config: {}", e))?;
+    let services = config
+        .get("services")
+        .and_then(Value::as_object)
+        .ok_or_else(|| "Compose config has no services".to_string())?;
+    let top_networks = config.get("networks").and_then(Value::as_object);
+    let top_volumes = config.get("volumes").and_then(Value::as_object);
+    // The real name of a network or volume, e.g. `<project>_<key>` or an external name
+    let real_name = |top: Option<&serde_json::Map<String, Value>>, key: &str| {
+        top.and_then(|t| t.get(key)).and_then(|v| str_field(v, "name")).unwrap_or(key).to_string()
+    };
+
+    let mut scripts = Vec::new();
+    let mut skipped = Vec::new();
+    for (name, service) in services {
+        let path = format!("{}/start{}.sh", dir.trim_end_matches('/'), capitalize_first(name));
+        let mut script = DeploymentScript::new(path, client_name.to_string());
+
+        script.container_name = str_field(service, "container_name").unwrap_or(name).to_string();
+        script.repo = str_field(service, "image").unwrap_or_default().to_string();
+        script.restart_policy = str_field(service, "restart").map(str::to_string);
+
+        if let Some(environment) = service.get("environment").and_then(Value::as_object) {
+            for (key, value) in environment {
+                let value = match value {
+                    Value::String(s) => s.clone(),
+                    Value::Null => String::new(),
+                    other => other.to_string(),
+                };
+                script.env_vars.push(EnvVar::new(key.clone(), value));
+            }
+        }
+
+        for port in service.get("ports").and_then(Value::as_array).into_iter().flatten() {
+            let container_port = port.get("target").and_then(Value::as_u64).and_then(|p| u16::try_from(p).ok());
+            // Unpublished ports and ranges have no single host port to map
+            let host_port = str_field(port, "published").and_then(|p| p.parse().ok());
+            if let (Some(host_port), Some(container_port)) = (host_port, container_port) {
+                let protocol = str_field(port, "protocol").unwrap_or("tcp").to_string();
+                let host_ip = str_field(port, "host_ip").and_then(PortMapping::parse_host_ip);
+                script.ports.push(PortMapping { host_port, container_port, protocol, host_ip });
+            }
+        }
+
+        for volume in service.get("volumes").and_then(Value::as_array).into_iter().flatten() {
+            if !matches!(str_field(volume, "type"), Some("bind" | "volume")) {
+                continue;
+            }
+            if let (Some(source), Some(target)) = (str_field(volume, "source"), str_field(volume, "target")) {
+                let source = if str_field(volume, "type") == Some("volume") {
+                    real_name(top_volumes, source)
+                } else {
+                    source.to_string()
+                };
+                script.volumes.push(VolumeMount {
+                    host_path: source,
+                    container_path: target.to_string(),
+                    read_only: volume.get("read_only").and_then(Value::as_bool).unwrap_or(false),
+                });
+            }
+        }
+
+        match str_field(service, "network_mode") {
+            Some(mode) => script.network = Some(mode.to_string()),
+            None => {
+                let mut joined = service
+                    .get("networks")
+                    .and_then(Value::as_object)
+                    .into_iter()
+                    .flat_map(|networks| networks.keys())
+                    .filter(|key| *key != "default")
+                    .map(|key| real_name(top_networks, key));
+                script.network = joined.next();
+                script.extra_networks = joined.collect();
+            }
+        }
+
+        // `docker run` takes the entrypoint's first word; the rest go before the command
+        let mut entrypoint = string_list(service.get("entrypoint")).into_iter();
+        script.entrypoint = entrypoint.next();
+        script.command = entrypoint.chain(string_list(service.get("command"))).collect();
+
+        if let Some(labels) = service.get("labels").and_then(Value::as_object) {
+            for (key, value) in labels {
+                script.labels.push((key.clone(), value.as_str().unwrap_or_default().to_string()));
+            }
+        }
+        script.user = str_field(service, "user").map(str::to_string);
+        script.memory = scalar_field(service, "mem_limit");
+        script.cpus = scalar_field(service, "cpus");
+        script.healthcheck = service.get("healthcheck").and_then(compose_healthcheck);
+
+        skipped.extend(
+            service
+                .as_object()
+                .into_iter()
+                .flat_map(|fields| fields.keys())
+                .filter(|key| !CONVERTED_KEYS.contains(&key.as_str()))
+                .map(|key| format!("{}.{}", name, key)),
+        );
+        scripts.push(script);
+    }
+
+    Ok((scripts, skipped))
+}
+
+/// A compose healthcheck as `--health-*` options. `CMD` arrays are quoted
+/// into a shell command; `NONE` and disabled checks have nothing to carry
+fn compose_healthcheck(health: &Value) -> Option<HealthCheck> {
+    if health.get("disable").and_then(Value::as_bool) == Some(true) {
+        return None;
+    }
+    let test = string_list(health.get("test"));
+    let command = match test.first().map(String::as_str) {
+        Some("CMD-SHELL") => test[1..].join(" "),
+        Some("CMD") => test[1..].iter().map(|arg| quote(arg)).collect::<Vec<_>>().join(" "),
+        Some("NONE") | None => return None,
+        Some(_) if health.get("test").is_some_and(Value::is_string) => test.join(" "),
+        Some(_) => return None,
+    };
+    Some(HealthCheck {
+        command,
+        interval: str_field(health, "interval").map(str::to_string),
+        timeout: str_field(health, "timeout").map(str::to_string),
+        start_period: str_field(health, "start_period").map(str::to_string),
+        retries: health.get("retries").and_then(Value::as_u64).and_then(|r| u32::try_from(r).ok()),
+    })
+}
+
+/// A list of strings, or a single string as a list of one
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(items)) => items.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+        Some(Value::String(s)) => vec![s.clone()],
+        _ => Vec::new(),
+    }
+}
+
+/// A string or number field as text
+fn scalar_field(value: &Value, key: &str) -> Option<String> {
+    match value.get(key)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Compose service key for a script's container
+fn service_name(script: &DeploymentScript) -> String {
+    let name = ComposeProject::name_from_dir(&script.container_name);
+    if name.is_empty() {
+        "app".to_string()
+    } else {
+        name
+    }
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+/// A YAML double-quoted scalar (JSON string syntax is valid YAML)
+fn yaml_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| format!("\"{}\"", value))
+}
+
+fn capitalize_first(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docker::script_parser::parse_script;
+
+    #[test]
+    fn test_scripts_to_compose() {
+        let content = "NAME=web\ndocker run -d --name $NAME --net=proxy --restart always \\\n  -p 8080:80 -p 53:53/udp -v /srv/web:/data:ro -e MODE=\"prod ready\" nginx:1.25\n";
+        let script = parse_script(content, "/srv/web/start.sh", "web");
+
+        assert_eq!(
+            scripts_to_compose(&[script]),
+            "services:
+  web:
+    image: \"nginx:1.25\"
+    container_name: \"web\"
+    restart: \"always\"
+    networks:
+      - \"proxy\"
+    ports:
+      - \"8080:80\"
+      - \"53:53/udp\"
+    volumes:
+      - \"/srv/web:/data:ro\"
+    environment:
+      MODE: \"prod ready\"
+
+networks:
+  \"proxy\":
+    external: true
+"
+        );
+    }
+
+    #[test]
+    fn test_compose_to_scripts() {
+        let json = r#"{
+            "name": "shop",
+            "services": {
+                "api": {
+                    "image": "shop/api:2",
+                    "container_name": "shop-api",
+                    "restart": "unless-stopped",
+                    "environment": {"DB_HOST": "db", "DEBUG": null},
+                    "ports": [
+                        {"mode": "ingress", "target": 8000, "published": "8000", "protocol": "tcp"},
+                        {"mode": "ingress", "target": 9000, "protocol": "tcp"}
+                    ],
+                    "volumes": [
+                        {"type": "bind", "source": "/srv/shop/media", "target": "/media", "read_only": true},
+                        {"type": "tmpfs", "target": "/tmp"}
+                    ],
+                    "networks": {"backend": null, "default": null}
+                }
+            },
+            "networks": {"backend": {"name": "shop_backend"}, "default": {"name": "shop_default"}}
+        }"#;
+
+        let (scripts, skipped) = compose_to_scripts(json, "/srv/shop/", "shop").unwrap();
+        assert_eq!(scripts.len(), 1);
+        assert!(skipped.is_empty());
+        let script = &scripts[0];
+        assert_eq!(script.path, "/srv/shop/startApi.sh");
+        assert_eq!(script.container_name, "shop-api");
+        assert_eq!(script.repo, "shop/api:2");
+        assert_eq!(script.restart_policy.as_deref(), Some("unless-stopped"));
+        assert_eq!(script.env_vars, vec![EnvVar::new("DB_HOST".into(), "db".into()), EnvVar::new("DEBUG".into(), String::new())]);
+        assert_eq!(script.ports, vec![PortMapping { host_port: 8000, container_port: 8000, protocol: "tcp".into(), host_ip: None }]);
+        assert_eq!(script.volumes.len(), 1);
+        assert!(script.volumes[0].read_only);
+        assert_eq!(script.network.as_deref(), Some("shop_backend"));
+
+        assert!(compose_to_scripts("not json", "/srv", "shop").is_err());
+    }
+
+    #[test]
+    fn test_compose_to_scripts_settings() {
+        let json = r#"{
+            "name": "shop",
+            "services": {
+                "db": {
+                    "image": "postgres:16",
+                    "entrypoint": ["docker-entrypoint.sh", "--verbose"],
+                    "command": ["postgres", "-c", "max_connections=200"],
+                    "labels": {"backup": "nightly"},
+                    "user": "999:999",
+                    "healthcheck": {"test": ["CMD", "pg_isready", "-U", "shop user"], "interval": "10s", "retries": 5},
+                    "volumes": [
+                        {"type": "volume", "source": "data", "target": "/var/lib/postgresql/data"},
+                        {"type": "volume", "source": "legacy", "target": "/legacy"},
+                        {"type": "volume", "target": "/anonymous"}
+                    ],
+                    "networks": {"backend": null, "monitoring": null},
+                    "depends_on": {"cache": {"condition": "service_started"}},
+                    "deploy": {"replicas": 2}
+                }
+            },
+            "networks": {"backend": {"name": "shop_backend"}, "monitoring": {"name": "metrics", "external": true}},
+            "volumes": {"data": {"name": "shop_data"}, "legacy": {"name": "old_data", "external": true}}
+        }"#;
+
+        let (scripts, skipped) = compose_to_scripts(json, "/srv/shop", "shop").unwrap();
+        let script = &scripts[0];
+        // Named volumes are mounted by their real, project-prefixed name
+        let sources: Vec<&str> = script.volumes.iter().map(|v| v.host_path.as_str()).collect();
+        assert_eq!(sources, vec!["shop_data", "old_data"]);
+        assert_eq!(script.network.as_deref(), Some("shop_backend"));
+        assert_eq!(script.extra_networks, vec!["metrics".to_string()]);
+        assert_eq!(script.entrypoint.as_deref(), Some("docker-entrypoint.sh"));
+        assert_eq!(script.command, vec!["--verbose", "postgres", "-c", "max_connections=200"]);
+        assert_eq!(script.labels, vec![("backup".to_string(), "nightly".to_string())]);
+        assert_eq!(script.user.as_deref(), Some("999:999"));
+        let health = script.healthcheck.as_ref().unwrap();
+        assert_eq!(health.command, "pg_isready -U 'shop user'");
+        assert_eq!(health.interval.as_deref(), Some("10s"));
+        assert_eq!(health.retries, Some(5));
+        assert_eq!(skipped, vec!["db.depends_on", "db.deploy"]);
+    }
+
+    #[test]
+    fn test_scripts_to_compose_extra_networks_and_env_files() {
+        let content = "docker run -d --name api --env-file /srv/api/.env nginx\ndocker network connect backend api\n";
+        let script = parse_script(content, "/srv/api/start.sh", "api");
+        assert_eq!(script.network, None);
+
+        let compose = scripts_to_compose(&[script]);
+        assert!(compose.contains("    networks:\n      - \"backend\"\n"));
+        assert!(compose.contains("    env_file:\n      - \"/srv/api/.env\"\n"));
+        assert!(compose.ends_with("networks:\n  \"backend\":\n    external: true\n"));
+    }
+}
@@ -1,40 +1,68 @@
-use crate::models::{DeploymentScript, Project};
+use crate::models::{DeploymentScript, DockerSettings, Project};
+use crate::utils::shell::{quote, quote_path};
+use super::commands::SECTION_SEPARATOR;
 use super::script_parser::parse_script;
 
-/// List all project directories in the clients path
-pub fn list_projects_command(clients_path: &str) -> String {
+/// List the project directories under each of the host's search roots, as full paths
+pub fn list_projects_command(settings: &DockerSettings) -> String {
+    let roots: Vec<String> = settings.roots().iter().map(|r| quote_path(r)).collect();
+    format!("find {} -maxdepth 1 -mindepth 1 -type d 2>/dev/null | sort", roots.join(" "))
+}
+
+/// Find the deployment scripts in a project directory matching the host's patterns
+pub fn find_scripts_command(project_path: &str, settings: &DockerSettings) -> String {
+    let max_depth = settings.max_depth.map(|d| format!(" -maxdepth {}", d)).unwrap_or_default();
     format!(
-        "find {} -maxdepth 1 -mindepth 1 -type d -exec basename {{}} \\; 2>/dev/null | sort",
-        expand_path(clients_path)
+        "find {} -mindepth 1{}{} -type f {} -print 2>/dev/null",
+        quote_path(project_path),
+        max_depth,
+        prune_clause(&settings.excludes()),
+        name_tests(&settings.includes())
     )
 }
 
-/// Find all docker-related shell scripts in a project directory
-pub fn find_scripts_command(project_path: &str) -> String {
+/// Find compose files in the project directories under the search roots
+pub fn find_compose_files_command(settings: &DockerSettings) -> String {
+    let roots: Vec<String> = settings.roots().iter().map(|r| quote_path(r)).collect();
     format!(
-        r#"find {} -type f \( -name "start*.sh" -o -name "deploy*.sh" -o -name "run*.sh" -o -name "docker*.sh" \) \
-        ! -path "*/node_modules/*" \
-        ! -path "*/.git/*" \
-        ! -path "*/vendor/*" \
-        2>/dev/null"#,
-        project_path
+        "find {} -maxdepth 3{} -type f {} -print 2>/dev/null || true",
+        roots.join(" "),
+        prune_clause(&settings.excludes()),
+        name_tests(&COMPOSE_FILE_NAMES.map(str::to_string))
     )
 }
 
+const COMPOSE_FILE_NAMES: [&str; 4] = ["docker-compose.yml", "docker-compose.yaml", "compose.yml", "compose.yaml"];
+
+/// `\( -name 'a' -o -name 'b' \)`
+fn name_tests(patterns: &[String]) -> String {
+    let tests: Vec<String> = patterns.iter().map(|p| format!("-name {}", quote(p))).collect();
+    format!("\\( {} \\)", tests.join(" -o "))
+}
+
+/// Skip matching files and directories (and everything below them)
+fn prune_clause(patterns: &[String]) -> String {
+    if patterns.is_empty() {
+        String::new()
+    } else {
+        format!(" {} -prune -o", name_tests(patterns))
+    }
+}
+
 /// Read a script file content
 pub fn read_script_command(script_path: &str) -> String {
-    format!("cat {}", script_path)
+    format!("cat -- {}", quote_path(script_path))
 }
 
-/// Parse project listing output into Project structs
-pub fn parse_project_listing(output: &str, clients_path: &str) -> Vec<Project> {
+/// Parse project listing output (one directory per line) into Project structs
+pub fn parse_project_listing(output: &str) -> Vec<Project> {
     output
         .lines()
-        .filter(|line| !line.trim().is_empty())
-        .map(|name| {
-            let name = name.trim().to_string();
-            let path = format!("{}/{}", expand_path(clients_path), name);
-            Project::new(name, path)
+        .map(|line| line.trim().trim_end_matches('/'))
+        .filter(|path| !path.is_empty())
+        .map(|path| {
+            let name = path.rsplit('/').next().unwrap_or(path).to_string();
+            Project::new(name, path.to_string())
         })
         .collect()
 }
@@ -69,24 +97,190 @@ pub fn create_script_from_content(
     Some(script)
 }
 
-/// Write script content to file on remote server
+/// Suffix of the timestamped copies kept when a script is overwritten
+pub const SCRIPT_BACKUP_SUFFIX: &str = ".bak";
+
+/// Write script content to file on remote server. The current version is kept
+/// as `<script>.<YYYYmmdd-HHMMSS>.bak`, and the new content goes to a temporary
+/// file in the same directory that is renamed over the script, so the script is
+/// never seen half-written.
 pub fn write_script_command(script_path: &str, content: &str) -> String {
-    let escaped = content.replace('\'', "'\\''");
+    write_file_command(script_path, content, true)
+}
+
+/// Write a file the way `write_script_command` does, for files that are not
+/// executable (e.g. a generated compose file)
+pub fn write_file_command(path: &str, content: &str, executable: bool) -> String {
+    remote_script(&write_file_script(path, content, executable))
+}
+
+/// Shell lines behind `write_file_command`, for callers that write more than one file
+pub(super) fn write_file_script(path: &str, content: &str, executable: bool) -> String {
+    let mut content = content.to_string();
+    if !content.ends_with('\n') {
+        content.push('\n');
+    }
     format!(
-        "cat > '{}' << 'DOCKERING_SCRIPT_EOF'\n{}\nDOCKERING_SCRIPT_EOF && chmod +x '{}'",
-        script_path, escaped, script_path
+        r#"set -e
+f={path}
+mkdir -p "$(dirname "$f")"
+if [ -e "$f" ]; then cp -p "$f" "$f.$(date +%Y%m%d-%H%M%S){suffix}"; fi
+tmp="$f.tmp.$$"
+printf '%s' '{data}' | base64 -d > "$tmp"
+{chmod}mv -f "$tmp" "$f"
+"#,
+        path = quote_path(path),
+        suffix = SCRIPT_BACKUP_SUFFIX,
+        data = base64_encode(content.as_bytes()),
+        chmod = if executable { "chmod +x \"$tmp\"\n" } else { "" },
     )
 }
 
-/// Run a deployment script
-pub fn run_script_command(script_path: &str) -> String {
-    format!("cd $(dirname '{}') && bash '{}'", script_path, script_path)
+/// List the backups of a script, newest first
+pub fn list_script_backups_command(script_path: &str) -> String {
+    remote_script(&format!(
+        "ls -1t -- {}.*{} 2>/dev/null || true\n",
+        quote_path(script_path),
+        SCRIPT_BACKUP_SUFFIX
+    ))
 }
 
-fn expand_path(path: &str) -> String {
-    if path.starts_with("~/") {
-        path.replacen("~", "$HOME", 1)
-    } else {
-        path.to_string()
+/// Read a script and one of its backups, separated by `SECTION_SEPARATOR`
+pub fn read_script_and_backup_command(script_path: &str, backup_path: &str) -> String {
+    remote_script(&format!(
+        "cat -- {}\nprintf '\\n%s\\n' '{}'\ncat -- {}\n",
+        quote_path(script_path),
+        SECTION_SEPARATOR,
+        quote_path(backup_path)
+    ))
+}
+
+/// Backup paths from `list_script_backups_command` output
+pub fn parse_script_backups(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.ends_with(SCRIPT_BACKUP_SUFFIX))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Split `read_script_and_backup_command` output into (current, backup) content
+pub fn split_script_and_backup(output: &str) -> Option<(&str, &str)> {
+    output.split_once(&format!("\n{}\n", SECTION_SEPARATOR))
+}
+
+/// When a backup was taken, as `YYYY-mm-dd HH:MM:SS`, from its file name
+pub fn script_backup_time(backup_path: &str) -> String {
+    let stamp = backup_path
+        .strip_suffix(SCRIPT_BACKUP_SUFFIX)
+        .and_then(|rest| rest.rsplit('.').next())
+        .unwrap_or(backup_path);
+    match (stamp.get(0..4), stamp.get(4..6), stamp.get(6..8), stamp.get(9..11), stamp.get(11..13), stamp.get(13..15)) {
+        (Some(y), Some(mo), Some(d), Some(h), Some(mi), Some(s)) if stamp.len() == 15 => {
+            format!("{}-{}-{} {}:{}:{}", y, mo, d, h, mi, s)
+        }
+        _ => stamp.to_string(),
+    }
+}
+
+/// Run a deployment script from its directory, in a session of its own
+/// (see `run_in_session`)
+pub fn run_script_command(script_path: &str, pid_file: &str) -> String {
+    let path = quote_path(script_path);
+    remote_script(&format!(
+        "cd \"$(dirname -- {path})\" || exit\n{}",
+        run_in_session(&format!("bash {path}"), pid_file),
+    ))
+}
+
+/// Shell lines that run `command` and exit with its status. Killing ssh does
+/// not stop a command that has no terminal, so it runs in a session of its
+/// own (where `setsid` exists) whose id is kept in `pid_file` while it runs,
+/// for `stop_script_command` to stop it with everything it started
+pub(crate) fn run_in_session(command: &str, pid_file: &str) -> String {
+    format!(
+        r#"if command -v setsid >/dev/null 2>&1; then setsid {command} & else {command} & fi
+pid=$!
+echo "$pid" > {pid_file}
+wait "$pid"
+status=$?
+rm -f {pid_file}
+exit "$status"
+"#,
+        pid_file = quote(pid_file),
+    )
+}
+
+/// Stop a command started by `run_in_session`, such as a script: its whole
+/// session, or the command alone where it could not get one
+pub fn stop_script_command(pid_file: &str) -> String {
+    remote_script(&format!(
+        r#"pid=$(cat {pid_file} 2>/dev/null) || exit 0
+kill -s TERM -- "-$pid" 2>/dev/null || kill -s TERM "$pid" 2>/dev/null || true
+"#,
+        pid_file = quote(pid_file),
+    ))
+}
+
+/// Run a multi-line shell script remotely. The script travels base64-encoded,
+/// so it survives both ssh and `sudo -i` (which expands `$` before running it)
+pub(crate) fn remote_script(script: &str) -> String {
+    format!("sh -c 'printf %s {} | base64 -d | sh'", base64_encode(script.as_bytes()))
+}
+
+/// Like `remote_script`, for scripts that read the command's stdin: the
+/// script itself arrives on the inner shell's stdin, so the outer one is
+/// passed along as file descriptor 3
+pub(crate) fn remote_script_with_input(script: &str) -> String {
+    format!("sh -c 'exec 3<&0; printf %s {} | base64 -d | sh'", base64_encode(script.as_bytes()))
+}
+
+pub(crate) fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 63] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discovery_settings() {
+        let defaults = DockerSettings::default();
+        assert_eq!(
+            find_scripts_command("/srv/clients/web", &defaults),
+            "find /srv/clients/web -mindepth 1 \\( -name node_modules -o -name .git -o -name vendor \\) -prune -o \
+             -type f \\( -name 'start*.sh' -o -name 'deploy*.sh' -o -name 'run*.sh' -o -name 'docker*.sh' \\) -print 2>/dev/null"
+        );
+        assert_eq!(list_projects_command(&defaults), "find ~/clients -maxdepth 1 -mindepth 1 -type d 2>/dev/null | sort");
+
+        let settings = DockerSettings {
+            search_roots: vec!["/opt".to_string(), "~/apps".to_string()],
+            include_patterns: vec!["*.sh".to_string()],
+            exclude_patterns: vec!["old".to_string()],
+            max_depth: Some(2),
+            use_sudo: None,
+        };
+        assert_eq!(
+            find_scripts_command("/opt/web", &settings),
+            "find /opt/web -mindepth 1 -maxdepth 2 \\( -name old \\) -prune -o -type f \\( -name '*.sh' \\) -print 2>/dev/null"
+        );
+        assert!(list_projects_command(&settings).starts_with("find /opt ~/apps -maxdepth 1"));
+
+        let projects = parse_project_listing("/opt/web\n/root/apps/api/\n\n");
+        assert_eq!(projects.len(), 2);
+        assert_eq!((projects[1].name.as_str(), projects[1].path.as_str()), ("api", "/root/apps/api"));
     }
 }
@@ -0,0 +1,221 @@
+//! Drift between deployment scripts and the containers they created: settings
+//! that were changed by hand after the script last ran
+
+use std::collections::HashSet;
+
+use crate::models::{DeploymentScript, DriftItem, PortMapping, VolumeMount};
+use crate::utils::shell::{quote, quote_path};
+use super::discovery::remote_script;
+use super::inspect_script::{inspect_env, inspect_lines, script_from_inspect};
+use super::script_parser::script_image;
+use super::secrets::{resolve, script_dir};
+
+const CONTAINER_MARKER: &str = "__SSHING_CONTAINER__ ";
+/// Followed by the keys the script's env files set, one per line
+const ENV_FILE_KEYS_MARKER: &str = "__SSHING_ENV_FILE_KEYS__";
+/// Listed for an env file that could not be read
+const UNKNOWN_KEYS: &str = "*";
+
+/// Inspect each container and its image, and list the keys (never the
+/// values) of the env files its script passes, one marked section per container
+pub fn drift_command(containers: &[(&str, &DeploymentScript)]) -> String {
+    let mut lines = Vec::new();
+    for (container, script) in containers {
+        lines.push(format!("echo {}", quote(&format!("{}{}", CONTAINER_MARKER, container))));
+        lines.extend(inspect_lines(container));
+        lines.push(format!("echo {}", ENV_FILE_KEYS_MARKER));
+        for env_file in script.env_files.iter().filter(|f| is_static(f)) {
+            lines.push(format!(
+                "awk -F= '!/^[[:space:]]*(#|$)/ {{ sub(/^[[:space:]]+/, \"\", $1); print $1 }}' {} 2>/dev/null || echo '{}'",
+                quote_path(&resolve(script_dir(&script.path), env_file)),
+                UNKNOWN_KEYS
+            ));
+        }
+    }
+    lines.push("true".to_string());
+    remote_script(&lines.join("\n"))
+}
+
+/// Split the output of `drift_command` into (container name, inspect output)
+pub fn split_drift_output(output: &str) -> Vec<(String, String)> {
+    let mut sections: Vec<(String, String)> = Vec::new();
+    for line in output.lines() {
+        if let Some(name) = line.strip_prefix(CONTAINER_MARKER) {
+            sections.push((name.trim().to_string(), String::new()));
+        } else if let Some((_, body)) = sections.last_mut() {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    sections
+}
+
+/// Compare a script with the inspect output of its container. Values the
+/// script computes at run time (`$(...)`, unset variables) are not compared
+pub fn detect_drift(script: &DeploymentScript, output: &str) -> Result<Vec<DriftItem>, String> {
+    let (inspect_output, env_file_keys) = match output.split_once(ENV_FILE_KEYS_MARKER) {
+        Some((inspect, keys)) => (inspect, Some(keys.lines().map(str::trim).filter(|k| !k.is_empty()).collect::<HashSet<_>>())),
+        None => (output, None),
+    };
+    let actual = script_from_inspect(inspect_output, "", "")?;
+    let mut drift = Vec::new();
+
+    if let Some(image) = script_image(script) {
+        if normalize_image(&image) != normalize_image(&actual.repo) {
+            drift.push(DriftItem::new("image", image, actual.repo.clone()));
+        }
+    }
+
+    // The full environment, so values the script repeats from the image are not missing
+    let (container_env, _) = inspect_env(inspect_output);
+    for env in script.env_vars.iter().filter(|e| is_static(&e.value)) {
+        let prefix = format!("{}=", env.key);
+        match container_env.iter().find_map(|entry| entry.strip_prefix(&prefix)) {
+            Some(value) if value == env.value => {}
+            Some(value) => drift.push(DriftItem::new(format!("env {}", env.key), &env.value, value)),
+            None => drift.push(DriftItem::new(format!("env {}", env.key), &env.value, "-")),
+        }
+    }
+    // Keys the env files set are theirs to compare. When a file could not be
+    // read, or its path is only known at run time, any key may come from it
+    let env_files_known = script.env_files.iter().all(|f| is_static(f))
+        && (script.env_files.is_empty() || env_file_keys.as_ref().is_some_and(|keys| !keys.contains(UNKNOWN_KEYS)));
+    let from_env_file = |key: &str| !env_files_known || env_file_keys.as_ref().is_some_and(|keys| keys.contains(key));
+    for env in actual.env_vars.iter().filter(|e| !from_env_file(&e.key)) {
+        if !script.env_vars.iter().any(|e| e.key == env.key) {
+            drift.push(DriftItem::new(format!("env {}", env.key), "-", &env.value));
+        }
+    }
+
+    let expected: Vec<String> = script.ports.iter().map(port_label).collect();
+    let found: Vec<String> = actual.ports.iter().map(port_label).collect();
+    push_set_drift(&mut drift, "port", &expected, &found);
+
+    let dir = script.path.rsplit_once('/').map_or("", |(dir, _)| dir);
+    let comparable = |v: &&VolumeMount| is_static(&v.host_path) && !v.host_path.starts_with('~');
+    let expected: Vec<String> = script.volumes.iter().filter(comparable).map(|v| volume_label(v, dir)).collect();
+    // Mounts the script sets from a variable are skipped on both sides
+    let found: Vec<String> = actual
+        .volumes
+        .iter()
+        .filter(|v| !script.volumes.iter().any(|s| !comparable(&s) && s.container_path == v.container_path))
+        .map(|v| volume_label(v, dir))
+        .collect();
+    push_set_drift(&mut drift, "volume", &expected, &found);
+
+    let network = script.network.as_deref().filter(|n| !matches!(*n, "bridge" | "default"));
+    if network.is_none_or(is_static) && network != actual.network.as_deref() {
+        drift.push(DriftItem::new("network", network.unwrap_or("default"), actual.network.as_deref().unwrap_or("default")));
+    }
+
+    let restart = script.restart_policy.as_deref().filter(|r| *r != "no");
+    if restart.is_none_or(is_static) && restart != actual.restart_policy.as_deref() {
+        drift.push(DriftItem::new("restart", restart.unwrap_or("no"), actual.restart_policy.as_deref().unwrap_or("no")));
+    }
+
+    Ok(drift)
+}
+
+/// Values without variables or command substitutions left in them
+fn is_static(value: &str) -> bool {
+    !value.contains(['$', '`'])
+}
+
+/// `nginx`, `nginx:latest` and `docker.io/library/nginx:latest` are the same image
+fn normalize_image(image: &str) -> String {
+    let image = image.strip_prefix("docker.io/").unwrap_or(image);
+    let image = image.strip_prefix("library/").unwrap_or(image);
+    let name = image.rsplit('/').next().unwrap_or(image);
+    if name.contains([':', '@']) {
+        image.to_string()
+    } else {
+        format!("{}:latest", image)
+    }
+}
+
+fn port_label(port: &PortMapping) -> String {
+    format!("{}:{}/{}", port.host_address(), port.container_port, port.protocol)
+}
+
+/// Bind sources relative to the script resolve against its directory, where it runs
+fn volume_label(volume: &VolumeMount, dir: &str) -> String {
+    let host = match volume.host_path.strip_prefix("./") {
+        Some(rest) => format!("{}/{}", dir, rest),
+        None => volume.host_path.clone(),
+    };
+    let ro = if volume.read_only { ":ro" } else { "" };
+    format!("{}:{}{}", host.trim_end_matches('/'), volume.container_path.trim_end_matches('/'), ro)
+}
+
+/// Items only in the script, then items only on the container
+fn push_set_drift(drift: &mut Vec<DriftItem>, field: &str, expected: &[String], found: &[String]) {
+    let expected_set: HashSet<&String> = expected.iter().collect();
+    let found_set: HashSet<&String> = found.iter().collect();
+    for item in expected.iter().filter(|i| !found_set.contains(i)) {
+        drift.push(DriftItem::new(field, item, "-"));
+    }
+    for item in found.iter().filter(|i| !expected_set.contains(i)) {
+        drift.push(DriftItem::new(field, "-", item));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docker::commands::SECTION_SEPARATOR;
+    use crate::docker::script_parser::parse_script;
+
+    #[test]
+    fn test_detect_drift() {
+        let content = "docker run -d --name web --restart always -p 8080:80 -v ./html:/usr/share/nginx/html:ro \\\n  -e MODE=prod -e SECRET=$(cat /srv/secret) -e NGINX_VERSION=1.25.4 nginx\n";
+        let script = parse_script(content, "/srv/web/start.sh", "web");
+
+        let container = r#"[{
+            "Name": "/web",
+            "Config": {
+                "Image": "nginx:1.27",
+                "Env": ["MODE=debug", "SECRET=hunter2", "EXTRA=1", "NGINX_VERSION=1.25.4"]
+            },
+            "HostConfig": {
+                "NetworkMode": "bridge",
+                "RestartPolicy": {"Name": "always", "MaximumRetryCount": 0},
+                "PortBindings": {"80/tcp": [{"HostIp": "", "HostPort": "8080"}], "443/tcp": [{"HostIp": "", "HostPort": "8443"}]}
+            },
+            "Mounts": [{"Type": "bind", "Source": "/srv/web/html", "Destination": "/usr/share/nginx/html", "RW": false}]
+        }]"#;
+        let image = r#"[{"Config": {"Env": ["NGINX_VERSION=1.25.4"]}}]"#;
+        let output = format!("{}\n{}\n{}\n", container, SECTION_SEPARATOR, image);
+
+        let drift = detect_drift(&script, &output).unwrap();
+        assert_eq!(
+            drift,
+            vec![
+                DriftItem::new("image", "nginx", "nginx:1.27"),
+                DriftItem::new("env MODE", "prod", "debug"),
+                DriftItem::new("env EXTRA", "-", "1"),
+                DriftItem::new("port", "-", "8443:443/tcp"),
+            ]
+        );
+
+        let sections = split_drift_output(&format!("{}web\n{}", CONTAINER_MARKER, output));
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].0, "web");
+        assert_eq!(detect_drift(&script, &sections[0].1).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_detect_drift_with_env_file() {
+        let script = parse_script("docker run -d --name api --env-file .env -e MODE=prod api:1\n", "/srv/api/start.sh", "api");
+
+        let container = r#"[{"Config": {"Image": "api:1", "Env": ["MODE=prod", "DB_URL=postgres://db", "DEBUG=1"]}, "HostConfig": {}}]"#;
+        let inspect = format!("{}\n{}\n[]\n", container, SECTION_SEPARATOR);
+
+        // DB_URL comes from the env file, DEBUG was added by hand
+        let output = format!("{}{}\nDB_URL\n", inspect, ENV_FILE_KEYS_MARKER);
+        assert_eq!(detect_drift(&script, &output).unwrap(), vec![DriftItem::new("env DEBUG", "-", "1")]);
+
+        // An env file that could not be read may set any key
+        let output = format!("{}{}\n{}\n", inspect, ENV_FILE_KEYS_MARKER, UNKNOWN_KEYS);
+        assert!(detect_drift(&script, &output).unwrap().is_empty());
+    }
+}
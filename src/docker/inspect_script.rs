@@ -0,0 +1,312 @@
+//! Build a deployment script for a container that was started by hand, from
+//! `docker inspect` of the container and of its image. Settings the image
+//! already provides (env, labels, user, command, healthcheck) are left out
+
+use serde_json::Value;
+
+use crate::models::{DeploymentScript, EnvVar, HealthCheck, PortMapping, VolumeMount};
+use crate::utils::shell::quote;
+use super::commands::SECTION_SEPARATOR;
+use super::discovery::remote_script;
+
+/// Network modes that mean "no network was chosen"
+const DEFAULT_NETWORKS: &[&str] = &["default", "bridge"];
+
+/// Inspect the container, then the image it was created from
+pub fn inspect_for_script_command(container: &str) -> String {
+    remote_script(&inspect_lines(container).join("\n"))
+}
+
+/// Shell lines printing the container's inspect output, a separator and the
+/// inspect output of its image
+pub(super) fn inspect_lines(container: &str) -> Vec<String> {
+    vec![
+        format!("c={}", quote(container)),
+        "docker inspect --type container \"$c\"".to_string(),
+        format!("echo {}", SECTION_SEPARATOR),
+        "docker inspect --type image \"$(docker inspect --type container --format '{{.Image}}' \"$c\")\" 2>/dev/null || echo '[]'".to_string(),
+    ]
+}
+
+/// `Config.Env` of the container and of its image, as `KEY=value` entries
+pub(super) fn inspect_env(output: &str) -> (Vec<String>, Vec<String>) {
+    let (container_json, image_json) = output.split_once(SECTION_SEPARATOR).unwrap_or((output, "[]"));
+    let env = |json| first_object(json).map(|v| strings(&v["Config"]["Env"])).unwrap_or_default();
+    (env(container_json), env(image_json))
+}
+
+/// Turn the output of `inspect_for_script_command` into a new script at `path`
+pub fn script_from_inspect(output: &str, path: &str, client_name: &str) -> Result<DeploymentScript, String> {
+    let (container_json, image_json) = output.split_once(SECTION_SEPARATOR).unwrap_or((output, "[]"));
+    let container = first_object(container_json).ok_or_else(|| "Could not read docker inspect output".to_string())?;
+    let image = first_object(image_json).unwrap_or(Value::Null);
+
+    let config = &container["Config"];
+    let host_config = &container["HostConfig"];
+    let image_config = &image["Config"];
+
+    let mut script = DeploymentScript::new(path.to_string(), client_name.to_string());
+    script.container_name = str_at(&container, "Name").unwrap_or_default().trim_start_matches('/').to_string();
+    script.repo = str_at(config, "Image").unwrap_or_default().to_string();
+
+    let image_env = strings(&image_config["Env"]);
+    script.env_vars = strings(&config["Env"])
+        .into_iter()
+        .filter(|entry| !image_env.contains(entry))
+        .filter_map(|entry| {
+            let (key, value) = entry.split_once('=')?;
+            Some(EnvVar::new(key.to_string(), value.to_string()))
+        })
+        .collect();
+
+    for mount in container["Mounts"].as_array().into_iter().flatten() {
+        let source = match str_at(mount, "Type") {
+            Some("bind") => str_at(mount, "Source"),
+            Some("volume") => str_at(mount, "Name"),
+            _ => None,
+        };
+        if let (Some(source), Some(target)) = (source, str_at(mount, "Destination")) {
+            script.volumes.push(VolumeMount {
+                host_path: source.to_string(),
+                container_path: target.to_string(),
+                read_only: mount["RW"].as_bool() == Some(false),
+            });
+        }
+    }
+
+    if let Some(bindings) = host_config["PortBindings"].as_object() {
+        for (spec, hosts) in bindings {
+            let (port, protocol) = spec.split_once('/').unwrap_or((spec, "tcp"));
+            let Ok(container_port) = port.parse() else {
+                continue;
+            };
+            for host in hosts.as_array().into_iter().flatten() {
+                if let Some(host_port) = str_at(host, "HostPort").and_then(|p| p.parse().ok()) {
+                    let host_ip = str_at(host, "HostIp").and_then(PortMapping::parse_host_ip);
+                    script.ports.push(PortMapping { host_port, container_port, protocol: protocol.to_string(), host_ip });
+                }
+            }
+        }
+    }
+
+    let mode = str_at(host_config, "NetworkMode").unwrap_or("default");
+    script.network = (!DEFAULT_NETWORKS.contains(&mode)).then(|| mode.to_string());
+    // Only user-defined networks can be joined after the container is created
+    if !mode.contains(':') && !matches!(mode, "host" | "none") {
+        if let Some(networks) = container["NetworkSettings"]["Networks"].as_object() {
+            script.extra_networks = networks
+                .keys()
+                .filter(|name| *name != mode && !DEFAULT_NETWORKS.contains(&name.as_str()))
+                .cloned()
+                .collect();
+        }
+    }
+
+    let restart = &host_config["RestartPolicy"];
+    script.restart_policy = match (str_at(restart, "Name").unwrap_or(""), restart["MaximumRetryCount"].as_u64()) {
+        ("" | "no", _) => None,
+        ("on-failure", Some(retries)) if retries > 0 => Some(format!("on-failure:{}", retries)),
+        (name, _) => Some(name.to_string()),
+    };
+
+    if let Some(labels) = config["Labels"].as_object() {
+        script.labels = labels
+            .iter()
+            .filter(|(key, value)| image_config["Labels"].get(key.as_str()) != Some(*value))
+            .map(|(key, value)| (key.clone(), value.as_str().unwrap_or_default().to_string()))
+            .collect();
+    }
+
+    script.user = str_at(config, "User")
+        .filter(|user| !user.is_empty() && Some(*user) != str_at(image_config, "User"))
+        .map(str::to_string);
+
+    let entrypoint = strings(&config["Entrypoint"]);
+    let cmd = strings(&config["Cmd"]);
+    if entrypoint == strings(&image_config["Entrypoint"]) {
+        if cmd != strings(&image_config["Cmd"]) {
+            script.command = cmd;
+        }
+    } else {
+        // --entrypoint takes a single word, the rest of it goes before the command
+        let mut words = entrypoint.into_iter();
+        script.entrypoint = Some(words.next().unwrap_or_default());
+        script.command = words.chain(cmd).collect();
+    }
+
+    if let Some(memory) = host_config["Memory"].as_u64().filter(|m| *m > 0) {
+        script.memory = Some(format_bytes(memory));
+    }
+    if let Some(nano_cpus) = host_config["NanoCpus"].as_u64().filter(|n| *n > 0) {
+        script.cpus = Some((nano_cpus as f64 / 1e9).to_string());
+    }
+
+    if config["Healthcheck"] != image_config["Healthcheck"] {
+        script.healthcheck = healthcheck(&config["Healthcheck"]);
+    }
+
+    Ok(script)
+}
+
+/// The first element of a `docker inspect` array
+fn first_object(json: &str) -> Option<Value> {
+    let value: Value = serde_json::from_str(json.trim()).ok()?;
+    value.as_array()?.first().cloned()
+}
+
+fn str_at<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+/// A JSON array of strings; null and missing values are empty
+fn strings(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| items.iter().filter_map(Value::as_str).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn healthcheck(value: &Value) -> Option<HealthCheck> {
+    let test = strings(&value["Test"]);
+    let command = match test.split_first() {
+        Some((kind, rest)) if kind == "CMD-SHELL" => rest.join(" "),
+        // An exec array runs without a shell; quoted, each argument stays whole
+        Some((kind, rest)) if kind == "CMD" => rest.iter().map(|arg| quote(arg)).collect::<Vec<_>>().join(" "),
+        _ => return None,
+    };
+    let duration = |key: &str| value[key].as_u64().filter(|ns| *ns > 0).map(format_duration);
+    Some(HealthCheck {
+        command,
+        interval: duration("Interval"),
+        timeout: duration("Timeout"),
+        start_period: duration("StartPeriod"),
+        retries: value["Retries"].as_u64().filter(|r| *r > 0).and_then(|r| u32::try_from(r).ok()),
+    })
+}
+
+/// Bytes in the largest unit `--memory` accepts that divides them evenly
+fn format_bytes(bytes: u64) -> String {
+    for (shift, unit) in [(30, "g"), (20, "m"), (10, "k")] {
+        if bytes.is_multiple_of(1 << shift) {
+            return format!("{}{}", bytes >> shift, unit);
+        }
+    }
+    format!("{}b", bytes)
+}
+
+/// Nanoseconds as a Go duration, e.g. `30s` or `1500ms`
+fn format_duration(ns: u64) -> String {
+    if ns.is_multiple_of(1_000_000_000) {
+        format!("{}s", ns / 1_000_000_000)
+    } else {
+        format!("{}ms", ns / 1_000_000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_from_inspect() {
+        let container = r#"[{
+            "Name": "/web",
+            "Config": {
+                "Image": "nginx:1.25",
+                "User": "101",
+                "Env": ["API_URL=http://api:8000", "PATH=/usr/local/bin:/usr/bin", "NGINX_VERSION=1.25.4"],
+                "Cmd": ["nginx", "-g", "daemon off;"],
+                "Entrypoint": ["/docker-entrypoint.sh"],
+                "Labels": {"maintainer": "NGINX", "traefik.enable": "true"},
+                "Healthcheck": {"Test": ["CMD-SHELL", "curl -f http://localhost/"], "Interval": 30000000000, "Retries": 3}
+            },
+            "HostConfig": {
+                "NetworkMode": "proxy",
+                "RestartPolicy": {"Name": "on-failure", "MaximumRetryCount": 5},
+                "PortBindings": {"80/tcp": [{"HostIp": "", "HostPort": "8080"}], "53/udp": [{"HostIp": "", "HostPort": "53"}]},
+                "Memory": 536870912,
+                "NanoCpus": 1500000000
+            },
+            "Mounts": [
+                {"Type": "bind", "Source": "/srv/web/html", "Destination": "/usr/share/nginx/html", "RW": false},
+                {"Type": "volume", "Name": "web-cache", "Source": "/var/lib/docker/volumes/web-cache/_data", "Destination": "/cache", "RW": true},
+                {"Type": "tmpfs", "Destination": "/tmp"}
+            ],
+            "NetworkSettings": {"Networks": {"proxy": {}, "backend": {}}}
+        }]"#;
+        let image = r#"[{
+            "Config": {
+                "Env": ["PATH=/usr/local/bin:/usr/bin", "NGINX_VERSION=1.25.4"],
+                "Cmd": ["nginx", "-g", "daemon off;"],
+                "Entrypoint": ["/docker-entrypoint.sh"],
+                "Labels": {"maintainer": "NGINX"}
+            }
+        }]"#;
+        let output = format!("{}\n{}\n{}", container, SECTION_SEPARATOR, image);
+
+        let script = script_from_inspect(&output, "/srv/clients/web/startWeb.sh", "web").unwrap();
+        assert_eq!(script.container_name, "web");
+        assert_eq!(script.repo, "nginx:1.25");
+        assert_eq!(script.env_vars, vec![EnvVar::new("API_URL".into(), "http://api:8000".into())]);
+        assert_eq!(script.volumes.len(), 2);
+        assert!(script.volumes[0].read_only);
+        assert_eq!(script.volumes[1].host_path, "web-cache");
+        assert_eq!(script.ports.len(), 2);
+        assert_eq!(script.network.as_deref(), Some("proxy"));
+        assert_eq!(script.extra_networks, vec!["backend".to_string()]);
+        assert_eq!(script.restart_policy.as_deref(), Some("on-failure:5"));
+        assert_eq!(script.labels, vec![("traefik.enable".to_string(), "true".to_string())]);
+        assert_eq!(script.user.as_deref(), Some("101"));
+        assert_eq!(script.entrypoint, None);
+        assert!(script.command.is_empty());
+        assert_eq!(script.memory.as_deref(), Some("512m"));
+        assert_eq!(script.cpus.as_deref(), Some("1.5"));
+        let health = script.healthcheck.as_ref().unwrap();
+        assert_eq!(health.command, "curl -f http://localhost/");
+        assert_eq!(health.interval.as_deref(), Some("30s"));
+        assert_eq!(health.retries, Some(3));
+
+        // The generated script reads back to the same settings
+        let mut generated = crate::docker::script_parser::generate_script(&script);
+        generated.push('\n');
+        let parsed = crate::docker::script_parser::parse_script(&generated, &script.path, "web");
+        assert_eq!(parsed.labels, script.labels);
+        assert_eq!(parsed.extra_networks, script.extra_networks);
+        assert_eq!(parsed.healthcheck, script.healthcheck);
+        assert_eq!((parsed.memory, parsed.cpus, parsed.user), (script.memory, script.cpus, script.user));
+
+        assert!(script_from_inspect("Error: No such container", "/x", "x").is_err());
+    }
+
+    #[test]
+    fn test_script_from_inspect_bind_address_and_exec_healthcheck() {
+        let container = r#"[{
+            "Name": "/admin",
+            "Config": {
+                "Image": "adminer:4",
+                "Healthcheck": {"Test": ["CMD", "wget", "-qO-", "http://localhost:8080/?status check"]}
+            },
+            "HostConfig": {
+                "NetworkMode": "bridge",
+                "PortBindings": {
+                    "8080/tcp": [{"HostIp": "127.0.0.1", "HostPort": "8081"}, {"HostIp": "::1", "HostPort": "8081"}],
+                    "9000/tcp": [{"HostIp": "0.0.0.0", "HostPort": "9000"}]
+                }
+            }
+        }]"#;
+        let output = format!("{}\n{}\n[{{}}]", container, SECTION_SEPARATOR);
+
+        let script = script_from_inspect(&output, "/srv/admin/startAdmin.sh", "admin").unwrap();
+        let binds: Vec<String> = script.ports.iter().map(PortMapping::publish_spec).collect();
+        assert_eq!(binds, vec!["127.0.0.1:8081:8080", "[::1]:8081:8080", "9000:9000"]);
+        let health = script.healthcheck.as_ref().unwrap();
+        assert_eq!(health.command, "wget -qO- 'http://localhost:8080/?status check'");
+
+        let mut generated = crate::docker::script_parser::generate_script(&script);
+        assert!(generated.contains("-p 127.0.0.1:8081:8080"));
+        generated.push('\n');
+        let parsed = crate::docker::script_parser::parse_script(&generated, &script.path, "admin");
+        assert_eq!(parsed.ports, script.ports);
+        assert_eq!(parsed.healthcheck, script.healthcheck);
+    }
+}
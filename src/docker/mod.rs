@@ -2,16 +2,40 @@ pub mod parser;
 pub mod commands;
 pub mod discovery;
 pub mod script_parser;
+pub mod shell_lexer;
+pub mod script_lint;
+pub mod compose_convert;
+pub mod inspect_script;
+pub mod drift;
+pub mod secrets;
 
-pub use parser::parse_docker_ps;
+pub use parser::{
+    parse_docker_ps, parse_exec_output, parse_docker_images, parse_image_update_check,
+    parse_docker_volumes, parse_docker_networks, parse_compose_projects,
+};
 pub use commands::{
     docker_ps_command, docker_pull_command, docker_start_command, docker_stop_command,
     docker_restart_command, docker_rm_command, docker_rm_with_volumes_command, docker_rmi_command,
     docker_logs_command, docker_exec_env_command, docker_stats_command, docker_top_command,
-    docker_inspect_command, list_directory_command,
+    docker_inspect_command, list_directory_command, docker_exec_probe_command,
+    docker_exec_shell_command, docker_exec_oneoff_command, CONTAINER_SHELLS, EXEC_EXIT_MARKER,
+    docker_images_command, docker_rmi_image_command, docker_image_prune_command,
+    docker_image_digests_command, docker_remote_digest_command, IMAGE_DIGEST_SEPARATOR,
+    docker_volume_ls_command, docker_ps_mounts_command, docker_system_df_command,
+    docker_volume_create_command, docker_volume_rm_command, docker_volume_prune_command,
+    docker_network_ids_command, docker_network_inspect_stdin_command, docker_network_create_command,
+    docker_network_rm_command, docker_network_prune_command, docker_volume_ls_dir_command,
+    docker_volume_cat_command, SECTION_SEPARATOR, docker_compose_ls_command, docker_compose_command,
 };
 pub use discovery::{
-    list_projects_command, find_scripts_command, read_script_command, write_script_command,
-    parse_project_listing, parse_script_paths, create_script_from_content, run_script_command,
+    list_projects_command, find_scripts_command, read_script_command, write_script_command, write_file_command,
+    parse_project_listing, parse_script_paths, create_script_from_content, run_script_command, stop_script_command,
+    find_compose_files_command, list_script_backups_command, read_script_and_backup_command,
+    parse_script_backups, split_script_and_backup, script_backup_time,
 };
 pub use script_parser::apply_script_changes;
+pub use compose_convert::{scripts_to_compose, compose_to_scripts};
+pub use inspect_script::{inspect_for_script_command, script_from_inspect};
+pub use drift::{drift_command, split_drift_output, detect_drift};
+pub use secrets::{externalize_secrets, secret_file_warnings, write_script_with_secrets_command};
+pub use script_lint::{lint_script, lint_command, parse_lint_output, sort_issues};
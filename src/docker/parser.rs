@@ -1,6 +1,7 @@
-use crate::models::{Container, ContainerStatus, PortMapping};
+use crate::models::{ComposeProject, Container, ContainerStatus, DockerImage, DockerNetwork, DockerVolume, FileEntry, FileSort, ImageUpdateStatus, PortMapping};
+use super::commands::SECTION_SEPARATOR;
 
-/// Parse output from `docker ps --format '{{.ID}}|{{.Names}}|{{.Image}}|{{.Status}}|{{.Ports}}'`
+/// Parse output from `docker ps --format '{{.ID}}|{{.Names}}|{{.Image}}|{{.Status}}|{{.Ports}}|<compose project label>'`
 pub fn parse_docker_ps(output: &str, server_name: &str) -> Vec<Container> {
     output
         .lines()
@@ -24,6 +25,11 @@ fn parse_container_line(line: &str, server_name: &str) -> Option<Container> {
     } else {
         Vec::new()
     };
+    let compose_project = parts
+        .get(5)
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(String::from);
 
     Some(Container {
         id,
@@ -35,6 +41,7 @@ fn parse_container_line(line: &str, server_name: &str) -> Option<Container> {
         server_name: server_name.to_string(),
         script_path: None,
         networks: Vec::new(),
+        compose_project,
     })
 }
 
@@ -65,8 +72,9 @@ fn parse_single_port(port_str: &str) -> Option<PortMapping> {
         let left = &port_str[..arrow_pos];
         let right = &port_str[arrow_pos + 2..];
 
-        // Extract host port (after last :)
-        let host_port: u16 = left.rsplit(':').next()?.parse().ok()?;
+        // Extract host port (after last :) and the address before it
+        let (host_ip, host_port) = left.rsplit_once(':').unwrap_or(("", left));
+        let host_port: u16 = host_port.parse().ok()?;
 
         // Extract container port and protocol
         let (container_port, protocol) = parse_port_protocol(right)?;
@@ -75,6 +83,7 @@ fn parse_single_port(port_str: &str) -> Option<PortMapping> {
             host_port,
             container_port,
             protocol,
+            host_ip: PortMapping::parse_host_ip(host_ip),
         })
     } else {
         // Just an exposed port without mapping
@@ -83,6 +92,7 @@ fn parse_single_port(port_str: &str) -> Option<PortMapping> {
             host_port: container_port,
             container_port,
             protocol,
+            host_ip: None,
         })
     }
 }
@@ -101,7 +111,7 @@ pub fn parse_docker_stats(output: &str) -> crate::models::ContainerStats {
     let parts: Vec<&str> = line.split('|').collect();
 
     crate::models::ContainerStats {
-        cpu_percent: parts.get(0).unwrap_or(&"--").to_string(),
+        cpu_percent: parts.first().unwrap_or(&"--").to_string(),
         memory_usage: parts.get(1).map(|s| s.split('/').next().unwrap_or(s).trim().to_string()).unwrap_or_else(|| "--".to_string()),
         memory_limit: parts.get(1).map(|s| s.split('/').nth(1).unwrap_or("--").trim().to_string()).unwrap_or_else(|| "--".to_string()),
         memory_percent: parts.get(2).unwrap_or(&"--").to_string(),
@@ -176,46 +186,508 @@ fn extract_json_value(line: &str) -> String {
         .to_string()
 }
 
-/// Parse directory listing for file browser
-/// Format from dockering: ls -la output (without header, via tail -n +2)
-/// Example line: drwxr-xr-x  2 user group  4096 Jan  1 12:00 dirname
-pub fn parse_directory_listing(output: &str, _path: &str) -> Vec<crate::models::FileEntry> {
-    let mut entries = vec![crate::models::FileEntry::parent()];
+/// Parse the output of `list_directory_command` for the file browsers:
+/// NUL-separated `find -printf` records, or `ls -la` lines (without the
+/// header) where find has no `-printf`. Entries start with `..` and are
+/// sorted by name, directories first
+pub fn parse_directory_listing(output: &str, _path: &str) -> Vec<FileEntry> {
+    let mut entries = vec![FileEntry::parent()];
+    if output.contains('\0') {
+        entries.extend(parse_find_listing(output));
+    } else {
+        entries.extend(output.lines().filter_map(parse_ls_line));
+    }
+    FileSort::Name.sort(&mut entries);
+    entries
+}
 
-    for line in output.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
+fn parse_find_listing(output: &str) -> Vec<FileEntry> {
+    let fields: Vec<&str> = output.split('\0').collect();
+    fields
+        .chunks_exact(8)
+        .filter_map(|record| {
+            let [kind, target_kind, size, modified, mode, owner, link, name] = record else {
+                return None;
+            };
+            // Output from before the listing (a login banner) ends up in the first field
+            let kind = kind.trim().chars().last()?;
+            if name.is_empty() || *name == "." || *name == ".." {
+                return None;
+            }
+            let mut entry = FileEntry::new(name.to_string(), *target_kind == "d");
+            entry.size = size.parse().unwrap_or(0);
+            entry.modified = modified.split('.').next().and_then(|secs| secs.parse().ok());
+            entry.mode = u32::from_str_radix(mode, 8).ok();
+            entry.owner = owner.to_string();
+            entry.link_target = (kind == 'l').then(|| link.to_string());
+            Some(entry)
+        })
+        .collect()
+}
+
+/// One `ls -la` line, e.g. `drwxr-xr-x  2 user group  4096 Jan  1 12:00 dirname`.
+/// Its date depends on the remote locale, so it is not kept
+fn parse_ls_line(line: &str) -> Option<FileEntry> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 9 {
+        return None;
+    }
+
+    // First char indicates type: d=directory, -=file, l=link
+    let perms = parts[0];
+    // Filename is everything after the 8th field (spaces inside it are collapsed)
+    let name = parts[8..].join(" ");
+    let (name, link_target) = match name.split_once(" -> ") {
+        Some((name, target)) if perms.starts_with('l') => (name.to_string(), Some(target.to_string())),
+        _ => (name, None),
+    };
+    if name == "." || name == ".." {
+        return None;
+    }
+
+    let mut entry = FileEntry::new(name, perms.starts_with('d'));
+    entry.size = parts[4].parse().unwrap_or(0);
+    entry.owner = parts[2].to_string();
+    entry.mode = perms.get(1..10).map(|bits| {
+        bits.chars().fold(0, |mode, c| (mode << 1) | u32::from(c != '-'))
+    });
+    entry.link_target = link_target;
+    Some(entry)
+}
+
+/// Split ad-hoc exec output into its lines and the exit status printed after
+/// `EXEC_EXIT_MARKER` (None if the marker is missing, e.g. the ssh was cut off).
+/// The marker follows a newline of its own, which leaves an empty line behind
+/// when the output already ended with one
+pub fn parse_exec_output(output: &str) -> (Vec<String>, Option<i32>) {
+    let mut lines: Vec<String> = output.lines().map(String::from).collect();
+
+    let exit_code = match lines.last() {
+        Some(last) if last.starts_with(super::commands::EXEC_EXIT_MARKER) => {
+            let code = last[super::commands::EXEC_EXIT_MARKER.len()..].trim().parse().ok();
+            lines.pop();
+            if lines.last().is_some_and(|line| line.is_empty()) {
+                lines.pop();
+            }
+            code
         }
+        _ => None,
+    };
 
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() < 9 {
-            continue;
+    (lines, exit_code)
+}
+
+/// Parse output from `docker images --format '{{.ID}}|{{.Repository}}|{{.Tag}}|{{.Size}}|{{.CreatedAt}}'`
+/// and mark which of the given containers use each image
+pub fn parse_docker_images(output: &str, containers: &[Container]) -> Vec<DockerImage> {
+    output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split('|').collect();
+            if parts.len() < 5 {
+                return None;
+            }
+
+            // CreatedAt looks like "2024-01-15 10:32:11 +0000 UTC", keep the date
+            let created = parts[4].split_whitespace().next().unwrap_or("").to_string();
+
+            let mut image = DockerImage {
+                id: parts[0].to_string(),
+                repository: parts[1].to_string(),
+                tag: parts[2].to_string(),
+                size: parts[3].to_string(),
+                created,
+                used_by: Vec::new(),
+                update_status: None,
+            };
+            image.used_by = containers
+                .iter()
+                .filter(|c| image.is_used_by(c))
+                .map(|c| c.name.clone())
+                .collect();
+            Some(image)
+        })
+        .collect()
+}
+
+/// Compare local repo digests with the registry digest printed after
+/// `IMAGE_DIGEST_SEPARATOR` by `docker buildx imagetools inspect`
+pub fn parse_image_update_check(output: &str) -> ImageUpdateStatus {
+    let (local, remote) = match output.split_once(super::commands::IMAGE_DIGEST_SEPARATOR) {
+        Some(halves) => halves,
+        None => return ImageUpdateStatus::Unknown("no output from update check".to_string()),
+    };
+
+    let local_digests: Vec<&str> = local
+        .lines()
+        .filter_map(|line| line.trim().split_once('@').map(|(_, digest)| digest))
+        .collect();
+    if local_digests.is_empty() {
+        return ImageUpdateStatus::Unknown("image was not pulled from a registry".to_string());
+    }
+
+    let remote_digest = remote
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Digest:").map(|d| d.trim()));
+
+    match remote_digest {
+        Some(digest) if local_digests.contains(&digest) => ImageUpdateStatus::UpToDate,
+        Some(_) => ImageUpdateStatus::UpdateAvailable,
+        None => {
+            let reason = remote.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("registry lookup failed");
+            ImageUpdateStatus::Unknown(reason.to_string())
         }
+    }
+}
+
+/// Parse the volume listing: `docker volume ls`, then `docker ps` mounts, then
+/// `docker system df -v`, separated by `SECTION_SEPARATOR` lines
+pub fn parse_docker_volumes(output: &str) -> Vec<DockerVolume> {
+    let mut sections = output.split(SECTION_SEPARATOR);
+    let volume_ls = sections.next().unwrap_or("");
+    let mounts = sections.next().unwrap_or("");
+    let system_df = sections.next().unwrap_or("");
+
+    // container name -> mounted volume names
+    let container_mounts: Vec<(&str, Vec<&str>)> = mounts
+        .lines()
+        .filter_map(|line| line.split_once('|'))
+        .map(|(name, mounts)| (name, mounts.split(',').map(str::trim).collect()))
+        .collect();
+
+    let sizes = parse_volume_sizes(system_df);
+
+    volume_ls
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split('|').collect();
+            if parts.len() < 3 || parts[0].is_empty() {
+                return None;
+            }
+            let name = parts[0].to_string();
+            let used_by = container_mounts
+                .iter()
+                .filter(|(_, mounts)| mounts.contains(&name.as_str()))
+                .map(|(container, _)| container.to_string())
+                .collect();
+            Some(DockerVolume {
+                size: sizes.iter().find(|(n, _)| *n == name).map(|(_, size)| size.to_string()),
+                name,
+                driver: parts[1].to_string(),
+                mountpoint: parts[2].to_string(),
+                used_by,
+            })
+        })
+        .collect()
+}
+
+/// Pull (name, size) pairs out of the "Local Volumes space usage" table
+fn parse_volume_sizes(system_df: &str) -> Vec<(&str, &str)> {
+    system_df
+        .lines()
+        .skip_while(|line| !line.starts_with("Local Volumes"))
+        .skip(1)
+        .map(str::trim)
+        .skip_while(|line| line.is_empty())
+        .skip(1) // column header
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            match (parts.first(), parts.last()) {
+                (Some(name), Some(size)) if parts.len() >= 3 => Some((*name, *size)),
+                _ => None,
+            }
+        })
+        .collect()
+}
 
-        // First char indicates type: d=directory, -=file, l=link
-        let perms = parts[0];
-        let is_dir = perms.starts_with('d');
+/// Parse `docker network inspect --format '{{.Id}}|{{.Name}}|{{.Driver}}|{{.Scope}}|<subnets>|<containers>'`
+pub fn parse_docker_networks(output: &str) -> Vec<DockerNetwork> {
+    let mut networks: Vec<DockerNetwork> = output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split('|').collect();
+            if parts.len() < 6 {
+                return None;
+            }
+            Some(DockerNetwork {
+                id: parts[0].chars().take(12).collect(),
+                name: parts[1].to_string(),
+                driver: parts[2].to_string(),
+                scope: parts[3].to_string(),
+                subnets: parts[4].split_whitespace().map(String::from).collect(),
+                containers: parts[5].split_whitespace().map(String::from).collect(),
+            })
+        })
+        .collect();
 
-        // Filename is everything after the 8th field (to handle spaces in names)
-        let name = parts[8..].join(" ");
+    networks.sort_by(|a, b| a.name.cmp(&b.name));
+    networks
+}
+
+#[derive(serde::Deserialize)]
+struct ComposeLsEntry {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Status", default)]
+    status: String,
+    #[serde(rename = "ConfigFiles", default)]
+    config_files: String,
+}
 
-        // Skip . and ..
-        if name == "." || name == ".." {
+/// Parse `docker compose ls --format json`, then (after `SECTION_SEPARATOR`)
+/// compose file paths found under the clients path. Files that no deployed
+/// project uses become "not deployed" projects named after their directory.
+pub fn parse_compose_projects(output: &str) -> Vec<ComposeProject> {
+    let (ls_output, files_output) = output.split_once(SECTION_SEPARATOR).unwrap_or((output, ""));
+
+    let mut projects: Vec<ComposeProject> = ls_output
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with('['))
+        .and_then(|json| serde_json::from_str::<Vec<ComposeLsEntry>>(json).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|entry| ComposeProject {
+            name: entry.name,
+            status: entry.status,
+            config_files: entry
+                .config_files
+                .split(',')
+                .map(str::trim)
+                .filter(|f| !f.is_empty())
+                .map(String::from)
+                .collect(),
+        })
+        .collect();
+
+    for file in files_output.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if projects.iter().any(|p| p.config_files.iter().any(|f| f == file)) {
             continue;
         }
+        let dir_name = std::path::Path::new(file)
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let name = ComposeProject::name_from_dir(&dir_name);
+        if name.is_empty() || projects.iter().any(|p| p.name == name) {
+            continue;
+        }
+        projects.push(ComposeProject {
+            name,
+            status: ComposeProject::NOT_DEPLOYED.to_string(),
+            config_files: vec![file.to_string()],
+        });
+    }
+
+    projects.sort_by(|a, b| a.name.cmp(&b.name));
+    projects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docker::EXEC_EXIT_MARKER;
+
+    #[test]
+    fn test_parse_exec_output() {
+        // What `printf '\n%s%d\n'` leaves after output that ends with a newline...
+        let output = format!("line one\nline two\n\n{}0\n", EXEC_EXIT_MARKER);
+        assert_eq!(parse_exec_output(&output), (vec!["line one".to_string(), "line two".to_string()], Some(0)));
+
+        // ...and after output that does not
+        let output = format!("{{\"ok\":true}}\n{}3\n", EXEC_EXIT_MARKER);
+        assert_eq!(parse_exec_output(&output), (vec!["{\"ok\":true}".to_string()], Some(3)));
+
+        // A blank line the command printed itself is kept
+        let output = format!("done\n\n\n{}0\n", EXEC_EXIT_MARKER);
+        assert_eq!(parse_exec_output(&output), (vec!["done".to_string(), String::new()], Some(0)));
 
-        entries.push(crate::models::FileEntry::new(name, is_dir));
+        let output = format!("\n{}127\n", EXEC_EXIT_MARKER);
+        assert_eq!(parse_exec_output(&output), (Vec::new(), Some(127)));
+
+        // Cut off before the marker
+        assert_eq!(parse_exec_output("partial\n"), (vec!["partial".to_string()], None));
     }
 
-    // Sort: directories first, then files, both alphabetically
-    entries[1..].sort_by(|a, b| {
-        match (a.is_dir, b.is_dir) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-        }
-    });
+    #[test]
+    fn test_parse_docker_images() {
+        let containers = parse_docker_ps(
+            "c1|web|nginx|Up 2 hours||\nc2|old-web|3f8a1b2c4d5e|Exited (0) 3 days ago||\nc3|pinned|redis@sha256:9f2c|Up 1 hour||\n",
+            "prod",
+        );
+        let output = "\
+9d1e0b7c2a4f|nginx|latest|187MB|2024-03-01 10:32:11 +0000 UTC
+3f8a1b2c4d5e|<none>|<none>|187MB|2024-01-15 08:00:00 +0000 UTC
+7b6c5d4e3f2a|redis|<none>|117MB|2024-02-20 12:00:00 +0000 UTC
+too|few|fields
+
+";
+        let images = parse_docker_images(output, &containers);
+        assert_eq!(images.len(), 3);
+
+        assert_eq!(images[0].reference(), "nginx:latest");
+        assert_eq!(images[0].created, "2024-03-01");
+        assert_eq!(images[0].used_by, vec!["web".to_string()]);
+
+        // Left behind when the tag moved; the container still shows its ID
+        assert!(images[1].is_dangling());
+        assert_eq!(images[1].reference(), "3f8a1b2c4d5e");
+        assert_eq!(images[1].used_by, vec!["old-web".to_string()]);
+
+        // Pulled by digest: it has a repository but no tag, and `redis` alone
+        // would name redis:latest, so only its ID refers to it
+        assert!(!images[2].is_dangling());
+        assert_eq!(images[2].reference(), "7b6c5d4e3f2a");
+        assert!(images[2].used_by.is_empty());
+    }
 
-    entries
+    #[test]
+    fn test_parse_image_update_check() {
+        let separator = crate::docker::IMAGE_DIGEST_SEPARATOR;
+        let remote = "Name:      docker.io/library/nginx:latest\nMediaType: application/vnd.oci.image.index.v1+json\nDigest:    sha256:aaa\n";
+
+        let output = format!("nginx@sha256:bbb\nnginx@sha256:aaa\n{}\n{}", separator, remote);
+        assert_eq!(parse_image_update_check(&output), ImageUpdateStatus::UpToDate);
+
+        let output = format!("nginx@sha256:bbb\n{}\n{}", separator, remote);
+        assert_eq!(parse_image_update_check(&output), ImageUpdateStatus::UpdateAvailable);
+
+        // Built locally: no repo digests to compare
+        let output = format!("\n{}\n{}", separator, remote);
+        assert_eq!(
+            parse_image_update_check(&output),
+            ImageUpdateStatus::Unknown("image was not pulled from a registry".to_string())
+        );
+
+        let output = format!("nginx@sha256:bbb\n{}\nERROR: docker: 'buildx' is not a docker command.\n", separator);
+        assert_eq!(
+            parse_image_update_check(&output),
+            ImageUpdateStatus::Unknown("ERROR: docker: 'buildx' is not a docker command.".to_string())
+        );
+
+        assert!(matches!(parse_image_update_check("nginx@sha256:bbb\n"), ImageUpdateStatus::Unknown(_)));
+    }
+
+    const SYSTEM_DF: &str = "\
+Images space usage:
+
+REPOSITORY   TAG       IMAGE ID       CREATED       SIZE      SHARED SIZE   UNIQUE SIZE   CONTAINERS
+postgres     16        1a2b3c4d5e6f   2 weeks ago   432MB     0B            432MB         1
+
+Containers space usage:
+
+CONTAINER ID   IMAGE         COMMAND                  LOCAL VOLUMES   SIZE      CREATED       STATUS       NAMES
+0f1e2d3c4b5a   postgres:16   \"docker-entrypoint.s…\"   1               63B       2 weeks ago   Up 2 weeks   db
+
+Local Volumes space usage:
+
+VOLUME NAME                                                        LINKS     SIZE
+pgdata                                                             1         48.2MB
+9c8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a2b1c0d9e8f7a6b5c4d3e2f1a0b9c8d   0         0B
+truncated
+
+Build cache usage: 0B
+";
+
+    #[test]
+    fn test_parse_volume_sizes() {
+        assert_eq!(
+            parse_volume_sizes(SYSTEM_DF),
+            vec![("pgdata", "48.2MB"), ("9c8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a2b1c0d9e8f7a6b5c4d3e2f1a0b9c8d", "0B")]
+        );
+        // An older docker without the table, or an error in its place
+        assert!(parse_volume_sizes("").is_empty());
+        assert!(parse_volume_sizes("Cannot connect to the Docker daemon\n").is_empty());
+    }
+
+    #[test]
+    fn test_parse_docker_volumes() {
+        let anonymous = "9c8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a2b1c0d9e8f7a6b5c4d3e2f1a0b9c8d";
+        let output = format!(
+            "pgdata|local|/var/lib/docker/volumes/pgdata/_data\n{anon}|local|/var/lib/docker/volumes/{anon}/_data\ncache|local|/var/lib/docker/volumes/cache/_data\nno-fields\n|local|/nowhere\n\
+{sep}\ndb|pgdata,/srv/db/conf\nbackup|pgdata\nworker|\n{sep}\n{df}",
+            anon = anonymous,
+            sep = SECTION_SEPARATOR,
+            df = SYSTEM_DF
+        );
+
+        let volumes = parse_docker_volumes(&output);
+        let names: Vec<&str> = volumes.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["pgdata", anonymous, "cache"]);
+
+        assert_eq!(volumes[0].used_by, vec!["db".to_string(), "backup".to_string()]);
+        assert_eq!(volumes[0].size.as_deref(), Some("48.2MB"));
+        assert_eq!(volumes[0].mountpoint, "/var/lib/docker/volumes/pgdata/_data");
+
+        assert!(volumes[1].is_anonymous() && volumes[1].is_orphaned());
+        assert_eq!(volumes[1].short_name(), "9c8d7e6f5a4b");
+        assert_eq!(volumes[1].size.as_deref(), Some("0B"));
+
+        // Not in the df table: size unknown rather than zero
+        assert_eq!(volumes[2].size, None);
+
+        // Without the mounts and df sections every volume still lists
+        assert_eq!(parse_docker_volumes("pgdata|local|/data\n").len(), 1);
+    }
+
+    #[test]
+    fn test_parse_docker_networks() {
+        let output = "\
+f3c9a1b2d4e5f60718293a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c4d|proxy|bridge|local|172.20.0.0/16 fd00:20::/64 |web api |
+0a1b2c3d4e5f|bridge|bridge|local|172.17.0.0/16 ||
+9e8d7c6b5a4f|host|host|local|||
+Error response from daemon: network 1234 not found
+short|fields|only
+";
+        let networks = parse_docker_networks(output);
+        let names: Vec<&str> = networks.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["bridge", "host", "proxy"]);
+
+        let proxy = &networks[2];
+        assert_eq!(proxy.id, "f3c9a1b2d4e5");
+        assert_eq!(proxy.subnets, vec!["172.20.0.0/16".to_string(), "fd00:20::/64".to_string()]);
+        assert_eq!(proxy.containers, vec!["web".to_string(), "api".to_string()]);
+        assert!(!proxy.is_builtin());
+
+        assert!(networks[0].containers.is_empty());
+        assert!(networks[1].is_builtin() && networks[1].subnets.is_empty());
+    }
+
+    #[test]
+    fn test_parse_compose_projects() {
+        let ls = r#"[{"Name":"shop","Status":"running(3)","ConfigFiles":"/srv/clients/shop/compose.yml,/srv/clients/shop/compose.prod.yml"},{"Name":"wiki","Status":"exited(1)","ConfigFiles":""}]"#;
+        let files = "\
+/srv/clients/shop/compose.yml
+/srv/clients/My.Blog/docker-compose.yml
+
+/srv/clients/wiki/docker-compose.yaml
+/docker-compose.yml
+";
+        // Compose may warn before the JSON line
+        let output = format!("WARN[0000] version is obsolete\n{}\n{}\n{}", ls, SECTION_SEPARATOR, files);
+
+        let projects = parse_compose_projects(&output);
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["myblog", "shop", "wiki"]);
+
+        // A file a deployed project already uses is not listed again
+        assert!(projects[1].is_running());
+        assert_eq!(projects[1].config_files.len(), 2);
+
+        // A stopped project keeps its name over a file that would take it
+        assert!(projects[2].is_deployed() && !projects[2].is_running());
+        assert!(projects[2].config_files.is_empty());
+
+        assert!(!projects[0].is_deployed());
+        assert_eq!(projects[0].config_files, vec!["/srv/clients/My.Blog/docker-compose.yml".to_string()]);
+
+        // Without a readable `compose ls` the files still show
+        let output = format!("docker: 'compose' is not a docker command.\n{}\n{}", SECTION_SEPARATOR, files);
+        let names: Vec<String> = parse_compose_projects(&output).into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["myblog", "shop", "wiki"]);
+        assert!(parse_compose_projects("").is_empty());
+    }
 }
@@ -0,0 +1,211 @@
+//! Checks run on a deployment script before it is saved or run. The local
+//! checks use what is already known about the host; the remote ones need a
+//! round trip (syntax, bind mount sources and the image)
+
+use std::collections::HashSet;
+
+use crate::models::{Container, ContainerStatus, DeploymentScript, LintIssue};
+use crate::utils::shell::quote_path;
+use super::discovery::{base64_encode, remote_script};
+use super::script_parser::{env_key, script_image, ParsedScript};
+
+const SYNTAX_PREFIX: &str = "SYNTAX ";
+const MISSING_PREFIX: &str = "MISSING ";
+const UNRESOLVED_MARKER: &str = "UNRESOLVED";
+
+/// Checks that need no remote access: duplicate env keys, host ports already
+/// taken by other containers or scripts on the host, and a missing restart policy
+pub fn lint_script(script: &DeploymentScript, containers: &[Container], scripts: &[DeploymentScript]) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+
+    let parsed = ParsedScript::parse(&script.raw_content);
+    if let Some(run) = parsed.primary_run() {
+        let mut seen = HashSet::new();
+        let mut reported = HashSet::new();
+        for key in run.options.iter().filter(|o| o.name == "env").filter_map(env_key) {
+            if !seen.insert(key) && reported.insert(key) {
+                issues.push(LintIssue::warning(format!("Env var {} is set more than once (the last value wins)", key)));
+            }
+        }
+    }
+
+    let mut own_ports = HashSet::new();
+    for port in &script.ports {
+        let label = format!("{}/{}", port.host_port, port.protocol);
+        if !own_ports.insert((port.host_port, port.protocol.as_str())) {
+            issues.push(LintIssue::error(format!("Host port {} is published twice by this script", label)));
+            continue;
+        }
+
+        // The container the script replaces is expected to hold its own ports
+        for container in containers.iter().filter(|c| c.name != script.container_name) {
+            if !container.ports.iter().any(|p| p.host_port == port.host_port && p.protocol == port.protocol) {
+                continue;
+            }
+            if container.status == ContainerStatus::Running {
+                issues.push(LintIssue::error(format!("Host port {} is already published by running container {}", label, container.name)));
+            } else {
+                issues.push(LintIssue::warning(format!("Host port {} is also published by stopped container {}", label, container.name)));
+            }
+        }
+
+        for other in scripts.iter().filter(|s| s.path != script.path && s.container_name != script.container_name) {
+            if other.ports.iter().any(|p| p.host_port == port.host_port && p.protocol == port.protocol) {
+                issues.push(LintIssue::warning(format!("Host port {} is also published by script {}", label, other.path)));
+            }
+        }
+    }
+
+    // Values from variables or files are fine; literal ones end up wherever the script is copied
+    for env in script.env_vars.iter().filter(|e| e.is_secret && !e.value.is_empty() && !e.value.contains(['$', '`'])) {
+        issues.push(LintIssue::warning(format!(
+            "{} looks like a secret and is written into the script in plain text ([m] in the editor moves it out)",
+            env.key
+        )));
+    }
+
+    if script.restart_policy.is_none() {
+        issues.push(LintIssue::warning("No --restart policy: the container will not come back after a reboot"));
+    }
+
+    issues
+}
+
+/// Remote checks: `bash -n` on the script content, bind mount sources that do
+/// not exist and an image that is neither local nor in its registry. Relative
+/// paths resolve against the script's directory, where `run_script_command` runs it
+pub fn lint_command(script: &DeploymentScript) -> String {
+    let dir = script.path.rsplit_once('/').map(|(dir, _)| if dir.is_empty() { "/" } else { dir }).unwrap_or(".");
+
+    let mut lines = vec![
+        format!("cd {} 2>/dev/null", quote_path(dir)),
+        "if command -v bash >/dev/null 2>&1; then sh_bin=bash; else sh_bin=sh; fi".to_string(),
+        format!(
+            "printf %s {} | base64 -d | $sh_bin -n 2>&1 | sed 's/^/{}/'",
+            base64_encode(script.raw_content.as_bytes()),
+            SYNTAX_PREFIX
+        ),
+    ];
+
+    for (i, volume) in script.volumes.iter().enumerate() {
+        if is_bind_source(&volume.host_path) {
+            lines.push(format!("[ -e {} ] || echo '{}{}'", quote_path(&volume.host_path), MISSING_PREFIX, i));
+        }
+    }
+
+    if let Some(image) = script_image(script) {
+        let image = quote_path(&image);
+        lines.push(format!(
+            "docker image inspect {image} >/dev/null 2>&1 || docker manifest inspect {image} >/dev/null 2>&1 || echo {}",
+            UNRESOLVED_MARKER
+        ));
+    }
+
+    lines.push("true".to_string());
+    remote_script(&lines.join("\n"))
+}
+
+/// Turn the output of `lint_command` into issues for the same script
+pub fn parse_lint_output(output: &str, script: &DeploymentScript) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+
+    for line in output.lines() {
+        if let Some(message) = line.strip_prefix(SYNTAX_PREFIX) {
+            // `bash: line 3: syntax error ...` when read from stdin
+            let message = message.strip_prefix("bash: ").or_else(|| message.strip_prefix("sh: ")).unwrap_or(message);
+            issues.push(LintIssue::error(format!("Syntax: {}", message.trim())));
+        } else if let Some(index) = line.strip_prefix(MISSING_PREFIX) {
+            if let Some(volume) = index.trim().parse::<usize>().ok().and_then(|i| script.volumes.get(i)) {
+                issues.push(LintIssue::warning(format!(
+                    "Volume source {} does not exist (docker will create it as an empty directory)",
+                    volume.host_path
+                )));
+            }
+        } else if line.trim() == UNRESOLVED_MARKER {
+            if let Some(image) = script_image(script) {
+                issues.push(LintIssue::warning(format!("Image {} was not found locally or in its registry", image)));
+            }
+        }
+    }
+
+    issues
+}
+
+/// Errors first, keeping the order in which the checks found them
+pub fn sort_issues(issues: &mut [LintIssue]) {
+    issues.sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+}
+
+/// `-v` sources that are paths rather than named volumes
+fn is_bind_source(host_path: &str) -> bool {
+    host_path.starts_with(['/', '.', '~', '$'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docker::script_parser::parse_script;
+    use crate::models::PortMapping;
+
+    fn container(name: &str, status: ContainerStatus, host_port: u16) -> Container {
+        Container {
+            id: name.to_string(),
+            name: name.to_string(),
+            image: "nginx".to_string(),
+            status,
+            ports: vec![PortMapping { host_port, container_port: 80, protocol: "tcp".to_string(), host_ip: None }],
+            created: None,
+            server_name: "server".to_string(),
+            script_path: None,
+            networks: Vec::new(),
+            compose_project: None,
+        }
+    }
+
+    #[test]
+    fn test_lint_script() {
+        let content = "docker run -d --name web -e A=1 -e B=2 -e A=3 -p 8080:80 -p 8443:443 -p 8443:443 nginx\n";
+        let script = parse_script(content, "/srv/web/start.sh", "web");
+        let containers = vec![
+            container("web", ContainerStatus::Running, 8080),
+            container("api", ContainerStatus::Running, 8080),
+            container("old", ContainerStatus::Exited(0), 8443),
+        ];
+
+        let mut issues = lint_script(&script, &containers, &[]);
+        sort_issues(&mut issues);
+        let messages: Vec<_> = issues.iter().map(|i| (i.is_error(), i.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            vec![
+                (true, "Host port 8080/tcp is already published by running container api"),
+                (true, "Host port 8443/tcp is published twice by this script"),
+                (false, "Env var A is set more than once (the last value wins)"),
+                (false, "Host port 8443/tcp is also published by stopped container old"),
+                (false, "No --restart policy: the container will not come back after a reboot"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_lint_output() {
+        let content = "docker run -d --name web --restart always -v /srv/data:/data -v logs:/logs -v ./conf:/conf nginx:1.25\n";
+        let script = parse_script(content, "/srv/web/start.sh", "web");
+
+        let command = lint_command(&script);
+        assert!(command.starts_with("sh -c 'printf %s "));
+
+        let output = "SYNTAX bash: line 2: syntax error: unexpected end of file\nMISSING 2\nUNRESOLVED\n";
+        let issues = parse_lint_output(output, &script);
+        let messages: Vec<_> = issues.iter().map(|i| i.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Syntax: line 2: syntax error: unexpected end of file",
+                "Volume source ./conf does not exist (docker will create it as an empty directory)",
+                "Image nginx:1.25 was not found locally or in its registry",
+            ]
+        );
+        assert!(issues[0].is_error());
+    }
+}
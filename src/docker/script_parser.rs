@@ -1,229 +1,584 @@
-use regex::Regex;
-
-use crate::models::{DeploymentScript, EnvVar, PortMapping, VolumeMount};
+use std::collections::HashMap;
+
+use crate::models::{DeploymentScript, EnvVar, HealthCheck, PortMapping, VolumeMount};
+use crate::utils::shell::{quote, quote_path};
+
+use super::shell_lexer::{self, expand_parts, Command, Span, Word};
+
+/// `docker run`/`docker create` options that take a value, as (long name, short name)
+const VALUE_FLAGS: &[(&str, Option<char>)] = &[
+    ("add-host", None),
+    ("annotation", None),
+    ("attach", Some('a')),
+    ("blkio-weight", None),
+    ("blkio-weight-device", None),
+    ("cap-add", None),
+    ("cap-drop", None),
+    ("cgroup-parent", None),
+    ("cgroupns", None),
+    ("cidfile", None),
+    ("cpu-count", None),
+    ("cpu-percent", None),
+    ("cpu-period", None),
+    ("cpu-quota", None),
+    ("cpu-rt-period", None),
+    ("cpu-rt-runtime", None),
+    ("cpu-shares", Some('c')),
+    ("cpus", None),
+    ("cpuset-cpus", None),
+    ("cpuset-mems", None),
+    ("detach-keys", None),
+    ("device", None),
+    ("device-cgroup-rule", None),
+    ("device-read-bps", None),
+    ("device-read-iops", None),
+    ("device-write-bps", None),
+    ("device-write-iops", None),
+    ("dns", None),
+    ("dns-option", None),
+    ("dns-search", None),
+    ("domainname", None),
+    ("entrypoint", None),
+    ("env", Some('e')),
+    ("env-file", None),
+    ("expose", None),
+    ("gpus", None),
+    ("group-add", None),
+    ("health-cmd", None),
+    ("health-interval", None),
+    ("health-retries", None),
+    ("health-start-interval", None),
+    ("health-start-period", None),
+    ("health-timeout", None),
+    ("hostname", Some('h')),
+    ("io-maxbandwidth", None),
+    ("io-maxiops", None),
+    ("ip", None),
+    ("ip6", None),
+    ("ipc", None),
+    ("isolation", None),
+    ("kernel-memory", None),
+    ("label", Some('l')),
+    ("label-file", None),
+    ("link", None),
+    ("link-local-ip", None),
+    ("log-driver", None),
+    ("log-opt", None),
+    ("mac-address", None),
+    ("memory", Some('m')),
+    ("memory-reservation", None),
+    ("memory-swap", None),
+    ("memory-swappiness", None),
+    ("mount", None),
+    ("name", None),
+    ("network", None),
+    ("network-alias", None),
+    ("oom-score-adj", None),
+    ("pid", None),
+    ("pids-limit", None),
+    ("platform", None),
+    ("publish", Some('p')),
+    ("pull", None),
+    ("restart", None),
+    ("runtime", None),
+    ("security-opt", None),
+    ("shm-size", None),
+    ("stop-signal", None),
+    ("stop-timeout", None),
+    ("storage-opt", None),
+    ("sysctl", None),
+    ("tmpfs", None),
+    ("ulimit", None),
+    ("user", Some('u')),
+    ("userns", None),
+    ("uts", None),
+    ("volume", Some('v')),
+    ("volume-driver", None),
+    ("volumes-from", None),
+    ("workdir", Some('w')),
+];
+
+/// Boolean `docker run`/`docker create` options, as (long name, short name)
+const BOOL_FLAGS: &[(&str, Option<char>)] = &[
+    ("detach", Some('d')),
+    ("disable-content-trust", None),
+    ("help", None),
+    ("init", None),
+    ("interactive", Some('i')),
+    ("no-healthcheck", None),
+    ("oom-kill-disable", None),
+    ("privileged", None),
+    ("publish-all", Some('P')),
+    ("quiet", Some('q')),
+    ("read-only", None),
+    ("rm", None),
+    ("sig-proxy", None),
+    ("tty", Some('t')),
+    ("use-api-socket", None),
+];
+
+/// Deprecated spellings accepted by docker
+const FLAG_ALIASES: &[(&str, &str)] = &[("net", "network"), ("net-alias", "network-alias")];
+
+/// Global `docker` options (before the subcommand) that take a value
+const GLOBAL_VALUE_FLAGS: &[&str] = &["--config", "--context", "-c", "--host", "-H", "--log-level", "-l"];
+
+/// One option of a `docker run`/`docker create` command
+#[derive(Debug, Clone)]
+pub struct RunOption {
+    /// Canonical long name without dashes, e.g. `env` for `-e`
+    pub name: &'static str,
+    /// Expanded value, for options that take one
+    pub value: Option<String>,
+    /// The option as written, including its value
+    pub span: Span,
+    /// Just the value as written, when it can be replaced on its own
+    pub value_span: Option<Span>,
+}
 
-/// Parse a deployment script to extract configuration
-pub fn parse_script(content: &str, path: &str, client_name: &str) -> DeploymentScript {
-    let mut script = DeploymentScript::new(path.to_string(), client_name.to_string());
-    script.raw_content = content.to_string();
+/// A `docker run` or `docker create` invocation found in a script
+#[derive(Debug, Clone)]
+pub struct DockerRun {
+    pub options: Vec<RunOption>,
+    pub image: Option<(String, Span)>,
+    pub args: Vec<String>,
+}
 
-    // Extract NAME variable first, then fall back to --name flag
-    if let Some(name) = extract_variable(content, "NAME") {
-        script.container_name = name;
-    } else if let Some(name) = extract_container_name_flag(content) {
-        script.container_name = name;
+impl DockerRun {
+    /// Value of the last option with the given canonical name
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.options.iter().rev().filter(|o| o.name == name).find_map(|o| o.value.as_deref())
     }
+}
+
+/// Result of walking a script: variables it assigns, the containers it runs
+/// and the networks it connects them to afterwards
+#[derive(Debug, Clone, Default)]
+pub struct ParsedScript {
+    pub variables: HashMap<String, String>,
+    pub runs: Vec<DockerRun>,
+    /// `docker network connect NETWORK CONTAINER`, as (network, container)
+    pub connects: Vec<(String, String)>,
+}
+
+impl ParsedScript {
+    pub fn parse(content: &str) -> Self {
+        let mut parsed = Self::default();
+
+        for command in shell_lexer::parse_commands(content) {
+            if let Some(assignments) = assignments(&command) {
+                for (name, value) in assignments {
+                    let value = expand_parts(&value, &parsed.variables);
+                    parsed.variables.insert(name, value);
+                }
+            } else if let Some(run) = parse_docker_run(content, &command, &parsed.variables) {
+                parsed.runs.push(run);
+            } else if let Some(connect) = parse_network_connect(&command, &parsed.variables) {
+                parsed.connects.push(connect);
+            }
+        }
 
-    // Extract REPO variable, or try to get image from docker command
-    if let Some(repo) = extract_variable(content, "REPO") {
-        script.repo = repo;
-    } else if let Some(image) = extract_docker_image(content) {
-        script.repo = image;
+        parsed
     }
 
-    // Extract environment variables
-    script.env_vars = extract_env_vars(content);
+    /// The invocation that creates the script's container: the one named after
+    /// `$NAME`, else the first with a `--name`, else the first one
+    pub fn primary_run(&self) -> Option<&DockerRun> {
+        let name = self.variables.get("NAME");
+        self.runs
+            .iter()
+            .find(|r| name.is_some() && r.value("name") == name.map(String::as_str))
+            .or_else(|| self.runs.iter().find(|r| r.value("name").is_some()))
+            .or_else(|| self.runs.first())
+    }
+}
 
-    // Extract volume mounts
-    script.volumes = extract_volumes(content);
+/// Assignments made by a command consisting only of `NAME=value` words, or
+/// by `export`/`readonly`/`local`/`declare`
+fn assignments(command: &Command) -> Option<Vec<(String, Vec<shell_lexer::WordPart>)>> {
+    let mut words = command.words.as_slice();
+    if let Some(first) = words.first() {
+        if matches!(first.text().as_str(), "export" | "readonly" | "local" | "declare" | "typeset") {
+            words = &words[1..];
+            while words.first().is_some_and(|w| w.text().starts_with('-')) {
+                words = &words[1..];
+            }
+            return Some(words.iter().filter_map(Word::as_assignment).collect());
+        }
+    }
 
-    // Extract port mappings
-    script.ports = extract_ports(content);
+    let assigned: Vec<_> = words.iter().map(Word::as_assignment).collect();
+    if assigned.iter().all(Option::is_some) {
+        Some(assigned.into_iter().flatten().collect())
+    } else {
+        None
+    }
+}
 
-    // Extract network
-    script.network = extract_network(content);
+/// Index of the first word after `[sudo ...] docker [global opts]`
+fn docker_args_start(words: &[Word], texts: &[String]) -> Option<usize> {
+    // Skip environment prefixes and wrappers
+    let mut i = 0;
+    while i < words.len() && words[i].as_assignment().is_some() {
+        i += 1;
+    }
+    while i < texts.len() && matches!(texts[i].as_str(), "sudo" | "exec" | "command" | "time" | "nohup") {
+        i += 1;
+        while i < texts.len() && texts[i].starts_with('-') {
+            i += 1;
+        }
+    }
 
-    // Extract restart policy
-    script.restart_policy = extract_restart_policy(content);
+    if texts.get(i).map(|t| t.rsplit('/').next().unwrap_or(t)) != Some("docker") {
+        return None;
+    }
+    i += 1;
 
-    script
+    while i < texts.len() && texts[i].starts_with('-') {
+        let takes_value = GLOBAL_VALUE_FLAGS.contains(&texts[i].as_str());
+        i += if takes_value { 2 } else { 1 };
+    }
+    Some(i)
 }
 
-/// Extract a shell variable assignment: NAME='value' or NAME="value"
-fn extract_variable(content: &str, var_name: &str) -> Option<String> {
-    let patterns = [
-        format!(r#"{}='([^']*)'"#, var_name),
-        format!(r#"{}="([^"]*)""#, var_name),
-        format!(r#"{}=(\S+)"#, var_name),
-    ];
+/// Recognise `docker network connect [opts] NETWORK CONTAINER`
+fn parse_network_connect(command: &Command, vars: &HashMap<String, String>) -> Option<(String, String)> {
+    let texts: Vec<String> = command.words.iter().map(|w| w.expand(vars)).collect();
+    let i = docker_args_start(&command.words, &texts)?;
+    if texts.get(i..i + 2)? != ["network", "connect"] {
+        return None;
+    }
 
-    for pattern in &patterns {
-        if let Ok(re) = Regex::new(pattern) {
-            if let Some(caps) = re.captures(content) {
-                if let Some(value) = caps.get(1) {
-                    return Some(value.as_str().to_string());
-                }
+    // --alias, --ip, --ip6, --link and --link-local-ip all take a value
+    let mut positional = Vec::new();
+    let mut rest = texts[i + 2..].iter();
+    while let Some(text) = rest.next() {
+        if text.starts_with('-') {
+            if !text.contains('=') {
+                rest.next();
             }
+        } else {
+            positional.push(text.clone());
         }
     }
-    None
+    match <[String; 2]>::try_from(positional) {
+        Ok([network, container]) => Some((network, container)),
+        Err(_) => None,
+    }
 }
 
-/// Extract environment variables from -e flags
-fn extract_env_vars(content: &str) -> Vec<EnvVar> {
-    let mut env_vars = Vec::new();
+/// Recognise `[sudo ...] docker [global opts] [container] run|create ...`
+fn parse_docker_run(src: &str, command: &Command, vars: &HashMap<String, String>) -> Option<DockerRun> {
+    let words = &command.words;
+    let texts: Vec<String> = words.iter().map(|w| w.expand(vars)).collect();
+    let mut i = docker_args_start(words, &texts)?;
+
+    if texts.get(i).map(String::as_str) == Some("container") {
+        i += 1;
+    }
+    if !matches!(texts.get(i).map(String::as_str), Some("run" | "create")) {
+        return None;
+    }
+    i += 1;
+
+    let mut run = DockerRun { options: Vec::new(), image: None, args: Vec::new() };
 
-    let patterns = [
-        r#"-e\s+([A-Za-z_][A-Za-z0-9_]*)="([^"]*)""#,
-        r#"-e\s+([A-Za-z_][A-Za-z0-9_]*)='([^']*)'"#,
-        r#"-e\s*([A-Za-z_][A-Za-z0-9_]*)=(\S+)"#,
-        r#"-e([A-Za-z_][A-Za-z0-9_]*)=(\S+)"#,
-    ];
+    while i < words.len() {
+        let word = &words[i];
+        let text = &texts[i];
+
+        if run.image.is_some() {
+            run.args.push(text.clone());
+            i += 1;
+            continue;
+        }
 
-    for pattern in &patterns {
-        if let Ok(re) = Regex::new(pattern) {
-            for caps in re.captures_iter(content) {
-                if let (Some(key), Some(value)) = (caps.get(1), caps.get(2)) {
-                    let key_str = key.as_str().to_string();
-                    let value_str = value.as_str().to_string();
+        if text == "--" {
+            i += 1;
+            if let Some(image) = words.get(i) {
+                run.image = Some((texts[i].clone(), image.span));
+                i += 1;
+            }
+            continue;
+        }
+
+        if let Some(long) = text.strip_prefix("--") {
+            let (flag, inline) = match long.split_once('=') {
+                Some((flag, value)) => (flag, Some(value)),
+                None => (long, None),
+            };
+            let Some((name, takes_value)) = lookup_long(flag) else {
+                // Unknown flag: assume it is boolean unless written with a value
+                i += 1;
+                continue;
+            };
+
+            if !takes_value {
+                run.options.push(RunOption { name, value: None, span: word.span, value_span: None });
+                i += 1;
+            } else if let Some(value) = inline {
+                let prefix = text.len() - value.len();
+                run.options.push(RunOption {
+                    name,
+                    value: Some(value.to_string()),
+                    span: word.span,
+                    value_span: sub_span(src, word, &text[..prefix]),
+                });
+                i += 1;
+            } else {
+                run.options.push(value_option(name, word, words.get(i + 1), texts.get(i + 1)));
+                i += 2;
+            }
+            continue;
+        }
 
-                    // Avoid duplicates
-                    if !env_vars.iter().any(|e: &EnvVar| e.key == key_str) {
-                        env_vars.push(EnvVar::new(key_str, value_str));
+        if text.len() > 1 && text.starts_with('-') {
+            // Cluster of short options, e.g. -dit or -e KEY=value or -eKEY=value
+            let mut consumed = 1;
+            for (offset, c) in text.char_indices().skip(1) {
+                match lookup_short(c) {
+                    Some((name, true)) => {
+                        let rest = &text[offset + c.len_utf8()..];
+                        if rest.is_empty() {
+                            run.options.push(value_option(name, word, words.get(i + 1), texts.get(i + 1)));
+                            consumed = 2;
+                        } else {
+                            run.options.push(RunOption {
+                                name,
+                                value: Some(rest.to_string()),
+                                span: word.span,
+                                value_span: sub_span(src, word, &text[..text.len() - rest.len()]),
+                            });
+                        }
+                        break;
+                    }
+                    Some((name, false)) => {
+                        run.options.push(RunOption { name, value: None, span: word.span, value_span: None });
                     }
+                    None => {}
                 }
             }
+            i += consumed;
+            continue;
         }
+
+        run.image = Some((text.clone(), word.span));
+        i += 1;
     }
 
-    env_vars
+    Some(run)
 }
 
-/// Extract volume mounts from -v flags
-fn extract_volumes(content: &str) -> Vec<VolumeMount> {
-    let mut volumes = Vec::new();
+fn lookup_long(flag: &str) -> Option<(&'static str, bool)> {
+    let flag = FLAG_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == flag)
+        .map(|(_, canonical)| *canonical)
+        .unwrap_or(flag);
+    VALUE_FLAGS
+        .iter()
+        .find(|(name, _)| *name == flag)
+        .map(|(name, _)| (*name, true))
+        .or_else(|| BOOL_FLAGS.iter().find(|(name, _)| *name == flag).map(|(name, _)| (*name, false)))
+}
 
-    let re = Regex::new(r#"-v\s+([^:\s]+):([^:\s]+)(:ro)?"#).unwrap();
+fn lookup_short(c: char) -> Option<(&'static str, bool)> {
+    VALUE_FLAGS
+        .iter()
+        .find(|(_, short)| *short == Some(c))
+        .map(|(name, _)| (*name, true))
+        .or_else(|| BOOL_FLAGS.iter().find(|(_, short)| *short == Some(c)).map(|(name, _)| (*name, false)))
+}
 
-    for caps in re.captures_iter(content) {
-        if let (Some(host), Some(container)) = (caps.get(1), caps.get(2)) {
-            let read_only = caps.get(3).is_some();
-            volumes.push(VolumeMount {
-                host_path: host.as_str().to_string(),
-                container_path: container.as_str().to_string(),
-                read_only,
-            });
-        }
+/// An option whose value is the following word
+fn value_option(name: &'static str, flag: &Word, value: Option<&Word>, text: Option<&String>) -> RunOption {
+    match value {
+        Some(value) => RunOption {
+            name,
+            value: text.cloned(),
+            span: Span { start: flag.span.start, end: value.span.end },
+            value_span: Some(value.span),
+        },
+        None => RunOption { name, value: None, span: flag.span, value_span: None },
     }
+}
 
-    volumes
+/// Span of the part of `word` after `prefix`, if the prefix is written literally
+fn sub_span(src: &str, word: &Word, prefix: &str) -> Option<Span> {
+    let start = word.span.start + prefix.len();
+    (src.get(word.span.start..start) == Some(prefix)).then_some(Span { start, end: word.span.end })
 }
 
-/// Extract port mappings from -p flags
-fn extract_ports(content: &str) -> Vec<PortMapping> {
-    let mut ports = Vec::new();
+/// Parse a deployment script to extract configuration
+pub fn parse_script(content: &str, path: &str, client_name: &str) -> DeploymentScript {
+    let mut script = DeploymentScript::new(path.to_string(), client_name.to_string());
+    script.raw_content = content.to_string();
 
-    let re = Regex::new(r#"-p\s+(\d+):(\d+)(/\w+)?"#).unwrap();
+    let parsed = ParsedScript::parse(content);
+    let run = parsed.primary_run();
 
-    for caps in re.captures_iter(content) {
-        if let (Some(host), Some(container)) = (caps.get(1), caps.get(2)) {
-            if let (Ok(host_port), Ok(container_port)) = (
-                host.as_str().parse::<u16>(),
-                container.as_str().parse::<u16>(),
-            ) {
-                let protocol = caps
-                    .get(3)
-                    .map(|p| p.as_str().trim_start_matches('/').to_string())
-                    .unwrap_or_else(|| "tcp".to_string());
+    // NAME variable takes precedence over the --name flag
+    if let Some(name) = parsed.variables.get("NAME") {
+        script.container_name = name.clone();
+    } else if let Some(name) = run.and_then(|r| r.value("name")).filter(|n| !n.starts_with('$')) {
+        script.container_name = name.to_string();
+    }
 
-                ports.push(PortMapping {
-                    host_port,
-                    container_port,
-                    protocol,
-                });
-            }
-        }
+    // REPO variable takes precedence over the image of the docker command
+    if let Some(repo) = parsed.variables.get("REPO") {
+        script.repo = repo.clone();
+    } else if let Some((image, _)) = run.and_then(|r| r.image.as_ref()).filter(|(i, _)| !i.starts_with('$')) {
+        script.repo = image.clone();
     }
 
-    ports
+    let Some(run) = run else {
+        return script;
+    };
+
+    script.env_vars = env_entries(run, &parsed.variables).into_iter().map(|(env, _)| env).collect();
+    script.volumes = volume_entries(run).into_iter().map(|(volume, _)| volume).collect();
+    script.ports = port_entries(run).into_iter().map(|(port, _)| port).collect();
+    script.network = run.value("network").map(str::to_string);
+    script.restart_policy = run.value("restart").map(str::to_string);
+
+    script.labels = run
+        .options
+        .iter()
+        .filter(|o| o.name == "label")
+        .filter_map(|o| o.value.as_deref())
+        .map(|label| {
+            let (key, value) = label.split_once('=').unwrap_or((label, ""));
+            (key.to_string(), value.to_string())
+        })
+        .collect();
+    script.env_files = run.options.iter().filter(|o| o.name == "env-file").filter_map(|o| o.value.clone()).collect();
+    script.user = run.value("user").map(str::to_string);
+    script.entrypoint = run.value("entrypoint").map(str::to_string);
+    script.command = run.args.clone();
+    script.memory = run.value("memory").map(str::to_string);
+    script.cpus = run.value("cpus").map(str::to_string);
+    script.healthcheck = run.value("health-cmd").map(|command| HealthCheck {
+        command: command.to_string(),
+        interval: run.value("health-interval").map(str::to_string),
+        timeout: run.value("health-timeout").map(str::to_string),
+        start_period: run.value("health-start-period").map(str::to_string),
+        retries: run.value("health-retries").and_then(|r| r.parse().ok()),
+    });
+    script.extra_networks = parsed
+        .connects
+        .iter()
+        .filter(|(_, container)| *container == script.container_name)
+        .map(|(network, _)| network.clone())
+        .collect();
+
+    script
 }
 
-/// Extract network from --net or --network flag
-fn extract_network(content: &str) -> Option<String> {
-    let patterns = [
-        r#"--net=(\S+)"#,
-        r#"--network=(\S+)"#,
-        r#"--net\s+(\S+)"#,
-        r#"--network\s+(\S+)"#,
-    ];
+/// The image the script runs, when it is known without running the script
+pub fn script_image(script: &DeploymentScript) -> Option<String> {
+    let parsed = ParsedScript::parse(&script.raw_content);
+    let image = parsed
+        .primary_run()
+        .and_then(|run| run.image.as_ref())
+        .map(|(image, _)| image.clone())
+        .unwrap_or_else(|| script.repo.clone());
+    (!image.is_empty() && !image.contains('$')).then_some(image)
+}
 
-    for pattern in &patterns {
-        if let Ok(re) = Regex::new(pattern) {
-            if let Some(caps) = re.captures(content) {
-                if let Some(network) = caps.get(1) {
-                    return Some(network.as_str().to_string());
-                }
-            }
+/// Environment variables from -e/--env with the option defining each. `-e KEY`
+/// takes the value from the script, and later definitions win as they do for docker
+fn env_entries<'a>(run: &'a DockerRun, vars: &HashMap<String, String>) -> Vec<(EnvVar, &'a RunOption)> {
+    let mut entries: Vec<(EnvVar, &RunOption)> = Vec::new();
+
+    for option in run.options.iter().filter(|o| o.name == "env") {
+        let Some(value) = option.value.as_deref() else {
+            continue;
+        };
+        let (key, value) = match value.split_once('=') {
+            Some((key, value)) => (key, value.to_string()),
+            None => (value, vars.get(value).cloned().unwrap_or_default()),
+        };
+        if key.is_empty() {
+            continue;
+        }
+
+        let entry = (EnvVar::new(key.to_string(), value), option);
+        match entries.iter_mut().find(|(e, _)| e.key == key) {
+            Some(existing) => *existing = entry,
+            None => entries.push(entry),
         }
     }
-    None
+
+    entries
 }
 
-/// Extract restart policy from --restart flag
-fn extract_restart_policy(content: &str) -> Option<String> {
-    let patterns = [
-        r#"--restart=(\S+)"#,
-        r#"--restart\s+(\S+)"#,
-    ];
+pub(super) fn env_key(option: &RunOption) -> Option<&str> {
+    option.value.as_deref()?.split('=').next()
+}
 
-    for pattern in &patterns {
-        if let Ok(re) = Regex::new(pattern) {
-            if let Some(caps) = re.captures(content) {
-                if let Some(m) = caps.get(1) {
-                    return Some(m.as_str().to_string());
-                }
-            }
-        }
-    }
-    None
+fn volume_entries(run: &DockerRun) -> Vec<(VolumeMount, &RunOption)> {
+    run.options.iter().filter_map(|o| Some((parse_volume_option(o)?, o))).collect()
 }
 
-/// Extract container name from --name flag (for scripts without NAME variable)
-fn extract_container_name_flag(content: &str) -> Option<String> {
-    let patterns = [
-        r#"--name=([^\s\\]+)"#,
-        r#"--name\s+([^\s\\]+)"#,
-    ];
+fn port_entries(run: &DockerRun) -> Vec<(PortMapping, &RunOption)> {
+    run.options
+        .iter()
+        .filter(|o| o.name == "publish")
+        .filter_map(|o| Some((parse_port(o.value.as_deref()?)?, o)))
+        .collect()
+}
 
-    for pattern in &patterns {
-        if let Ok(re) = Regex::new(pattern) {
-            if let Some(caps) = re.captures(content) {
-                if let Some(m) = caps.get(1) {
-                    let name = m.as_str().trim_matches('"').trim_matches('\'');
-                    // Skip if it's a variable reference like $NAME
-                    if !name.starts_with('$') {
-                        return Some(name.to_string());
-                    }
+/// Bind mounts from -v/--volume or --mount type=bind; anonymous volumes are skipped
+fn parse_volume_option(option: &RunOption) -> Option<VolumeMount> {
+    let value = option.value.as_deref()?;
+    match option.name {
+        "volume" => {
+            let mut parts = value.splitn(3, ':');
+            let host_path = parts.next()?.to_string();
+            let container_path = parts.next()?.to_string();
+            let read_only = parts.next().is_some_and(|opts| opts.split(',').any(|o| o == "ro"));
+            Some(VolumeMount { host_path, container_path, read_only })
+        }
+        "mount" => {
+            let mut kind = "volume";
+            let mut source = None;
+            let mut target = None;
+            let mut read_only = false;
+            for field in value.split(',') {
+                let (key, val) = field.split_once('=').unwrap_or((field, "true"));
+                match key {
+                    "type" => kind = val,
+                    "source" | "src" => source = Some(val),
+                    "target" | "destination" | "dst" => target = Some(val),
+                    "readonly" | "ro" => read_only = matches!(val, "true" | "1"),
+                    _ => {}
                 }
             }
+            if kind == "tmpfs" {
+                return None;
+            }
+            Some(VolumeMount {
+                host_path: source?.to_string(),
+                container_path: target?.to_string(),
+                read_only,
+            })
         }
+        _ => None,
     }
-    None
 }
 
-/// Extract docker image from the end of docker run/create command
-fn extract_docker_image(content: &str) -> Option<String> {
-    // Look for the image at the end of docker run or docker create command
-    // The image is typically the last argument before any command to run
-    let patterns = [
-        // Match image after all flags, before newline or end
-        r#"(?:docker\s+(?:run|create)[^\n]*?)\s+([a-zA-Z0-9._/-]+(?::[a-zA-Z0-9._-]+)?)\s*(?:\n|$)"#,
-    ];
+/// Port mapping from -p: `[ip:]host:container[/proto]`
+fn parse_port(value: &str) -> Option<PortMapping> {
+    let (mapping, protocol) = match value.rsplit_once('/') {
+        Some((mapping, protocol)) => (mapping, protocol.to_string()),
+        None => (value, "tcp".to_string()),
+    };
+    let mut parts = mapping.rsplitn(3, ':');
+    let container_port = parts.next()?.parse().ok()?;
+    let host_port = parts.next()?.parse().ok()?;
+    let host_ip = parts.next().and_then(PortMapping::parse_host_ip);
 
-    for pattern in &patterns {
-        if let Ok(re) = Regex::new(pattern) {
-            if let Some(caps) = re.captures(content) {
-                if let Some(m) = caps.get(1) {
-                    let image = m.as_str();
-                    // Skip if it looks like a flag or variable
-                    if !image.starts_with('-') && !image.starts_with('$') {
-                        return Some(image.to_string());
-                    }
-                }
-            }
-        }
-    }
-    None
+    Some(PortMapping { host_port, container_port, protocol, host_ip })
 }
 
 /// Generate script content from a DeploymentScript (only used for new scripts)
@@ -233,276 +588,638 @@ pub fn generate_script(script: &DeploymentScript) -> String {
     lines.push("#!/usr/bin/env bash".to_string());
     lines.push(String::new());
     lines.push("# Configuration".to_string());
-    lines.push(format!("NAME='{}'", script.container_name));
-    lines.push(format!("REPO=\"{}\"", script.repo));
+    lines.push(format!("NAME={}", quote(&script.container_name)));
+    lines.push(format!("REPO={}", quote(&script.repo)));
     lines.push(String::new());
 
-    lines.push("docker pull $REPO".to_string());
-    lines.push("docker stop $NAME".to_string());
-    lines.push("docker rm $NAME".to_string());
+    lines.push("docker pull \"$REPO\"".to_string());
+    lines.push("docker stop \"$NAME\"".to_string());
+    lines.push("docker rm \"$NAME\"".to_string());
     lines.push(String::new());
 
     // Build docker create command
     let mut create_parts = vec!["docker create".to_string()];
 
     if let Some(ref network) = script.network {
-        create_parts.push(format!("  --net={}", network));
+        create_parts.push(format!("  --net={}", quote(network)));
     }
 
-    create_parts.push("  --name $NAME".to_string());
-    create_parts.push("  --restart=unless-stopped".to_string());
+    create_parts.push("  --name \"$NAME\"".to_string());
+    let restart = script.restart_policy.as_deref().unwrap_or("unless-stopped");
+    create_parts.push(format!("  --restart={}", quote(restart)));
 
     // Add ports
     for port in &script.ports {
-        create_parts.push(format!("  -p {}:{}", port.host_port, port.container_port));
+        create_parts.push(format!("  -p {}", port.publish_spec()));
     }
 
     // Add volumes
     for vol in &script.volumes {
         let ro = if vol.read_only { ":ro" } else { "" };
-        create_parts.push(format!("  -v {}:{}{}", vol.host_path, vol.container_path, ro));
+        create_parts.push(format!("  -v {}:{}{}", quote_path(&vol.host_path), quote(&vol.container_path), ro));
     }
 
     // Add environment variables
+    for env_file in &script.env_files {
+        create_parts.push(format!("  --env-file {}", quote_path(env_file)));
+    }
     for env in &script.env_vars {
-        create_parts.push(format!("  -e {}=\"{}\"", env.key, env.value));
+        create_parts.push(format!("  -e {}", env_assignment(env)));
+    }
+
+    for (key, value) in &script.labels {
+        create_parts.push(format!("  --label {}", quote(&format!("{}={}", key, value))));
+    }
+
+    if let Some(ref user) = script.user {
+        create_parts.push(format!("  --user {}", quote(user)));
+    }
+    if let Some(ref memory) = script.memory {
+        create_parts.push(format!("  --memory {}", quote(memory)));
+    }
+    if let Some(ref cpus) = script.cpus {
+        create_parts.push(format!("  --cpus {}", quote(cpus)));
     }
 
-    create_parts.push("  $REPO".to_string());
+    if let Some(ref health) = script.healthcheck {
+        create_parts.push(format!("  --health-cmd {}", quote(&health.command)));
+        for (name, value) in [
+            ("interval", &health.interval),
+            ("timeout", &health.timeout),
+            ("start-period", &health.start_period),
+        ] {
+            if let Some(value) = value {
+                create_parts.push(format!("  --health-{} {}", name, quote(value)));
+            }
+        }
+        if let Some(retries) = health.retries {
+            create_parts.push(format!("  --health-retries {}", retries));
+        }
+    }
+
+    if let Some(ref entrypoint) = script.entrypoint {
+        create_parts.push(format!("  --entrypoint {}", quote(entrypoint)));
+    }
+
+    create_parts.push("  \"$REPO\"".to_string());
+    for arg in &script.command {
+        create_parts.push(format!("  {}", quote(arg)));
+    }
 
     // Join with line continuations
     let create_cmd = create_parts.join(" \\\n");
     lines.push(create_cmd);
     lines.push(String::new());
 
-    lines.push("docker start $NAME".to_string());
+    for network in &script.extra_networks {
+        lines.push(format!("docker network connect {} \"$NAME\"", quote(network)));
+    }
+
+    lines.push("docker start \"$NAME\"".to_string());
 
     lines.join("\n")
 }
 
 /// Apply changes from a DeploymentScript back to its raw_content in-place
-/// This preserves the original script structure while updating env vars
-pub fn apply_script_changes(script: &DeploymentScript, original_env_vars: &[EnvVar]) -> String {
-    let mut content = script.raw_content.clone();
+/// The script's container command is re-parsed from raw_content and only the
+/// options whose values changed are touched; everything else is kept byte for byte
+pub fn apply_script_changes(script: &DeploymentScript) -> String {
+    let content = &script.raw_content;
+    let parsed = ParsedScript::parse(content);
+    let Some(run) = parsed.primary_run() else {
+        return content.clone();
+    };
+
+    let env = env_entries(run, &parsed.variables);
+    let env_diff = diff_section(&env, &script.env_vars, |e| e.key.clone());
+    let volumes = volume_entries(run);
+    let volume_diff = diff_section(&volumes, &script.volumes, |v| v.container_path.clone());
+    let ports = port_entries(run);
+    let port_diff = diff_section(&ports, &script.ports, |p| (p.container_port, p.protocol.clone()));
 
-    // Find which env vars were added, modified, or removed
-    let current_keys: std::collections::HashSet<_> = script.env_vars.iter().map(|e| &e.key).collect();
-    let original_keys: std::collections::HashSet<_> = original_env_vars.iter().map(|e| &e.key).collect();
+    let mut edits = ScriptEdits::new(content);
 
-    // Remove deleted env vars first
-    for orig in original_env_vars {
-        if !current_keys.contains(&orig.key) {
-            content = remove_env_var(&content, &orig.key);
+    // Removals first, so new options are never anchored to a removed one
+    for removed in &env_diff.removed {
+        let key = env_key(removed);
+        for option in run.options.iter().filter(|o| o.name == "env" && env_key(o) == key) {
+            edits.remove(option.span);
+        }
+    }
+    for option in volume_diff.removed.iter().chain(&port_diff.removed) {
+        edits.remove(option.span);
+    }
+    if script.network.is_none() || script.restart_policy.is_none() {
+        let cleared = |name: &str| match name {
+            "network" => script.network.is_none(),
+            "restart" => script.restart_policy.is_none(),
+            _ => false,
+        };
+        for option in run.options.iter().filter(|o| cleared(o.name)) {
+            edits.remove(option.span);
         }
     }
 
-    // Update existing and add new env vars
-    for env in &script.env_vars {
-        if original_keys.contains(&env.key) {
-            // Update existing
-            content = update_env_var(&content, &env.key, &env.value);
-        } else {
-            // Add new
-            content = add_env_var(&content, &env.key, &env.value);
+    for (_, option, env) in &env_diff.changed {
+        edits.replace_value(option, "-e", env_assignment(env));
+    }
+    for (original, option, volume) in &volume_diff.changed {
+        let flag = if option.name == "mount" { "--mount" } else { "-v" };
+        edits.replace_value(option, flag, volume_value(content, original, option, volume));
+    }
+    for (_, option, port) in &port_diff.changed {
+        edits.replace_value(option, "-p", port_value(option, port));
+    }
+    for env in &env_diff.added {
+        edits.add_option(run, &["env"], format!("-e {}", env_assignment(env)));
+    }
+    for volume in &volume_diff.added {
+        let ro = if volume.read_only { ":ro" } else { "" };
+        let value = format!("{}:{}{}", quote_path(&volume.host_path), quote(&volume.container_path), ro);
+        edits.add_option(run, &["volume", "mount"], format!("-v {}", value));
+    }
+    for port in &port_diff.added {
+        edits.add_option(run, &["publish"], format!("-p {}", port.publish_spec()));
+    }
+    for env_file in &script.env_files {
+        if !run.options.iter().any(|o| o.name == "env-file" && o.value.as_ref() == Some(env_file)) {
+            edits.add_option(run, &["env-file", "env"], format!("--env-file {}", quote_path(env_file)));
+        }
+    }
+    for (name, value) in [("network", &script.network), ("restart", &script.restart_policy)] {
+        let Some(value) = value else {
+            continue;
+        };
+        match run.options.iter().rev().find(|o| o.name == name) {
+            Some(option) if option.value.as_ref() == Some(value) => {}
+            Some(option) => edits.replace_value(option, &format!("--{}", name), quote(value)),
+            None => edits.add_option(run, &[name], format!("--{}={}", name, quote(value))),
         }
     }
 
-    content
+    edits.apply()
 }
 
-/// Update an existing environment variable in the script content
-fn update_env_var(content: &str, key: &str, new_value: &str) -> String {
-    // Match various forms of -e KEY=VALUE
-    let patterns = [
-        format!(r#"(-e\s+{}=)"([^"]*)""#, regex::escape(key)),
-        format!(r#"(-e\s+{}=)'([^']*)'"#, regex::escape(key)),
-        format!(r#"(-e\s+{}=)([^\s\\]+)"#, regex::escape(key)),
-        format!(r#"(-e{}=)"([^"]*)""#, regex::escape(key)),
-        format!(r#"(-e{}=)'([^']*)'"#, regex::escape(key)),
-        format!(r#"(-e{}=)([^\s\\]+)"#, regex::escape(key)),
-    ];
+/// How the items of one script section changed relative to the options they
+/// were parsed from
+struct SectionDiff<'a, 'b, T> {
+    removed: Vec<&'a RunOption>,
+    changed: Vec<(&'a T, &'a RunOption, &'b T)>,
+    added: Vec<&'b T>,
+}
 
-    for pattern in &patterns {
-        if let Ok(re) = Regex::new(pattern) {
-            if re.is_match(content) {
-                // Escape special characters in replacement value
-                let escaped_value = new_value.replace('\\', "\\\\").replace('$', "\\$");
-                let replacement = format!("${{1}}\"{}\"", escaped_value);
-                return re.replace(content, replacement.as_str()).to_string();
+/// Pair parsed entries with the edited items: identical items first, then items
+/// with the same key, then leftovers at the same position (an edited key)
+fn diff_section<'a, 'b, T: PartialEq, K: PartialEq>(
+    original: &'a [(T, &'a RunOption)],
+    current: &'b [T],
+    key: impl Fn(&T) -> K,
+) -> SectionDiff<'a, 'b, T> {
+    let mut used = vec![false; original.len()];
+    let mut pairs: Vec<Option<usize>> = vec![None; current.len()];
+
+    for pass in 0..3 {
+        for (j, (pair, item)) in pairs.iter_mut().zip(current).enumerate() {
+            if pair.is_some() {
+                continue;
+            }
+            *pair = (0..original.len()).find(|&i| {
+                !used[i]
+                    && match pass {
+                        0 => original[i].0 == *item,
+                        1 => key(&original[i].0) == key(item),
+                        _ => i == j,
+                    }
+            });
+            if let Some(i) = *pair {
+                used[i] = true;
             }
         }
     }
 
-    content.to_string()
+    let mut diff = SectionDiff { removed: Vec::new(), changed: Vec::new(), added: Vec::new() };
+    let mut paired = vec![false; original.len()];
+    for (pair, item) in pairs.iter().zip(current) {
+        match *pair {
+            Some(i) => {
+                paired[i] = true;
+                if original[i].0 != *item {
+                    diff.changed.push((&original[i].0, original[i].1, item));
+                }
+            }
+            None => diff.added.push(item),
+        }
+    }
+    diff.removed = original.iter().zip(paired).filter(|(_, p)| !p).map(|((_, o), _)| *o).collect();
+    diff
 }
 
-/// Remove an environment variable from the script content
-fn remove_env_var(content: &str, key: &str) -> String {
-    let lines: Vec<&str> = content.lines().collect();
-    let mut result_lines: Vec<String> = Vec::new();
-    let mut i = 0;
+fn env_assignment(env: &EnvVar) -> String {
+    format!("{}={}", env.key, quote(&env.value))
+}
 
-    while i < lines.len() {
-        let line = lines[i];
-        let trimmed = line.trim();
-
-        // Check if this line contains the env var we want to remove
-        let env_pattern = format!(r#"-e\s*{}="#, regex::escape(key));
-        if let Ok(re) = Regex::new(&env_pattern) {
-            if re.is_match(trimmed) {
-                // Skip this line
-                // If previous line ends with \, we need to handle continuation
-                if !result_lines.is_empty() {
-                    let last_idx = result_lines.len() - 1;
-                    let last_line = &result_lines[last_idx];
-
-                    // Check if next line exists and is a continuation
-                    let has_more_after = i + 1 < lines.len() && !lines[i + 1].trim().is_empty()
-                        && (lines[i + 1].trim().starts_with('-')
-                            || lines[i + 1].trim().starts_with('$')
-                            || lines[i + 1].trim().starts_with('"'));
-
-                    if last_line.trim_end().ends_with('\\') && !has_more_after {
-                        // Remove the trailing \ from previous line
-                        result_lines[last_idx] = last_line.trim_end().trim_end_matches('\\').trim_end().to_string();
-                    }
-                }
-                i += 1;
-                continue;
-            }
+/// New value for a changed -v/--mount option; parts the user left alone are
+/// kept as written so variables in them survive
+fn volume_value(content: &str, original: &VolumeMount, option: &RunOption, volume: &VolumeMount) -> String {
+    let expanded = option.value.as_deref().unwrap_or_default();
+
+    if option.name == "mount" {
+        let mut fields: Vec<String> = expanded
+            .split(',')
+            .filter_map(|field| match field.split_once('=').map(|(k, _)| k).unwrap_or(field) {
+                "readonly" | "ro" => None,
+                key @ ("source" | "src") => Some(format!("{}={}", key, volume.host_path)),
+                key @ ("target" | "destination" | "dst") => Some(format!("{}={}", key, volume.container_path)),
+                _ => Some(field.to_string()),
+            })
+            .collect();
+        if volume.read_only {
+            fields.push("readonly".to_string());
         }
+        return quote(&fields.join(","));
+    }
 
-        result_lines.push(line.to_string());
-        i += 1;
+    let raw = option
+        .value_span
+        .map(|span| split_unquoted(&content[span.start..span.end], ':'))
+        .filter(|raw| raw.len() == expanded.split(':').count().min(3))
+        .unwrap_or_default();
+    let part = |i: usize, unchanged: bool, value: &str| match raw.get(i) {
+        Some(raw) if unchanged => raw.to_string(),
+        _ => quote(value),
+    };
+    let host = part(0, volume.host_path == original.host_path, &volume.host_path);
+    let container = part(1, volume.container_path == original.container_path, &volume.container_path);
+
+    let mut options: Vec<&str> = expanded
+        .splitn(3, ':')
+        .nth(2)
+        .map(|o| o.split(',').filter(|o| !o.is_empty() && *o != "ro" && *o != "rw").collect())
+        .unwrap_or_default();
+    if volume.read_only {
+        options.insert(0, "ro");
+    }
+
+    if options.is_empty() {
+        format!("{}:{}", host, container)
+    } else {
+        format!("{}:{}:{}", host, container, options.join(","))
+    }
+}
+
+/// Split shell text on `separator` outside quotes, into at most three parts
+fn split_unquoted(raw: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in raw.char_indices() {
+        match (quote, c) {
+            _ if escaped => escaped = false,
+            (Some('\''), '\'') | (Some('"'), '"') => quote = None,
+            (Some('"'), '\\') | (None, '\\') => escaped = true,
+            (None, '\'' | '"') => quote = Some(c),
+            (None, _) if c == separator && parts.len() < 2 => {
+                parts.push(&raw[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
     }
+    parts.push(&raw[start..]);
+    parts
+}
 
-    result_lines.join("\n")
-}
-
-/// Add a new environment variable to the script content
-fn add_env_var(content: &str, key: &str, value: &str) -> String {
-    let lines: Vec<&str> = content.lines().collect();
-    let mut result_lines: Vec<String> = Vec::new();
-    let mut inserted = false;
-
-    // Find the best place to insert:
-    // 1. After the last existing -e flag
-    // 2. Before the image/repo reference at the end of docker command
-    // 3. After -p or -v flags
-
-    let mut last_env_line_idx: Option<usize> = None;
-    let mut last_flag_line_idx: Option<usize> = None;
-    let mut docker_cmd_end_idx: Option<usize> = None;
-
-    // Find positions
-    for (i, line) in lines.iter().enumerate() {
-        let trimmed = line.trim();
-        if trimmed.contains("-e ") || trimmed.starts_with("-e") {
-            last_env_line_idx = Some(i);
-        }
-        if trimmed.starts_with("-p ") || trimmed.starts_with("-p")
-            || trimmed.starts_with("-v ") || trimmed.starts_with("-v")
-            || trimmed.starts_with("--")
-        {
-            last_flag_line_idx = Some(i);
-        }
-        // Find line with $REPO or image name at end of docker command
-        if (trimmed.starts_with('$') || trimmed.contains("$REPO"))
-            && !trimmed.contains('=')
-            && i > 0
-            && lines[i - 1].trim_end().ends_with('\\')
-        {
-            docker_cmd_end_idx = Some(i);
-        }
-    }
-
-    // Determine insertion point
-    let insert_after = last_env_line_idx
-        .or(last_flag_line_idx)
-        .or(docker_cmd_end_idx.map(|i| i.saturating_sub(1)));
-
-    // Detect indentation from existing -e lines or other flag lines
-    let indent = if let Some(idx) = last_env_line_idx {
-        let line = lines[idx];
-        let trimmed = line.trim_start();
-        &line[..line.len() - trimmed.len()]
-    } else if let Some(idx) = last_flag_line_idx {
-        let line = lines[idx];
-        let trimmed = line.trim_start();
-        &line[..line.len() - trimmed.len()]
+/// New value for a changed -p option, keeping an explicit protocol
+fn port_value(option: &RunOption, port: &PortMapping) -> String {
+    let expanded = option.value.as_deref().unwrap_or_default();
+    let proto = if port.protocol != "tcp" || expanded.contains('/') {
+        format!("/{}", port.protocol)
     } else {
-        "  "
+        String::new()
     };
+    format!("{}:{}{}", port.host_address(), port.container_port, proto)
+}
 
-    // Escape the value properly for shell
-    let escaped_value = escape_shell_value(value);
-    let new_line = format!("{}-e {}={}", indent, key, escaped_value);
-
-    for (i, line) in lines.iter().enumerate() {
-        if let Some(insert_idx) = insert_after {
-            if i == insert_idx && !inserted {
-                // Make sure current line has continuation
-                let mut current_line = line.to_string();
-                if !current_line.trim_end().ends_with('\\') {
-                    current_line = format!("{} \\", current_line.trim_end());
-                }
-                result_lines.push(current_line);
+/// A set of non-overlapping source edits, applied in one pass
+struct ScriptEdits<'a> {
+    content: &'a str,
+    edits: Vec<(Span, String)>,
+    removed: Vec<Span>,
+}
+
+impl<'a> ScriptEdits<'a> {
+    fn new(content: &'a str) -> Self {
+        Self { content, edits: Vec::new(), removed: Vec::new() }
+    }
+
+    fn replace(&mut self, span: Span, text: String) {
+        self.edits.push((span, text));
+    }
+
+    /// Replace an option's value, or the whole option when the value is not separable
+    fn replace_value(&mut self, option: &RunOption, flag: &str, value: String) {
+        match option.value_span {
+            Some(span) => self.replace(span, value),
+            None => self.replace(option.span, format!("{} {}", flag, value)),
+        }
+    }
+
+    /// Add an option after the last kept one of the given kinds, else after the
+    /// last kept option, else right before the image
+    fn add_option(&mut self, run: &DockerRun, kinds: &[&str], text: String) {
+        let kept = |o: &&RunOption| !self.removed.contains(&o.span);
+        let anchor = run
+            .options
+            .iter()
+            .filter(kept)
+            .rfind(|o| kinds.contains(&o.name))
+            .or_else(|| run.options.iter().rfind(|o| kept(o)))
+            .map(|o| o.span);
+
+        match (anchor, &run.image) {
+            (Some(anchor), _) => self.insert_after(anchor, text),
+            (None, Some((_, image))) => self.insert_before(*image, text),
+            (None, None) => {}
+        }
+    }
 
-                // Add the new env var with continuation if needed
-                let needs_continuation = i + 1 < lines.len()
-                    && !lines[i + 1].trim().is_empty()
-                    && !lines[i + 1].trim().starts_with('#');
+    /// Start and end of the line containing `span` (end excludes the newline)
+    fn line_bounds(&self, span: Span) -> (usize, usize) {
+        let start = self.content[..span.start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let end = self.content[span.end..].find('\n').map(|i| span.end + i).unwrap_or(self.content.len());
+        (start, end)
+    }
 
-                if needs_continuation {
-                    result_lines.push(format!("{} \\", new_line));
-                } else {
-                    result_lines.push(new_line.clone());
+    /// Whether `span` sits alone on its line, optionally followed by a continuation
+    fn alone_on_line(&self, span: Span) -> Option<(usize, usize, bool)> {
+        let (start, end) = self.line_bounds(span);
+        let before = &self.content[start..span.start];
+        let after = self.content[span.end..end].trim();
+        (before.trim().is_empty() && (after.is_empty() || after == "\\")).then_some((start, end, after == "\\"))
+    }
+
+    /// Remove an option together with its line when it has one to itself
+    fn remove(&mut self, span: Span) {
+        if self.removed.contains(&span) {
+            return;
+        }
+        self.removed.push(span);
+        if let Some((start, end, continued)) = self.alone_on_line(span) {
+            let end = (end + 1).min(self.content.len());
+            self.edits.push((Span { start, end }, String::new()));
+
+            // The option ended the command: the previous line must not continue
+            if !continued && start > 0 {
+                let previous = self.content[..start - 1].trim_end();
+                if let Some(stripped) = previous.strip_suffix('\\') {
+                    let cut = stripped.trim_end().len();
+                    self.edits.push((Span { start: cut, end: start - 1 }, String::new()));
                 }
-                inserted = true;
-                continue;
             }
+            return;
         }
-        result_lines.push(line.to_string());
+
+        // Inline: take the whitespace after it too
+        let rest = &self.content[span.end..];
+        let trailing = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+        self.edits.push((Span { start: span.start, end: span.end + trailing }, String::new()));
     }
 
-    // If we couldn't find a good place, append before docker start
-    if !inserted {
-        // Find docker start line and insert before it
-        let mut final_lines: Vec<String> = Vec::new();
-        for line in &result_lines {
-            if line.trim().starts_with("docker start") && !inserted {
-                final_lines.push(format!("  -e {}={} \\", key, escape_shell_value(value)));
-                inserted = true;
+    /// Insert an option after `anchor`, on its own line if the anchor has one
+    fn insert_after(&mut self, anchor: Span, text: String) {
+        match self.alone_on_line(anchor) {
+            Some((start, end, true)) if end < self.content.len() => {
+                let indent = &self.content[start..anchor.start];
+                let at = end + 1;
+                self.edits.push((Span { start: at, end: at }, format!("{}{} \\\n", indent, text)));
             }
-            final_lines.push(line.clone());
-        }
-        if inserted {
-            return final_lines.join("\n");
+            _ => self.edits.push((Span { start: anchor.end, end: anchor.end }, format!(" {}", text))),
         }
     }
 
-    result_lines.join("\n")
+    fn insert_before(&mut self, anchor: Span, text: String) {
+        self.edits.push((Span { start: anchor.start, end: anchor.start }, format!("{} ", text)));
+    }
+
+    fn apply(self) -> String {
+        let mut edits: Vec<(usize, Span, String)> =
+            self.edits.into_iter().enumerate().map(|(i, (span, text))| (i, span, text)).collect();
+        // Back to front; at the same spot removals go before insertions, and
+        // insertions keep the order they were made in
+        edits.sort_by(|a, b| b.1.start.cmp(&a.1.start).then(b.1.end.cmp(&a.1.end)).then(b.0.cmp(&a.0)));
+
+        let mut content = self.content.to_string();
+        let mut limit = usize::MAX;
+        for (_, span, text) in edits {
+            // Skip anything overlapping an edit already applied
+            if span.end > limit {
+                continue;
+            }
+            content.replace_range(span.start..span.end, &text);
+            limit = span.start;
+        }
+        content
+    }
 }
 
-/// Escape a value for safe use in shell scripts
-fn escape_shell_value(value: &str) -> String {
-    // If value contains special characters, wrap in double quotes
-    if value.contains(' ')
-        || value.contains('$')
-        || value.contains('\\')
-        || value.contains('"')
-        || value.contains('\'')
-        || value.contains('!')
-        || value.contains('`')
-        || value.is_empty()
-    {
-        // Escape internal double quotes and backslashes
-        let escaped = value
-            .replace('\\', "\\\\")
-            .replace('"', "\\\"");
-        format!("\"{}\"", escaped)
-    } else {
-        // Simple value can be quoted for consistency
-        format!("\"{}\"", value)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = r#"#!/usr/bin/env bash
+NAME='web'
+REPO="nginx:1.27"
+DATA=/srv/web
+
+docker pull $REPO
+docker stop $NAME 2>/dev/null || true
+docker rm $NAME || true
+
+docker run --rm --name migrate -e ONLY=here $REPO migrate
+
+docker create \
+  --net=frontend \
+  --name "$NAME" \
+  --restart unless-stopped \
+  -p 127.0.0.1:8080:80 \
+  --publish=5353:53/udp \
+  -v $DATA/html:/usr/share/nginx/html:ro \
+  --mount type=bind,source=/etc/ssl,target=/ssl,readonly \
+  -e 'GREETING=hello world' \
+  --env=DEBUG=0 \
+  -eLOG_LEVEL=info \
+  $REPO
+
+docker start $NAME
+"#;
+
+    #[test]
+    fn test_parse_script() {
+        let script = parse_script(SCRIPT, "/clients/acme/web.sh", "acme");
+        assert_eq!(script.container_name, "web");
+        assert_eq!(script.repo, "nginx:1.27");
+        assert_eq!(script.network.as_deref(), Some("frontend"));
+        assert_eq!(script.restart_policy.as_deref(), Some("unless-stopped"));
+
+        let env: Vec<(&str, &str)> = script.env_vars.iter().map(|e| (e.key.as_str(), e.value.as_str())).collect();
+        assert_eq!(env, vec![("GREETING", "hello world"), ("DEBUG", "0"), ("LOG_LEVEL", "info")]);
+
+        let ports: Vec<(u16, u16, &str)> =
+            script.ports.iter().map(|p| (p.host_port, p.container_port, p.protocol.as_str())).collect();
+        assert_eq!(ports, vec![(8080, 80, "tcp"), (5353, 53, "udp")]);
+
+        assert_eq!(script.volumes.len(), 2);
+        assert_eq!(script.volumes[0].host_path, "/srv/web/html");
+        assert!(script.volumes[0].read_only);
+        assert_eq!(script.volumes[1].container_path, "/ssl");
+        assert!(script.volumes[1].read_only);
+    }
+
+    #[test]
+    fn test_apply_env_changes() {
+        let mut script = parse_script(SCRIPT, "/clients/acme/web.sh", "acme");
+        script.env_vars.retain(|e| e.key != "DEBUG");
+        script.env_vars[0].value = "hi $USER".to_string();
+        script.env_vars.push(EnvVar::new("TZ".to_string(), "UTC".to_string()));
+
+        let updated = apply_script_changes(&script);
+        assert!(updated.contains("  -e GREETING='hi $USER' \\\n  -eLOG_LEVEL=info \\\n  -e TZ=UTC \\\n  $REPO"));
+        assert!(!updated.contains("DEBUG"));
+        // The other docker run is left alone
+        assert!(updated.contains("-e ONLY=here $REPO migrate"));
+
+        let reparsed = parse_script(&updated, "/clients/acme/web.sh", "acme");
+        let env: Vec<(&str, &str)> = reparsed.env_vars.iter().map(|e| (e.key.as_str(), e.value.as_str())).collect();
+        assert_eq!(env, vec![("GREETING", "hi $USER"), ("LOG_LEVEL", "info"), ("TZ", "UTC")]);
+    }
+
+    #[test]
+    fn test_unchanged_script_is_preserved() {
+        let script = parse_script(SCRIPT, "/clients/acme/web.sh", "acme");
+        assert_eq!(apply_script_changes(&script), SCRIPT);
+    }
+
+    /// One line per parsed setting, used for the `.parsed` golden files
+    fn describe(script: &DeploymentScript) -> String {
+        let mut lines = vec![
+            format!("name: {}", script.container_name),
+            format!("repo: {}", script.repo),
+            format!("network: {}", script.network.as_deref().unwrap_or("-")),
+            format!("restart: {}", script.restart_policy.as_deref().unwrap_or("-")),
+        ];
+        lines.extend(script.env_vars.iter().map(|e| format!("env: {}={}", e.key, e.value)));
+        lines.extend(script.volumes.iter().map(|v| format!("volume: {}", v.display())));
+        lines.extend(
+            script.ports.iter().map(|p| format!("port: {} -> {}/{}", p.host_address(), p.container_port, p.protocol)),
+        );
+        lines.extend(script.extra_networks.iter().map(|n| format!("connect: {}", n)));
+        lines.extend(script.labels.iter().map(|(k, v)| format!("label: {}={}", k, v)));
+        for (name, value) in [
+            ("user", &script.user),
+            ("entrypoint", &script.entrypoint),
+            ("memory", &script.memory),
+            ("cpus", &script.cpus),
+        ] {
+            lines.extend(value.iter().map(|v| format!("{}: {}", name, v)));
+        }
+        lines.extend(script.healthcheck.iter().map(|h| format!("health: {}", h.command)));
+        lines.extend(script.command.iter().map(|arg| format!("arg: {}", arg)));
+        lines.join("\n") + "\n"
+    }
+
+    /// The same edits to every section, applied to each golden script
+    fn edit_every_section(script: &mut DeploymentScript) {
+        if let Some(first) = script.env_vars.first_mut() {
+            first.value = "changed value".to_string();
+        }
+        if script.env_vars.len() > 1 {
+            script.env_vars.pop();
+        }
+        script.env_vars.push(EnvVar::new("GOLDEN_ADDED".to_string(), "yes".to_string()));
+
+        if let Some(first) = script.volumes.first_mut() {
+            first.read_only = !first.read_only;
+        }
+        script.volumes.push(VolumeMount {
+            host_path: "/srv/golden".to_string(),
+            container_path: "/golden".to_string(),
+            read_only: false,
+        });
+
+        if !script.ports.is_empty() {
+            script.ports.remove(0);
+        }
+        script.ports.push(PortMapping { host_port: 9999, container_port: 99, protocol: "udp".to_string(), host_ip: None });
+
+        script.network = Some("golden-net".to_string());
+        script.restart_policy = Some("always".to_string());
+    }
+
+    fn check_golden(path: &std::path::Path, actual: &str, update: bool) {
+        if update {
+            std::fs::write(path, actual).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(path)
+            .unwrap_or_else(|_| panic!("missing golden file {} (run with UPDATE_GOLDEN=1)", path.display()));
+        assert_eq!(actual, expected, "golden file {} differs", path.display());
+    }
+
+    /// A new script for names the shell would split or expand must keep them
+    /// as they are: `tests/golden/generated/odd_names.sh`
+    #[test]
+    fn test_golden_generated_script() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden/generated/odd_names.sh");
+        let mut script = DeploymentScript::new("/srv/clients/odd/start.sh".to_string(), "odd".to_string());
+        script.container_name = "it's $HOME web".to_string();
+        script.repo = "registry.local/app:\"v1\"".to_string();
+        script.network = Some("front end".to_string());
+        script.extra_networks = vec!["back`end`".to_string()];
+
+        let mut generated = generate_script(&script);
+        generated.push('\n');
+        check_golden(&path, &generated, std::env::var_os("UPDATE_GOLDEN").is_some());
+
+        let parsed = parse_script(&generated, &script.path, "odd");
+        assert_eq!(parsed.container_name, script.container_name);
+        assert_eq!(parsed.repo, script.repo);
+        assert_eq!(parsed.network, script.network);
+        assert_eq!(parsed.extra_networks, script.extra_networks);
+    }
+
+    /// For every `tests/golden/scripts/<case>.sh`: the parse must match
+    /// `<case>.parsed`, saving without changes must reproduce the script, and
+    /// editing every section must produce `<case>.edited.sh` and parse back to
+    /// the edited values. Run with UPDATE_GOLDEN=1 to rewrite the golden files.
+    #[test]
+    fn test_golden_scripts() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden/scripts");
+        let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+
+        let mut cases: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.extension().is_some_and(|e| e == "sh") && !p.to_string_lossy().ends_with(".edited.sh"))
+            .collect();
+        cases.sort();
+        assert!(!cases.is_empty());
+
+        for case in cases {
+            let content = std::fs::read_to_string(&case).unwrap();
+            let path = case.to_string_lossy();
+            let mut script = parse_script(&content, &path, "golden");
+
+            check_golden(&case.with_extension("parsed"), &describe(&script), update);
+            assert_eq!(apply_script_changes(&script), content, "{} not preserved", path);
+
+            edit_every_section(&mut script);
+            let edited = apply_script_changes(&script);
+            check_golden(&case.with_extension("edited.sh"), &edited, update);
+
+            let mut reparsed = parse_script(&edited, &path, "golden");
+            reparsed.raw_content = script.raw_content.clone();
+            assert_eq!(describe(&reparsed), describe(&script), "{} does not parse back", path);
+        }
     }
 }
@@ -0,0 +1,183 @@
+//! Moving secret env vars out of deployment scripts, into a 0600 `.env` file
+//! passed with `--env-file` or into files mounted the way Docker secrets are
+//! (`/run/secrets/<name>`, with `KEY_FILE` pointing at it)
+
+use crate::models::{DeploymentScript, EnvStorage, EnvVar, LintIssue, SecretWrite, VolumeMount};
+use crate::utils::shell::{quote, quote_path};
+use super::discovery::{base64_encode, remote_script, write_file_script};
+
+/// Env file created next to the script when it does not pass one yet
+pub const DEFAULT_ENV_FILE: &str = ".env";
+const SECRETS_DIR: &str = "secrets";
+const SECRETS_MOUNT: &str = "/run/secrets";
+
+/// The script as it will be written, with every env var marked for an env
+/// file or a secret file taken out of it, and the writes that keep their values
+pub fn externalize_secrets(script: &DeploymentScript) -> Result<(DeploymentScript, Vec<SecretWrite>), String> {
+    let mut result = script.clone();
+    let mut writes = Vec::new();
+    let dir = script_dir(&script.path);
+
+    result.env_vars.retain(|env| env.storage == EnvStorage::Inline);
+    for env in script.env_vars.iter().filter(|e| e.storage != EnvStorage::Inline) {
+        match env.storage {
+            EnvStorage::EnvFile => {
+                if env.value.contains('\n') {
+                    return Err(format!("{} has a line break, which an env file cannot hold - use a secret file", env.key));
+                }
+                // Merge into the env file the script already passes, when it names one
+                let env_file = match result.env_files.iter().find(|f| !f.contains(['$', '`'])) {
+                    Some(file) => resolve(dir, file),
+                    None => {
+                        // Passed by its full path, so it is found wherever the script is run from
+                        let file = resolve(dir, DEFAULT_ENV_FILE);
+                        result.env_files.push(file.clone());
+                        file
+                    }
+                };
+                writes.push(SecretWrite::EnvFileEntry {
+                    path: env_file,
+                    key: env.key.clone(),
+                    value: env.value.clone(),
+                });
+            }
+            EnvStorage::SecretFile => {
+                let name = env.key.to_lowercase();
+                let file = resolve(dir, &format!("{}/{}", SECRETS_DIR, name));
+                let target = format!("{}/{}", SECRETS_MOUNT, name);
+                writes.push(SecretWrite::File { path: file.clone(), value: env.value.clone() });
+
+                let file_key = format!("{}_FILE", env.key);
+                if !result.env_vars.iter().any(|e| e.key == file_key) {
+                    result.env_vars.push(EnvVar::new(file_key, target.clone()));
+                }
+                if !result.volumes.iter().any(|v| v.container_path == target) {
+                    result.volumes.push(VolumeMount { host_path: file, container_path: target, read_only: true });
+                }
+            }
+            EnvStorage::Inline => {}
+        }
+    }
+
+    Ok((result, writes))
+}
+
+/// A warning for every env var moving to a secret file: the container no
+/// longer gets `KEY` itself, only `KEY_FILE`, which not every image reads.
+/// Then one about who can read the files, which are 0600 and owned by
+/// whoever writes them: the ssh user, or root with sudo
+pub fn secret_file_warnings(script: &DeploymentScript) -> Vec<LintIssue> {
+    let mut warnings: Vec<LintIssue> = script
+        .env_vars
+        .iter()
+        .filter(|env| env.storage == EnvStorage::SecretFile)
+        .map(|env| {
+            LintIssue::warning(format!(
+                "{} is replaced by {}_FILE={}/{}: the container only gets the value if its image reads *_FILE variables",
+                env.key,
+                env.key,
+                SECRETS_MOUNT,
+                env.key.to_lowercase()
+            ))
+        })
+        .collect();
+    if warnings.is_empty() {
+        return warnings;
+    }
+
+    let owner = "owned by the ssh user (or root with sudo) and readable by no one else";
+    let root = |user: &str| matches!(user.split(':').next(), Some("root" | "0"));
+    warnings.push(LintIssue::warning(match script.user.as_deref() {
+        Some(user) if !root(user) => format!(
+            "Secret files are {}: the container runs as {}, which cannot read them unless it has the same uid",
+            owner, user
+        ),
+        _ => format!("Secret files are {}: an image that runs as a non-root user cannot read them", owner),
+    }));
+    warnings
+}
+
+/// Write the secrets (readable by the owner only), then the script itself
+pub fn write_script_with_secrets_command(script_path: &str, content: &str, secrets: &[SecretWrite]) -> String {
+    let mut lines = vec!["set -e".to_string(), "umask 077".to_string()];
+    for secret in secrets {
+        match secret {
+            SecretWrite::EnvFileEntry { path, key, value } => {
+                lines.push(format!("f={}", quote_path(path)));
+                lines.push("mkdir -p \"$(dirname \"$f\")\"".to_string());
+                lines.push("touch \"$f\"".to_string());
+                // Replace any earlier line for the key, keeping the rest of the file
+                lines.push(format!(
+                    "awk -v k={} 'index($0, k) != 1' \"$f\" > \"$f.tmp.$$\"",
+                    quote(&format!("{}=", key))
+                ));
+                lines.push(format!(
+                    "printf '%s' '{}' | base64 -d >> \"$f.tmp.$$\"",
+                    base64_encode(format!("{}={}\n", key, value).as_bytes())
+                ));
+                lines.push("chmod 600 \"$f.tmp.$$\" && mv -f \"$f.tmp.$$\" \"$f\"".to_string());
+            }
+            SecretWrite::File { path, value } => {
+                lines.push(format!("f={}", quote_path(path)));
+                lines.push("mkdir -p \"$(dirname \"$f\")\"".to_string());
+                lines.push(format!("printf '%s' '{}' | base64 -d > \"$f.tmp.$$\"", base64_encode(value.as_bytes())));
+                lines.push("chmod 600 \"$f.tmp.$$\" && mv -f \"$f.tmp.$$\" \"$f\"".to_string());
+            }
+        }
+    }
+    // The script keeps its usual permissions
+    lines.push("umask 022".to_string());
+    lines.push(write_file_script(script_path, content, true));
+    remote_script(&lines.join("\n"))
+}
+
+pub(super) fn script_dir(path: &str) -> &str {
+    path.rsplit_once('/').map_or(".", |(dir, _)| if dir.is_empty() { "/" } else { dir })
+}
+
+/// A path from the script, relative to the directory it runs in
+pub(super) fn resolve(dir: &str, path: &str) -> String {
+    if path.starts_with(['/', '~']) {
+        path.to_string()
+    } else {
+        format!("{}/{}", dir.trim_end_matches('/'), path.trim_start_matches("./"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docker::script_parser::{apply_script_changes, parse_script};
+
+    #[test]
+    fn test_externalize_secrets() {
+        let content = "docker run -d --name db \\\n  -e POSTGRES_USER=app \\\n  -e POSTGRES_PASSWORD=hunter2 \\\n  -e API_TOKEN=abc123 \\\n  postgres:16\n";
+        let mut script = parse_script(content, "/srv/db/start.sh", "db");
+        script.env_vars[1].storage = EnvStorage::SecretFile;
+        script.env_vars[2].storage = EnvStorage::EnvFile;
+
+        let (moved, writes) = externalize_secrets(&script).unwrap();
+        assert_eq!(
+            writes,
+            vec![
+                SecretWrite::File { path: "/srv/db/secrets/postgres_password".into(), value: "hunter2".into() },
+                SecretWrite::EnvFileEntry { path: "/srv/db/.env".into(), key: "API_TOKEN".into(), value: "abc123".into() },
+            ]
+        );
+        assert_eq!(
+            apply_script_changes(&moved),
+            "docker run -d --name db \\\n  -e POSTGRES_USER=app \\\n  -e POSTGRES_PASSWORD_FILE=/run/secrets/postgres_password \\\n  -v /srv/db/secrets/postgres_password:/run/secrets/postgres_password:ro \\\n  --env-file /srv/db/.env \\\n  postgres:16\n"
+        );
+        let warnings = secret_file_warnings(&script);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].message.starts_with("POSTGRES_PASSWORD is replaced by POSTGRES_PASSWORD_FILE=/run/secrets/postgres_password"));
+        assert!(warnings[1].message.ends_with("an image that runs as a non-root user cannot read them"));
+        script.user = Some("999:999".into());
+        assert!(secret_file_warnings(&script)[1].message.contains("the container runs as 999:999, which cannot read them"));
+        script.user = Some("0:0".into());
+        assert!(secret_file_warnings(&script)[1].message.contains("an image that runs as a non-root user"));
+
+        script.env_vars[2].value = "two\nlines".into();
+        assert!(externalize_secrets(&script).is_err());
+    }
+}
@@ -0,0 +1,558 @@
+//! Small POSIX-shell lexer for deployment scripts
+//!
+//! Splits a script into simple commands following the quoting, escaping,
+//! comment, line-continuation and here-document rules of `sh`. Every word keeps
+//! its byte span in the source so callers can rewrite the script in place, and
+//! variable references are kept as separate parts so they can be resolved
+//! against assignments made earlier in the script.
+
+use std::collections::HashMap;
+
+/// Byte range in the script source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WordPart {
+    /// Literal text after quote removal
+    Literal { text: String, quoted: bool },
+    /// `$NAME`, `${NAME}` or `${NAME:-default}`
+    Variable { name: String, default: Option<String>, raw: String },
+    /// `$(...)`, `` `...` ``, `$((...))` or an unsupported `${...}` form (never evaluated)
+    Substitution { raw: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word {
+    pub parts: Vec<WordPart>,
+    pub span: Span,
+}
+
+impl Word {
+    /// Expand the word, resolving variables from `vars`. Unknown variables and
+    /// substitutions are kept as written.
+    pub fn expand(&self, vars: &HashMap<String, String>) -> String {
+        expand_parts(&self.parts, vars)
+    }
+
+    /// The word with quotes removed and nothing resolved
+    pub fn text(&self) -> String {
+        self.expand(&HashMap::new())
+    }
+
+    /// Split a `NAME=value` assignment into the name and the value's parts
+    pub fn as_assignment(&self) -> Option<(String, Vec<WordPart>)> {
+        let (text, rest) = match self.parts.split_first() {
+            Some((WordPart::Literal { text, quoted: false }, rest)) => (text, rest),
+            _ => return None,
+        };
+        let (name, value) = text.split_once('=')?;
+        if !is_name(name) {
+            return None;
+        }
+
+        let mut parts = Vec::with_capacity(rest.len() + 1);
+        if !value.is_empty() {
+            parts.push(WordPart::Literal { text: value.to_string(), quoted: false });
+        }
+        parts.extend(rest.iter().cloned());
+        Some((name.to_string(), parts))
+    }
+}
+
+/// Expand word parts, resolving variables from `vars`
+pub fn expand_parts(parts: &[WordPart], vars: &HashMap<String, String>) -> String {
+    let mut out = String::new();
+    for part in parts {
+        match part {
+            WordPart::Literal { text, .. } => out.push_str(text),
+            WordPart::Variable { name, default, raw } => match (vars.get(name), default) {
+                (Some(value), _) => out.push_str(value),
+                (None, Some(default)) => out.push_str(default),
+                (None, None) => out.push_str(raw),
+            },
+            WordPart::Substitution { raw } => out.push_str(raw),
+        }
+    }
+    out
+}
+
+/// A simple command: its words (redirections removed) and where it sits in the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Command {
+    pub words: Vec<Word>,
+    pub span: Span,
+}
+
+/// Whether `s` is a valid shell variable name
+pub fn is_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Reserved words that can start a command without being its name
+const LEADING_KEYWORDS: [&str; 10] = ["if", "then", "else", "elif", "do", "while", "until", "!", "{", "time"];
+
+/// Split a script into simple commands
+pub fn parse_commands(src: &str) -> Vec<Command> {
+    let mut lexer = Lexer::new(src);
+    lexer.run();
+    lexer.commands
+}
+
+/// What the next completed word is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NextWord {
+    Normal,
+    /// Target of a redirection (dropped)
+    RedirectTarget,
+    /// Here-document delimiter (dropped, body skipped after the line ends)
+    HeredocDelimiter { strip_tabs: bool },
+}
+
+struct Lexer<'a> {
+    src: &'a str,
+    chars: Vec<(usize, char)>,
+    pos: usize,
+    commands: Vec<Command>,
+    words: Vec<Word>,
+    parts: Vec<WordPart>,
+    literal: String,
+    literal_quoted: bool,
+    word_start: Option<usize>,
+    word_end: usize,
+    next_word: NextWord,
+    pending_heredocs: Vec<(String, bool)>,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(src: &'a str) -> Self {
+        Self {
+            src,
+            chars: src.char_indices().collect(),
+            pos: 0,
+            commands: Vec::new(),
+            words: Vec::new(),
+            parts: Vec::new(),
+            literal: String::new(),
+            literal_quoted: false,
+            word_start: None,
+            word_end: 0,
+            next_word: NextWord::Normal,
+            pending_heredocs: Vec::new(),
+        }
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).map(|(_, c)| *c)
+    }
+
+    /// Byte offset of the character at `pos` (or the end of the source)
+    fn offset(&self, pos: usize) -> usize {
+        self.chars.get(pos).map(|(i, _)| *i).unwrap_or(self.src.len())
+    }
+
+    fn start_word(&mut self) {
+        if self.word_start.is_none() {
+            self.word_start = Some(self.offset(self.pos));
+        }
+    }
+
+    /// Mark everything before `pos` as part of the current word
+    fn extend_word(&mut self) {
+        self.word_end = self.offset(self.pos);
+    }
+
+    fn push_literal(&mut self, c: char, quoted: bool) {
+        if !self.literal.is_empty() && self.literal_quoted != quoted {
+            self.flush_literal();
+        }
+        self.literal_quoted = quoted;
+        self.literal.push(c);
+    }
+
+    fn flush_literal(&mut self) {
+        if !self.literal.is_empty() {
+            self.parts.push(WordPart::Literal {
+                text: std::mem::take(&mut self.literal),
+                quoted: self.literal_quoted,
+            });
+        }
+    }
+
+    fn push_part(&mut self, part: WordPart) {
+        self.flush_literal();
+        self.parts.push(part);
+    }
+
+    fn end_word(&mut self) {
+        let Some(start) = self.word_start.take() else {
+            return;
+        };
+        self.flush_literal();
+        let word = Word {
+            parts: std::mem::take(&mut self.parts),
+            span: Span { start, end: self.word_end },
+        };
+
+        match std::mem::replace(&mut self.next_word, NextWord::Normal) {
+            NextWord::Normal => self.words.push(word),
+            NextWord::RedirectTarget => {}
+            NextWord::HeredocDelimiter { strip_tabs } => {
+                self.pending_heredocs.push((word.text(), strip_tabs));
+            }
+        }
+    }
+
+    fn end_command(&mut self) {
+        self.end_word();
+        let mut words = std::mem::take(&mut self.words);
+        let leading = words
+            .iter()
+            .take_while(|w| LEADING_KEYWORDS.contains(&w.text().as_str()) && w.parts.len() == 1)
+            .count();
+        words.drain(..leading);
+
+        if let (Some(first), Some(last)) = (words.first(), words.last()) {
+            let span = Span { start: first.span.start, end: last.span.end };
+            self.commands.push(Command { words, span });
+        }
+    }
+
+    fn run(&mut self) {
+        while let Some(c) = self.peek(0) {
+            match c {
+                ' ' | '\t' | '\r' => {
+                    self.end_word();
+                    self.pos += 1;
+                }
+                '\n' => {
+                    self.end_command();
+                    self.pos += 1;
+                    self.skip_heredoc_bodies();
+                }
+                '\\' => {
+                    match self.peek(1) {
+                        // Line continuation: removed entirely
+                        Some('\n') => self.pos += 2,
+                        Some(escaped) => {
+                            self.start_word();
+                            self.push_literal(escaped, true);
+                            self.pos += 2;
+                            self.extend_word();
+                        }
+                        None => self.pos += 1,
+                    }
+                }
+                '#' if self.word_start.is_none() => {
+                    while self.peek(0).is_some_and(|c| c != '\n') {
+                        self.pos += 1;
+                    }
+                }
+                '\'' => {
+                    self.start_word();
+                    self.pos += 1;
+                    let mut text = String::new();
+                    while let Some(c) = self.peek(0) {
+                        self.pos += 1;
+                        if c == '\'' {
+                            break;
+                        }
+                        text.push(c);
+                    }
+                    self.flush_literal();
+                    self.parts.push(WordPart::Literal { text, quoted: true });
+                    self.extend_word();
+                }
+                '"' => {
+                    self.start_word();
+                    self.pos += 1;
+                    self.read_double_quoted();
+                    self.extend_word();
+                }
+                '$' => {
+                    self.start_word();
+                    self.read_dollar(false);
+                    self.extend_word();
+                }
+                '`' => {
+                    self.start_word();
+                    self.read_backticks();
+                    self.extend_word();
+                }
+                ';' | '|' | '(' | ')' => {
+                    self.end_command();
+                    self.pos += 1;
+                    while self.peek(0) == Some(c) && c != '(' && c != ')' {
+                        self.pos += 1;
+                    }
+                }
+                '&' if self.peek(1) == Some('>') => self.read_redirection(),
+                '&' => {
+                    self.end_command();
+                    self.pos += 1;
+                    if self.peek(0) == Some('&') {
+                        self.pos += 1;
+                    }
+                }
+                '<' | '>' => self.read_redirection(),
+                _ => {
+                    self.start_word();
+                    self.push_literal(c, false);
+                    self.pos += 1;
+                    self.extend_word();
+                }
+            }
+        }
+        self.end_command();
+    }
+
+    fn read_double_quoted(&mut self) {
+        while let Some(c) = self.peek(0) {
+            match c {
+                '"' => {
+                    self.pos += 1;
+                    break;
+                }
+                '\\' => match self.peek(1) {
+                    Some('\n') => self.pos += 2,
+                    Some(escaped @ ('$' | '`' | '"' | '\\')) => {
+                        self.push_literal(escaped, true);
+                        self.pos += 2;
+                    }
+                    _ => {
+                        self.push_literal('\\', true);
+                        self.pos += 1;
+                    }
+                },
+                '$' => self.read_dollar(true),
+                '`' => self.read_backticks(),
+                _ => {
+                    self.push_literal(c, true);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    /// Read from a `$`: a variable, a substitution, or a literal dollar sign
+    fn read_dollar(&mut self, quoted: bool) {
+        let start = self.pos;
+        match self.peek(1) {
+            Some('{') => {
+                self.pos += 2;
+                let mut inner = String::new();
+                while let Some(c) = self.peek(0) {
+                    self.pos += 1;
+                    if c == '}' {
+                        break;
+                    }
+                    inner.push(c);
+                }
+                let raw = self.raw_since(start);
+                self.push_part(braced_variable(&inner, raw));
+            }
+            Some('(') => {
+                self.pos += 1;
+                let mut depth = 0usize;
+                while let Some(c) = self.peek(0) {
+                    self.pos += 1;
+                    match c {
+                        '(' => depth += 1,
+                        ')' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                let raw = self.raw_since(start);
+                self.push_part(WordPart::Substitution { raw });
+            }
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                self.pos += 1;
+                let mut name = String::new();
+                while let Some(c) = self.peek(0).filter(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    name.push(c);
+                    self.pos += 1;
+                }
+                let raw = self.raw_since(start);
+                self.push_part(WordPart::Variable { name, default: None, raw });
+            }
+            Some(c) if c.is_ascii_digit() || "?@*#$!-".contains(c) => {
+                self.pos += 2;
+                let raw = self.raw_since(start);
+                self.push_part(WordPart::Variable { name: c.to_string(), default: None, raw });
+            }
+            _ => {
+                self.push_literal('$', quoted);
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn read_backticks(&mut self) {
+        let start = self.pos;
+        self.pos += 1;
+        while let Some(c) = self.peek(0) {
+            self.pos += 1;
+            match c {
+                '\\' => self.pos += 1,
+                '`' => break,
+                _ => {}
+            }
+        }
+        let raw = self.raw_since(start);
+        self.push_part(WordPart::Substitution { raw });
+    }
+
+    fn raw_since(&self, start: usize) -> String {
+        self.src[self.offset(start)..self.offset(self.pos)].to_string()
+    }
+
+    /// Consume a redirection operator; the following word is its target
+    fn read_redirection(&mut self) {
+        // A word of digits directly before the operator is a file descriptor
+        let is_fd = self.word_start.is_some()
+            && self.parts.is_empty()
+            && !self.literal.is_empty()
+            && !self.literal_quoted
+            && self.literal.chars().all(|c| c.is_ascii_digit());
+        if is_fd {
+            self.word_start = None;
+            self.literal.clear();
+        } else {
+            self.end_word();
+        }
+
+        let first = self.peek(0);
+        self.pos += 1;
+        let mut heredoc = None;
+        match (first, self.peek(0)) {
+            (Some('<'), Some('<')) => {
+                self.pos += 1;
+                let strip_tabs = self.peek(0) == Some('-');
+                if strip_tabs {
+                    self.pos += 1;
+                }
+                heredoc = Some(strip_tabs);
+            }
+            (Some('&'), Some('>')) => {
+                self.pos += 1;
+                if self.peek(0) == Some('>') {
+                    self.pos += 1;
+                }
+            }
+            (Some('>'), Some('>' | '&' | '|')) | (Some('<'), Some('&' | '>')) => self.pos += 1,
+            _ => {}
+        }
+
+        self.next_word = match heredoc {
+            Some(strip_tabs) => NextWord::HeredocDelimiter { strip_tabs },
+            None => NextWord::RedirectTarget,
+        };
+    }
+
+    /// Skip the bodies of here-documents started on the line just ended
+    fn skip_heredoc_bodies(&mut self) {
+        for (delimiter, strip_tabs) in std::mem::take(&mut self.pending_heredocs) {
+            while self.pos < self.chars.len() {
+                let line_start = self.pos;
+                while self.peek(0).is_some_and(|c| c != '\n') {
+                    self.pos += 1;
+                }
+                let line = &self.src[self.offset(line_start)..self.offset(self.pos)];
+                self.pos += 1; // newline
+                let line = if strip_tabs { line.trim_start_matches('\t') } else { line };
+                if line == delimiter {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Interpret the inside of `${...}`
+fn braced_variable(inner: &str, raw: String) -> WordPart {
+    if is_name(inner) {
+        return WordPart::Variable { name: inner.to_string(), default: None, raw };
+    }
+    for op in [":-", "-", ":=", "="] {
+        if let Some((name, default)) = inner.split_once(op) {
+            if is_name(name) {
+                return WordPart::Variable {
+                    name: name.to_string(),
+                    default: Some(default.to_string()),
+                    raw,
+                };
+            }
+        }
+    }
+    WordPart::Substitution { raw }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(cmd: &Command) -> Vec<String> {
+        cmd.words.iter().map(|w| w.text()).collect()
+    }
+
+    #[test]
+    fn test_quoting_and_continuations() {
+        let src = "docker run \\\n  -e 'A=one two' \\\n  -e \"B=x\\\"y\" \\\n  --name=web nginx # trailing comment\n";
+        let commands = parse_commands(src);
+        assert_eq!(commands.len(), 1);
+        assert_eq!(
+            texts(&commands[0]),
+            vec!["docker", "run", "-e", "A=one two", "-e", "B=x\"y", "--name=web", "nginx"]
+        );
+
+        // Spans point at the words as written
+        let quoted = &commands[0].words[3];
+        assert_eq!(&src[quoted.span.start..quoted.span.end], "'A=one two'");
+    }
+
+    #[test]
+    fn test_operators_and_redirections() {
+        let src = "docker stop $NAME 2>/dev/null || true; docker rm $NAME >/dev/null 2>&1 && echo ok";
+        let commands = parse_commands(src);
+        let all: Vec<Vec<String>> = commands.iter().map(texts).collect();
+        assert_eq!(
+            all,
+            vec![
+                vec!["docker", "stop", "$NAME"],
+                vec!["true"],
+                vec!["docker", "rm", "$NAME"],
+                vec!["echo", "ok"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_variables() {
+        let commands = parse_commands("NAME=web\nTAG=\"${TAG:-latest}\"\ndocker run -e FOO=$BAR --name \"$NAME\" img:$TAG $(date)");
+        let vars: HashMap<String, String> = [("NAME".to_string(), "web".to_string())].into();
+
+        let (name, value) = commands[1].words[0].as_assignment().unwrap();
+        assert_eq!(name, "TAG");
+        assert_eq!(expand_parts(&value, &vars), "latest");
+
+        let run: Vec<String> = commands[2].words.iter().map(|w| w.expand(&vars)).collect();
+        assert_eq!(run, vec!["docker", "run", "-e", "FOO=$BAR", "--name", "web", "img:$TAG", "$(date)"]);
+    }
+
+    #[test]
+    fn test_heredoc_body_is_skipped() {
+        let src = "cat > /tmp/x <<'EOF'\ndocker run not-a-command\nEOF\ndocker start web\n";
+        let all: Vec<Vec<String>> = parse_commands(src).iter().map(texts).collect();
+        assert_eq!(all, vec![vec!["cat"], vec!["docker", "start", "web"]]);
+    }
+}
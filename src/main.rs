@@ -1,4 +1,5 @@
 mod app;
+mod cli;
 mod docker;
 mod models;
 mod ssh;
@@ -19,20 +20,37 @@ use std::io;
 
 use app::App;
 use models::AppMode;
-use ssh::executor::connect_to_host;
+use ssh::executor::{connect_to_host, run_interactive_command};
 use ui::{
     render_delete_confirmation, render_editor_view, render_help_view, render_key_selection_view,
     render_search_overlay, render_shell_selection_view, render_ssh_flags_selection_view,
     render_table_view, render_tag_edit_view, render_tag_filter_view, render_rsync_view,
-    render_rsync_file_browser,
-    render_container_list, render_docker_confirm,
+    render_rsync_file_browser, render_rsync_review, render_rsync_progress, render_rsync_profiles,
+    render_file_manager, render_file_preview,
+    render_container_list, render_docker_confirm, render_docker_settings,
     render_logs_viewer, render_file_browser, render_stats_viewer, render_inspect_viewer,
     render_process_viewer, render_env_inspector, render_script_viewer,
-    render_script_edit, render_env_var_dialog,
+    render_script_edit, render_script_entry_dialog, render_script_diff, render_script_backups,
+    render_script_run_console, render_script_run_history, render_drift_report,
+    render_exec_user_prompt,
+    render_exec_command_prompt, render_exec_output, render_image_list,
+    render_volume_list, render_network_list, render_volume_browser, render_create_resource_prompt,
+    render_compose_projects, render_compose_output, render_compose_export, render_compose_import,
 };
 use utils::handle_input;
 
 fn main() -> Result<()> {
+    // Commands like `sshing rsync <host> <profile>` run without the TUI
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match cli::run(&args) {
+        Ok(Some(code)) => std::process::exit(code),
+        Ok(None) => {}
+        Err(err) => {
+            eprintln!("Error: {:#}", err);
+            std::process::exit(1);
+        }
+    }
+
     // Initialize the application
     let mut app = App::new()?;
 
@@ -146,19 +164,45 @@ fn run_app<B: ratatui::backend::Backend>(
                         render_delete_confirmation(frame, host, area);
                     }
                 }
+                AppMode::DockerSettingsForm { host_index, settings, focused_field, field_buffer, editing_mode } => {
+                    render_table_view(frame, app, area);
+                    let host_name = app.hosts.get(*host_index).map_or("", |h| h.host.as_str());
+                    render_docker_settings(frame, host_name, settings, *focused_field, field_buffer, *editing_mode, area);
+                }
                 AppMode::Rsync { .. } => {
                     render_rsync_view(frame, app, area);
                 }
                 AppMode::RsyncFileBrowser { .. } => {
                     render_rsync_file_browser(frame, app);
                 }
+                AppMode::RsyncReview { .. } => {
+                    render_rsync_review(frame, app, area);
+                }
+                AppMode::RsyncProgress { .. } => {
+                    render_rsync_progress(frame, app, area);
+                }
+                AppMode::RsyncProfiles { .. } => {
+                    render_rsync_profiles(frame, app, area);
+                }
+                AppMode::FileManager { .. } => {
+                    render_file_manager(frame, app);
+                }
+                AppMode::FilePreview { .. } => {
+                    render_file_preview(frame, app);
+                }
 
                 // Docker modes
                 AppMode::ContainerList { .. } => {
                     render_container_list(frame, app, area);
                 }
                 AppMode::ConfirmDockerAction { action, .. } => {
-                    render_container_list(frame, app, area);
+                    match app.mode.underlying() {
+                        AppMode::ImageList { .. } => render_image_list(frame, app, area),
+                        AppMode::VolumeList { .. } => render_volume_list(frame, app, area),
+                        AppMode::NetworkList { .. } => render_network_list(frame, app, area),
+                        AppMode::ComposeProjects { .. } => render_compose_projects(frame, app, area),
+                        _ => render_container_list(frame, app, area),
+                    }
                     render_docker_confirm(frame, action, area);
                 }
                 AppMode::LogsViewer { .. } => {
@@ -182,14 +226,83 @@ fn run_app<B: ratatui::backend::Backend>(
                 AppMode::ScriptEdit { .. } => {
                     render_script_edit(frame, app, area);
                 }
-                AppMode::EnvVarEditor { .. } => {
+                AppMode::ScriptEntryEditor { .. } => {
                     // Render script edit in background, then overlay the dialog
                     render_script_edit(frame, app, area);
-                    render_env_var_dialog(frame, app);
+                    render_script_entry_dialog(frame, app);
+                }
+                AppMode::ScriptDiff { .. } => {
+                    render_script_diff(frame, app);
+                }
+                AppMode::ScriptBackups { .. } => {
+                    render_script_backups(frame, app);
+                }
+                AppMode::ScriptRunConsole { .. } => {
+                    render_script_run_console(frame, app);
+                }
+                AppMode::ScriptRunHistory { .. } => {
+                    render_script_run_history(frame, app);
+                }
+                AppMode::DriftReport { .. } => {
+                    render_drift_report(frame, app);
                 }
                 AppMode::FileBrowser { .. } => {
                     render_file_browser(frame, app);
                 }
+                AppMode::ExecUserPrompt { container_index, user_buffer, .. } => {
+                    render_container_list(frame, app, area);
+                    let container_name = app
+                        .containers
+                        .get(*container_index)
+                        .map(|c| c.name.as_str())
+                        .unwrap_or("Unknown");
+                    render_exec_user_prompt(frame, container_name, user_buffer, area);
+                }
+                AppMode::ExecCommandPrompt { host_index, container_index, command_buffer, history_index } => {
+                    render_container_list(frame, app, area);
+                    let container_name = app
+                        .containers
+                        .get(*container_index)
+                        .map(|c| c.name.as_str())
+                        .unwrap_or("Unknown");
+                    let history_position = history_index
+                        .map(|i| (i, app.exec_history(*host_index, *container_index).len()));
+                    render_exec_command_prompt(frame, container_name, command_buffer, history_position, area);
+                }
+                AppMode::ExecOutput { .. } => {
+                    render_exec_output(frame, app);
+                }
+                AppMode::ImageList { .. } => {
+                    render_image_list(frame, app, area);
+                }
+                AppMode::VolumeList { .. } => {
+                    render_volume_list(frame, app, area);
+                }
+                AppMode::NetworkList { .. } => {
+                    render_network_list(frame, app, area);
+                }
+                AppMode::VolumeBrowser { .. } => {
+                    render_volume_browser(frame, app);
+                }
+                AppMode::ComposeProjects { .. } => {
+                    render_compose_projects(frame, app, area);
+                }
+                AppMode::ComposeOutput { .. } => {
+                    render_compose_output(frame, app);
+                }
+                AppMode::ComposeExport { .. } => {
+                    render_compose_export(frame, app);
+                }
+                AppMode::ComposeImport { .. } => {
+                    render_compose_import(frame, app);
+                }
+                AppMode::CreateResourcePrompt { kind, name_buffer, return_mode, .. } => {
+                    match return_mode.as_ref() {
+                        AppMode::NetworkList { .. } => render_network_list(frame, app, area),
+                        _ => render_volume_list(frame, app, area),
+                    }
+                    render_create_resource_prompt(frame, *kind, name_buffer, area);
+                }
             }
         })?;
 
@@ -233,9 +346,9 @@ fn run_app<B: ratatui::backend::Backend>(
             terminal.clear()?;
         }
 
-        // Check if there's a pending rsync execution
-        if let Some((host, source, dest, to_host, compress)) = app.pending_rsync.clone() {
-            // Cleanup terminal before rsync
+        // Check if there's a pending interactive command (e.g. container shell)
+        if let Some(pending) = app.pending_interactive.clone() {
+            // Cleanup terminal before handing it to SSH
             disable_raw_mode()?;
             let mut stdout = io::stdout();
             execute!(
@@ -244,11 +357,9 @@ fn run_app<B: ratatui::backend::Backend>(
                 DisableMouseCapture
             )?;
 
-            // Execute rsync
-            let (success, output) = crate::ssh::rsync::execute_rsync(&host, &source, &dest, to_host, None, compress)
-                .unwrap_or((false, "Failed to execute rsync".to_string()));
+            let result = run_interactive_command(&pending.host, &pending.command);
 
-            // Restore terminal after rsync
+            // Restore terminal after SSH
             execute!(
                 stdout,
                 EnterAlternateScreen,
@@ -256,19 +367,60 @@ fn run_app<B: ratatui::backend::Backend>(
             )?;
             enable_raw_mode()?;
 
-            // Return to table and show result
-            app.return_to_table();
-            if success {
-                app.set_status(format!("Rsync completed successfully"));
-            } else {
-                app.set_error(format!("Rsync failed:\n{}", output));
+            match result {
+                Ok(_) => app.complete_interactive_command(true, None),
+                Err(e) => app.complete_interactive_command(false, Some(format!("{}: {}", pending.label, e))),
             }
-            app.pending_rsync = None;
 
             // Force a redraw
             terminal.clear()?;
         }
 
+        // Check if there's a host file to edit locally
+        if let Some(pending) = app.pending_file_edit.clone() {
+            // Cleanup terminal before handing it to the editor
+            disable_raw_mode()?;
+            let mut stdout = io::stdout();
+            execute!(
+                stdout,
+                LeaveAlternateScreen,
+                DisableMouseCapture
+            )?;
+
+            let result = crate::ssh::files::edit_remote_file(&pending.host, &pending.path, pending.sudo);
+
+            // Restore terminal after editing
+            execute!(
+                stdout,
+                EnterAlternateScreen,
+                EnableMouseCapture
+            )?;
+            enable_raw_mode()?;
+
+            app.complete_file_edit(result);
+
+            // Force a redraw
+            terminal.clear()?;
+        }
+
+        // Run a pending rsync dry run (the TUI stays up, it only lists changes)
+        if let Some((host_index, mut transfer)) = app.pending_rsync_dry_run.take() {
+            // Between two hosts, stream through here when rsync cannot run from one to the other
+            if let Some(dest_host) = &transfer.dest_host {
+                transfer.relay = !crate::ssh::rsync::hosts_can_reach(&transfer.host, dest_host);
+            }
+            // Without rsync on both ends, copy with sftp or scp instead
+            transfer.tool = crate::ssh::rsync::pick_transfer_tool(&transfer, app.rsync_available);
+            let result = crate::ssh::rsync::rsync_dry_run(&transfer);
+            app.show_rsync_review(host_index, transfer, result);
+        }
+
+        // Collect progress from a running rsync transfer
+        app.poll_rsync_run();
+
+        // Collect output from a running deployment script
+        app.poll_script_run();
+
         // Process pending Docker SSH commands
         if let Some(pending) = app.pending_ssh_command.take() {
             match app.execute_ssh_command(&pending.host, &pending.command) {
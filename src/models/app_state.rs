@@ -1,5 +1,6 @@
-use crate::models::Host;
-use crate::models::docker::{DeploymentScript, ContainerStats, ProcessInfo, ContainerInfo, FileEntry, EnvVar};
+use crate::models::{DockerSettings, FilePane, FilePreview, FilePrompt, Host, RsyncChange, RsyncOptions, RsyncTransfer};
+use crate::utils::diff::DiffLine;
+use crate::models::docker::{DeploymentScript, LintIssue, SecretWrite, ContainerStats, ProcessInfo, ContainerInfo, FileEntry, DockerImage, DockerVolume, DockerNetwork, ComposeProject};
 
 /// Application mode/state
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -115,6 +116,16 @@ pub enum AppMode {
         editing_mode: bool,
         /// Enable compression (-z flag)
         compress: bool,
+        /// Options beyond -a and -z
+        options: RsyncOptions,
+        /// Text of the focused option while it is edited
+        field_buffer: String,
+        /// The saved profile loaded into the form
+        profile: Option<String>,
+        /// Name being typed while the form is saved as a profile
+        profile_name: Option<String>,
+        /// Destination host of a transfer between two hosts (the source is `editing_host`)
+        dest_host: Option<Host>,
     },
 
     /// Rsync file browser for selecting source or destination paths
@@ -143,6 +154,61 @@ pub enum AppMode {
         sync_to_host: bool,
         /// Compress setting (to restore)
         compress: bool,
+        /// Options (to restore)
+        options: RsyncOptions,
+        /// Loaded profile (to restore)
+        profile: Option<String>,
+        /// Destination host of a transfer between two hosts (to restore)
+        dest_host: Option<Host>,
+    },
+
+    /// The host's saved rsync profiles
+    RsyncProfiles {
+        /// Index of host the profiles belong to
+        host_index: usize,
+        /// Currently selected profile
+        selected_index: usize,
+        /// Whether deleting the selected profile waits for confirmation
+        confirm_delete: bool,
+    },
+
+    /// Review of a dry run's changes before the real transfer
+    RsyncReview {
+        /// Index of host being synced with
+        host_index: usize,
+        /// The transfer as set up in the rsync form
+        transfer: RsyncTransfer,
+        /// What the dry run would change
+        changes: Vec<RsyncChange>,
+        /// Currently selected change
+        selected_index: usize,
+    },
+
+    /// A transfer running in the background, then its summary
+    RsyncProgress {
+        /// Index of host being synced with
+        host_index: usize,
+    },
+
+    /// Two panes, this machine and the host, to copy and manage files
+    FileManager {
+        /// Index of host in the remote pane
+        host_index: usize,
+        local: FilePane,
+        remote: FilePane,
+        /// Whether the remote pane has the focus
+        remote_focused: bool,
+        /// Rename, mkdir or delete waiting for input
+        prompt: Option<FilePrompt>,
+        /// A file being viewed, over the panes
+        preview: Option<FilePreview>,
+    },
+
+    /// A file viewed from the script or rsync file browser
+    FilePreview {
+        preview: FilePreview,
+        /// The browser to go back to
+        return_mode: Box<AppMode>,
     },
 
     // ==================== Docker Mode ====================
@@ -214,22 +280,63 @@ pub enum AppMode {
         focused_section: ScriptSection,
         selected_index: usize,
         editing_mode: bool,
-        /// Original env vars when entering edit mode (for in-place modification)
-        original_env_vars: Vec<EnvVar>,
     },
 
-    /// Environment variable editor
-    EnvVarEditor {
+    /// Output of a deployment script run, live or from the history
+    ScriptRunConsole {
+        host_index: usize,
+        container_name: String,
+        /// Index into the container's recorded runs; None follows the live run
+        run_index: Option<usize>,
+        scroll_offset: usize,
+        /// Keep the newest output in view
+        follow: bool,
+    },
+
+    /// Past deployment script runs of a container
+    ScriptRunHistory {
+        host_index: usize,
+        container_name: String,
+        selected_index: usize,
+    },
+
+    /// Review of a script change before it is written
+    ScriptDiff {
+        host_index: usize,
+        container_index: usize,
+        /// The script as it will be once written (raw_content is the new content)
+        script: DeploymentScript,
+        diff: Vec<DiffLine>,
+        scroll_offset: usize,
+        /// Problems found in the new version (None while the remote checks run)
+        lint: Option<Vec<LintIssue>>,
+        /// Secrets moved out of the script, written along with it
+        secrets: Vec<SecretWrite>,
+        /// Where to go back to without writing (the editor or the backup list)
+        return_mode: Box<AppMode>,
+    },
+
+    /// Timestamped backups of a script, newest first
+    ScriptBackups {
+        host_index: usize,
+        container_index: usize,
+        script_path: String,
+        backups: Vec<String>,
+        selected_index: usize,
+        loading: bool,
+    },
+
+    /// Add/edit dialog for one entry of a script editor section
+    ScriptEntryEditor {
         host_index: usize,
         container_index: usize,
         editing_script: DeploymentScript,
-        var_index: Option<usize>,
+        section: ScriptSection,
+        entry_index: Option<usize>,
         key_buffer: String,
         value_buffer: String,
         editing_key: bool,
         is_new: bool,
-        /// Original env vars for in-place modification
-        original_env_vars: Vec<EnvVar>,
     },
 
     /// File browser for selecting scripts
@@ -242,6 +349,132 @@ pub enum AppMode {
         loading: bool,
     },
 
+    /// Prompt for the user to run an interactive container shell as
+    ExecUserPrompt {
+        host_index: usize,
+        container_index: usize,
+        /// User passed to `docker exec -u` (empty for the image default)
+        user_buffer: String,
+    },
+
+    /// Prompt for a one-off command to run inside a container
+    ExecCommandPrompt {
+        host_index: usize,
+        container_index: usize,
+        command_buffer: String,
+        /// Position in the container's command history while browsing with Up/Down
+        history_index: Option<usize>,
+    },
+
+    /// Output of a one-off container command
+    ExecOutput {
+        host_index: usize,
+        container_index: usize,
+        command: String,
+        output: Vec<String>,
+        /// Exit status of the command (None while running or if unknown)
+        exit_code: Option<i32>,
+        scroll_offset: usize,
+    },
+
+    /// Docker images on the host
+    ImageList {
+        host_index: usize,
+        images: Vec<DockerImage>,
+        selected_index: usize,
+    },
+
+    /// Docker volumes on the host
+    VolumeList {
+        host_index: usize,
+        volumes: Vec<DockerVolume>,
+        selected_index: usize,
+    },
+
+    /// Docker networks on the host
+    NetworkList {
+        host_index: usize,
+        networks: Vec<DockerNetwork>,
+        selected_index: usize,
+    },
+
+    /// Read-only browser for a volume's contents (via a throwaway container)
+    VolumeBrowser {
+        host_index: usize,
+        volume: String,
+        /// Path relative to the volume root
+        current_path: String,
+        entries: Vec<FileEntry>,
+        selected_index: usize,
+        loading: bool,
+        /// Contents of the file being previewed, if any
+        preview: Option<Vec<String>>,
+        preview_scroll: usize,
+    },
+
+    /// Docker compose projects on the host
+    ComposeProjects {
+        host_index: usize,
+        projects: Vec<ComposeProject>,
+        selected_index: usize,
+    },
+
+    /// Differences between containers and their deployment scripts
+    DriftReport {
+        host_index: usize,
+        /// One container, or every container with a script
+        container: Option<String>,
+        scroll_offset: usize,
+    },
+
+    /// Preview of deployment scripts converted to a compose file
+    ComposeExport {
+        host_index: usize,
+        /// Script of the selected container
+        script_path: String,
+        /// Export every loaded script of the same project instead of just one
+        whole_project: bool,
+        /// Where the compose file will be written
+        target_path: String,
+        lines: Vec<String>,
+        scroll_offset: usize,
+    },
+
+    /// Services of a compose project, each ready to become a deployment script
+    ComposeImport {
+        host_index: usize,
+        project: String,
+        scripts: Vec<DeploymentScript>,
+        selected_index: usize,
+    },
+
+    /// Output of `docker compose logs` or `docker compose config` for a project
+    ComposeOutput {
+        host_index: usize,
+        project: String,
+        view: ComposeView,
+        lines: Vec<String>,
+        scroll_offset: usize,
+    },
+
+    /// Prompt for the name of a new volume or network
+    CreateResourcePrompt {
+        host_index: usize,
+        kind: DockerResourceKind,
+        name_buffer: String,
+        return_mode: Box<AppMode>,
+    },
+
+    /// Editing where Docker mode looks for scripts on a host
+    DockerSettingsForm {
+        host_index: usize,
+        settings: DockerSettings,
+        focused_field: DockerSettingsField,
+        /// Text of the focused field (lists are comma-separated)
+        field_buffer: String,
+        editing_mode: bool,
+    },
+
     /// Confirmation dialog for docker actions
     ConfirmDockerAction {
         action: DockerPendingAction,
@@ -249,11 +482,129 @@ pub enum AppMode {
     },
 }
 
+impl AppMode {
+    /// The view a dialog is drawn over (the mode itself for non-dialog modes)
+    pub fn underlying(&self) -> &AppMode {
+        match self {
+            AppMode::ConfirmDockerAction { return_mode, .. }
+            | AppMode::CreateResourcePrompt { return_mode, .. } => return_mode.underlying(),
+            mode => mode,
+        }
+    }
+}
+
 /// Fields in rsync mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RsyncField {
     SourcePath,
     DestPath,
+    Excludes,
+    Includes,
+    Delete,
+    Checksum,
+    BwLimit,
+    Resume,
+    Chmod,
+    Chown,
+    BackupDir,
+}
+
+impl RsyncField {
+    pub const ALL: [RsyncField; 11] = [
+        RsyncField::SourcePath,
+        RsyncField::DestPath,
+        RsyncField::Excludes,
+        RsyncField::Includes,
+        RsyncField::Delete,
+        RsyncField::Checksum,
+        RsyncField::BwLimit,
+        RsyncField::Resume,
+        RsyncField::Chmod,
+        RsyncField::Chown,
+        RsyncField::BackupDir,
+    ];
+
+    /// The option rows shown below the paths
+    pub const OPTIONS: &'static [RsyncField] = Self::ALL.split_at(2).1;
+
+    pub fn next(&self) -> RsyncField {
+        let index = Self::ALL.iter().position(|f| f == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn previous(&self) -> RsyncField {
+        let index = Self::ALL.iter().position(|f| f == self).unwrap_or(0);
+        Self::ALL[(index + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RsyncField::SourcePath => "Source",
+            RsyncField::DestPath => "Destination",
+            RsyncField::Excludes => "Exclude",
+            RsyncField::Includes => "Include",
+            RsyncField::Delete => "Delete",
+            RsyncField::Checksum => "Checksum",
+            RsyncField::BwLimit => "Bandwidth",
+            RsyncField::Resume => "Resume",
+            RsyncField::Chmod => "Chmod",
+            RsyncField::Chown => "Chown",
+            RsyncField::BackupDir => "Backup dir",
+        }
+    }
+
+    /// Options switched with Enter instead of typed
+    pub fn is_toggle(&self) -> bool {
+        matches!(self, RsyncField::Delete | RsyncField::Checksum | RsyncField::Resume)
+    }
+
+    /// The option's value as edited in the form (lists are comma-separated)
+    pub fn value(&self, options: &RsyncOptions) -> String {
+        let text = |value: &Option<String>| value.clone().unwrap_or_default();
+        let on_off = |on: bool| if on { "on" } else { "off" }.to_string();
+        match self {
+            RsyncField::SourcePath | RsyncField::DestPath => String::new(),
+            RsyncField::Excludes => options.excludes.join(", "),
+            RsyncField::Includes => options.includes.join(", "),
+            RsyncField::Delete => on_off(options.delete),
+            RsyncField::Checksum => on_off(options.checksum),
+            RsyncField::BwLimit => text(&options.bwlimit),
+            RsyncField::Resume => options.resume.label().to_string(),
+            RsyncField::Chmod => text(&options.chmod),
+            RsyncField::Chown => text(&options.chown),
+            RsyncField::BackupDir => text(&options.backup_dir),
+        }
+    }
+
+    /// Store the edited text of an option, checking it first
+    pub fn apply(&self, options: &mut RsyncOptions, buffer: &str) -> Result<(), String> {
+        let mut updated = options.clone();
+        let list = || buffer.split(',').map(str::trim).filter(|v| !v.is_empty()).map(str::to_string).collect();
+        let text = || Some(buffer.trim().to_string()).filter(|v| !v.is_empty());
+        match self {
+            RsyncField::Excludes => updated.excludes = list(),
+            RsyncField::Includes => updated.includes = list(),
+            RsyncField::BwLimit => updated.bwlimit = text(),
+            RsyncField::Chmod => updated.chmod = text(),
+            RsyncField::Chown => updated.chown = text(),
+            RsyncField::BackupDir => updated.backup_dir = text(),
+            _ => return Ok(()),
+        }
+        // The destination is checked when the transfer starts
+        updated.validate("-")?;
+        *options = updated;
+        Ok(())
+    }
+
+    /// Switch a toggle option to its next value
+    pub fn toggle(&self, options: &mut RsyncOptions) {
+        match self {
+            RsyncField::Delete => options.delete = !options.delete,
+            RsyncField::Checksum => options.checksum = !options.checksum,
+            RsyncField::Resume => options.resume = options.resume.next(),
+            _ => {}
+        }
+    }
 }
 
 /// Fields in the host edit form
@@ -305,6 +656,81 @@ impl HostField {
     }
 }
 
+/// Fields in the Docker settings form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DockerSettingsField {
+    SearchRoots,
+    IncludePatterns,
+    ExcludePatterns,
+    MaxDepth,
+    Sudo,
+}
+
+impl DockerSettingsField {
+    pub const ALL: [DockerSettingsField; 5] = [
+        DockerSettingsField::SearchRoots,
+        DockerSettingsField::IncludePatterns,
+        DockerSettingsField::ExcludePatterns,
+        DockerSettingsField::MaxDepth,
+        DockerSettingsField::Sudo,
+    ];
+
+    pub fn next(&self) -> DockerSettingsField {
+        let index = Self::ALL.iter().position(|f| f == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn previous(&self) -> DockerSettingsField {
+        let index = Self::ALL.iter().position(|f| f == self).unwrap_or(0);
+        Self::ALL[(index + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DockerSettingsField::SearchRoots => "Search roots",
+            DockerSettingsField::IncludePatterns => "Include",
+            DockerSettingsField::ExcludePatterns => "Exclude",
+            DockerSettingsField::MaxDepth => "Max depth",
+            DockerSettingsField::Sudo => "Sudo",
+        }
+    }
+
+    /// The field's value as edited in the form
+    pub fn value(&self, settings: &DockerSettings) -> String {
+        match self {
+            DockerSettingsField::SearchRoots => settings.search_roots.join(", "),
+            DockerSettingsField::IncludePatterns => settings.include_patterns.join(", "),
+            DockerSettingsField::ExcludePatterns => settings.exclude_patterns.join(", "),
+            DockerSettingsField::MaxDepth => settings.max_depth.map(|d| d.to_string()).unwrap_or_default(),
+            DockerSettingsField::Sudo => match settings.use_sudo {
+                None => "ask",
+                Some(true) => "always",
+                Some(false) => "never",
+            }
+            .to_string(),
+        }
+    }
+
+    /// Store the edited text in the settings
+    pub fn apply(&self, settings: &mut DockerSettings, buffer: &str) -> Result<(), String> {
+        let list = || buffer.split(',').map(str::trim).filter(|v| !v.is_empty()).map(str::to_string).collect();
+        match self {
+            DockerSettingsField::SearchRoots => settings.search_roots = list(),
+            DockerSettingsField::IncludePatterns => settings.include_patterns = list(),
+            DockerSettingsField::ExcludePatterns => settings.exclude_patterns = list(),
+            DockerSettingsField::MaxDepth => {
+                settings.max_depth = match buffer.trim() {
+                    "" => None,
+                    depth => Some(depth.parse().map_err(|_| format!("Max depth must be a number, not '{}'", depth))?),
+                }
+            }
+            // Cycled with Enter/Space rather than typed
+            DockerSettingsField::Sudo => {}
+        }
+        Ok(())
+    }
+}
+
 /// Sort order for hosts
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum SortBy {
@@ -391,6 +817,26 @@ impl ScriptSection {
             ScriptSection::Network => ScriptSection::Ports,
         }
     }
+
+    /// What one entry of the section is called in the entry dialog
+    pub fn entry_name(&self) -> &'static str {
+        match self {
+            ScriptSection::EnvVars => "Environment Variable",
+            ScriptSection::Volumes => "Volume Mount",
+            ScriptSection::Ports => "Port Mapping",
+            ScriptSection::Network => "Network Settings",
+        }
+    }
+
+    /// Labels of the two fields of the entry dialog
+    pub fn entry_fields(&self) -> (&'static str, &'static str) {
+        match self {
+            ScriptSection::EnvVars => ("Key", "Value"),
+            ScriptSection::Volumes => ("Host path", "Container path"),
+            ScriptSection::Ports => ("Host port", "Container port"),
+            ScriptSection::Network => ("Network", "Restart policy"),
+        }
+    }
 }
 
 /// Pending docker actions requiring confirmation
@@ -401,11 +847,105 @@ pub enum DockerPendingAction {
     DockerStop { host_index: usize, container_id: String, container_name: String },
     DockerStart { host_index: usize, container_id: String, container_name: String },
     DockerRemove { host_index: usize, container_id: String, container_name: String, image_name: String, remove_volumes: bool, remove_image: bool },
-    RunScript { host_index: usize, script_path: String },
+    /// `lint` is None while the checks run on the remote
+    RunScript { host_index: usize, script_path: String, lint: Option<Vec<LintIssue>> },
     EnableSudo { host_index: usize },
+    ImagePull { host_index: usize, reference: String },
+    ImageRemove { host_index: usize, reference: String, used_by: Vec<String> },
+    ImagePrune { host_index: usize },
+    VolumeCreate { host_index: usize, name: String },
+    VolumeRemove { host_index: usize, name: String, used_by: Vec<String> },
+    VolumePrune { host_index: usize },
+    NetworkCreate { host_index: usize, name: String },
+    NetworkRemove { host_index: usize, name: String, containers: Vec<String> },
+    NetworkPrune { host_index: usize },
+    Compose { host_index: usize, project: String, config_files: Vec<String>, action: ComposeAction },
+}
+
+/// Project-level docker compose actions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComposeAction {
+    Up,
+    Down,
+    Pull,
+    Restart,
+}
+
+impl ComposeAction {
+    /// The compose subcommand (with flags) that performs the action
+    pub fn subcommand(&self) -> &'static str {
+        match self {
+            ComposeAction::Up => "up -d",
+            ComposeAction::Down => "down",
+            ComposeAction::Pull => "pull",
+            ComposeAction::Restart => "restart",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ComposeAction::Up => "Up",
+            ComposeAction::Down => "Down",
+            ComposeAction::Pull => "Pull",
+            ComposeAction::Restart => "Restart",
+        }
+    }
+}
+
+/// What the compose output view shows for a project
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComposeView {
+    Logs,
+    Config,
+}
+
+impl ComposeView {
+    /// The compose subcommand that prints it; failures show as output
+    pub fn subcommand(&self) -> &'static str {
+        match self {
+            ComposeView::Logs => "logs --no-color --tail 500 2>&1 || true",
+            ComposeView::Config => "config 2>&1 || true",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ComposeView::Logs => "logs",
+            ComposeView::Config => "config",
+        }
+    }
+}
+
+/// Host-wide docker resources that can be created from the TUI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DockerResourceKind {
+    Volume,
+    Network,
+}
+
+impl DockerResourceKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DockerResourceKind::Volume => "volume",
+            DockerResourceKind::Network => "network",
+        }
+    }
 }
 
 impl DockerPendingAction {
+    /// Results of the script checks, for actions that run a script
+    pub fn script_lint(&self) -> Option<&Option<Vec<LintIssue>>> {
+        match self {
+            DockerPendingAction::RunScript { lint, .. } => Some(lint),
+            _ => None,
+        }
+    }
+
+    /// Whether the checks found problems that should stop the action
+    pub fn has_lint_errors(&self) -> bool {
+        matches!(self.script_lint(), Some(Some(issues)) if issues.iter().any(LintIssue::is_error))
+    }
+
     pub fn description(&self) -> String {
         match self {
             DockerPendingAction::DockerPull { container_name, .. } => {
@@ -437,6 +977,51 @@ impl DockerPendingAction {
             DockerPendingAction::EnableSudo { .. } => {
                 "Use sudo for Docker commands? (Required if Docker runs as root)".to_string()
             }
+            DockerPendingAction::ImagePull { reference, .. } => {
+                format!("Pull image '{}'?", reference)
+            }
+            DockerPendingAction::ImageRemove { reference, used_by, .. } => {
+                if used_by.is_empty() {
+                    format!("Remove image '{}'? (Cannot be undone!)", reference)
+                } else {
+                    format!("Remove image '{}'? Used by: {} (Cannot be undone!)", reference, used_by.join(", "))
+                }
+            }
+            DockerPendingAction::ImagePrune { .. } => {
+                "Remove all dangling (untagged) images? (Cannot be undone!)".to_string()
+            }
+            DockerPendingAction::VolumeCreate { name, .. } => {
+                format!("Create volume '{}'?", name)
+            }
+            DockerPendingAction::VolumeRemove { name, used_by, .. } => {
+                if used_by.is_empty() {
+                    format!("Remove volume '{}' and its data? (Cannot be undone!)", name)
+                } else {
+                    format!("Remove volume '{}'? Used by: {} (Cannot be undone!)", name, used_by.join(", "))
+                }
+            }
+            DockerPendingAction::VolumePrune { .. } => {
+                "Remove all unused volumes and their data? (Cannot be undone!)".to_string()
+            }
+            DockerPendingAction::NetworkCreate { name, .. } => {
+                format!("Create network '{}'?", name)
+            }
+            DockerPendingAction::NetworkRemove { name, containers, .. } => {
+                if containers.is_empty() {
+                    format!("Remove network '{}'?", name)
+                } else {
+                    format!("Remove network '{}'? Attached: {}", name, containers.join(", "))
+                }
+            }
+            DockerPendingAction::NetworkPrune { .. } => {
+                "Remove all networks not used by a container?".to_string()
+            }
+            DockerPendingAction::Compose { project, action, .. } => match action {
+                ComposeAction::Up => format!("Start compose project '{}' (up -d)?", project),
+                ComposeAction::Down => format!("Stop and remove compose project '{}' (down)?", project),
+                ComposeAction::Pull => format!("Pull images for compose project '{}'?", project),
+                ComposeAction::Restart => format!("Restart compose project '{}'?", project),
+            },
         }
     }
 }
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// A docker compose project, either deployed (from `docker compose ls`) or
+/// only present as a compose file under the clients path
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ComposeProject {
+    pub name: String,
+    /// Status as reported by compose, e.g. "running(3)" or "exited(1)"
+    pub status: String,
+    pub config_files: Vec<String>,
+}
+
+impl ComposeProject {
+    pub fn is_running(&self) -> bool {
+        self.status.starts_with("running")
+    }
+
+    pub fn is_deployed(&self) -> bool {
+        self.status != ComposeProject::NOT_DEPLOYED
+    }
+
+    pub const NOT_DEPLOYED: &'static str = "not deployed";
+
+    /// Project name compose derives from a directory name
+    pub fn name_from_dir(dir: &str) -> String {
+        dir.to_lowercase()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+            .collect()
+    }
+}
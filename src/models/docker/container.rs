@@ -53,7 +53,6 @@ impl ContainerStatus {
         }
     }
 
-    #[allow(dead_code)]
     pub fn is_running(&self) -> bool {
         matches!(self, ContainerStatus::Running)
     }
@@ -64,12 +63,37 @@ pub struct PortMapping {
     pub host_port: u16,
     pub container_port: u16,
     pub protocol: String,
+    /// Host address the port is bound to; None for every address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_ip: Option<String>,
 }
 
 impl PortMapping {
     pub fn display(&self) -> String {
         format!("{}:{}", self.host_port, self.container_port)
     }
+
+    /// The host side as `-p` takes it: `[ip:]port`, an IPv6 address in brackets
+    pub fn host_address(&self) -> String {
+        match self.host_ip.as_deref() {
+            Some(ip) if ip.contains(':') => format!("[{}]:{}", ip, self.host_port),
+            Some(ip) => format!("{}:{}", ip, self.host_port),
+            None => self.host_port.to_string(),
+        }
+    }
+
+    /// The mapping as `-p` and compose take it: `[ip:]host:container[/proto]`,
+    /// leaving out the default tcp
+    pub fn publish_spec(&self) -> String {
+        let proto = if self.protocol == "tcp" { String::new() } else { format!("/{}", self.protocol) };
+        format!("{}:{}{}", self.host_address(), self.container_port, proto)
+    }
+
+    /// A bind address as Docker or `-p` gives it, None when it means every address
+    pub fn parse_host_ip(ip: &str) -> Option<String> {
+        let ip = ip.trim_start_matches('[').trim_end_matches(']');
+        (!matches!(ip, "" | "0.0.0.0" | "::")).then(|| ip.to_string())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -85,6 +109,9 @@ pub struct Container {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub script_path: Option<String>,
     pub networks: Vec<String>,
+    /// Value of the `com.docker.compose.project` label
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compose_project: Option<String>,
 }
 
 impl Container {
@@ -108,7 +135,7 @@ impl Container {
         // Extract just the image name from full path
         self.image
             .split('/')
-            .last()
+            .next_back()
             .unwrap_or(&self.image)
             .split(':')
             .next()
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+use super::Container;
+
+/// Result of comparing a local image with the registry
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ImageUpdateStatus {
+    UpToDate,
+    UpdateAvailable,
+    Unknown(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DockerImage {
+    pub id: String,
+    pub repository: String,
+    pub tag: String,
+    pub size: String,
+    pub created: String,
+    /// Names of containers (running or not) created from this image
+    pub used_by: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_status: Option<ImageUpdateStatus>,
+}
+
+impl DockerImage {
+    /// Untagged image left behind by a rebuild or re-pull
+    pub fn is_dangling(&self) -> bool {
+        self.repository == "<none>" && self.tag == "<none>"
+    }
+
+    /// `repo:tag`, or the image ID when the image has no tag. A repository
+    /// alone would mean `repo:latest`, which may be another image
+    pub fn reference(&self) -> String {
+        if self.repository == "<none>" || self.tag == "<none>" {
+            self.id.clone()
+        } else {
+            format!("{}:{}", self.repository, self.tag)
+        }
+    }
+
+    /// Whether a container was created from this image. `docker ps` shows the
+    /// name the container was started with (`nginx` means `nginx:latest`), or
+    /// the short ID once that tag has moved to a newer image.
+    pub fn is_used_by(&self, container: &Container) -> bool {
+        let image = container.image.as_str();
+        if image == self.id || (!image.is_empty() && self.id.starts_with(image)) {
+            return true;
+        }
+        if self.repository == "<none>" {
+            return false;
+        }
+        image == self.reference() || (self.tag == "latest" && image == self.repository)
+    }
+}
@@ -1,5 +1,11 @@
 mod container;
 mod script;
+mod image;
+mod volume;
+mod compose;
 
 pub use container::{Container, ContainerStatus, PortMapping};
-pub use script::{DeploymentScript, EnvVar, VolumeMount, ContainerStats, ProcessInfo, ContainerInfo, FileEntry, Project};
+pub use script::{DeploymentScript, EnvVar, EnvStorage, VolumeMount, HealthCheck, LintIssue, DriftItem, SecretWrite, ContainerStats, ProcessInfo, ContainerInfo, FileEntry, FileSort, Project};
+pub use image::{DockerImage, ImageUpdateStatus};
+pub use volume::{DockerVolume, DockerNetwork};
+pub use compose::ComposeProject;
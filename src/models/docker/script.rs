@@ -3,21 +3,54 @@ use serde::{Deserialize, Serialize};
 
 use super::PortMapping;
 
+/// Where an env var's value is kept when the script is saved
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EnvStorage {
+    /// `-e KEY=value` in the script itself
+    #[default]
+    Inline,
+    /// A `KEY=value` line in a 0600 `.env` file passed with `--env-file`
+    EnvFile,
+    /// A 0600 file mounted at `/run/secrets/<key>`, with `KEY_FILE` pointing at it
+    SecretFile,
+}
+
+impl EnvStorage {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Inline => Self::EnvFile,
+            Self::EnvFile => Self::SecretFile,
+            Self::SecretFile => Self::Inline,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Inline => "inline",
+            Self::EnvFile => ".env file",
+            Self::SecretFile => "secret file",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct EnvVar {
     pub key: String,
     pub value: String,
     #[serde(default)]
     pub is_secret: bool,
+    /// Set in the editor to move the value out of the script on the next save
+    #[serde(default)]
+    pub storage: EnvStorage,
 }
 
 impl EnvVar {
     pub fn new(key: String, value: String) -> Self {
         let is_secret = Self::detect_secret(&key);
-        Self { key, value, is_secret }
+        Self { key, value, is_secret, storage: EnvStorage::Inline }
     }
 
-    fn detect_secret(key: &str) -> bool {
+    pub fn detect_secret(key: &str) -> bool {
         let key_upper = key.to_uppercase();
         key_upper.contains("PASSWORD")
             || key_upper.contains("SECRET")
@@ -63,6 +96,22 @@ impl VolumeMount {
     }
 }
 
+/// A container healthcheck (`--health-*` options). Durations are kept as
+/// docker writes them, e.g. `30s`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HealthCheck {
+    /// Shell command run by `CMD-SHELL`
+    pub command: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_period: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct DeploymentScript {
     pub path: String,
@@ -76,6 +125,28 @@ pub struct DeploymentScript {
     pub ports: Vec<PortMapping>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub restart_policy: Option<String>,
+    /// Networks connected after the container is created (`docker network connect`)
+    #[serde(default)]
+    pub extra_networks: Vec<String>,
+    #[serde(default)]
+    pub labels: Vec<(String, String)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entrypoint: Option<String>,
+    /// Arguments after the image
+    #[serde(default)]
+    pub command: Vec<String>,
+    /// Memory limit as given to `--memory`, e.g. `512m`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpus: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub healthcheck: Option<HealthCheck>,
+    /// Files passed with `--env-file`, as written in the script
+    #[serde(default)]
+    pub env_files: Vec<String>,
     pub raw_content: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_modified: Option<DateTime<Utc>>,
@@ -93,6 +164,15 @@ impl DeploymentScript {
             network: None,
             ports: Vec::new(),
             restart_policy: None,
+            extra_networks: Vec::new(),
+            labels: Vec::new(),
+            user: None,
+            entrypoint: None,
+            command: Vec::new(),
+            memory: None,
+            cpus: None,
+            healthcheck: None,
+            env_files: Vec::new(),
             raw_content: String::new(),
             last_modified: None,
         }
@@ -119,6 +199,60 @@ impl DeploymentScript {
     }
 }
 
+/// How serious a problem found by the script checks is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintSeverity {
+    Warning,
+    Error,
+}
+
+/// A problem found in a deployment script before it is saved or run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    pub severity: LintSeverity,
+    pub message: String,
+}
+
+impl LintIssue {
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self { severity: LintSeverity::Warning, message: message.into() }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self { severity: LintSeverity::Error, message: message.into() }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == LintSeverity::Error
+    }
+}
+
+/// A secret moved out of a script, written on the remote along with the script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretWrite {
+    /// `KEY=value` merged into an env file
+    EnvFileEntry { path: String, key: String, value: String },
+    /// A file holding only the value
+    File { path: String, value: String },
+}
+
+/// A setting where the running container no longer matches its script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriftItem {
+    /// What differs, e.g. `image`, `env API_URL` or `port`
+    pub field: String,
+    /// The value in the script, or `-` when the script does not set it
+    pub script: String,
+    /// The value in `docker inspect`, or `-` when the container does not have it
+    pub container: String,
+}
+
+impl DriftItem {
+    pub fn new(field: impl Into<String>, script: impl Into<String>, container: impl Into<String>) -> Self {
+        Self { field: field.into(), script: script.into(), container: container.into() }
+    }
+}
+
 /// Container resource statistics
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct ContainerStats {
@@ -159,26 +293,122 @@ pub struct ContainerInfo {
     pub labels: Vec<(String, String)>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct FileEntry {
     pub name: String,
+    /// A directory, or a symlink to one
     pub is_dir: bool,
     pub is_script: bool,
+    /// Size in bytes (of the link itself for symlinks)
+    pub size: u64,
+    /// Last modification as a unix timestamp, when the listing has one
+    pub modified: Option<i64>,
+    /// Permission bits, e.g. 0o755
+    pub mode: Option<u32>,
+    pub owner: String,
+    /// Where a symlink points
+    pub link_target: Option<String>,
 }
 
 impl FileEntry {
     pub fn new(name: String, is_dir: bool) -> Self {
         let is_script = !is_dir && (name.ends_with(".sh") || name.starts_with("start"));
-        Self { name, is_dir, is_script }
+        Self { name, is_dir, is_script, ..Self::default() }
     }
 
     pub fn parent() -> Self {
         Self {
             name: "..".to_string(),
             is_dir: true,
-            is_script: false,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.name.starts_with('.') && self.name != ".."
+    }
+
+    /// The name, followed by ` -> target` for symlinks
+    pub fn display_name(&self) -> String {
+        match &self.link_target {
+            Some(target) => format!("{} -> {}", self.name, target),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Permissions as `ls` shows them, e.g. `drwxr-xr-x` (blank when unknown)
+    pub fn mode_label(&self) -> String {
+        let Some(mode) = self.mode else {
+            return String::new();
+        };
+        let kind = if self.link_target.is_some() {
+            'l'
+        } else if self.is_dir {
+            'd'
+        } else {
+            '-'
+        };
+        let mut label = String::from(kind);
+        for shift in [6, 3, 0] {
+            let bits = mode >> shift;
+            label.push(if bits & 4 != 0 { 'r' } else { '-' });
+            label.push(if bits & 2 != 0 { 'w' } else { '-' });
+            label.push(if bits & 1 != 0 { 'x' } else { '-' });
+        }
+        label
+    }
+
+    /// Modification time in local time, e.g. `2024-03-01 14:05` (blank when unknown)
+    pub fn modified_label(&self) -> String {
+        self.modified
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// The order of entries in the file browsers. Directories always come first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FileSort {
+    #[default]
+    Name,
+    /// Largest first
+    Size,
+    /// Newest first
+    Modified,
+}
+
+impl FileSort {
+    pub fn next(self) -> Self {
+        match self {
+            FileSort::Name => FileSort::Size,
+            FileSort::Size => FileSort::Modified,
+            FileSort::Modified => FileSort::Name,
         }
     }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FileSort::Name => "name",
+            FileSort::Size => "size",
+            FileSort::Modified => "modified",
+        }
+    }
+
+    /// Sort entries in place, keeping `..` at the top
+    pub fn sort(self, entries: &mut [FileEntry]) {
+        entries.sort_by(|a, b| {
+            (b.name == "..")
+                .cmp(&(a.name == ".."))
+                .then(b.is_dir.cmp(&a.is_dir))
+                .then_with(|| match self {
+                    FileSort::Name => std::cmp::Ordering::Equal,
+                    FileSort::Size => b.size.cmp(&a.size),
+                    FileSort::Modified => b.modified.cmp(&a.modified),
+                })
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        });
+    }
 }
 
 /// Represents a project folder on the remote server
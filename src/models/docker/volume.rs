@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DockerVolume {
+    pub name: String,
+    pub driver: String,
+    pub mountpoint: String,
+    /// Disk usage from `docker system df -v` (None if docker didn't report it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<String>,
+    /// Names of containers (running or not) mounting this volume
+    pub used_by: Vec<String>,
+}
+
+impl DockerVolume {
+    /// Not mounted by any container, so `docker volume prune` may remove it
+    pub fn is_orphaned(&self) -> bool {
+        self.used_by.is_empty()
+    }
+
+    /// Anonymous volumes are named with a 64 character hex ID
+    pub fn is_anonymous(&self) -> bool {
+        self.name.len() == 64 && self.name.chars().all(|c| c.is_ascii_hexdigit())
+    }
+
+    pub fn short_name(&self) -> String {
+        if self.is_anonymous() {
+            self.name[..12].to_string()
+        } else {
+            self.name.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DockerNetwork {
+    pub id: String,
+    pub name: String,
+    pub driver: String,
+    pub scope: String,
+    /// IPAM subnets (a network can have both IPv4 and IPv6)
+    pub subnets: Vec<String>,
+    /// Names of attached containers
+    pub containers: Vec<String>,
+}
+
+impl DockerNetwork {
+    /// Networks docker creates itself and refuses to remove
+    pub fn is_builtin(&self) -> bool {
+        matches!(self.name.as_str(), "bridge" | "host" | "none")
+    }
+}
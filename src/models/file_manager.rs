@@ -0,0 +1,137 @@
+use crate::models::FileEntry;
+
+/// One side of the file manager: a directory on this machine or on the host
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FilePane {
+    /// Absolute path of the directory shown
+    pub path: String,
+    pub entries: Vec<FileEntry>,
+    pub selected_index: usize,
+    /// Names marked for copying or deleting
+    pub marked: Vec<String>,
+    pub loading: bool,
+}
+
+impl FilePane {
+    pub fn new(path: impl Into<String>) -> Self {
+        FilePane { path: path.into(), ..FilePane::default() }
+    }
+
+    /// Show a directory's entries, keeping the selection on the same name
+    /// when the directory is only refreshed
+    pub fn show(&mut self, path: String, entries: Vec<FileEntry>) {
+        let selected = (path == self.path).then(|| self.selected().map(|e| e.name.clone())).flatten();
+        self.selected_index = selected
+            .and_then(|name| entries.iter().position(|e| e.name == name))
+            .unwrap_or(0);
+        self.marked.retain(|name| path == self.path && entries.iter().any(|e| e.name == *name));
+        self.path = path;
+        self.entries = entries;
+        self.loading = false;
+    }
+
+    /// The selected entry, unless it is `..`
+    pub fn selected(&self) -> Option<&FileEntry> {
+        self.entries.get(self.selected_index).filter(|e| e.name != "..")
+    }
+
+    /// The marked names, or the selected one when nothing is marked
+    pub fn targets(&self) -> Vec<String> {
+        if self.marked.is_empty() {
+            self.selected().map(|e| vec![e.name.clone()]).unwrap_or_default()
+        } else {
+            self.marked.clone()
+        }
+    }
+
+    /// Mark the selected entry, or unmark it, and move to the next one
+    pub fn toggle_mark(&mut self) {
+        if let Some(name) = self.selected().map(|e| e.name.clone()) {
+            match self.marked.iter().position(|m| *m == name) {
+                Some(index) => {
+                    self.marked.remove(index);
+                }
+                None => self.marked.push(name),
+            }
+        }
+        self.selected_index = (self.selected_index + 1).min(self.entries.len().saturating_sub(1));
+    }
+
+    /// Path of `name` inside the pane's directory
+    pub fn child_path(&self, name: &str) -> String {
+        if self.path.ends_with('/') {
+            format!("{}{}", self.path, name)
+        } else {
+            format!("{}/{}", self.path, name)
+        }
+    }
+
+    /// The directory above the pane's, or `/` at the top
+    pub fn parent_path(&self) -> String {
+        std::path::Path::new(&self.path)
+            .parent()
+            .map(|parent| parent.to_string_lossy().to_string())
+            .filter(|parent| !parent.is_empty())
+            .unwrap_or_else(|| "/".to_string())
+    }
+}
+
+/// A question the file manager asks before changing files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilePrompt {
+    /// New name for `name` in the focused pane
+    Rename { name: String, buffer: String },
+    /// Name of a directory to create in the focused pane
+    Mkdir { buffer: String },
+    /// Delete these names from the focused pane, once confirmed with y
+    ConfirmDelete { names: Vec<String> },
+}
+
+/// A file shown in the pager of the file manager or the file browsers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilePreview {
+    pub path: String,
+    pub lines: Vec<String>,
+    pub scroll: usize,
+    /// Only the start of the file was read
+    pub truncated: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_pane() {
+        let mut pane = FilePane::new("/srv");
+        pane.show(
+            "/srv".to_string(),
+            vec![
+                FileEntry::parent(),
+                FileEntry::new("app".to_string(), true),
+                FileEntry::new("notes.txt".to_string(), false),
+            ],
+        );
+        assert_eq!(pane.targets(), Vec::<String>::new());
+
+        pane.selected_index = 1;
+        assert_eq!(pane.targets(), vec!["app".to_string()]);
+        pane.toggle_mark();
+        pane.toggle_mark();
+        assert_eq!(pane.selected_index, 2);
+        assert_eq!(pane.targets(), vec!["app".to_string(), "notes.txt".to_string()]);
+
+        // A refresh keeps the selection and the marks that still exist
+        pane.show("/srv".to_string(), vec![FileEntry::parent(), FileEntry::new("notes.txt".to_string(), false)]);
+        assert_eq!(pane.selected_index, 1);
+        assert_eq!(pane.marked, vec!["notes.txt".to_string()]);
+
+        // Another directory starts over
+        pane.show("/srv/app".to_string(), vec![FileEntry::parent(), FileEntry::new("notes.txt".to_string(), false)]);
+        assert_eq!((pane.selected_index, pane.marked.len()), (0, 0));
+        assert_eq!(pane.child_path("main.rs"), "/srv/app/main.rs");
+        assert_eq!(pane.parent_path(), "/srv");
+        assert_eq!(FilePane::new("/srv").parent_path(), "/");
+        assert_eq!(FilePane::new("/").child_path("etc"), "/etc");
+    }
+}
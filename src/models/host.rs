@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::models::RsyncProfile;
+
 /// Represents an SSH host configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Host {
@@ -43,6 +45,81 @@ pub struct Host {
     /// Timestamp of last connection
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_used: Option<DateTime<Utc>>,
+
+    /// Where Docker mode looks for deployment scripts on this host
+    #[serde(default, skip_serializing_if = "DockerSettings::is_default")]
+    pub docker: DockerSettings,
+
+    /// Saved rsync transfers for this host
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rsync_profiles: Vec<RsyncProfile>,
+}
+
+/// Directory searched for projects when a host has no search roots
+pub const DEFAULT_SEARCH_ROOT: &str = "~/clients";
+
+/// Script names matched when a host has no include patterns
+pub const DEFAULT_INCLUDE_PATTERNS: &[&str] = &["start*.sh", "deploy*.sh", "run*.sh", "docker*.sh"];
+
+/// Files and directories skipped when a host has no exclude patterns
+pub const DEFAULT_EXCLUDE_PATTERNS: &[&str] = &["node_modules", ".git", "vendor"];
+
+/// Per-host script discovery settings. Empty lists fall back to the defaults
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DockerSettings {
+    /// Directories whose subdirectories are projects
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub search_roots: Vec<String>,
+
+    /// Name globs a deployment script must match
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include_patterns: Vec<String>,
+
+    /// Name globs of files and directories to skip
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_patterns: Vec<String>,
+
+    /// How deep below a project directory scripts are looked for (unlimited if None)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<u32>,
+
+    /// Run commands with sudo without asking (None asks on each connection)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_sudo: Option<bool>,
+}
+
+impl DockerSettings {
+    pub fn is_default(&self) -> bool {
+        *self == DockerSettings::default()
+    }
+
+    /// The search roots in effect
+    pub fn roots(&self) -> Vec<String> {
+        with_default(&self.search_roots, &[DEFAULT_SEARCH_ROOT])
+    }
+
+    /// The include patterns in effect
+    pub fn includes(&self) -> Vec<String> {
+        with_default(&self.include_patterns, DEFAULT_INCLUDE_PATTERNS)
+    }
+
+    /// The exclude patterns in effect
+    pub fn excludes(&self) -> Vec<String> {
+        with_default(&self.exclude_patterns, DEFAULT_EXCLUDE_PATTERNS)
+    }
+
+    /// Where new scripts are created: the first search root
+    pub fn primary_root(&self) -> String {
+        self.roots().swap_remove(0)
+    }
+}
+
+fn with_default(values: &[String], default: &[&str]) -> Vec<String> {
+    if values.is_empty() {
+        default.iter().map(|v| v.to_string()).collect()
+    } else {
+        values.to_vec()
+    }
 }
 
 impl Host {
@@ -60,6 +137,8 @@ impl Host {
             ssh_flags: Vec::new(),
             shell: None,
             last_used: None,
+            docker: DockerSettings::default(),
+            rsync_profiles: Vec::new(),
         }
     }
 
@@ -163,10 +242,10 @@ mod tests {
         let mut host = Host::new("test".to_string(), "192.168.1.1".to_string());
         host.tags = vec!["prod".to_string(), "web".to_string()];
 
-        assert!(host.has_any_tag(&vec!["prod".to_string()]));
-        assert!(host.has_any_tag(&vec!["web".to_string()]));
-        assert!(host.has_any_tag(&vec!["prod".to_string(), "db".to_string()]));
-        assert!(!host.has_any_tag(&vec!["staging".to_string()]));
-        assert!(host.has_any_tag(&vec![])); // Empty filter matches all
+        assert!(host.has_any_tag(&["prod".to_string()]));
+        assert!(host.has_any_tag(&["web".to_string()]));
+        assert!(host.has_any_tag(&["prod".to_string(), "db".to_string()]));
+        assert!(!host.has_any_tag(&["staging".to_string()]));
+        assert!(host.has_any_tag(&[])); // Empty filter matches all
     }
 }
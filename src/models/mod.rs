@@ -1,9 +1,13 @@
 pub mod host;
 pub mod app_state;
 pub mod ssh_options;
+pub mod rsync;
 pub mod docker;
+pub mod file_manager;
 
-pub use host::Host;
-pub use app_state::{AppMode, HostField, SortBy, ScriptSection, DockerPendingAction};
+pub use host::{Host, DockerSettings};
+pub use app_state::{AppMode, HostField, DockerSettingsField, SortBy, ScriptSection, DockerPendingAction, DockerResourceKind, ComposeAction, ComposeView};
+pub use rsync::{RsyncChange, RsyncChangeKind, RsyncOptions, RsyncProfile, RsyncProfileRun, RsyncProgress, RsyncTransfer, ResumeMode, TransferTool};
+pub use file_manager::{FilePane, FilePreview, FilePrompt};
 pub use ssh_options::{get_ssh_flag_options, get_shell_options};
-pub use docker::{Container, ContainerStatus, PortMapping, DeploymentScript, EnvVar, VolumeMount, ContainerStats, ProcessInfo, ContainerInfo, FileEntry, Project};
+pub use docker::{Container, ContainerStatus, PortMapping, DeploymentScript, EnvVar, EnvStorage, VolumeMount, HealthCheck, LintIssue, DriftItem, SecretWrite, ContainerStats, ProcessInfo, ContainerInfo, FileEntry, FileSort, Project, DockerImage, ImageUpdateStatus, DockerVolume, DockerNetwork, ComposeProject};
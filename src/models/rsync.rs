@@ -0,0 +1,319 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::Host;
+
+/// One rsync run between the local machine and a host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsyncTransfer {
+    pub host: Host,
+    pub source: String,
+    pub dest: String,
+    /// Sending to (true) or receiving from (false) the host
+    pub to_host: bool,
+    pub compress: bool,
+    pub options: RsyncOptions,
+    /// Paths from the dry run left out of the transfer (as rsync names them)
+    pub skipped: Vec<String>,
+    /// The saved profile this transfer was started from, to record its result
+    pub profile: Option<String>,
+    /// For a transfer between two hosts: the destination, with `host` the source
+    pub dest_host: Option<Host>,
+    /// Whether a transfer between two hosts streams through this machine,
+    /// because the source host cannot reach the destination
+    pub relay: bool,
+    /// The program that copies the files, picked before the dry run
+    pub tool: TransferTool,
+}
+
+/// A transfer saved under a name, to be run again as it is
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RsyncProfile {
+    pub name: String,
+    pub source: String,
+    pub dest: String,
+    /// Sending to (true) or receiving from (false) the host
+    pub to_host: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub compress: bool,
+    #[serde(default)]
+    pub options: RsyncOptions,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<RsyncProfileRun>,
+}
+
+impl RsyncProfile {
+    /// The profile's transfer with the given host
+    pub fn transfer(&self, host: &Host) -> RsyncTransfer {
+        RsyncTransfer {
+            host: host.clone(),
+            source: self.source.clone(),
+            dest: self.dest.clone(),
+            to_host: self.to_host,
+            compress: self.compress,
+            options: self.options.clone(),
+            skipped: Vec::new(),
+            profile: Some(self.name.clone()),
+            dest_host: None,
+            relay: false,
+            tool: TransferTool::Rsync,
+        }
+    }
+}
+
+/// The program that copies the files: rsync when both ends have it,
+/// otherwise sftp, or scp when the host has no SFTP server
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransferTool {
+    #[default]
+    Rsync,
+    Sftp,
+    Scp,
+}
+
+impl TransferTool {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TransferTool::Rsync => "rsync",
+            TransferTool::Sftp => "sftp",
+            TransferTool::Scp => "scp",
+        }
+    }
+
+    /// For titles and status messages
+    pub fn name(&self) -> &'static str {
+        match self {
+            TransferTool::Rsync => "Rsync",
+            TransferTool::Sftp => "SFTP",
+            TransferTool::Scp => "SCP",
+        }
+    }
+}
+
+/// How a profile's last run went
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RsyncProfileRun {
+    pub started: DateTime<Utc>,
+    pub duration_secs: u64,
+    /// None when the run was cancelled or stopped by a signal
+    pub exit_code: Option<i32>,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// How interrupted transfers of large files are picked up again
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResumeMode {
+    #[default]
+    Off,
+    /// `--partial`: keep partly transferred files
+    Partial,
+    /// `--partial --append-verify`: also continue them where they stopped
+    AppendVerify,
+}
+
+impl ResumeMode {
+    pub fn next(&self) -> ResumeMode {
+        match self {
+            ResumeMode::Off => ResumeMode::Partial,
+            ResumeMode::Partial => ResumeMode::AppendVerify,
+            ResumeMode::AppendVerify => ResumeMode::Off,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ResumeMode::Off => "off",
+            ResumeMode::Partial => "--partial",
+            ResumeMode::AppendVerify => "--partial --append-verify",
+        }
+    }
+}
+
+/// Rsync options beyond `-a` and `-z`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RsyncOptions {
+    /// `--exclude` patterns
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excludes: Vec<String>,
+    /// `--include` patterns, passed before the excludes so they win
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub includes: Vec<String>,
+    /// `--delete`: remove destination files missing from the source
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub delete: bool,
+    /// `--checksum`: compare content instead of size and modification time
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub checksum: bool,
+    /// `--bwlimit`, e.g. `500` (KiB/s) or `1.5m`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bwlimit: Option<String>,
+    #[serde(default)]
+    pub resume: ResumeMode,
+    /// `--chmod`, e.g. `D755,F644`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chmod: Option<String>,
+    /// `--chown`, e.g. `www-data:www-data`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chown: Option<String>,
+    /// `--backup --backup-dir`: keep replaced and deleted files there
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_dir: Option<String>,
+}
+
+impl RsyncOptions {
+    /// Check every option, and that `--delete` is not aimed at a whole
+    /// filesystem or home directory
+    pub fn validate(&self, dest: &str) -> Result<(), String> {
+        if let Some(limit) = &self.bwlimit {
+            validate_bwlimit(limit)?;
+        }
+        if let Some(chmod) = &self.chmod {
+            validate_chmod(chmod)?;
+        }
+        if let Some(chown) = &self.chown {
+            validate_chown(chown)?;
+        }
+        if self.backup_dir.as_deref().is_some_and(|dir| dir.contains(char::is_control)) {
+            return Err("The backup directory contains control characters".to_string());
+        }
+        if self.delete && matches!(dest.trim().trim_end_matches('/'), "" | "~" | "." | "$HOME" | "/root" | "/home") {
+            return Err(format!("Refusing --delete with '{}' as the destination, pick a directory below it", dest.trim()));
+        }
+        Ok(())
+    }
+}
+
+/// A number of KiB per second, or a number with a unit (`1.5m`, `100K`, `2MB`)
+fn validate_bwlimit(limit: &str) -> Result<(), String> {
+    let number = limit.strip_suffix(['b', 'B']).unwrap_or(limit);
+    let number = number.strip_suffix(|c: char| "kKmMgGtTpP".contains(c)).unwrap_or(number);
+    let valid = number.chars().all(|c| c.is_ascii_digit() || c == '.') && number.parse::<f64>().is_ok();
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Bandwidth limit '{}' is not a rate like 500, 1.5m or 100K", limit))
+    }
+}
+
+/// Comma-separated chmod items, optionally for `D`irectories or `F`iles only:
+/// octal (`D755`) or symbolic (`u+rwx`, `go-w`, `a=rX`)
+fn validate_chmod(chmod: &str) -> Result<(), String> {
+    for item in chmod.split(',') {
+        let mode = item.strip_prefix(['D', 'F']).unwrap_or(item);
+        let octal = (3..=4).contains(&mode.len()) && mode.chars().all(|c| c.is_digit(8));
+        let symbolic = mode
+            .find(['+', '-', '='])
+            .is_some_and(|op| {
+                let (who, perms) = mode.split_at(op);
+                who.chars().all(|c| "ugoa".contains(c)) && perms[1..].chars().all(|c| "rwxXst".contains(c))
+            });
+        if !octal && !symbolic {
+            return Err(format!("'{}' is not a chmod mode like D755, F644 or u+rwx", item));
+        }
+    }
+    Ok(())
+}
+
+/// `user`, `user:group` or `:group`
+fn validate_chown(chown: &str) -> Result<(), String> {
+    let valid_name = |name: &str| name.chars().all(|c| c.is_alphanumeric() || "._-".contains(c));
+    let (user, group) = chown.split_once(':').unwrap_or((chown, ""));
+    if (user.is_empty() && group.is_empty()) || !valid_name(user) || !valid_name(group) {
+        return Err(format!("'{}' is not an owner like user, user:group or :group", chown));
+    }
+    Ok(())
+}
+
+/// What a transfer would do to one path on the destination
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RsyncChangeKind {
+    Create,
+    Update,
+    Delete,
+}
+
+impl RsyncChangeKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RsyncChangeKind::Create => "create",
+            RsyncChangeKind::Update => "update",
+            RsyncChangeKind::Delete => "delete",
+        }
+    }
+}
+
+/// One line of a dry run's change list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsyncChange {
+    pub kind: RsyncChangeKind,
+    /// Path relative to the transfer root; directories end with `/`
+    pub path: String,
+    /// Size in bytes (0 for directories and deletions)
+    pub size: u64,
+    /// Left out of the real run by the user
+    pub excluded: bool,
+}
+
+impl RsyncChange {
+    pub fn new(kind: RsyncChangeKind, path: impl Into<String>, size: u64) -> Self {
+        RsyncChange { kind, path: path.into(), size, excluded: false }
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.path.ends_with('/')
+    }
+
+    /// Whether this path is inside the directory `dir` (which ends with `/`)
+    pub fn is_under(&self, dir: &str) -> bool {
+        dir.ends_with('/') && self.path.len() > dir.len() && self.path.starts_with(dir)
+    }
+}
+
+/// The last `--info=progress2` update of a running transfer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsyncProgress {
+    /// Bytes transferred so far
+    pub bytes: u64,
+    pub percent: u8,
+    /// As rsync prints it, e.g. `12.34MB/s`
+    pub rate: String,
+    /// As rsync prints it, e.g. `0:01:05`
+    pub eta: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_options() {
+        let mut options = RsyncOptions {
+            bwlimit: Some("1.5m".to_string()),
+            chmod: Some("D755,F644,go-w".to_string()),
+            chown: Some("www-data:www-data".to_string()),
+            delete: true,
+            ..RsyncOptions::default()
+        };
+        assert_eq!(options.validate("/var/www/app/"), Ok(()));
+        assert!(options.validate("~/").is_err());
+        assert!(options.validate("/").is_err());
+
+        for bad in ["fast", "1.5mm", "m", "-1"] {
+            options.bwlimit = Some(bad.to_string());
+            assert!(options.validate("/srv").is_err(), "{}", bad);
+        }
+        options.bwlimit = Some("500".to_string());
+
+        options.chmod = Some("D759".to_string());
+        assert!(options.validate("/srv").is_err());
+        options.chmod = Some("a=rX".to_string());
+        assert_eq!(options.validate("/srv"), Ok(()));
+
+        options.chown = Some("root; rm".to_string());
+        assert!(options.validate("/srv").is_err());
+        options.chown = Some(":deploy".to_string());
+        assert_eq!(options.validate("/srv"), Ok(()));
+    }
+}
@@ -218,7 +218,7 @@ fn write_host_block(content: &mut String, host: &Host) {
 }
 
 /// Expand ~ in paths to home directory
-fn expand_tilde(path: &str) -> String {
+pub(crate) fn expand_tilde(path: &str) -> String {
     if path.starts_with("~/") {
         if let Some(home) = dirs::home_dir() {
             return path.replacen("~", &home.to_string_lossy(), 1);
@@ -1,34 +1,117 @@
 use anyhow::{Context, Result};
-use std::process::Command;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
 
 use crate::models::Host;
+use super::config::expand_tilde;
 
-/// Connect to a host via SSH
-pub fn connect_to_host(host: &Host) -> Result<()> {
-    let mut cmd = Command::new("ssh");
+/// Flags that need a terminal or stop ssh running a command. They suit an
+/// interactive login but break ssh used as another program's transport
+const INTERACTIVE_ONLY_FLAGS: &[&str] = &["-t", "-tt", "-N", "-f"];
+
+/// The options that reach a host: user, port, identity files and jump host
+pub(crate) fn ssh_args(host: &Host) -> Vec<String> {
+    let mut args = Vec::new();
 
     // Add user if specified
     if let Some(ref user) = host.user {
-        cmd.arg("-l").arg(user);
+        args.extend(["-l".to_string(), user.clone()]);
     }
 
     // Add port if specified
     if let Some(port) = host.port {
-        cmd.arg("-p").arg(port.to_string());
+        args.extend(["-p".to_string(), port.to_string()]);
     }
 
     // Add identity files if specified
     if let Some(ref identity_files) = host.identity_file {
         for file in identity_files {
-            cmd.arg("-i").arg(file);
+            args.extend(["-i".to_string(), file.clone()]);
         }
     }
 
     // Add ProxyJump if specified
     if let Some(ref proxy_jump) = host.proxy_jump {
-        cmd.arg("-J").arg(proxy_jump);
+        args.extend(["-J".to_string(), proxy_jump.clone()]);
+    }
+
+    args
+}
+
+/// Options for ssh carrying another program such as rsync: the host's
+/// options and its flags that work without a terminal. ssh never prompts,
+/// and host keys are checked as the user's ssh config says
+pub(crate) fn transport_ssh_args(host: &Host) -> Vec<String> {
+    let mut args = ssh_args(host);
+    args.extend(
+        host.ssh_flags
+            .iter()
+            .filter(|flag| !INTERACTIVE_ONLY_FLAGS.contains(&flag.as_str()))
+            .cloned(),
+    );
+    args.extend(["-o".to_string(), "BatchMode=yes".to_string()]);
+    args
+}
+
+/// Build an `ssh` command carrying another program to the host
+pub(crate) fn transport_ssh_command(host: &Host) -> Command {
+    let mut cmd = Command::new("ssh");
+    cmd.args(transport_ssh_args(host));
+    cmd
+}
+
+/// Build an `ssh` command carrying the host's user, port, keys and jump host
+pub(crate) fn base_ssh_command(host: &Host) -> Command {
+    let mut cmd = Command::new("ssh");
+    cmd.args(ssh_args(host));
+    cmd
+}
+
+/// Options and destination for the commands sshing runs on a host itself
+/// (listings, docker, scripts, file edits): the host's user, or root when
+/// it has none, its port, keys and jump host. A host key seen for the first
+/// time is accepted, since these commands cannot ask about it
+pub(crate) fn remote_command_args(host: &Host) -> Vec<String> {
+    let mut args = vec!["-o".to_string(), "StrictHostKeyChecking=accept-new".to_string()];
+
+    // Add port if specified
+    if let Some(port) = host.port {
+        args.extend(["-p".to_string(), port.to_string()]);
+    }
+
+    // Add identity files if specified
+    if let Some(ref keys) = host.identity_file {
+        for key in keys {
+            args.extend(["-i".to_string(), expand_tilde(key)]);
+        }
     }
 
+    // Add ProxyJump if specified
+    if let Some(ref proxy_jump) = host.proxy_jump {
+        args.extend(["-J".to_string(), proxy_jump.clone()]);
+    }
+
+    let user = host.user.as_deref().unwrap_or("root");
+    args.push(format!("{}@{}", user, host.hostname));
+    args
+}
+
+/// Build an `ssh` command that runs `command` on the host the way
+/// `remote_command_args` reaches it, never prompting
+pub(crate) fn remote_command(host: &Host, command: &str) -> Command {
+    let mut cmd = Command::new("ssh");
+    cmd.args(["-o", "BatchMode=yes"]);
+    cmd.args(remote_command_args(host));
+    cmd.arg(command);
+    cmd
+}
+
+/// Connect to a host via SSH
+pub fn connect_to_host(host: &Host) -> Result<()> {
+    let mut cmd = base_ssh_command(host);
+
     // Add SSH flags (e.g., -t, -A, -X, etc.)
     for flag in &host.ssh_flags {
         cmd.arg(flag);
@@ -56,3 +139,215 @@ pub fn connect_to_host(host: &Host) -> Result<()> {
 
     Ok(())
 }
+
+/// Run a command on a host with a forced pseudo-terminal (e.g. `docker exec -it`)
+pub fn run_interactive_command(host: &Host, command: &str) -> Result<()> {
+    // Reached like the commands that listed what it acts on, with a terminal
+    let mut cmd = Command::new("ssh");
+    cmd.arg("-t");
+    cmd.args(remote_command_args(host));
+    cmd.arg(command);
+
+    // Execute SSH - this will take over the terminal
+    let status = cmd
+        .status()
+        .context("Failed to execute SSH command")?;
+
+    if !status.success() {
+        return Err(anyhow::anyhow!(
+            "Session ended with exit code: {}",
+            status.code().unwrap_or(-1)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Run a command on the host without a terminal, writing `input` (if any)
+/// to its stdin, and return its stdout as it is, bytes and all
+pub fn run_with_input(host: &Host, command: &str, input: Option<&[u8]>) -> Result<Vec<u8>> {
+    let mut child = remote_command(host, command)
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to execute SSH command")?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        // Dropped at the end of the block, so the command sees end of input
+        stdin.write_all(input).context("Failed to send input over SSH")?;
+    }
+
+    let output = child.wait_with_output().context("Failed to execute SSH command")?;
+    if !output.status.success() {
+        anyhow::bail!("SSH command failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(output.stdout)
+}
+
+/// A line of output from a streamed command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamLine {
+    pub stderr: bool,
+    pub text: String,
+}
+
+/// A remote command whose stdout and stderr are read line by line while it
+/// runs, so the TUI can show progress instead of blocking until it exits
+pub struct StreamingCommand {
+    child: Child,
+    lines: Receiver<StreamLine>,
+    /// Both output pipes have been read to the end
+    drained: bool,
+    /// The child leads a process group of its own, signalled as a whole
+    group: bool,
+}
+
+impl StreamingCommand {
+    /// Start `command` on the host without a terminal
+    pub fn spawn(host: &Host, command: &str) -> Result<Self> {
+        let child = remote_command(host, command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to execute SSH command")?;
+
+        Ok(Self::read_from(child, false, false))
+    }
+
+    /// Start a local command, such as rsync. Each `\r` redraw of a progress
+    /// line on stdout is read as a line of its own. It runs in a process
+    /// group of its own, so `terminate` also reaches whatever a `bash -c`
+    /// pipeline started: those hold the output pipes open after bash exits
+    pub fn spawn_local(mut cmd: Command) -> Result<Self> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+        let child = cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to execute {}", cmd.get_program().to_string_lossy()))?;
+
+        Ok(Self::read_from(child, true, cfg!(unix)))
+    }
+
+    fn read_from(mut child: Child, progress: bool, group: bool) -> Self {
+        let (sender, lines) = mpsc::channel();
+        if let Some(stdout) = child.stdout.take() {
+            if progress {
+                forward_progress(stdout, sender.clone());
+            } else {
+                forward_lines(stdout, false, sender.clone());
+            }
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(stderr, true, sender);
+        }
+
+        Self { child, lines, drained: false, group }
+    }
+
+    /// Output received since the last call
+    pub fn read_lines(&mut self) -> Vec<StreamLine> {
+        let mut lines = Vec::new();
+        loop {
+            match self.lines.try_recv() {
+                Ok(line) => lines.push(line),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.drained = true;
+                    break;
+                }
+            }
+        }
+        lines
+    }
+
+    /// The exit code once the command has exited and all its output was read.
+    /// The inner value is None when the process was killed by a signal
+    pub fn try_finish(&mut self) -> Option<Option<i32>> {
+        if !self.drained {
+            return None;
+        }
+        match self.child.try_wait() {
+            Ok(Some(status)) => Some(status.code()),
+            Ok(None) => None,
+            Err(_) => Some(None),
+        }
+    }
+
+    /// Kill the local process. For a remote command that is only the ssh
+    /// client: without a terminal, the command keeps running on the host
+    pub fn kill(&mut self) {
+        let _ = self.child.kill();
+    }
+
+    /// Ask the command to stop with SIGTERM, so it can clean up after itself
+    /// (rsync removes its partial files). Killed outright where that fails
+    pub fn terminate(&mut self) {
+        let target = if self.group {
+            format!("-{}", self.child.id())
+        } else {
+            self.child.id().to_string()
+        };
+        let sent = Command::new("kill")
+            .args(["-TERM", "--"])
+            .arg(target)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success());
+        if !sent {
+            self.kill();
+        }
+    }
+}
+
+/// Send each line read from `pipe` until it closes
+fn forward_lines(pipe: impl Read + Send + 'static, stderr: bool, sender: Sender<StreamLine>) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(pipe);
+        let mut buf = Vec::new();
+        while matches!(reader.read_until(b'\n', &mut buf), Ok(n) if n > 0) {
+            let text = String::from_utf8_lossy(&buf);
+            let text = text.trim_end_matches(['\n', '\r']);
+            // Progress bars redraw with \r; only the final state is worth keeping
+            let text = text.rsplit('\r').next().unwrap_or_default().to_string();
+            if sender.send(StreamLine { stderr, text }).is_err() {
+                break;
+            }
+            buf.clear();
+        }
+    });
+}
+
+/// Like `forward_lines`, but a `\r` also ends a line, so every redraw of a
+/// progress line arrives as it happens
+fn forward_progress(pipe: impl Read + Send + 'static, sender: Sender<StreamLine>) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(pipe);
+        let mut line = Vec::new();
+        let mut byte = [0u8; 1];
+        while matches!(reader.read(&mut byte), Ok(1)) {
+            if byte[0] != b'\r' && byte[0] != b'\n' {
+                line.push(byte[0]);
+                continue;
+            }
+            if line.is_empty() {
+                continue;
+            }
+            let text = String::from_utf8_lossy(&line).to_string();
+            if sender.send(StreamLine { stderr: false, text }).is_err() {
+                return;
+            }
+            line.clear();
+        }
+        if !line.is_empty() {
+            let _ = sender.send(StreamLine { stderr: false, text: String::from_utf8_lossy(&line).to_string() });
+        }
+    });
+}
@@ -0,0 +1,331 @@
+//! Remote commands for the file manager's host pane, and for viewing and
+//! editing files from the file browsers
+
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::docker::discovery::{remote_script, remote_script_with_input, SCRIPT_BACKUP_SUFFIX};
+use crate::models::{FileEntry, FilePreview, Host};
+use super::executor::run_with_input;
+use crate::utils::shell::{quote, quote_path};
+use super::rsync::format_size;
+
+/// Most of a file read for the pager
+pub const PREVIEW_LIMIT: usize = 256 * 1024;
+
+/// Largest file opened in the editor
+pub const EDIT_LIMIT: u64 = 1024 * 1024;
+
+/// Printed by `upload_command` once the file is replaced
+const SAVED_MARKER: &str = "SSHING_SAVED";
+/// Printed by `upload_command` when the file is no longer the one that was downloaded
+const CHANGED_MARKER: &str = "SSHING_CHANGED";
+
+/// List a directory: its absolute path on the first line (so `~` and `..`
+/// are resolved), then the file browsers' listing. When the directory cannot
+/// be opened, the output is `cd`'s error instead
+pub fn list_command(path: &str) -> String {
+    format!(
+        "cd {} 2>&1 && pwd && {{ {}; }}; true",
+        quote_path(path),
+        crate::docker::list_directory_command(".")
+    )
+}
+
+/// Parse the output of `list_command` into the directory's path and entries
+pub fn parse_listing(output: &str) -> Option<(String, Vec<FileEntry>)> {
+    let (path, listing) = output.split_once('\n').unwrap_or((output, ""));
+    let path = path.trim();
+    if !path.starts_with('/') {
+        return None;
+    }
+    let mut entries = crate::docker::parser::parse_directory_listing(listing, path);
+    if path == "/" {
+        entries.retain(|e| e.name != "..");
+    }
+    Some((path.to_string(), entries))
+}
+
+/// Rename `from` to `to` inside `dir`, refusing to replace an existing file
+pub fn rename_command(dir: &str, from: &str, to: &str) -> String {
+    format!("cd {} && mv -n -- {} {}", quote(dir), quote(from), quote(to))
+}
+
+pub fn mkdir_command(dir: &str, name: &str) -> String {
+    format!("cd {} && mkdir -- {}", quote(dir), quote(name))
+}
+
+/// Delete the names inside `dir`, directories with their contents
+pub fn delete_command(dir: &str, names: &[String]) -> String {
+    let names: Vec<String> = names.iter().map(|name| quote(name)).collect();
+    format!("cd {} && rm -rf -- {}", quote(dir), names.join(" "))
+}
+
+/// The start of a file, one byte past the preview limit so a longer file shows as cut off
+pub fn read_command(path: &str) -> String {
+    format!("head -c {} -- {}", PREVIEW_LIMIT + 1, quote_path(path))
+}
+
+/// Whether content looks like a binary file: a NUL byte near its start, as git decides
+pub fn is_binary(content: &[u8]) -> bool {
+    content.iter().take(8000).any(|b| *b == 0)
+}
+
+/// The pager's view of what `read_command` (or a local read of the same
+/// length) returned, or why it cannot be shown
+pub fn preview(path: String, content: &[u8]) -> Result<FilePreview, String> {
+    if is_binary(content) {
+        return Err(format!("{} looks like a binary file", path));
+    }
+    let truncated = content.len() > PREVIEW_LIMIT;
+    let text = String::from_utf8_lossy(&content[..content.len().min(PREVIEW_LIMIT)]);
+    Ok(FilePreview { path, lines: text.lines().map(String::from).collect(), scroll: 0, truncated })
+}
+
+/// A file's `cksum` line (checksum and size), then its content up to one
+/// byte past the edit limit
+pub fn fetch_command(path: &str) -> String {
+    remote_script(&format!(
+        "set -e\nf={}\ncksum < \"$f\"\nhead -c {} \"$f\"\n",
+        quote_path(path),
+        EDIT_LIMIT + 1
+    ))
+}
+
+/// Split `fetch_command` output into the checksum line and the content,
+/// refusing files too large or too binary to edit
+pub fn parse_fetched(output: &[u8]) -> Result<(String, Vec<u8>), String> {
+    let split = output.iter().position(|b| *b == b'\n').ok_or("The file could not be read")?;
+    let checksum = String::from_utf8_lossy(&output[..split]).trim().to_string();
+    let size: u64 = checksum
+        .split_whitespace()
+        .nth(1)
+        .and_then(|size| size.parse().ok())
+        .ok_or_else(|| format!("Unexpected cksum output: {}", checksum))?;
+    if size > EDIT_LIMIT {
+        return Err(format!("The file is too large to edit ({}, at most {})", format_size(size), format_size(EDIT_LIMIT)));
+    }
+    let content = output[split + 1..].to_vec();
+    if is_binary(&content) {
+        return Err("The file looks binary".to_string());
+    }
+    Ok((checksum, content))
+}
+
+/// Replace a file with the content on stdin. Unless `force`d, nothing is
+/// written when its `cksum` is no longer `checksum`. Otherwise it is written
+/// like a deployment script: the content goes to a temporary copy (so it
+/// keeps the file's mode) that is renamed over the file, and the old version
+/// is kept as `<file>.<YYYYmmdd-HHMMSS>.bak`
+pub fn upload_command(path: &str, checksum: &str, force: bool) -> String {
+    let guard = if force {
+        String::new()
+    } else {
+        format!("if [ \"$(cksum < \"$f\")\" != {} ]; then echo {}; exit 0; fi\n", quote(checksum), CHANGED_MARKER)
+    };
+    remote_script_with_input(&format!(
+        r#"set -e
+f={path}
+{guard}tmp="$f.tmp.$$"
+trap 'rm -f "$tmp"' EXIT
+cp -p "$f" "$tmp"
+cat <&3 > "$tmp"
+cp -p "$f" "$f.$(date +%Y%m%d-%H%M%S){suffix}"
+mv -f "$tmp" "$f"
+echo {saved}
+"#,
+        path = quote_path(path),
+        suffix = SCRIPT_BACKUP_SUFFIX,
+        saved = SAVED_MARKER,
+    ))
+}
+
+/// What `upload_command` did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Upload {
+    Saved,
+    /// The file changed on the host since it was downloaded, so it was left alone
+    RemoteChanged,
+}
+
+pub fn parse_upload(output: &[u8]) -> Option<Upload> {
+    let output = String::from_utf8_lossy(output);
+    match output.lines().last().map(str::trim) {
+        Some(SAVED_MARKER) => Some(Upload::Saved),
+        Some(CHANGED_MARKER) => Some(Upload::RemoteChanged),
+        _ => None,
+    }
+}
+
+/// Edit a host's file in the local `$VISUAL` or `$EDITOR` (vi without
+/// either), through a copy in a private temporary directory, and upload it
+/// again if it changed. Runs with the TUI suspended, so it can ask on the
+/// terminal whether to overwrite a file that changed on the host meanwhile.
+/// Returns the message for the status line
+pub fn edit_remote_file(host: &Host, path: &str, sudo: bool) -> Result<String> {
+    let as_user = |command: String| if sudo { format!("sudo -i {}", command) } else { command };
+
+    let fetched = run_with_input(host, &as_user(fetch_command(path)), None)?;
+    let (checksum, original) = parse_fetched(&fetched).map_err(|e| anyhow::anyhow!("{}: {}", path, e))?;
+
+    let dir = edit_dir()?;
+    let name = Path::new(path).file_name().map_or("file".into(), |name| name.to_string_lossy().to_string());
+    let local = dir.join(name);
+    std::fs::write(&local, &original).context("Failed to write the local copy")?;
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // Through sh, so editors set with arguments (`code --wait`) work
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(&local)
+        .status()
+        .with_context(|| format!("Failed to start {}", editor))?;
+    if !status.success() {
+        let _ = std::fs::remove_dir_all(&dir);
+        anyhow::bail!("{} exited with {}, {} was not changed", editor, status, path);
+    }
+
+    let edited = std::fs::read(&local).context("Failed to read the edited copy")?;
+    if edited == original {
+        let _ = std::fs::remove_dir_all(&dir);
+        return Ok(format!("No changes to {}", path));
+    }
+
+    let kept = |reason: String| anyhow::anyhow!("{}; your version is kept at {}", reason, local.display());
+    let mut force = false;
+    loop {
+        let output = run_with_input(host, &as_user(upload_command(path, &checksum, force)), Some(&edited))
+            .map_err(|e| kept(e.to_string()))?;
+        match parse_upload(&output) {
+            Some(Upload::Saved) => {
+                let _ = std::fs::remove_dir_all(&dir);
+                return Ok(format!("Saved {} (the previous version is kept as a {} copy)", path, SCRIPT_BACKUP_SUFFIX));
+            }
+            Some(Upload::RemoteChanged) if !force && confirm_overwrite(path)? => force = true,
+            Some(Upload::RemoteChanged) => return Err(kept(format!("{} changed on {} while it was being edited", path, host.host))),
+            None => return Err(kept(format!("Saving {} failed: {}", path, String::from_utf8_lossy(&output).trim()))),
+        }
+    }
+}
+
+/// A new directory only this user can read, for the copy being edited. Each
+/// edit gets its own, so a copy kept after a failed upload is never reused
+fn edit_dir() -> Result<PathBuf> {
+    let stamp = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default();
+    for attempt in 0..100 {
+        let dir = std::env::temp_dir().join(format!("sshing-edit-{}-{}-{}", std::process::id(), stamp, attempt));
+        let mut builder = std::fs::DirBuilder::new();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::DirBuilderExt;
+            builder.mode(0o700);
+        }
+        // Not recursive, so an existing directory is never taken over
+        match builder.create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e).context("Failed to create a temporary directory"),
+        }
+    }
+    anyhow::bail!("Failed to create a temporary directory")
+}
+
+/// Ask on the plain terminal whether to overwrite a file that changed on the host
+fn confirm_overwrite(path: &str) -> Result<bool> {
+    print!("\n{} changed on the host since it was opened. Overwrite it with your version? [y/N] ", path);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim().eq_ignore_ascii_case("y"))
+}
+
+/// Whether a name can be created in a directory as it is
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name == "." || name == ".." {
+        Err("Enter a name".to_string())
+    } else if name.contains('/') || name.contains(char::is_control) {
+        Err(format!("'{}' is not a plain file name", name))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FileSort;
+
+    #[test]
+    fn test_remote_file_commands() {
+        assert!(list_command("~/my app").starts_with("cd ~/'my app' 2>&1 && pwd && { find ./ -mindepth 1 -maxdepth 1 -printf "));
+
+        // find's NUL-separated records: names with spaces and symlinks survive
+        let record = |fields: [&str; 8]| fields.iter().map(|field| format!("{}\0", field)).collect::<String>();
+        let output = format!(
+            "/home/deploy\n{}{}{}",
+            record(["f", "f", "220", "1704110400.1234", "644", "deploy", "", ".profile"]),
+            record(["d", "d", "4096", "1704110400.0", "755", "deploy", "", "my app"]),
+            record(["l", "d", "12", "1704110400.0", "777", "root", "/srv/current", "current"]),
+        );
+        let (path, entries) = parse_listing(&output).unwrap();
+        assert_eq!(path, "/home/deploy");
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["..", "current", "my app", ".profile"]);
+        assert!(entries[1].is_dir);
+        assert_eq!(entries[1].display_name(), "current -> /srv/current");
+        assert_eq!(entries[1].mode_label(), "lrwxrwxrwx");
+        assert_eq!((entries[3].size, entries[3].modified, entries[3].owner.as_str()), (220, Some(1704110400), "deploy"));
+        assert!(entries[3].is_hidden());
+
+        // ls -la where find has no -printf
+        let output = "/home/deploy\n\
+drwxr-xr-x  5 deploy deploy 4096 Jan  1 12:00 .\n\
+drwxr-xr-x  3 root   root   4096 Jan  1 12:00 ..\n\
+-rw-r--r--  1 deploy deploy  220 Jan  1 12:00 .profile\n\
+lrwxrwxrwx  1 root   root     12 Jan  1 12:00 current -> /srv/current\n\
+drwxr-xr-x  2 deploy deploy 4096 Jan  1 12:00 my app\n";
+        let (_, entries) = parse_listing(output).unwrap();
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["..", "my app", ".profile", "current"]);
+        assert_eq!((entries[2].mode, entries[2].size), (Some(0o644), 220));
+        assert_eq!(entries[3].link_target.as_deref(), Some("/srv/current"));
+        assert!(parse_listing("bash: cd: nope: No such file or directory").is_none());
+        assert_eq!(parse_listing("/\n").unwrap().1, Vec::new());
+
+        let mut entries = vec![FileEntry::new("small".to_string(), false), FileEntry::parent(), FileEntry::new("big".to_string(), false)];
+        entries[0].size = 10;
+        entries[2].size = 900;
+        FileSort::Size.sort(&mut entries);
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["..", "big", "small"]);
+
+        assert_eq!(rename_command("/srv", "old name", "new"), "cd /srv && mv -n -- 'old name' new");
+        assert_eq!(
+            delete_command("/srv/app", &["dist".to_string(), "it's.log".to_string()]),
+            "cd /srv/app && rm -rf -- dist 'it'\\''s.log'"
+        );
+        assert_eq!(read_command("/etc/hosts"), "head -c 262145 -- /etc/hosts");
+        assert!(preview("/bin/ls".to_string(), b"\x7fELF\x02\x01\0\0").is_err());
+        let long = vec![b'x'; PREVIEW_LIMIT + 1];
+        let shown = preview("/var/log/big.log".to_string(), &long).unwrap();
+        assert!(shown.truncated);
+        assert_eq!(shown.lines[0].len(), PREVIEW_LIMIT);
+
+        assert_eq!(parse_fetched(b"123 6\nhello\n"), Ok(("123 6".to_string(), b"hello\n".to_vec())));
+        assert!(parse_fetched(b"123 9999999\n...").is_err());
+        assert!(parse_fetched(b"123 4\na\0b\n").is_err());
+        assert_eq!(parse_upload(b"SSHING_SAVED\n"), Some(Upload::Saved));
+        assert_eq!(parse_upload(b"SSHING_CHANGED\n"), Some(Upload::RemoteChanged));
+        assert_eq!(parse_upload(b"cp: permission denied\n"), None);
+
+        assert!(validate_name("a/b").is_err());
+        assert!(validate_name("..").is_err());
+        assert_eq!(validate_name("releases"), Ok(()));
+    }
+}
@@ -5,7 +5,7 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::models::Host;
+use crate::models::{DockerSettings, Host, RsyncProfile};
 
 /// Metadata for a single host (fields not in SSH config)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +24,42 @@ pub struct HostMetadata {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_used: Option<DateTime<Utc>>,
+
+    #[serde(default, skip_serializing_if = "DockerSettings::is_default")]
+    pub docker: DockerSettings,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rsync_profiles: Vec<RsyncProfile>,
+}
+
+/// One line of a script run's output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunLogLine {
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stderr: bool,
+    pub text: String,
+}
+
+/// A past run of a container's deployment script, with its output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptRun {
+    pub script_path: String,
+    pub started: DateTime<Utc>,
+    pub duration_secs: u64,
+    /// None when the run was cancelled or ssh could not report a status
+    pub exit_code: Option<i32>,
+    /// The last `MAX_RUN_LOG_LINES` lines of output. Output can hold secrets,
+    /// so it is kept in a run log file only this user can read (see
+    /// `save_run_logs`), not in the metadata file
+    #[serde(default, skip_serializing)]
+    pub log: Vec<RunLogLine>,
+}
+
+/// A run's output in a run log file, matched to its run by start time
+#[derive(Debug, Serialize, Deserialize)]
+struct RunLog {
+    started: DateTime<Utc>,
+    log: Vec<RunLogLine>,
 }
 
 /// Container for all metadata
@@ -45,11 +81,31 @@ pub struct Metadata {
     #[serde(default)]
     pub docker_container_scripts: HashMap<String, String>,
 
+    /// Ad-hoc `docker exec` command history (key: "host:container", most recent last)
+    #[serde(default)]
+    pub docker_exec_history: HashMap<String, Vec<String>>,
+
+    /// Deployment script runs (key: "host:container", most recent last)
+    #[serde(default)]
+    pub docker_script_runs: HashMap<String, Vec<ScriptRun>>,
+
     /// Saved sort preference (persists across sessions)
     #[serde(default)]
     pub sort_by: String,
 }
 
+/// Maximum number of commands remembered per container
+const MAX_EXEC_HISTORY: usize = 50;
+
+/// Maximum number of script runs remembered per container
+const MAX_SCRIPT_RUNS: usize = 10;
+
+/// Maximum number of output lines kept per script run
+pub const MAX_RUN_LOG_LINES: usize = 2000;
+
+/// The directory next to the metadata file that holds the run log files
+const RUN_LOG_DIR: &str = "sshing-runs";
+
 fn default_version() -> String {
     "1.0".to_string()
 }
@@ -62,6 +118,8 @@ impl Metadata {
             global_tags: Vec::new(),
             hosts: HashMap::new(),
             docker_container_scripts: HashMap::new(),
+            docker_exec_history: HashMap::new(),
+            docker_script_runs: HashMap::new(),
             sort_by: String::new(),
         }
     }
@@ -83,6 +141,46 @@ impl Metadata {
         self.docker_container_scripts.insert(key, script_path);
     }
 
+    /// Get the ad-hoc command history for a container (oldest first)
+    pub fn get_exec_history(&self, host_name: &str, container_name: &str) -> Vec<String> {
+        let key = Self::docker_container_key(host_name, container_name);
+        self.docker_exec_history.get(&key).cloned().unwrap_or_default()
+    }
+
+    /// Record an ad-hoc command for a container, moving repeats to the end
+    pub fn push_exec_history(&mut self, host_name: &str, container_name: &str, command: String) {
+        let key = Self::docker_container_key(host_name, container_name);
+        let history = self.docker_exec_history.entry(key).or_default();
+        history.retain(|c| c != &command);
+        history.push(command);
+        if history.len() > MAX_EXEC_HISTORY {
+            let excess = history.len() - MAX_EXEC_HISTORY;
+            history.drain(..excess);
+        }
+    }
+
+    /// Past script runs for a container (oldest first)
+    pub fn get_script_runs(&self, host_name: &str, container_name: &str) -> &[ScriptRun] {
+        let key = Self::docker_container_key(host_name, container_name);
+        self.docker_script_runs.get(&key).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Record a finished script run, keeping the last `MAX_SCRIPT_RUNS` runs
+    /// and the tail of each log
+    pub fn push_script_run(&mut self, host_name: &str, container_name: &str, mut run: ScriptRun) {
+        if run.log.len() > MAX_RUN_LOG_LINES {
+            let excess = run.log.len() - MAX_RUN_LOG_LINES;
+            run.log.drain(..excess);
+        }
+        let key = Self::docker_container_key(host_name, container_name);
+        let runs = self.docker_script_runs.entry(key).or_default();
+        runs.push(run);
+        if runs.len() > MAX_SCRIPT_RUNS {
+            let excess = runs.len() - MAX_SCRIPT_RUNS;
+            runs.drain(..excess);
+        }
+    }
+
     /// Add a tag to the global tag pool
     pub fn add_global_tag(&mut self, tag: String) {
         if !self.global_tags.contains(&tag) {
@@ -119,6 +217,8 @@ impl Metadata {
             host.ssh_flags = metadata.ssh_flags.clone();
             host.shell = metadata.shell.clone();
             host.last_used = metadata.last_used;
+            host.docker = metadata.docker.clone();
+            host.rsync_profiles = metadata.rsync_profiles.clone();
         }
     }
 
@@ -130,6 +230,8 @@ impl Metadata {
             ssh_flags: host.ssh_flags.clone(),
             shell: host.shell.clone(),
             last_used: host.last_used,
+            docker: host.docker.clone(),
+            rsync_profiles: host.rsync_profiles.clone(),
         };
 
         self.set(host.host.clone(), metadata);
@@ -166,12 +268,87 @@ pub fn load_metadata(path: &Path) -> Result<Metadata> {
     let content = fs::read_to_string(path)
         .context("Failed to read metadata file")?;
 
-    let metadata: Metadata = serde_json::from_str(&content)
+    let mut metadata: Metadata = serde_json::from_str(&content)
         .context("Failed to parse metadata JSON")?;
+    load_run_logs(path, &mut metadata);
 
     Ok(metadata)
 }
 
+/// Fill in the output of the recorded script runs from their run log files.
+/// Output from before run log files existed is still inline in the metadata
+/// file: it is moved into one, and left out of the metadata from then on
+fn load_run_logs(path: &Path, metadata: &mut Metadata) {
+    let keys: Vec<String> = metadata.docker_script_runs.keys().cloned().collect();
+    for key in keys {
+        let file = run_log_path(path, &key);
+        let runs = metadata.docker_script_runs.get_mut(&key).expect("key was just listed");
+        let Ok(content) = fs::read_to_string(&file) else {
+            if runs.iter().any(|run| !run.log.is_empty()) {
+                let _ = write_run_logs(&file, runs);
+            }
+            continue;
+        };
+        let logs: Vec<RunLog> = serde_json::from_str(&content).unwrap_or_default();
+        for run in runs.iter_mut() {
+            if let Some(saved) = logs.iter().find(|saved| saved.started == run.started) {
+                run.log = saved.log.clone();
+            }
+        }
+    }
+}
+
+/// Save the output of a container's recorded script runs to its run log file
+pub fn save_run_logs(path: &Path, metadata: &Metadata, host_name: &str, container_name: &str) -> Result<()> {
+    let key = Metadata::docker_container_key(host_name, container_name);
+    write_run_logs(&run_log_path(path, &key), metadata.get_script_runs(host_name, container_name))
+}
+
+fn write_run_logs(file: &Path, runs: &[ScriptRun]) -> Result<()> {
+    if let Some(dir) = file.parent() {
+        let mut builder = fs::DirBuilder::new();
+        builder.recursive(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::DirBuilderExt;
+            builder.mode(0o700);
+        }
+        builder.create(dir).context("Failed to create run log directory")?;
+    }
+
+    let logs: Vec<RunLog> = runs.iter().map(|run| RunLog { started: run.started, log: run.log.clone() }).collect();
+    let content = serde_json::to_string(&logs)
+        .context("Failed to serialize run logs")?;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(file)
+        .context("Failed to open run log file")?;
+    std::io::Write::write_all(&mut file, content.as_bytes())
+        .context("Failed to write run log file")?;
+
+    Ok(())
+}
+
+/// The run log file of a container ("host:container"). Characters that do
+/// not belong in a file name are written as `%XX`, so no two keys share one
+fn run_log_path(path: &Path, key: &str) -> PathBuf {
+    let mut name = String::new();
+    for byte in key.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'.' | b'_' | b'-') {
+            name.push(byte as char);
+        } else {
+            name.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    path.with_file_name(RUN_LOG_DIR).join(format!("{}.json", name))
+}
+
 /// Save metadata to file
 pub fn save_metadata(path: &Path, metadata: &Metadata) -> Result<()> {
     // Create parent directory if it doesn't exist
@@ -218,6 +395,8 @@ mod tests {
             ssh_flags: Vec::new(),
             shell: None,
             last_used: None,
+            docker: DockerSettings::default(),
+            rsync_profiles: Vec::new(),
         };
 
         metadata.set("test-host".to_string(), host_meta.clone());
@@ -239,6 +418,8 @@ mod tests {
                 ssh_flags: Vec::new(),
                 shell: None,
                 last_used: None,
+                docker: DockerSettings::default(),
+                rsync_profiles: Vec::new(),
             },
         );
 
@@ -264,6 +445,95 @@ mod tests {
         assert_eq!(retrieved.tags, vec!["dev".to_string()]);
     }
 
+    #[test]
+    fn test_exec_history() {
+        let mut metadata = Metadata::new();
+
+        metadata.push_exec_history("web", "nginx", "nginx -t".to_string());
+        metadata.push_exec_history("web", "nginx", "ls /etc".to_string());
+        metadata.push_exec_history("web", "nginx", "nginx -t".to_string());
+
+        assert_eq!(
+            metadata.get_exec_history("web", "nginx"),
+            vec!["ls /etc".to_string(), "nginx -t".to_string()]
+        );
+        assert!(metadata.get_exec_history("web", "php").is_empty());
+
+        for i in 0..(MAX_EXEC_HISTORY + 5) {
+            metadata.push_exec_history("web", "nginx", format!("echo {}", i));
+        }
+        let history = metadata.get_exec_history("web", "nginx");
+        assert_eq!(history.len(), MAX_EXEC_HISTORY);
+        assert_eq!(history.last().unwrap(), &format!("echo {}", MAX_EXEC_HISTORY + 4));
+    }
+
+    #[test]
+    fn test_script_runs() {
+        let mut metadata = Metadata::new();
+        let run = |exit_code, lines: usize| ScriptRun {
+            script_path: "/srv/web/start.sh".to_string(),
+            started: Utc::now(),
+            duration_secs: 3,
+            exit_code,
+            log: (0..lines).map(|i| RunLogLine { stderr: false, text: i.to_string() }).collect(),
+        };
+
+        metadata.push_script_run("web", "nginx", run(Some(0), MAX_RUN_LOG_LINES + 10));
+        let runs = metadata.get_script_runs("web", "nginx");
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].exit_code, Some(0));
+        assert_eq!(runs[0].log.len(), MAX_RUN_LOG_LINES);
+        assert_eq!(runs[0].log[0].text, "10");
+
+        for _ in 0..MAX_SCRIPT_RUNS {
+            metadata.push_script_run("web", "nginx", run(Some(1), 1));
+        }
+        let runs = metadata.get_script_runs("web", "nginx");
+        assert_eq!(runs.len(), MAX_SCRIPT_RUNS);
+        assert!(runs.iter().all(|r| r.exit_code == Some(1)));
+        assert!(metadata.get_script_runs("web", "php").is_empty());
+    }
+
+    #[test]
+    fn test_run_logs() {
+        let dir = std::env::temp_dir().join(format!("sshing-run-logs-{}", std::process::id()));
+        let path = dir.join("sshing.json");
+        let mut metadata = Metadata::new();
+        let run = ScriptRun {
+            script_path: "/srv/web/start.sh".to_string(),
+            started: Utc::now(),
+            duration_secs: 3,
+            exit_code: Some(0),
+            log: vec![RunLogLine { stderr: false, text: "token=s3cret".to_string() }],
+        };
+        metadata.push_script_run("web", "my app", run.clone());
+
+        // The output stays out of the metadata file, in a file of its own
+        save_metadata(&path, &metadata).unwrap();
+        assert!(!fs::read_to_string(&path).unwrap().contains("s3cret"));
+        assert_eq!(load_metadata(&path).unwrap().get_script_runs("web", "my app")[0].log, Vec::new());
+        save_run_logs(&path, &metadata, "web", "my app").unwrap();
+        let file = dir.join(RUN_LOG_DIR).join("web%3Amy%20app.json");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&file).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+        assert_eq!(load_metadata(&path).unwrap().get_script_runs("web", "my app"), std::slice::from_ref(&run));
+
+        // Output written inline by older versions is moved out on load
+        fs::remove_file(&file).unwrap();
+        let inline = serde_json::to_string(&metadata).unwrap().replace(
+            "\"exit_code\":0",
+            "\"exit_code\":0,\"log\":[{\"text\":\"token=s3cret\"}]",
+        );
+        fs::write(&path, inline).unwrap();
+        assert_eq!(load_metadata(&path).unwrap().get_script_runs("web", "my app"), std::slice::from_ref(&run));
+        assert!(fs::read_to_string(&file).unwrap().contains("s3cret"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_serialization() {
         let mut metadata = Metadata::new();
@@ -276,6 +546,8 @@ mod tests {
                 ssh_flags: Vec::new(),
                 shell: None,
                 last_used: None,
+                docker: DockerSettings::default(),
+                rsync_profiles: Vec::new(),
             },
         );
 
@@ -285,4 +557,59 @@ mod tests {
         assert_eq!(deserialized.version, metadata.version);
         assert_eq!(deserialized.hosts.len(), 1);
     }
+
+    #[test]
+    fn test_docker_settings() {
+        let mut metadata = Metadata::new();
+        let mut host = Host::new("test".to_string(), "192.168.1.1".to_string());
+        metadata.extract_from_host(&host);
+
+        // Default settings are left out of the file
+        let json = serde_json::to_string(&metadata).unwrap();
+        assert!(!json.contains("docker\""));
+
+        host.docker.search_roots = vec!["/opt/apps".to_string(), "/srv".to_string()];
+        host.docker.use_sudo = Some(true);
+        metadata.extract_from_host(&host);
+        let json = serde_json::to_string(&metadata).unwrap();
+        let deserialized: Metadata = serde_json::from_str(&json).unwrap();
+
+        let mut loaded = Host::new("test".to_string(), "192.168.1.1".to_string());
+        deserialized.apply_to_host(&mut loaded);
+        assert_eq!(loaded.docker, host.docker);
+        assert_eq!(loaded.docker.primary_root(), "/opt/apps");
+        assert_eq!(loaded.docker.includes().len(), 4);
+    }
+
+    #[test]
+    fn test_rsync_profiles() {
+        let mut metadata = Metadata::new();
+        let mut host = Host::new("web".to_string(), "web.example.com".to_string());
+        host.rsync_profiles.push(RsyncProfile {
+            name: "site".to_string(),
+            source: "./dist/".to_string(),
+            dest: "/var/www/app".to_string(),
+            to_host: true,
+            compress: true,
+            options: crate::models::RsyncOptions { delete: true, ..Default::default() },
+            last_run: Some(crate::models::RsyncProfileRun {
+                started: Utc::now(),
+                duration_secs: 4,
+                exit_code: Some(0),
+                files: 12,
+                bytes: 4096,
+            }),
+        });
+        metadata.extract_from_host(&host);
+
+        let json = serde_json::to_string(&metadata).unwrap();
+        let deserialized: Metadata = serde_json::from_str(&json).unwrap();
+        let mut loaded = Host::new("web".to_string(), "web.example.com".to_string());
+        deserialized.apply_to_host(&mut loaded);
+        assert_eq!(loaded.rsync_profiles, host.rsync_profiles);
+
+        let transfer = loaded.rsync_profiles[0].transfer(&loaded);
+        assert_eq!(transfer.profile.as_deref(), Some("site"));
+        assert!(transfer.options.delete);
+    }
 }
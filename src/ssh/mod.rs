@@ -1,4 +1,5 @@
 pub mod config;
 pub mod metadata;
 pub mod executor;
+pub mod files;
 pub mod rsync;
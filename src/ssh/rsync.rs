@@ -1,79 +1,892 @@
-use anyhow::Result;
-use std::process::Command;
-
-use crate::models::Host;
-
-/// Execute rsync with SSH connection
-pub fn execute_rsync(
-    host: &Host,
-    source: &str,
-    dest: &str,
-    to_host: bool,
-    _password: Option<String>,
-    compress: bool,
-) -> Result<(bool, String)> {
+use std::process::{Command, Stdio};
+
+use crate::models::{Host, ResumeMode, RsyncChange, RsyncChangeKind, RsyncOptions, RsyncProgress, RsyncTransfer, TransferTool};
+use crate::docker::discovery::{remote_script, run_in_session};
+use crate::utils::shell::{quote, quote_path};
+use super::executor::{transport_ssh_args, transport_ssh_command, StreamLine, StreamingCommand};
+
+/// Format of each changed path in a dry run or a transfer: itemized changes, size, name
+const CHANGE_FORMAT: &str = "%i %l %n";
+
+/// Build the rsync command for a transfer, with `extra` options before the paths
+fn rsync_command(transfer: &RsyncTransfer, extra: &[String]) -> Command {
+    if let Some(dest_host) = &transfer.dest_host {
+        return remote_rsync_command(transfer, dest_host, extra);
+    }
+
+    let host = &transfer.host;
     let mut cmd = Command::new("rsync");
 
-    // Build SSH options
-    let mut ssh_options = String::new();
+    // rsync splits -e into words itself, honouring quotes
+    cmd.arg("-e").arg(ssh_transport(host));
+
+    cmd.args(rsync_args(transfer, extra));
 
-    // Add user if specified
-    if let Some(ref user) = host.user {
-        ssh_options.push_str(&format!("-l {} ", user));
+    // Add source and destination
+    if transfer.to_host {
+        // Sending to host: local source to remote dest
+        cmd.arg(&transfer.source);
+        cmd.arg(format!("{}:{}", host.hostname, remote_path(&transfer.dest)));
+    } else {
+        // Receiving from host: remote source to local dest
+        cmd.arg(format!("{}:{}", host.hostname, remote_path(&transfer.source)));
+        cmd.arg(&transfer.dest);
     }
 
-    // Add port if specified
-    if let Some(port) = host.port {
-        ssh_options.push_str(&format!("-p {} ", port));
+    cmd
+}
+
+/// Everything between `rsync` and the paths
+fn rsync_args(transfer: &RsyncTransfer, extra: &[String]) -> Vec<String> {
+    // Add -a (archive) flag by default. With -s the remote path reaches
+    // rsync as one word: before 3.2.4 the remote shell split it at spaces
+    let mut args = vec!["-a".to_string(), "-s".to_string()];
+
+    // Add -z (compress) flag if requested
+    if transfer.compress {
+        args.push("-z".to_string());
     }
 
-    // Add identity files if specified
-    if let Some(ref identity_files) = host.identity_file {
-        for file in identity_files {
-            ssh_options.push_str(&format!("-i {} ", file));
+    // Anchored, so only the reviewed path is left out. First, so an include cannot bring it back
+    for path in &transfer.skipped {
+        args.push(format!("--exclude=/{}", escape_pattern(path)));
+    }
+    args.extend(option_args(&transfer.options));
+    args.extend_from_slice(extra);
+    args
+}
+
+/// A path on the far side of rsync's ssh, after `host:`. With `-s` no shell
+/// expands a leading `~` or `$HOME` there, but rsync starts in the home
+/// directory, so the rest of the path is relative to it already
+fn remote_path(path: &str) -> &str {
+    for home in ["~", "$HOME"] {
+        if let Some(rest) = path.strip_prefix(home) {
+            if rest.is_empty() || rest == "/" {
+                return ".";
+            }
+            if let Some(rest) = rest.strip_prefix('/') {
+                return rest;
+            }
         }
     }
+    path
+}
+
+/// Run rsync on the source host, sending straight to the destination host.
+/// The agent is forwarded so the source host can log in to the destination
+/// with the local keys
+fn remote_rsync_command(transfer: &RsyncTransfer, dest_host: &Host, extra: &[String]) -> Command {
+    source_host_command(transfer, &remote_rsync_line(transfer, dest_host, extra))
+}
+
+/// The rsync command line run on the source host
+fn remote_rsync_line(transfer: &RsyncTransfer, dest_host: &Host, extra: &[String]) -> String {
+    let mut remote = vec!["rsync".to_string()];
+    remote.extend(rsync_args(transfer, extra).iter().map(|arg| quote(arg)));
+    remote.extend(["-e".to_string(), quote(&ssh_command_words(&peer_ssh_args(dest_host)))]);
+    remote.push(quote_path(&transfer.source));
+    remote.push(quote(&format!("{}:{}", dest_host.hostname, remote_path(&transfer.dest))));
+    remote.join(" ")
+}
+
+fn source_host_command(transfer: &RsyncTransfer, remote: &str) -> Command {
+    let mut cmd = transport_ssh_command(&transfer.host);
+    cmd.arg("-A");
+    cmd.arg(&transfer.host.hostname);
+    cmd.arg(remote);
+    cmd
+}
+
+/// How the source host reaches the destination host. Local identity files
+/// mean nothing there, the forwarded agent stands in for them
+fn peer_ssh_args(host: &Host) -> Vec<String> {
+    transport_ssh_args(&Host { identity_file: None, ..host.clone() })
+}
+
+/// The `ssh` command line rsync uses to reach a host, for `-e`
+fn ssh_transport(host: &Host) -> String {
+    ssh_command_words(&transport_ssh_args(host))
+}
+
+/// `ssh` and its arguments as one quoted command line
+fn ssh_command_words(args: &[String]) -> String {
+    std::iter::once("ssh".to_string())
+        .chain(args.iter().map(|arg| quote(arg)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
 
-    // Set StrictHostKeyChecking=no to avoid prompts on new hosts
-    ssh_options.push_str("-o StrictHostKeyChecking=no ");
+/// Whether the source host can log in to the destination host (with the
+/// forwarded agent) and both have rsync, so rsync can run between them
+pub fn hosts_can_reach(source: &Host, dest: &Host) -> bool {
+    let peer_ssh = std::iter::once("ssh".to_string())
+        .chain(peer_ssh_args(dest))
+        .chain(["-o".to_string(), "ConnectTimeout=5".to_string(), dest.hostname.clone()])
+        .map(|arg| quote(&arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut cmd = transport_ssh_command(source);
+    cmd.args(["-A", "-o", "ConnectTimeout=10"]);
+    cmd.arg(&source.hostname);
+    cmd.arg(format!("command -v rsync >/dev/null && {} 'command -v rsync' >/dev/null", peer_ssh));
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
 
-    let ssh_arg = format!("ssh {}", ssh_options.trim());
+/// Options the relay cannot carry out: it copies with tar, which only knows excludes
+pub fn relay_unsupported(options: &RsyncOptions) -> Vec<&'static str> {
+    let mut unsupported = Vec::new();
+    if !options.includes.is_empty() {
+        unsupported.push("--include");
+    }
+    if options.delete {
+        unsupported.push("--delete");
+    }
+    if options.checksum {
+        unsupported.push("--checksum");
+    }
+    if options.bwlimit.is_some() {
+        unsupported.push("--bwlimit");
+    }
+    if options.resume != ResumeMode::Off {
+        unsupported.push("--partial");
+    }
+    if options.chmod.is_some() {
+        unsupported.push("--chmod");
+    }
+    if options.chown.is_some() {
+        unsupported.push("--chown");
+    }
+    if options.backup_dir.is_some() {
+        unsupported.push("--backup-dir");
+    }
+    unsupported
+}
 
-    // Add -e ssh option
-    if !ssh_options.trim().is_empty() {
-        cmd.arg("-e").arg(&ssh_arg);
+/// The directory tar works from on the source host, and what it archives
+/// there. As with rsync, `dir/` copies the directory's contents and `dir`
+/// the directory itself
+fn relay_source(source: &str) -> (String, String) {
+    if source.ends_with('/') {
+        return (source.to_string(), ".".to_string());
+    }
+    match source.rsplit_once('/') {
+        Some(("", name)) => ("/".to_string(), format!("./{}", name)),
+        Some((parent, name)) => (parent.to_string(), format!("./{}", name)),
+        None => (".".to_string(), format!("./{}", source)),
     }
+}
 
-    // Add -a (archive) flag by default
-    cmd.arg("-a");
+/// Stream the source from one host to the other through this machine:
+/// `ssh source tar -c | ssh dest tar -x`. The extracting tar lists each path
+fn relay_command(transfer: &RsyncTransfer, dest_host: &Host) -> Command {
+    let (base, target) = relay_source(&transfer.source);
+    let mut create = vec!["tar".to_string(), "-C".to_string(), quote_path(&base), "-cf".to_string(), "-".to_string()];
+    // tar's paths start with ./, so a skipped path only matches itself
+    for path in &transfer.skipped {
+        create.push(quote(&format!("--exclude=./{}", escape_pattern(path.trim_end_matches('/')))));
+    }
+    for pattern in &transfer.options.excludes {
+        create.push(quote(&format!("--exclude={}", pattern.trim_end_matches('/'))));
+    }
+    create.push(quote(&target));
+    let dest = quote_path(&transfer.dest);
+    let extract = format!("mkdir -p {} && tar -C {} -xvf -", dest, dest);
 
-    // Add -z (compress) flag if requested
-    if compress {
-        cmd.arg("-z");
+    let leg = |host: &Host, remote: String| {
+        let mut cmd = transport_ssh_command(host);
+        if transfer.compress {
+            cmd.arg("-C");
+        }
+        cmd.arg(&host.hostname).arg(remote);
+        command_line(&cmd)
+    };
+    let mut cmd = Command::new("bash");
+    cmd.arg("-c").arg(format!(
+        "set -o pipefail; {} | {}",
+        leg(&transfer.host, create.join(" ")),
+        leg(dest_host, extract)
+    ));
+    cmd
+}
+
+/// What the relay would send: every path under the source, as creates, since
+/// the destination is not compared. Excludes without a `/` are applied by name
+fn relay_listing(transfer: &RsyncTransfer) -> Result<Vec<RsyncChange>, String> {
+    let (base, target) = relay_source(&transfer.source);
+    let remote = format!(
+        "cd {} && find {} -mindepth 1 {}-printf '%y %s %p\\n'",
+        quote_path(&base),
+        quote(&target),
+        name_prune(&transfer.options.excludes)
+    );
+    let mut cmd = transport_ssh_command(&transfer.host);
+    cmd.arg(&transfer.host.hostname).arg(remote);
+    let output = cmd.output().map_err(|e| format!("Failed to execute ssh: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    let mut changes = parse_relay_listing(&String::from_utf8_lossy(&output.stdout));
+    if target != "." {
+        // The directory itself is copied too
+        let name = target.trim_start_matches("./");
+        changes.insert(0, RsyncChange::new(RsyncChangeKind::Create, format!("{}/", name), 0));
     }
+    Ok(changes)
+}
 
-    // Add source and destination
-    if to_host {
-        // Sending to host: local source to remote dest
-        cmd.arg(source);
-        cmd.arg(format!("{}:{}", host.hostname, dest));
+/// Parse `find -printf '%y %s %p\n'` output into the paths tar would send
+fn parse_relay_listing(output: &str) -> Vec<RsyncChange> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (file_type, rest) = line.split_once(' ')?;
+            let (size, path) = rest.split_once(' ')?;
+            let path = path.strip_prefix("./").unwrap_or(path);
+            Some(match file_type {
+                "d" => RsyncChange::new(RsyncChangeKind::Create, format!("{}/", path), 0),
+                _ => RsyncChange::new(RsyncChangeKind::Create, path, size.parse().unwrap_or(0)),
+            })
+        })
+        .collect()
+}
+
+/// `\( -name a -o -name b \) -prune -o ` for the excludes find can apply,
+/// those without a `/`
+fn name_prune(excludes: &[String]) -> String {
+    let names: Vec<String> = excludes
+        .iter()
+        .filter(|p| !p.trim_end_matches('/').contains('/'))
+        .map(|p| format!("-name {}", quote(p.trim_end_matches('/'))))
+        .collect();
+    if names.is_empty() {
+        String::new()
     } else {
-        // Receiving from host: remote source to local dest
-        cmd.arg(format!("{}:{}", host.hostname, source));
-        cmd.arg(dest);
+        format!("\\( {} \\) -prune -o ", names.join(" -o "))
+    }
+}
+
+/// Pick the program for a transfer with one host: rsync when this machine
+/// and the host both have it, otherwise sftp, or scp when the host has no
+/// SFTP server. Transfers between two hosts keep rsync (or the relay), and so
+/// does a host that cannot be reached, so rsync's error is the one shown
+pub fn pick_transfer_tool(transfer: &RsyncTransfer, local_rsync: bool) -> TransferTool {
+    if transfer.dest_host.is_some() {
+        return TransferTool::Rsync;
+    }
+    let host = &transfer.host;
+    let mut cmd = transport_ssh_command(host);
+    cmd.args(["-o", "ConnectTimeout=10"]);
+    cmd.arg(&host.hostname);
+    cmd.arg("command -v rsync >/dev/null && echo rsync || echo none");
+    let remote = match cmd.stdin(Stdio::null()).stderr(Stdio::null()).output() {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        _ => return TransferTool::Rsync,
+    };
+    if local_rsync && remote == "rsync" {
+        TransferTool::Rsync
+    } else if has_sftp_server(host) {
+        TransferTool::Sftp
+    } else {
+        TransferTool::Scp
+    }
+}
+
+/// Whether the host runs an SFTP server: sftp logs in and runs an empty batch
+fn has_sftp_server(host: &Host) -> bool {
+    Command::new("sftp")
+        .args(copy_tool_args(host))
+        .args(["-o", "ConnectTimeout=10", "-b", "/dev/null"])
+        .arg(&host.hostname)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// The host's transport options for sftp and scp, which have no `-l` and
+/// spell the port `-P`, so both go as `-o` options
+fn copy_tool_args(host: &Host) -> Vec<String> {
+    let ssh = transport_ssh_args(host);
+    let mut args = Vec::new();
+    let mut words = ssh.iter();
+    while let Some(word) = words.next() {
+        match word.as_str() {
+            "-l" => args.extend(words.next().map(|user| format!("-oUser={}", user))),
+            "-p" => args.extend(words.next().map(|port| format!("-oPort={}", port))),
+            "-J" => args.extend(words.next().map(|jump| format!("-oProxyJump={}", jump))),
+            "-i" | "-o" => {
+                args.push(word.clone());
+                args.extend(words.next().cloned());
+            }
+            "-C" | "-q" | "-4" | "-6" => args.push(word.clone()),
+            // Forwarding means nothing to a copy, and debug output would read as errors
+            _ => {}
+        }
+    }
+    args
+}
+
+/// Options the sftp or scp fallback cannot carry out. Like the relay they
+/// copy whole files; sftp applies excludes by name, scp none at all
+pub fn copy_unsupported(options: &RsyncOptions, tool: TransferTool) -> Vec<&'static str> {
+    let mut unsupported = relay_unsupported(options);
+    let excludes_applied = match tool {
+        TransferTool::Sftp => options.excludes.iter().all(|p| !p.trim_end_matches('/').contains('/')),
+        _ => options.excludes.is_empty(),
+    };
+    if !excludes_applied {
+        unsupported.insert(0, "--exclude");
+    }
+    unsupported
+}
+
+/// What sftp or scp would copy: every directory and file under the source,
+/// as creates, since the destination is not compared. Only POSIX find and
+/// wc are used, as minimal systems have no GNU find
+fn copy_listing(transfer: &RsyncTransfer) -> Result<Vec<RsyncChange>, String> {
+    let (base, target) = relay_source(&transfer.source);
+    let script = format!(
+        "cd {} && find {} -mindepth 1 {}\\( -type d -exec printf 'd %s\\n' {{}} + -o -type f -exec wc -c {{}} + \\)",
+        quote_path(&base),
+        quote(&target),
+        name_prune(&transfer.options.excludes)
+    );
+    // The source is local when sending to the host
+    let mut cmd = if transfer.to_host {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c");
+        cmd
+    } else {
+        let mut cmd = transport_ssh_command(&transfer.host);
+        cmd.arg(&transfer.host.hostname);
+        cmd
+    };
+    let output = cmd.arg(script).output().map_err(|e| format!("Failed to list the source: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    let mut changes = parse_copy_listing(&String::from_utf8_lossy(&output.stdout));
+    if target != "." {
+        // The directory itself is copied too
+        let name = target.trim_start_matches("./");
+        changes.insert(0, RsyncChange::new(RsyncChangeKind::Create, format!("{}/", name), 0));
+    }
+    // Paths left out in the review, with everything under them
+    changes.retain(|c| !transfer.skipped.iter().any(|skipped| c.path == *skipped || c.is_under(skipped)));
+    Ok(changes)
+}
+
+/// Parse the listing: `d ./dir` for directories, and `wc -c`'s `size ./file`
+/// for files (its `total` lines do not start with `./`)
+fn parse_copy_listing(output: &str) -> Vec<RsyncChange> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (first, path) = line.trim_start().split_once(' ')?;
+            let path = path.trim_start().strip_prefix("./")?;
+            Some(match first {
+                "d" => RsyncChange::new(RsyncChangeKind::Create, format!("{}/", path), 0),
+                size => RsyncChange::new(RsyncChangeKind::Create, path, size.parse().ok()?),
+            })
+        })
+        .collect()
+}
+
+/// The fallback command for the transfer, and the files it copies in order.
+/// The destination directories are created first, which sftp and scp do not
+/// do as rsync does
+fn copy_command(transfer: &RsyncTransfer) -> Result<(Command, Vec<RsyncChange>), String> {
+    let unsupported = copy_unsupported(&transfer.options, transfer.tool);
+    if !unsupported.is_empty() {
+        return Err(format!("{} does not support {}", transfer.tool.name(), unsupported.join(", ")));
+    }
+    let listing = copy_listing(transfer)?;
+    let cmd = match transfer.tool {
+        TransferTool::Scp => {
+            create_dest_dirs(transfer, &[])?;
+            scp_command(transfer, &listing)?
+        }
+        _ => {
+            let dirs: Vec<&str> = listing.iter().filter(|c| c.is_dir()).map(|c| c.path.as_str()).collect();
+            create_dest_dirs(transfer, &dirs)?;
+            sftp_command(transfer, &listing)
+        }
+    };
+    Ok((cmd, listing.into_iter().filter(|c| !c.is_dir()).collect()))
+}
+
+/// Create the destination directory and `dirs` under it
+fn create_dest_dirs(transfer: &RsyncTransfer, dirs: &[&str]) -> Result<(), String> {
+    let paths: Vec<String> = std::iter::once(transfer.dest.clone())
+        .chain(dirs.iter().map(|dir| join_path(&transfer.dest, dir.trim_end_matches('/'))))
+        .collect();
+    if !transfer.to_host {
+        for path in &paths {
+            std::fs::create_dir_all(local_path(path)).map_err(|e| format!("Failed to create {}: {}", path, e))?;
+        }
+        return Ok(());
+    }
+    let words: Vec<String> = paths.iter().map(|path| quote_path(path)).collect();
+    let mut cmd = transport_ssh_command(&transfer.host);
+    cmd.arg(&transfer.host.hostname).arg(format!("mkdir -p -- {}", words.join(" ")));
+    let output = cmd.stdin(Stdio::null()).output().map_err(|e| format!("Failed to execute ssh: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+/// sftp running a batch with a `put` or `get` per file, fed from a here-document.
+/// sftp echoes each command as it runs it, which is how progress is followed
+fn sftp_command(transfer: &RsyncTransfer, listing: &[RsyncChange]) -> Command {
+    let (base, _) = relay_source(&transfer.source);
+    let (remote, local, copy) = if transfer.to_host {
+        (&transfer.dest, &base, "put")
+    } else {
+        (&base, &transfer.dest, "get")
+    };
+    let mut batch = vec![
+        format!("cd {}", sftp_word(&home_relative(remote))),
+        format!("lcd {}", sftp_word(&local_path(local))),
+    ];
+    for change in listing.iter().filter(|c| !c.is_dir()) {
+        batch.push(format!("{} -p {} {}", copy, sftp_word(&change.path), sftp_word(&change.path)));
+    }
+
+    let sftp = std::iter::once("sftp".to_string())
+        .chain(copy_tool_args(&transfer.host))
+        .chain(["-q".to_string(), "-b".to_string(), "-".to_string(), transfer.host.hostname.clone()])
+        .map(|arg| quote(&arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut cmd = Command::new("bash");
+    cmd.arg("-c").arg(format!("{} <<'SSHING_BATCH'\n{}\nSSHING_BATCH", sftp, batch.join("\n")));
+    cmd
+}
+
+/// `scp -O -r -p -v` with the source's top-level entries, so `dir/` copies
+/// the directory's contents as with rsync. `-O` speaks the original protocol,
+/// which needs no SFTP server, and `-v` prints each file as it starts
+fn scp_command(transfer: &RsyncTransfer, listing: &[RsyncChange]) -> Result<Command, String> {
+    if let Some(nested) = transfer.skipped.iter().find(|path| path.trim_end_matches('/').contains('/')) {
+        return Err(format!("scp copies whole directories, so '{}' cannot be left out", nested));
+    }
+    let (base, _) = relay_source(&transfer.source);
+    let entries: Vec<String> = listing
+        .iter()
+        .map(|c| c.path.trim_end_matches('/'))
+        .filter(|path| !path.contains('/'))
+        .map(|path| join_path(&base, path))
+        .collect();
+    if entries.is_empty() {
+        return Err(format!("Nothing to copy in {}", transfer.source));
+    }
+
+    let host = &transfer.host;
+    let mut cmd = Command::new("scp");
+    cmd.args(copy_tool_args(host));
+    cmd.args(["-O", "-r", "-p", "-v"]);
+    if transfer.to_host {
+        cmd.args(entries.iter().map(|entry| local_path(entry)));
+        cmd.arg(format!("{}:{}", host.hostname, home_relative(&transfer.dest)));
+    } else {
+        cmd.args(entries.iter().map(|entry| format!("{}:{}", host.hostname, home_relative(entry))));
+        cmd.arg(local_path(&transfer.dest));
+    }
+    Ok(cmd)
+}
+
+/// `name` inside the directory `dir`
+fn join_path(dir: &str, name: &str) -> String {
+    match dir.trim_end_matches('/') {
+        "" if dir.starts_with('/') => format!("/{}", name),
+        "" => name.to_string(),
+        dir => format!("{}/{}", dir, name),
+    }
+}
+
+/// A local path with a leading `~` expanded, as sftp and scp get it without a shell
+fn local_path(path: &str) -> String {
+    match (path.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => format!("{}{}", home.display(), rest),
+        _ => path.to_string(),
+    }
+}
+
+/// A remote path for sftp and scp, which start in the home directory, so a
+/// leading `~/` is dropped
+fn home_relative(path: &str) -> String {
+    match path.strip_prefix("~/") {
+        Some(rest) => rest.to_string(),
+        None if path == "~" => ".".to_string(),
+        None => path.to_string(),
+    }
+}
+
+/// A word of an sftp batch, double-quoted. Quoted wildcards are taken literally
+fn sftp_word(word: &str) -> String {
+    format!("\"{}\"", word.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// What a line of sftp or scp output says
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CopyLine {
+    /// A file started copying; scp gives its size, sftp only the path
+    File { path: String, size: Option<u64> },
+    /// The echoed batch, or ssh's chatter from `scp -v`
+    Chatter,
+    /// Anything else, such as an error
+    Other,
+}
+
+/// Lines `scp -v` prints besides the files it copies
+const SCP_CHATTER: &[&str] = &[
+    "debug",
+    "OpenSSH_",
+    "Executing:",
+    "Sending file modes:",
+    "Sending file timestamps:",
+    "Sink:",
+    "Entering directory:",
+    "File mtime",
+    "Authenticated to",
+    "Transferred:",
+    "Bytes per second:",
+];
+
+/// Parse a line of sftp's batch echo (`sftp> put -p "path" "path"`), or of
+/// what `scp -v` prints on stderr (`Sending file modes: C0644 1536 name`, or
+/// `Sink: C0644 1536 name` when receiving)
+pub fn parse_copy_line(tool: TransferTool, line: &StreamLine) -> CopyLine {
+    match tool {
+        TransferTool::Sftp if !line.stderr => {
+            let args = line.text.strip_prefix("sftp> ").and_then(|command| {
+                command.strip_prefix("put -p ").or_else(|| command.strip_prefix("get -p "))
+            });
+            match args.and_then(sftp_unquote) {
+                Some(path) => CopyLine::File { path, size: None },
+                None => CopyLine::Chatter,
+            }
+        }
+        TransferTool::Scp if line.stderr => {
+            let header = line
+                .text
+                .strip_prefix("Sending file modes: ")
+                .or_else(|| line.text.strip_prefix("Sink: "))
+                .and_then(|header| header.strip_prefix('C'));
+            if let Some(mut parts) = header.map(|header| header.splitn(3, ' ')) {
+                if let (Some(_mode), Some(size), Some(name)) = (parts.next(), parts.next(), parts.next()) {
+                    return CopyLine::File { path: name.to_string(), size: size.parse().ok() };
+                }
+            }
+            if SCP_CHATTER.iter().any(|prefix| line.text.starts_with(prefix)) || line.text.contains(": debug") {
+                CopyLine::Chatter
+            } else {
+                CopyLine::Other
+            }
+        }
+        TransferTool::Scp => CopyLine::Chatter,
+        _ => CopyLine::Other,
+    }
+}
+
+/// The first double-quoted word of an sftp command
+fn sftp_unquote(args: &str) -> Option<String> {
+    let mut chars = args.strip_prefix('"')?.chars();
+    let mut word = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => word.push(chars.next()?),
+            '"' => return Some(word),
+            _ => word.push(c),
+        }
+    }
+    None
+}
+
+/// Progress of an sftp or scp copy, in the form rsync reports it, from the
+/// bytes of the files started so far
+pub fn copy_progress(bytes: u64, total: u64, elapsed_secs: u64) -> RsyncProgress {
+    let percent = (bytes.min(total) * 100).checked_div(total).unwrap_or(100) as u8;
+    let rate = bytes / elapsed_secs.max(1);
+    let eta = total.saturating_sub(bytes).checked_div(rate).unwrap_or(0);
+    RsyncProgress {
+        bytes,
+        percent,
+        rate: format!("{}/s", format_size(rate)),
+        eta: format!("{}:{:02}:{:02}", eta / 3600, eta / 60 % 60, eta % 60),
+    }
+}
+
+/// The rsync arguments for the options set in the form
+fn option_args(options: &RsyncOptions) -> Vec<String> {
+    let mut args = Vec::new();
+    // rsync uses the first matching rule, so includes go before excludes
+    args.extend(options.includes.iter().map(|p| format!("--include={}", p)));
+    args.extend(options.excludes.iter().map(|p| format!("--exclude={}", p)));
+    if options.delete {
+        args.push("--delete".to_string());
+    }
+    if options.checksum {
+        args.push("--checksum".to_string());
+    }
+    if let Some(limit) = &options.bwlimit {
+        args.push(format!("--bwlimit={}", limit));
+    }
+    match options.resume {
+        ResumeMode::Off => {}
+        ResumeMode::Partial => args.push("--partial".to_string()),
+        ResumeMode::AppendVerify => args.extend(["--partial".to_string(), "--append-verify".to_string()]),
+    }
+    if let Some(chmod) = &options.chmod {
+        args.push(format!("--chmod={}", chmod));
+    }
+    if let Some(chown) = &options.chown {
+        args.push(format!("--chown={}", chown));
+    }
+    if let Some(dir) = &options.backup_dir {
+        args.extend(["--backup".to_string(), format!("--backup-dir={}", dir)]);
+    }
+    args
+}
+
+/// The command line the transfer runs, quoted for a shell, as a preview
+pub fn rsync_command_line(transfer: &RsyncTransfer) -> String {
+    command_line(&transfer_command(transfer, &[]))
+}
+
+fn command_line(cmd: &Command) -> String {
+    std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|arg| quote(&arg.to_string_lossy()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Rules from a `.gitignore`: its patterns as excludes, and `!` patterns as
+/// includes. Comments and blank lines are skipped
+pub fn parse_gitignore(content: &str) -> (Vec<String>, Vec<String>) {
+    let mut includes = Vec::new();
+    let mut excludes = Vec::new();
+    for line in content.lines().map(str::trim_end) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.strip_prefix('!') {
+            Some(pattern) => includes.push(pattern.to_string()),
+            // `\#` and `\!` escape a leading # or !
+            None => excludes.push(line.strip_prefix('\\').unwrap_or(line).to_string()),
+        }
+    }
+    (includes, excludes)
+}
+
+/// Run the transfer with `--dry-run` and list what it would change
+pub fn rsync_dry_run(transfer: &RsyncTransfer) -> Result<Vec<RsyncChange>, String> {
+    let extra = [
+        "--dry-run".to_string(),
+        "--itemize-changes".to_string(),
+        format!("--out-format={}", CHANGE_FORMAT),
+    ];
+    if transfer.tool != TransferTool::Rsync {
+        let unsupported = copy_unsupported(&transfer.options, transfer.tool);
+        if !unsupported.is_empty() {
+            return Err(format!(
+                "Without rsync on both ends the transfer would use {}, which does not support {}",
+                transfer.tool.label(),
+                unsupported.join(", ")
+            ));
+        }
+        return copy_listing(transfer);
     }
+    if transfer.relay {
+        let unsupported = relay_unsupported(&transfer.options);
+        if !unsupported.is_empty() {
+            return Err(format!(
+                "{} cannot reach {}, so the transfer would stream through this machine, which does not support {}",
+                transfer.host.host,
+                transfer.dest_host.as_ref().map_or("the destination", |h| h.host.as_str()),
+                unsupported.join(", ")
+            ));
+        }
+        return relay_listing(transfer);
+    }
+    let output = rsync_command(transfer, &extra).output().map_err(|e| format!("Failed to execute rsync: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(match host_key_hint(&stderr) {
+            Some(hint) => format!("{} ({})", stderr, hint),
+            None => stderr,
+        });
+    }
+    Ok(parse_dry_run(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Transfers never prompt, so an unknown or changed host key fails them.
+/// Explain how to fix that when ssh's error says so
+pub fn host_key_hint(stderr: &str) -> Option<&'static str> {
+    if stderr.contains("REMOTE HOST IDENTIFICATION HAS CHANGED") {
+        Some("the host key changed: check it, then update known_hosts")
+    } else if stderr.contains("Host key verification failed") {
+        Some("the host key is not known yet: connect to the host once to accept it")
+    } else {
+        None
+    }
+}
 
-    // Execute rsync
-    let output = cmd.output()?;
+/// Start the transfer in the background. Rsync's stdout has a line per changed
+/// path (see `parse_change_line`) and `--info=progress2` updates
+/// (see `parse_progress`). For sftp and scp, whose output only names the
+/// files (see `parse_copy_line`), the files they copy are returned in order.
+/// When rsync runs on the source host (see `runs_on_source`), its session id
+/// is kept in `pid_file` there, for `stop_script_command` to stop it
+pub fn spawn_rsync(transfer: &RsyncTransfer, pid_file: &str) -> anyhow::Result<(StreamingCommand, Vec<RsyncChange>)> {
+    if transfer.tool != TransferTool::Rsync {
+        let (cmd, files) = copy_command(transfer).map_err(anyhow::Error::msg)?;
+        return Ok((StreamingCommand::spawn_local(cmd)?, files));
+    }
+    let extra = [
+        "--info=progress2".to_string(),
+        // The whole file list up front, so the overall percentage does not jump back
+        "--no-inc-recursive".to_string(),
+        format!("--out-format={}", CHANGE_FORMAT),
+    ];
+    Ok((StreamingCommand::spawn_local(run_command(transfer, pid_file, &extra))?, Vec::new()))
+}
+
+/// Like `transfer_command`, but rsync on the source host runs in a session
+/// of its own: stopping ssh would leave it running there
+fn run_command(transfer: &RsyncTransfer, pid_file: &str, extra: &[String]) -> Command {
+    match &transfer.dest_host {
+        Some(dest_host) if runs_on_source(transfer) => source_host_command(
+            transfer,
+            &remote_script(&run_in_session(&remote_rsync_line(transfer, dest_host, extra), pid_file)),
+        ),
+        _ => transfer_command(transfer, extra),
+    }
+}
+
+/// Whether rsync runs on the source host of a transfer between two hosts,
+/// rather than on this machine
+pub fn runs_on_source(transfer: &RsyncTransfer) -> bool {
+    transfer.tool == TransferTool::Rsync && transfer.dest_host.is_some() && !transfer.relay
+}
+
+/// The command for the transfer: rsync, or the tar relay when the hosts of a
+/// remote-to-remote transfer cannot reach each other
+fn transfer_command(transfer: &RsyncTransfer, extra: &[String]) -> Command {
+    match &transfer.dest_host {
+        Some(dest_host) if transfer.relay => relay_command(transfer, dest_host),
+        _ => rsync_command(transfer, extra),
+    }
+}
+
+/// Parse the output of a dry run
+pub fn parse_dry_run(output: &str) -> Vec<RsyncChange> {
+    output.lines().filter_map(parse_change_line).collect()
+}
+
+/// Parse one `--itemize-changes` line printed with `CHANGE_FORMAT`
+pub fn parse_change_line(line: &str) -> Option<RsyncChange> {
+    let (items, rest) = line.split_once(' ')?;
+    if items == "*deleting" {
+        // Deletions have no size; older rsync versions leave it out entirely
+        let rest = rest.trim_start();
+        let path = match rest.split_once(' ') {
+            Some((size, path)) if size.parse::<u64>().is_ok() => path,
+            _ => rest,
+        };
+        return Some(RsyncChange::new(RsyncChangeKind::Delete, path, 0));
+    }
 
-    let success = output.status.success();
-    let output_str = if success {
-        String::from_utf8_lossy(&output.stdout).to_string()
+    // YXcstpoguax: update type, file type, then attribute changes
+    let mut chars = items.chars();
+    let update = chars.next()?;
+    let file_type = chars.next()?;
+    if !"<>ch.".contains(update) || !"fdLDS".contains(file_type) {
+        return None;
+    }
+    let (size, path) = rest.split_once(' ')?;
+    // The transfer root itself
+    if path == "./" {
+        return None;
+    }
+    let kind = if chars.as_str().starts_with('+') {
+        RsyncChangeKind::Create
     } else {
-        String::from_utf8_lossy(&output.stderr).to_string()
+        RsyncChangeKind::Update
     };
+    let size = if file_type == 'f' { size.parse().unwrap_or(0) } else { 0 };
+    Some(RsyncChange::new(kind, path, size))
+}
+
+/// What a line of rsync's output says during a transfer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RsyncLine {
+    /// An `--info=progress2` update for the whole transfer
+    Progress(RsyncProgress),
+    /// A path rsync changed, printed with `CHANGE_FORMAT`
+    Change(RsyncChange),
+    /// Anything else, such as a warning
+    Other,
+}
 
-    Ok((success, output_str))
+/// Parse a line of a transfer's stdout, split at every `\r` redraw
+pub fn parse_rsync_line(line: &str) -> RsyncLine {
+    if let Some(progress) = parse_progress(line) {
+        RsyncLine::Progress(progress)
+    } else if let Some(change) = parse_change_line(line) {
+        RsyncLine::Change(change)
+    } else {
+        RsyncLine::Other
+    }
+}
+
+/// Parse a progress update: `1,234,567  45%  12.34MB/s  0:00:03 (xfr#5, to-chk=10/20)`
+pub fn parse_progress(line: &str) -> Option<RsyncProgress> {
+    let mut words = line.split_whitespace();
+    let bytes = words.next()?.replace(',', "").parse().ok()?;
+    let percent = words.next()?.strip_suffix('%')?.parse().ok()?;
+    let rate = words.next()?.to_string();
+    let eta = words.next()?.to_string();
+    Some(RsyncProgress { bytes, percent, rate, eta })
+}
+
+/// Escape rsync's wildcard characters, so a path only matches itself
+fn escape_pattern(path: &str) -> String {
+    if !path.contains(['*', '?', '[']) {
+        return path.to_string();
+    }
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        if matches!(c, '*' | '?' | '[' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Bytes in binary units, e.g. `1.5 MiB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
 }
 
 /// Check if rsync is available on the system
@@ -84,3 +897,392 @@ pub fn is_rsync_available() -> bool {
         .map(|output| output.status.success())
         .unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dry_run() {
+        let output = "\
+.d..t...... 4096 ./
+>f+++++++++ 1536 dist/app.js
+>f.st...... 20480 dist/index.html
+cd+++++++++ 4096 dist/assets/
+.f...p..... 12 dist/robots.txt
+*deleting   0 dist/old file.js
+*deleting   dist/legacy/
+sending incremental file list
+";
+        let changes = parse_dry_run(output);
+        assert_eq!(
+            changes,
+            vec![
+                RsyncChange::new(RsyncChangeKind::Create, "dist/app.js", 1536),
+                RsyncChange::new(RsyncChangeKind::Update, "dist/index.html", 20480),
+                RsyncChange::new(RsyncChangeKind::Create, "dist/assets/", 0),
+                RsyncChange::new(RsyncChangeKind::Update, "dist/robots.txt", 12),
+                RsyncChange::new(RsyncChangeKind::Delete, "dist/old file.js", 0),
+                RsyncChange::new(RsyncChangeKind::Delete, "dist/legacy/", 0),
+            ]
+        );
+
+        assert_eq!(
+            parse_progress("     1,234,567  45%   12.34MB/s    0:00:03 (xfr#5, to-chk=10/20)"),
+            Some(RsyncProgress { bytes: 1234567, percent: 45, rate: "12.34MB/s".into(), eta: "0:00:03".into() })
+        );
+        assert_eq!(parse_progress(">f+++++++++ 1536 dist/app.js"), None);
+
+        assert_eq!(escape_pattern("dist/app.js"), "dist/app.js");
+        assert_eq!(escape_pattern("dist/[draft]*.md"), "dist/\\[draft]\\*.md");
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+    }
+
+    /// Stdout of `rsync -a --delete --info=progress2 --no-inc-recursive
+    /// --out-format='%i %l %n'`: progress redraws end in `\r`, and the update
+    /// closing each file, like each changed path, in `\n`
+    const PROGRESS2_OUTPUT: &str = "\
+.d..t...... 4096 ./\n\
+\r              0   0%    0.00kB/s    0:00:00  \
+\r          1,536   0%    1.46MB/s    0:00:01 (xfr#1, to-chk=4/6)\n\
+>f+++++++++ 1536 dist/app.js\n\
+\r        131,072  12%  128.00MB/s    0:00:00  \
+\r      1,050,624 100%   89.44MB/s    0:00:00 (xfr#2, to-chk=2/6)\n\
+>f.st...... 1049088 dist/bundle.js\n\
+cd+++++++++ 4096 dist/assets/\n\
+*deleting   0 dist/old.js\n\
+\r      1,050,624 100%   89.44MB/s    0:00:00 (xfr#2, to-chk=0/6)\n";
+
+    #[test]
+    fn test_parse_transfer_output() {
+        // Split the way `StreamingCommand::spawn_local` reads rsync's stdout
+        let lines: Vec<RsyncLine> = PROGRESS2_OUTPUT
+            .split(['\r', '\n'])
+            .filter(|line| !line.is_empty())
+            .map(parse_rsync_line)
+            .collect();
+        assert_eq!(lines.len(), 10);
+
+        let progress: Vec<(u64, u8, &str)> = lines
+            .iter()
+            .filter_map(|line| match line {
+                RsyncLine::Progress(p) => Some((p.bytes, p.percent, p.rate.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            progress,
+            vec![
+                (0, 0, "0.00kB/s"),
+                (1536, 0, "1.46MB/s"),
+                (131072, 12, "128.00MB/s"),
+                (1050624, 100, "89.44MB/s"),
+                (1050624, 100, "89.44MB/s"),
+            ]
+        );
+
+        // The summary a finished run shows: files created or updated, and
+        // the bytes of the last progress update
+        let changes: Vec<&RsyncChange> = lines
+            .iter()
+            .filter_map(|line| match line {
+                RsyncLine::Change(change) => Some(change),
+                _ => None,
+            })
+            .collect();
+        // The transfer root itself is not a change
+        assert_eq!(changes.len(), 4);
+        let files: Vec<&RsyncChange> =
+            changes.into_iter().filter(|c| !c.is_dir() && c.kind != RsyncChangeKind::Delete).collect();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files.iter().map(|c| c.size).sum::<u64>(), 1050624);
+        assert_eq!(format_size(1050624), "1.0 MiB");
+
+        assert_eq!(parse_rsync_line("rsync: [sender] link_stat \"/srv/x\" failed: No such file or directory (2)"), RsyncLine::Other);
+    }
+
+    #[test]
+    fn test_rsync_command_line() {
+        let mut host = crate::models::Host::new("web".to_string(), "web.example.com".to_string());
+        host.user = Some("deploy".to_string());
+        let mut transfer = RsyncTransfer {
+            host,
+            source: "./dist/".to_string(),
+            dest: "/var/www/my app".to_string(),
+            to_host: true,
+            compress: true,
+            options: RsyncOptions {
+                excludes: vec!["*.map".to_string()],
+                includes: vec!["keep.map".to_string()],
+                delete: true,
+                bwlimit: Some("1m".to_string()),
+                resume: ResumeMode::AppendVerify,
+                chmod: Some("D755,F644".to_string()),
+                backup_dir: Some("/var/backups/app".to_string()),
+                ..RsyncOptions::default()
+            },
+            skipped: vec!["index.html".to_string()],
+            profile: None,
+            dest_host: None,
+            relay: false,
+            tool: TransferTool::Rsync,
+        };
+        assert_eq!(
+            rsync_command_line(&transfer),
+            "rsync -e 'ssh -l deploy -o BatchMode=yes' -a -s -z --exclude=/index.html \
+             --include=keep.map '--exclude=*.map' --delete --bwlimit=1m --partial --append-verify \
+             --chmod=D755,F644 --backup --backup-dir=/var/backups/app ./dist/ 'web.example.com:/var/www/my app'"
+        );
+
+        // The remote path keeps its space as one argument, and -s stops the
+        // remote shell from splitting it again
+        let cmd = transfer_command(&transfer, &[]);
+        let args: Vec<String> = cmd.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        assert!(args.contains(&"-s".to_string()));
+        assert_eq!(args.last().unwrap(), "web.example.com:/var/www/my app");
+
+        // No remote shell expands the home directory any more; rsync starts there
+        transfer.dest = "~/www/my app".to_string();
+        let cmd = transfer_command(&transfer, &[]);
+        let args: Vec<String> = cmd.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        assert_eq!(args.last().unwrap(), "web.example.com:www/my app");
+        assert_eq!(remote_path("$HOME"), ".");
+        assert_eq!(remote_path("~other/x"), "~other/x");
+
+        let (includes, excludes) = parse_gitignore("# build output\nnode_modules/\n\n*.log\n!important.log\n\\#notes\n");
+        assert_eq!(includes, vec!["important.log".to_string()]);
+        assert_eq!(excludes, vec!["node_modules/".to_string(), "*.log".to_string(), "#notes".to_string()]);
+    }
+
+    #[test]
+    fn test_rsync_behind_bastion() {
+        let mut host = crate::models::Host::new("app".to_string(), "10.0.3.7".to_string());
+        host.user = Some("deploy".to_string());
+        host.port = Some(2222);
+        host.identity_file = Some(vec!["~/.ssh/work key".to_string()]);
+        host.proxy_jump = Some("admin@bastion.example.com:2200".to_string());
+        host.ssh_flags = vec!["-A".to_string(), "-t".to_string(), "-C".to_string()];
+        let mut transfer = RsyncTransfer {
+            host,
+            source: "./dist/".to_string(),
+            dest: "/srv/app".to_string(),
+            to_host: true,
+            compress: false,
+            options: RsyncOptions::default(),
+            skipped: Vec::new(),
+            profile: None,
+            dest_host: None,
+            relay: false,
+            tool: TransferTool::Rsync,
+        };
+
+        // The jump host, quoted key and flags reach rsync's ssh; -t would break the transport
+        let cmd = transfer_command(&transfer, &[]);
+        let args: Vec<String> = cmd.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        assert_eq!(cmd.get_program(), "rsync");
+        assert_eq!(
+            args,
+            [
+                "-e",
+                "ssh -l deploy -p 2222 -i '~/.ssh/work key' -J admin@bastion.example.com:2200 -A -C -o BatchMode=yes",
+                "-a",
+                "-s",
+                "./dist/",
+                "10.0.3.7:/srv/app",
+            ]
+        );
+        assert!(!args.iter().any(|arg| arg.contains("StrictHostKeyChecking")));
+
+        // Pulling takes the same route
+        transfer.to_host = false;
+        let cmd = transfer_command(&transfer, &[]);
+        let args: Vec<String> = cmd.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        assert_eq!(args[1], "ssh -l deploy -p 2222 -i '~/.ssh/work key' -J admin@bastion.example.com:2200 -A -C -o BatchMode=yes");
+        assert_eq!(args[4..], ["10.0.3.7:./dist/", "/srv/app"]);
+
+        // From another host, the destination is reached through its bastion without local keys
+        let mut source = crate::models::Host::new("web".to_string(), "web.example.com".to_string());
+        source.proxy_jump = Some("bastion.example.com".to_string());
+        transfer.dest_host = Some(std::mem::replace(&mut transfer.host, source));
+        transfer.to_host = true;
+        let cmd = transfer_command(&transfer, &[]);
+        let args: Vec<String> = cmd.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        assert_eq!(args[..6], ["-J", "bastion.example.com", "-o", "BatchMode=yes", "-A", "web.example.com"]);
+        assert_eq!(
+            args[6],
+            "rsync -a -s -e 'ssh -l deploy -p 2222 -J admin@bastion.example.com:2200 -A -C -o BatchMode=yes' ./dist/ 10.0.3.7:/srv/app"
+        );
+    }
+
+    #[test]
+    fn test_remote_to_remote() {
+        let mut source = crate::models::Host::new("web".to_string(), "web.example.com".to_string());
+        source.user = Some("deploy".to_string());
+        let mut dest = crate::models::Host::new("db".to_string(), "db.internal".to_string());
+        dest.user = Some("app".to_string());
+        dest.port = Some(2222);
+        let mut transfer = RsyncTransfer {
+            host: source,
+            source: "~/uploads/".to_string(),
+            dest: "/srv/uploads".to_string(),
+            to_host: true,
+            compress: false,
+            options: RsyncOptions { excludes: vec!["*.tmp".to_string()], ..RsyncOptions::default() },
+            skipped: vec!["cache/".to_string()],
+            profile: None,
+            dest_host: Some(dest),
+            relay: false,
+            tool: TransferTool::Rsync,
+        };
+
+        // rsync runs on the source host, with the agent forwarded
+        let cmd = transfer_command(&transfer, &[]);
+        let args: Vec<String> = cmd.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        assert_eq!(cmd.get_program(), "ssh");
+        assert_eq!(args[..6], ["-l", "deploy", "-o", "BatchMode=yes", "-A", "web.example.com"]);
+        assert_eq!(
+            args[6],
+            "rsync -a -s --exclude=/cache/ '--exclude=*.tmp' -e 'ssh -l app -p 2222 -o BatchMode=yes' ~/uploads/ db.internal:/srv/uploads"
+        );
+
+        // Run for real, it gets a session of its own there that a cancel can stop
+        let cmd = run_command(&transfer, "/tmp/rsync.pid", &[]);
+        let run_args: Vec<String> = cmd.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        assert_eq!(run_args[..6], args[..6]);
+        assert_eq!(run_args[6], remote_script(&run_in_session(&args[6], "/tmp/rsync.pid")));
+        assert!(run_in_session(&args[6], "/tmp/rsync.pid").starts_with(
+            "if command -v setsid >/dev/null 2>&1; then setsid rsync -a -s --exclude=/cache/"
+        ));
+
+        // Relayed: tar out of one host and into the other
+        transfer.relay = true;
+        let cmd = transfer_command(&transfer, &[]);
+        let script = cmd.get_args().nth(1).unwrap().to_string_lossy().to_string();
+        assert!(script.starts_with("set -o pipefail; ssh -l deploy -o BatchMode=yes web.example.com "));
+        assert!(script.contains("tar -C ~/uploads/ -cf - --exclude=./cache '\\''--exclude=*.tmp'\\'' ."));
+        assert!(script.contains("| ssh -l app -p 2222 -o BatchMode=yes db.internal 'mkdir -p /srv/uploads && tar -C /srv/uploads -xvf -'"));
+
+        transfer.options.delete = true;
+        assert_eq!(relay_unsupported(&transfer.options), vec!["--delete"]);
+        assert!(rsync_dry_run(&transfer).unwrap_err().contains("--delete"));
+
+        assert_eq!(relay_source("/var/www/app"), ("/var/www".to_string(), "./app".to_string()));
+        assert_eq!(relay_source("/app"), ("/".to_string(), "./app".to_string()));
+        assert_eq!(relay_source("app"), (".".to_string(), "./app".to_string()));
+        assert_eq!(
+            parse_relay_listing("d 4096 ./app\nf 1536 ./app/index.html\n"),
+            vec![
+                RsyncChange::new(RsyncChangeKind::Create, "app/", 0),
+                RsyncChange::new(RsyncChangeKind::Create, "app/index.html", 1536),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cancel_relay_exits() {
+        // Shaped like the relay: bash waits on a pipeline whose commands
+        // hold the output pipe open, so stopping bash alone never finishes
+        let mut cmd = Command::new("bash");
+        cmd.arg("-c").arg("set -o pipefail; sleep 30 | sleep 30");
+        let mut process = StreamingCommand::spawn_local(cmd).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        process.terminate();
+
+        let started = std::time::Instant::now();
+        let code = loop {
+            process.read_lines();
+            if let Some(code) = process.try_finish() {
+                break code;
+            }
+            assert!(started.elapsed().as_secs() < 5, "cancelled relay still running");
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        };
+        assert_eq!(code, None);
+    }
+
+    #[test]
+    fn test_copy_fallback() {
+        let mut host = crate::models::Host::new("nas".to_string(), "nas.lan".to_string());
+        host.user = Some("admin".to_string());
+        host.port = Some(2222);
+        host.proxy_jump = Some("bastion".to_string());
+        host.ssh_flags = vec!["-A".to_string(), "-C".to_string(), "-v".to_string()];
+        assert_eq!(
+            copy_tool_args(&host),
+            ["-oUser=admin", "-oPort=2222", "-oProxyJump=bastion", "-C", "-o", "BatchMode=yes"]
+        );
+
+        let mut transfer = RsyncTransfer {
+            host,
+            source: "./site/".to_string(),
+            dest: "~/www".to_string(),
+            to_host: true,
+            compress: false,
+            options: RsyncOptions { excludes: vec!["*.log".to_string()], ..RsyncOptions::default() },
+            skipped: Vec::new(),
+            profile: None,
+            dest_host: None,
+            relay: false,
+            tool: TransferTool::Sftp,
+        };
+        let listing = parse_copy_listing("d ./img\n  1536 ./index.html\n 20480 ./img/logo \"a\".png\n 22016 total\n");
+        assert_eq!(
+            listing,
+            vec![
+                RsyncChange::new(RsyncChangeKind::Create, "img/", 0),
+                RsyncChange::new(RsyncChangeKind::Create, "index.html", 1536),
+                RsyncChange::new(RsyncChangeKind::Create, "img/logo \"a\".png", 20480),
+            ]
+        );
+
+        // sftp gets a batch with a put per file, run from the source and destination directories
+        let cmd = sftp_command(&transfer, &listing);
+        let script = cmd.get_args().nth(1).unwrap().to_string_lossy().to_string();
+        assert_eq!(
+            script,
+            "sftp -oUser=admin -oPort=2222 -oProxyJump=bastion -C -o BatchMode=yes -q -b - nas.lan <<'SSHING_BATCH'\n\
+             cd \"www\"\n\
+             lcd \"./site/\"\n\
+             put -p \"index.html\" \"index.html\"\n\
+             put -p \"img/logo \\\"a\\\".png\" \"img/logo \\\"a\\\".png\"\n\
+             SSHING_BATCH"
+        );
+        let echo = |text: &str, stderr: bool| parse_copy_line(TransferTool::Sftp, &StreamLine { stderr, text: text.to_string() });
+        assert_eq!(
+            echo("sftp> put -p \"img/logo \\\"a\\\".png\" \"img/logo \\\"a\\\".png\"", false),
+            CopyLine::File { path: "img/logo \"a\".png".to_string(), size: None }
+        );
+        assert_eq!(echo("sftp> lcd \"./site/\"", false), CopyLine::Chatter);
+        assert_eq!(echo("remote open(\"index.html\"): Permission denied", true), CopyLine::Other);
+
+        // scp copies the top-level entries, pulling into the local directory
+        transfer.tool = TransferTool::Scp;
+        transfer.to_host = false;
+        transfer.source = "/srv/site/".to_string();
+        transfer.dest = "backup".to_string();
+        let cmd = scp_command(&transfer, &listing).unwrap();
+        let args: Vec<String> = cmd.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        assert_eq!(args[6..], ["-O", "-r", "-p", "-v", "nas.lan:/srv/site/img", "nas.lan:/srv/site/index.html", "backup"]);
+        let scp = |text: &str| parse_copy_line(TransferTool::Scp, &StreamLine { stderr: true, text: text.to_string() });
+        assert_eq!(scp("Sink: C0644 1536 index.html"), CopyLine::File { path: "index.html".to_string(), size: Some(1536) });
+        assert_eq!(scp("Sending file modes: C0600 12 my key"), CopyLine::File { path: "my key".to_string(), size: Some(12) });
+        assert_eq!(scp("Sink: D0755 0 img"), CopyLine::Chatter);
+        assert_eq!(scp("debug1: Authentication succeeded (publickey)."), CopyLine::Chatter);
+        assert_eq!(scp("scp: /srv/site/img: Permission denied"), CopyLine::Other);
+
+        transfer.skipped = vec!["img/old/".to_string()];
+        assert!(scp_command(&transfer, &listing).unwrap_err().contains("img/old/"));
+        assert_eq!(copy_unsupported(&transfer.options, TransferTool::Scp), vec!["--exclude"]);
+        assert!(copy_unsupported(&transfer.options, TransferTool::Sftp).is_empty());
+        transfer.options.excludes.push("cache/tmp".to_string());
+        assert_eq!(copy_unsupported(&transfer.options, TransferTool::Sftp), vec!["--exclude"]);
+
+        assert_eq!(home_relative("~/www"), "www");
+        assert_eq!(join_path("/", "etc"), "/etc");
+        assert_eq!(
+            copy_progress(512 * 1024, 2048 * 1024, 2),
+            RsyncProgress { bytes: 512 * 1024, percent: 25, rate: "256.0 KiB/s".into(), eta: "0:00:06".into() }
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+
+use crate::app::App;
+use crate::models::AppMode;
+use super::docker_styles as styles;
+
+/// Render the compose project list
+pub fn render_compose_projects(frame: &mut Frame, app: &App, area: Rect) {
+    let (host_index, projects, selected_index) = match app.mode.underlying() {
+        AppMode::ComposeProjects { host_index, projects, selected_index } => (*host_index, projects, *selected_index),
+        _ => return,
+    };
+
+    let chunks = Layout::vertical([
+        Constraint::Length(3),  // Header
+        Constraint::Min(0),     // Project list
+        Constraint::Length(4),  // Footer/status
+    ])
+    .split(area);
+
+    // Header
+    let server_name = app
+        .get_current_docker_host()
+        .map(|s| s.host.as_str())
+        .unwrap_or("Unknown");
+    let running_count = projects.iter().filter(|p| p.is_running()).count();
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(" Compose: ", styles::style_default()),
+        Span::styled(server_name, styles::style_header()),
+        Span::styled(" │ ", styles::style_muted()),
+        Span::styled(format!("{} projects", projects.len()), styles::style_default()),
+        Span::styled(format!(" ({} running)", running_count), styles::style_running()),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, chunks[0]);
+
+    // Project table
+    if projects.is_empty() {
+        let message = if app.status_message.as_ref().is_some_and(|s| s.contains("...")) {
+            "Loading..."
+        } else {
+            "No compose projects found"
+        };
+        let empty = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled(message, styles::style_header())),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("Looked at `docker compose ls` and compose files under {}", app.hosts.get(host_index).map(|h| h.docker.roots().join(", ")).unwrap_or_default()),
+                styles::style_muted(),
+            )),
+        ])
+        .block(Block::default().borders(Borders::ALL))
+        .alignment(Alignment::Center);
+        frame.render_widget(empty, chunks[1]);
+    } else {
+        let visible_rows = chunks[1].height.saturating_sub(3) as usize;
+        let total = projects.len();
+        let scroll_offset = if visible_rows == 0 || selected_index < visible_rows / 2 {
+            0
+        } else if selected_index >= total.saturating_sub(visible_rows / 2) {
+            total.saturating_sub(visible_rows)
+        } else {
+            selected_index.saturating_sub(visible_rows / 2)
+        };
+        let end_index = (scroll_offset + visible_rows).min(total);
+
+        let header_cells = ["", "Project", "Status", "Compose files"]
+            .iter()
+            .map(|h| Cell::from(*h).style(styles::style_header()));
+        let header_row = Row::new(header_cells).height(1);
+
+        let rows: Vec<Row> = projects[scroll_offset..end_index]
+            .iter()
+            .enumerate()
+            .map(|(i, project)| {
+                let actual_index = scroll_offset + i;
+                let marker = if actual_index == selected_index { "▸" } else { " " };
+
+                let status_style = if project.is_running() {
+                    styles::style_running()
+                } else if project.is_deployed() {
+                    styles::style_stopped()
+                } else {
+                    styles::style_muted()
+                };
+
+                let row_style = if actual_index == selected_index {
+                    styles::style_selected()
+                } else {
+                    styles::style_default()
+                };
+
+                Row::new(vec![
+                    Cell::from(marker),
+                    Cell::from(project.name.clone()),
+                    Cell::from(project.status.clone()).style(status_style),
+                    Cell::from(project.config_files.join(", ")).style(styles::style_muted()),
+                ])
+                .style(row_style)
+            })
+            .collect();
+
+        let title = if total > visible_rows {
+            format!(" Compose projects ({}-{} of {}) ", scroll_offset + 1, end_index, total)
+        } else {
+            format!(" Compose projects ({}) ", total)
+        };
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(2),
+                Constraint::Percentage(25),
+                Constraint::Length(14),
+                Constraint::Percentage(60),
+            ],
+        )
+        .header(header_row)
+        .block(Block::default().borders(Borders::ALL).title(title));
+        frame.render_widget(table, chunks[1]);
+    }
+
+    // Footer
+    let help = "[u]p [D]own [p]ull [r]estart [l]ogs [c]onfig [i]mport as scripts [R]efresh [j/k] Move [Esc] Back";
+    let content = if let Some(ref err) = app.error_message {
+        vec![
+            Line::from(Span::styled(err.clone(), styles::style_error())),
+            Line::from(Span::styled(help, styles::style_muted())),
+        ]
+    } else if let Some(ref status) = app.status_message {
+        vec![
+            Line::from(Span::styled(status.clone(), styles::style_status())),
+            Line::from(Span::styled(help, styles::style_muted())),
+        ]
+    } else {
+        vec![Line::from(Span::styled(help, styles::style_muted()))]
+    };
+    let footer = Paragraph::new(content).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, chunks[2]);
+}
+
+/// Render compose logs or config for a project
+pub fn render_compose_output(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+
+    let (project, view, lines, scroll_offset) = match &app.mode {
+        AppMode::ComposeOutput { project, view, lines, scroll_offset, .. } => (project, *view, lines, *scroll_offset),
+        _ => return,
+    };
+
+    let chunks = Layout::vertical([
+        Constraint::Length(3),  // Header
+        Constraint::Min(0),     // Output
+        Constraint::Length(3),  // Footer
+    ])
+    .split(area);
+
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(format!(" Compose {}: ", view.label()), styles::style_muted()),
+        Span::styled(project.as_str(), styles::style_header()),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, chunks[0]);
+
+    let visible_height = chunks[1].height.saturating_sub(2) as usize;
+    let total_lines = lines.len();
+    let start = scroll_offset.min(total_lines.saturating_sub(visible_height));
+    let end = (start + visible_height).min(total_lines);
+
+    let visible: Vec<Line> = lines[start..end].iter().map(|l| Line::from(l.as_str())).collect();
+    let content = Paragraph::new(visible).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(content, chunks[1]);
+
+    let scroll_info = format!(
+        " Lines {}-{} of {} ",
+        if total_lines == 0 { 0 } else { start + 1 },
+        end,
+        total_lines
+    );
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled("[j/k] Scroll  [g/G] Top/Bottom  [Ctrl+d/u] Page  [Esc] Back", styles::style_muted()),
+        Span::styled(scroll_info, styles::style_accent()),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, chunks[2]);
+}
+
+/// Render the preview of scripts exported as a compose file
+pub fn render_compose_export(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+
+    let (whole_project, target_path, lines, scroll_offset) = match &app.mode {
+        AppMode::ComposeExport { whole_project, target_path, lines, scroll_offset, .. } => {
+            (*whole_project, target_path, lines, *scroll_offset)
+        }
+        _ => return,
+    };
+
+    let chunks = Layout::vertical([
+        Constraint::Length(3),  // Header
+        Constraint::Min(0),     // YAML
+        Constraint::Length(3),  // Footer
+    ])
+    .split(area);
+
+    let scope = if whole_project { "all scripts of the project" } else { "this script" };
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(" Export to compose ", styles::style_header()),
+        Span::styled("│ ", styles::style_muted()),
+        Span::styled(scope, styles::style_default()),
+        Span::styled(" → ", styles::style_muted()),
+        Span::styled(target_path.as_str(), styles::style_accent()),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, chunks[0]);
+
+    let visible_height = chunks[1].height.saturating_sub(2) as usize;
+    let start = scroll_offset.min(lines.len().saturating_sub(visible_height));
+    let end = (start + visible_height).min(lines.len());
+    let visible: Vec<Line> = lines[start..end]
+        .iter()
+        .map(|l| {
+            let style = if l.trim_end().ends_with(':') { styles::style_header() } else { styles::style_default() };
+            Line::from(Span::styled(l.as_str(), style))
+        })
+        .collect();
+    let content = Paragraph::new(visible).block(Block::default().borders(Borders::ALL).title(" docker-compose.yml "));
+    frame.render_widget(content, chunks[1]);
+
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled("[w] Write file  ", styles::style_accent()),
+        Span::styled("[a] This script / whole project  [j/k] Scroll  [Esc] Back", styles::style_muted()),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, chunks[2]);
+}
+
+/// Render the services of a compose project that can become scripts
+pub fn render_compose_import(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+
+    let (project, scripts, selected_index) = match &app.mode {
+        AppMode::ComposeImport { project, scripts, selected_index, .. } => (project, scripts, *selected_index),
+        _ => return,
+    };
+
+    let chunks = Layout::vertical([
+        Constraint::Length(3),  // Header
+        Constraint::Min(0),     // Services
+        Constraint::Length(3),  // Footer
+    ])
+    .split(area);
+
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(" Import from compose: ", styles::style_muted()),
+        Span::styled(project.as_str(), styles::style_header()),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, chunks[0]);
+
+    let header_cells = ["", "Container", "Image", "Ports", "Env", "Volumes", "Script"]
+        .iter()
+        .map(|h| Cell::from(*h).style(styles::style_header()));
+    let rows: Vec<Row> = scripts
+        .iter()
+        .enumerate()
+        .map(|(i, script)| {
+            let style = if i == selected_index { styles::style_selected() } else { styles::style_default() };
+            Row::new(vec![
+                Cell::from(if i == selected_index { "▸" } else { " " }),
+                Cell::from(script.container_name.clone()),
+                Cell::from(script.repo.clone()),
+                Cell::from(script.ports.len().to_string()),
+                Cell::from(script.env_vars.len().to_string()),
+                Cell::from(script.volumes.len().to_string()),
+                Cell::from(script.path.clone()).style(styles::style_muted()),
+            ])
+            .style(style)
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(2),
+            Constraint::Percentage(20),
+            Constraint::Percentage(25),
+            Constraint::Length(6),
+            Constraint::Length(5),
+            Constraint::Length(8),
+            Constraint::Percentage(40),
+        ],
+    )
+    .header(Row::new(header_cells).height(1))
+    .block(Block::default().borders(Borders::ALL).title(format!(" {} services ", scripts.len())));
+    frame.render_widget(table, chunks[1]);
+
+    let content = if let Some(ref err) = app.error_message {
+        Line::from(Span::styled(err.as_str(), styles::style_error()))
+    } else if let Some(ref status) = app.status_message {
+        Line::from(Span::styled(status.as_str(), styles::style_status()))
+    } else {
+        Line::from(Span::styled("[Enter] Edit & save as script  [j/k] Navigate  [Esc] Back", styles::style_muted()))
+    };
+    let footer = Paragraph::new(content).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, chunks[2]);
+}
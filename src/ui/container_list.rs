@@ -53,7 +53,7 @@ fn render_header(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
 }
 
 fn render_empty_state(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    let message = if app.status_message.as_ref().map_or(false, |s| s.contains("...")) {
+    let message = if app.status_message.as_ref().is_some_and(|s| s.contains("...")) {
         // Still loading
         vec![
             Line::from(""),
@@ -81,37 +81,63 @@ fn render_empty_state(frame: &mut Frame, app: &App, area: ratatui::layout::Rect)
 }
 
 fn render_container_table(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    // Containers arrive sorted by compose project; when any container belongs to one,
+    // a header row is shown above each project's group
+    let grouped = app.containers.iter().any(|c| c.compose_project.is_some());
+    let mut display_rows: Vec<Option<usize>> = Vec::with_capacity(app.containers.len());
+    let mut group_names: Vec<Option<&str>> = Vec::new();
+    for (index, container) in app.containers.iter().enumerate() {
+        let project = container.compose_project.as_deref();
+        if grouped && (index == 0 || app.containers[index - 1].compose_project.as_deref() != project) {
+            display_rows.push(None);
+            group_names.push(project);
+        }
+        display_rows.push(Some(index));
+    }
+
     // Calculate visible rows
     let visible_rows = area.height.saturating_sub(3) as usize;
+    let total_rows = display_rows.len();
     let total_containers = app.containers.len();
-    let selected = app.docker_selected_index;
+    let selected = display_rows
+        .iter()
+        .position(|row| *row == Some(app.docker_selected_index))
+        .unwrap_or(0);
 
     // Compute scroll_offset to keep selection visible
-    let scroll_offset = if visible_rows == 0 {
+    let scroll_offset = if visible_rows == 0 || selected < visible_rows / 2 {
         0
-    } else if selected < visible_rows / 2 {
-        0
-    } else if selected >= total_containers.saturating_sub(visible_rows / 2) {
-        total_containers.saturating_sub(visible_rows)
+    } else if selected >= total_rows.saturating_sub(visible_rows / 2) {
+        total_rows.saturating_sub(visible_rows)
     } else {
         selected.saturating_sub(visible_rows / 2)
     };
 
-    let header_cells = ["", "Name", "Status", "Image", "Ports", "Script"]
+    let header_cells = ["", "Name", "Status", "Image", "Ports", "Script", "Drift"]
         .iter()
         .map(|h| Cell::from(*h).style(styles::style_header()));
     let header = Row::new(header_cells).height(1);
 
     // Only render visible rows
-    let end_index = (scroll_offset + visible_rows).min(total_containers);
-    let visible_containers = &app.containers[scroll_offset..end_index];
+    let end_index = (scroll_offset + visible_rows).min(total_rows);
+    let groups_before = display_rows[..scroll_offset].iter().filter(|r| r.is_none()).count();
+    let mut group_iter = group_names.into_iter().skip(groups_before);
 
-    let rows: Vec<Row> = visible_containers
+    let rows: Vec<Row> = display_rows[scroll_offset..end_index]
         .iter()
-        .enumerate()
-        .map(|(i, container)| {
-            let actual_index = scroll_offset + i;
-            let marker = if actual_index == app.docker_selected_index { "▸" } else { " " };
+        .map(|row| {
+            let container_index = match row {
+                Some(index) => *index,
+                None => {
+                    let label = match group_iter.next().flatten() {
+                        Some(project) => format!("▾ {}", project),
+                        None => "▾ (no compose project)".to_string(),
+                    };
+                    return Row::new(vec![Cell::from(""), Cell::from(label).style(styles::style_accent())]);
+                }
+            };
+            let container = &app.containers[container_index];
+            let marker = if container_index == app.docker_selected_index { "▸" } else { " " };
 
             let status_style = match container.status {
                 ContainerStatus::Running => styles::style_running(),
@@ -142,27 +168,41 @@ fn render_container_table(frame: &mut Frame, app: &App, area: ratatui::layout::R
                 Cell::from("✗ no script").style(styles::style_muted())
             };
 
-            let row_style = if actual_index == app.docker_selected_index {
+            // Only containers compared with a loaded script get a mark
+            let drift_cell = match app.container_drift.get(&container.name) {
+                Some(drift) if drift.is_empty() => Cell::from("✓").style(styles::style_running()),
+                Some(drift) => Cell::from(format!("⚠ {}", drift.len())).style(styles::style_paused()),
+                None => Cell::from(""),
+            };
+
+            let row_style = if container_index == app.docker_selected_index {
                 styles::style_selected()
             } else {
                 styles::style_default()
             };
 
+            let name = if grouped {
+                format!("  {}", container.name)
+            } else {
+                container.name.clone()
+            };
+
             Row::new(vec![
                 Cell::from(marker),
-                Cell::from(container.name.clone()),
+                Cell::from(name),
                 Cell::from(status_indicator).style(status_style),
                 Cell::from(container.short_image()),
                 Cell::from(container.ports_display()),
                 script_cell,
+                drift_cell,
             ])
             .style(row_style)
         })
         .collect();
 
     // Show scroll position in title if needed
-    let title = if total_containers > visible_rows {
-        format!(" Containers ({}-{} of {}) ", scroll_offset + 1, end_index, total_containers)
+    let title = if total_rows > visible_rows {
+        format!(" Containers ({} of {}) ", app.docker_selected_index + 1, total_containers)
     } else {
         format!(" Containers ({}) ", total_containers)
     };
@@ -173,9 +213,10 @@ fn render_container_table(frame: &mut Frame, app: &App, area: ratatui::layout::R
             Constraint::Length(2),
             Constraint::Percentage(18),
             Constraint::Percentage(12),
-            Constraint::Percentage(28),
-            Constraint::Percentage(22),
-            Constraint::Percentage(18),
+            Constraint::Percentage(26),
+            Constraint::Percentage(20),
+            Constraint::Percentage(15),
+            Constraint::Length(6),
         ],
     )
     .header(header)
@@ -189,12 +230,12 @@ fn render_footer(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     // Context-aware help based on selected container
     let has_script = app.containers
         .get(app.docker_selected_index)
-        .map_or(false, |c| c.has_script());
+        .is_some_and(|c| c.has_script());
 
     let help = if has_script {
-        "[e]dit [v]iew [x]Run [b]Replace  [l]ogs [E]nv [D]stats [T]op [I]nfo [p]ull [r]estart [s]top [S]tart"
+        "[e]dit [v]iew [x]Run [H]istory [b]Replace [B]ackups [M]compose dri[f]t/[F]all  [c]Shell [!]Cmd [l]ogs [E]nv [D]stats [T]op [I]nfo [i]mages [V]ol [N]et c[O]mpose [p]ull [r]estart [s]top [S]tart"
     } else {
-        "[b]rowse [n]ew  [l]ogs [E]nv [D]stats [T]op [I]nfo [d]el [X]Purge [p]ull [r]estart [s]top [S]tart"
+        "[b]rowse [n]ew [A]uto  [c]Shell [!]Cmd [l]ogs [E]nv [D]stats [T]op [I]nfo [i]mages [V]ol [N]et c[O]mpose [d]el [X]Purge [p]ull [r]estart [s]top [S]tart"
     };
 
     // Show error/status on first line, help on second line
@@ -6,16 +6,21 @@ use ratatui::{
     Frame,
 };
 
-use crate::models::DockerPendingAction;
+use crate::models::{DockerPendingAction, DockerResourceKind, DockerSettings, DockerSettingsField, LintIssue};
+use super::docker_styles as styles;
 
 /// Render a docker confirmation dialog
 pub fn render_docker_confirm(frame: &mut Frame, action: &DockerPendingAction, area: Rect) {
+    // Script runs also list what the checks found
+    let lint_lines = action.script_lint().map(|lint| lint_lines(lint.as_deref())).unwrap_or_default();
+
     // Center the dialog
-    let dialog_width = 60.min(area.width - 4);
-    let dialog_height = 7;
+    let dialog_width = if lint_lines.is_empty() { 60 } else { 90 }.min(area.width - 4);
+    let lint_height = lint_lines.len().min(area.height.saturating_sub(9) as usize) as u16;
+    let dialog_height = 7 + lint_height;
 
     let x = (area.width - dialog_width) / 2;
-    let y = (area.height - dialog_height) / 2;
+    let y = (area.height.saturating_sub(dialog_height)) / 2;
 
     let dialog_area = Rect::new(x, y, dialog_width, dialog_height);
 
@@ -25,7 +30,7 @@ pub fn render_docker_confirm(frame: &mut Frame, action: &DockerPendingAction, ar
     let description = action.description();
 
     // Determine if this is a destructive action
-    let is_destructive = matches!(action, DockerPendingAction::DockerRemove { .. });
+    let is_destructive = matches!(action, DockerPendingAction::DockerRemove { .. }) || action.has_lint_errors();
 
     let border_style = if is_destructive {
         Style::default().fg(Color::Red)
@@ -35,6 +40,7 @@ pub fn render_docker_confirm(frame: &mut Frame, action: &DockerPendingAction, ar
 
     let chunks = Layout::vertical([
         Constraint::Length(3), // Description
+        Constraint::Length(lint_height), // Script checks
         Constraint::Length(2), // Buttons
     ])
     .split(Rect::new(dialog_area.x + 1, dialog_area.y + 1, dialog_area.width - 2, dialog_area.height - 2));
@@ -54,14 +60,239 @@ pub fn render_docker_confirm(frame: &mut Frame, action: &DockerPendingAction, ar
         .alignment(Alignment::Center);
     frame.render_widget(desc_paragraph, chunks[0]);
 
+    frame.render_widget(Paragraph::new(lint_lines), chunks[1]);
+
     // Buttons
-    let buttons = Line::from(vec![
+    let mut buttons = vec![
         Span::styled("[Y]es", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
         Span::raw("  "),
         Span::styled("[N]o", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-    ]);
+    ];
+    if action.has_lint_errors() {
+        buttons[0] = Span::styled("[F]orce", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
+    }
 
-    let buttons_paragraph = Paragraph::new(buttons)
+    let buttons_paragraph = Paragraph::new(Line::from(buttons))
         .alignment(Alignment::Center);
-    frame.render_widget(buttons_paragraph, chunks[1]);
+    frame.render_widget(buttons_paragraph, chunks[2]);
+}
+
+/// One line per problem found by the script checks
+pub fn lint_lines(lint: Option<&[LintIssue]>) -> Vec<Line<'static>> {
+    match lint {
+        None => vec![Line::from(Span::styled(" Checking script...", styles::style_muted()))],
+        Some([]) => vec![Line::from(Span::styled(" ✓ No problems found", styles::style_running()))],
+        Some(issues) => issues
+            .iter()
+            .map(|issue| {
+                let (marker, style) = if issue.is_error() {
+                    (" ✗ ", styles::style_error())
+                } else {
+                    (" ! ", styles::style_paused())
+                };
+                Line::from(vec![
+                    Span::styled(marker, style.add_modifier(Modifier::BOLD)),
+                    Span::styled(issue.message.clone(), style),
+                ])
+            })
+            .collect(),
+    }
+}
+
+/// Render the prompt asking which user to open a container shell as
+pub fn render_exec_user_prompt(frame: &mut Frame, container_name: &str, user_buffer: &str, area: Rect) {
+    let dialog_width = 60.min(area.width.saturating_sub(4));
+    let dialog_height = 8;
+
+    let x = (area.width.saturating_sub(dialog_width)) / 2;
+    let y = (area.height.saturating_sub(dialog_height)) / 2;
+
+    let dialog_area = Rect::new(x, y, dialog_width, dialog_height);
+
+    // Clear the area behind the dialog
+    frame.render_widget(Clear, dialog_area);
+
+    let content = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  Container: ", styles::style_header()),
+            Span::styled(container_name, styles::style_accent()),
+        ]),
+        Line::from(vec![
+            Span::styled("  User:      ", styles::style_header()),
+            Span::styled(user_buffer, styles::style_editing()),
+            Span::styled("▏", styles::style_accent()),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "  Leave empty for the image default  [Enter] Open  [Esc] Cancel",
+            styles::style_muted(),
+        )),
+    ];
+
+    let dialog = Paragraph::new(content)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(" Open Shell As ")
+            .border_style(styles::style_accent()));
+
+    frame.render_widget(dialog, dialog_area);
+}
+
+pub fn render_exec_command_prompt(
+    frame: &mut Frame,
+    container_name: &str,
+    command_buffer: &str,
+    history_position: Option<(usize, usize)>,
+    area: Rect,
+) {
+    let dialog_width = 70.min(area.width.saturating_sub(4));
+    let dialog_height = 8;
+
+    let x = (area.width.saturating_sub(dialog_width)) / 2;
+    let y = (area.height.saturating_sub(dialog_height)) / 2;
+
+    let dialog_area = Rect::new(x, y, dialog_width, dialog_height);
+
+    // Clear the area behind the dialog
+    frame.render_widget(Clear, dialog_area);
+
+    let history_info = match history_position {
+        Some((index, total)) => format!("  History {}/{}", index + 1, total),
+        None => String::new(),
+    };
+
+    let content = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  Container: ", styles::style_header()),
+            Span::styled(container_name, styles::style_accent()),
+            Span::styled(history_info, styles::style_muted()),
+        ]),
+        Line::from(vec![
+            Span::styled("  $ ", styles::style_header()),
+            Span::styled(command_buffer, styles::style_editing()),
+            Span::styled("▏", styles::style_accent()),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "  [Enter] Run  [↑/↓] History  [Esc] Cancel",
+            styles::style_muted(),
+        )),
+    ];
+
+    let dialog = Paragraph::new(content)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(" Run Command ")
+            .border_style(styles::style_accent()));
+
+    frame.render_widget(dialog, dialog_area);
+}
+
+pub fn render_create_resource_prompt(frame: &mut Frame, kind: DockerResourceKind, name_buffer: &str, area: Rect) {
+    let dialog_width = 60.min(area.width.saturating_sub(4));
+    let dialog_height = 7;
+
+    let x = (area.width.saturating_sub(dialog_width)) / 2;
+    let y = (area.height.saturating_sub(dialog_height)) / 2;
+
+    let dialog_area = Rect::new(x, y, dialog_width, dialog_height);
+
+    // Clear the area behind the dialog
+    frame.render_widget(Clear, dialog_area);
+
+    let content = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  Name: ", styles::style_header()),
+            Span::styled(name_buffer, styles::style_editing()),
+            Span::styled("▏", styles::style_accent()),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled("  [Enter] Create  [Esc] Cancel", styles::style_muted())),
+    ];
+
+    let dialog = Paragraph::new(content)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" New {} ", kind.label()))
+            .border_style(styles::style_accent()));
+
+    frame.render_widget(dialog, dialog_area);
+}
+
+/// Render the form for a host's script discovery settings
+pub fn render_docker_settings(
+    frame: &mut Frame,
+    host_name: &str,
+    settings: &DockerSettings,
+    focused_field: DockerSettingsField,
+    field_buffer: &str,
+    editing_mode: bool,
+    area: Rect,
+) {
+    let dialog_width = 76.min(area.width.saturating_sub(4));
+    let dialog_height = 13;
+
+    let x = (area.width.saturating_sub(dialog_width)) / 2;
+    let y = (area.height.saturating_sub(dialog_height)) / 2;
+
+    let dialog_area = Rect::new(x, y, dialog_width, dialog_height);
+
+    // Clear the area behind the dialog
+    frame.render_widget(Clear, dialog_area);
+
+    let mut content = vec![Line::from("")];
+    for field in DockerSettingsField::ALL {
+        let focused = field == focused_field;
+        let label_style = if focused { styles::style_selected() } else { styles::style_header() };
+        let mut spans = vec![Span::styled(format!("  {:<14}", field.label()), label_style)];
+        if focused && editing_mode {
+            spans.push(Span::styled(field_buffer.to_string(), styles::style_editing()));
+            spans.push(Span::styled("▏", styles::style_accent()));
+        } else {
+            let value = field.value(settings);
+            if value.is_empty() {
+                spans.push(Span::styled(default_label(field), styles::style_muted()));
+            } else {
+                spans.push(Span::styled(value, styles::style_default()));
+            }
+        }
+        content.push(Line::from(spans));
+    }
+    content.push(Line::from(""));
+    content.push(Line::from(Span::styled(
+        "  Lists are comma-separated; empty fields use the defaults",
+        styles::style_muted(),
+    )));
+    content.push(Line::from(""));
+    content.push(Line::from(Span::styled(
+        if editing_mode {
+            "  [Enter] Done  [Tab] Next field  [Esc] Undo"
+        } else {
+            "  [j/k] Field  [Enter] Edit  [Space] Cycle sudo  [s] Save  [Esc] Cancel"
+        },
+        styles::style_muted(),
+    )));
+
+    let dialog = Paragraph::new(content)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" Docker Settings: {} ", host_name))
+            .border_style(styles::style_accent()));
+
+    frame.render_widget(dialog, dialog_area);
+}
+
+/// What an empty settings field falls back to
+fn default_label(field: DockerSettingsField) -> String {
+    let defaults = DockerSettings::default();
+    match field {
+        DockerSettingsField::SearchRoots => defaults.roots().join(", "),
+        DockerSettingsField::IncludePatterns => defaults.includes().join(", "),
+        DockerSettingsField::ExcludePatterns => defaults.excludes().join(", "),
+        DockerSettingsField::MaxDepth => "unlimited".to_string(),
+        DockerSettingsField::Sudo => String::new(),
+    }
 }
@@ -0,0 +1,86 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::models::AppMode;
+use super::docker_styles as styles;
+
+/// Render the differences between containers and their deployment scripts
+pub fn render_drift_report(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+
+    let (container, scroll_offset) = match &app.mode {
+        AppMode::DriftReport { container, scroll_offset, .. } => (container.as_deref(), *scroll_offset),
+        _ => return,
+    };
+
+    let chunks = Layout::vertical([
+        Constraint::Length(3),  // Header
+        Constraint::Min(0),     // Report
+        Constraint::Length(3),  // Footer
+    ])
+    .split(area);
+
+    let rows = app.drift_report_rows(container);
+    let drifted = rows.iter().filter(|(_, drift)| drift.is_some_and(|d| !d.is_empty())).count();
+
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(" Drift Report ", styles::style_header()),
+        Span::styled("│ ", styles::style_muted()),
+        Span::styled(container.unwrap_or("all containers with a script"), styles::style_accent()),
+        Span::styled(" │ ", styles::style_muted()),
+        if drifted > 0 {
+            Span::styled(format!("{} of {} drifted", drifted, rows.len()), styles::style_paused())
+        } else {
+            Span::styled("in sync", styles::style_running())
+        },
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, chunks[0]);
+
+    let field_width = rows
+        .iter()
+        .flat_map(|(_, drift)| drift.unwrap_or_default())
+        .map(|item| item.field.len())
+        .max()
+        .unwrap_or(0)
+        .max(8);
+    let mut lines = Vec::new();
+    for (name, drift) in &rows {
+        let (state, style) = match drift {
+            None => ("not compared".to_string(), styles::style_muted()),
+            Some([]) => ("✓ matches its script".to_string(), styles::style_running()),
+            Some(items) => (format!("⚠ {} differences", items.len()), styles::style_paused()),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} ", name), styles::style_header()),
+            Span::styled(state, style),
+        ]));
+        for item in drift.unwrap_or_default() {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:<width$} ", item.field, width = field_width), styles::style_default()),
+                Span::styled("script ", styles::style_muted()),
+                Span::styled(item.script.as_str(), styles::style_running()),
+                Span::styled("  container ", styles::style_muted()),
+                Span::styled(item.container.as_str(), styles::style_stopped()),
+            ]));
+        }
+    }
+
+    let height = chunks[1].height.saturating_sub(2) as usize;
+    let start = scroll_offset.min(lines.len().saturating_sub(height));
+    let report = Paragraph::new(lines.into_iter().skip(start).take(height).collect::<Vec<_>>())
+        .block(Block::default().borders(Borders::ALL).title(" Script vs docker inspect "));
+    frame.render_widget(report, chunks[1]);
+
+    let footer = Paragraph::new(Line::from(Span::styled(
+        "[j/k] Scroll  [g/G] Top/Bottom  [R] Check again  [Esc] Back",
+        styles::style_muted(),
+    )))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, chunks[2]);
+}
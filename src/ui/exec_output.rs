@@ -0,0 +1,90 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::models::AppMode;
+use super::docker_styles as styles;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+
+    let (container_name, command, output, exit_code, scroll_offset) = match &app.mode {
+        AppMode::ExecOutput {
+            container_index,
+            command,
+            output,
+            exit_code,
+            scroll_offset,
+            ..
+        } => {
+            let name = app
+                .containers
+                .get(*container_index)
+                .map(|c| c.name.as_str())
+                .unwrap_or("Unknown");
+            (name, command, output, *exit_code, *scroll_offset)
+        }
+        _ => return,
+    };
+
+    let chunks = Layout::vertical([
+        Constraint::Length(3),  // Header
+        Constraint::Min(0),     // Output
+        Constraint::Length(3),  // Footer
+    ])
+    .split(area);
+
+    // Header
+    let (status_text, status_style) = match exit_code {
+        Some(0) => (" exit 0 ".to_string(), styles::style_running()),
+        Some(code) => (format!(" exit {} ", code), styles::style_error()),
+        None if output.is_empty() => (" running... ".to_string(), styles::style_paused()),
+        None => (" exit ? ".to_string(), styles::style_muted()),
+    };
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(" Exec: ", styles::style_muted()),
+        Span::styled(container_name, styles::style_header()),
+        Span::styled(" $ ", styles::style_muted()),
+        Span::styled(command.as_str(), styles::style_accent()),
+        Span::styled("  ", styles::style_muted()),
+        Span::styled(status_text, status_style),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, chunks[0]);
+
+    // Output content
+    let visible_height = chunks[1].height.saturating_sub(2) as usize;
+    let total_lines = output.len();
+    let start = scroll_offset.min(total_lines.saturating_sub(visible_height));
+    let end = (start + visible_height).min(total_lines);
+
+    let visible_lines: Vec<Line> = output[start..end]
+        .iter()
+        .map(|l| Line::from(Span::styled(l.as_str(), styles::style_default())))
+        .collect();
+
+    let content = Paragraph::new(visible_lines).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(content, chunks[1]);
+
+    // Footer
+    let scroll_info = format!(
+        " Lines {}-{} of {} ",
+        if total_lines == 0 { 0 } else { start + 1 },
+        end,
+        total_lines
+    );
+
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled(
+            "[j/k] Scroll  [g/G] Top/Bottom  [r] Re-run  [!] New command  [Esc] Back",
+            styles::style_muted(),
+        ),
+        Span::styled(scroll_info, styles::style_accent()),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, chunks[2]);
+}
@@ -6,7 +6,8 @@ use ratatui::{
 };
 
 use crate::app::App;
-use crate::models::AppMode;
+use crate::models::{AppMode, FileEntry};
+use crate::ssh::rsync::format_size;
 use super::docker_styles as styles;
 
 pub fn render(frame: &mut Frame, app: &App) {
@@ -42,6 +43,7 @@ fn render_header(frame: &mut Frame, app: &App, area: Rect) {
         Span::styled(&container_name, styles::style_accent()),
         Span::styled(" │ ", styles::style_muted()),
         Span::styled(&current_path, styles::style_default()),
+        Span::styled(listing_label(app), styles::style_muted()),
     ]))
     .block(Block::default().borders(Borders::ALL));
 
@@ -83,9 +85,7 @@ fn render_file_list(frame: &mut Frame, app: &App, area: Rect) {
     let total_entries = entries.len();
 
     // Compute scroll_offset to keep selection visible
-    let scroll_offset = if visible_rows == 0 {
-        0
-    } else if selected_index < visible_rows / 2 {
+    let scroll_offset = if visible_rows == 0 || selected_index < visible_rows / 2 {
         0
     } else if selected_index >= total_entries.saturating_sub(visible_rows / 2) {
         total_entries.saturating_sub(visible_rows)
@@ -120,8 +120,9 @@ fn render_file_list(frame: &mut Frame, app: &App, area: Rect) {
 
             ListItem::new(Line::from(vec![
                 Span::raw(marker),
+                Span::styled(entry_details(entry), styles::style_muted()),
                 Span::styled(icon, style),
-                Span::styled(&entry.name, line_style),
+                Span::styled(entry.display_name(), line_style),
             ]))
         })
         .collect();
@@ -140,7 +141,7 @@ fn render_file_list(frame: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
-    let help = "[Enter] Open/Select  [Esc] Cancel  [j/k] Navigate";
+    let help = "[Enter] Open/Select  [v] View  [e] Edit  [.] Hidden  [o] Sort  [Esc] Cancel  [j/k] Navigate";
 
     let (message, style) = if let Some(ref err) = app.error_message {
         (err.clone(), styles::style_error())
@@ -155,3 +156,22 @@ fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
 
     frame.render_widget(footer, area);
 }
+
+/// Permissions, owner, size and modification time, as columns before the
+/// name (blank for `..`)
+pub fn entry_details(entry: &FileEntry) -> String {
+    let size = if entry.is_dir { String::new() } else { format_size(entry.size) };
+    let owner: String = entry.owner.chars().take(8).collect();
+    let details = format!("{:<10} {:<8} {:>10} {:<16}  ", entry.mode_label(), owner, size, entry.modified_label());
+    if entry.name == ".." {
+        " ".repeat(details.len())
+    } else {
+        details
+    }
+}
+
+/// The listing order, and whether dotfiles are shown, for a browser's header
+pub fn listing_label(app: &App) -> String {
+    let hidden = if app.show_hidden_files { ", hidden shown" } else { "" };
+    format!("  (by {}{})", app.file_sort.label(), hidden)
+}
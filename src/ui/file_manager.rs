@@ -0,0 +1,224 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::models::{AppMode, FilePane, FilePrompt};
+use crate::ssh::rsync::format_size;
+use super::docker_styles as styles;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+
+    let AppMode::FileManager { host_index, local, remote, remote_focused, prompt, preview } = &app.mode else {
+        return;
+    };
+    let host_name = app.hosts.get(*host_index).map_or("", |h| h.host.as_str());
+
+    let chunks = Layout::vertical([
+        Constraint::Length(3),  // Header
+        Constraint::Min(0),     // Panes or file preview
+        Constraint::Length(3),  // Footer
+    ])
+    .split(area);
+
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(" Files ", styles::style_header()),
+        Span::styled(" │ ", styles::style_muted()),
+        Span::styled("Local", styles::style_accent()),
+        Span::styled(" ⇄ ", styles::style_muted()),
+        Span::styled(host_name, styles::style_accent()),
+        Span::styled(super::file_browser::listing_label(app), styles::style_muted()),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, chunks[0]);
+
+    if let Some(preview) = preview {
+        super::file_preview::render_pager(frame, preview, chunks[1]);
+    } else {
+        let panes = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).split(chunks[1]);
+        render_pane(frame, "Local", local, !remote_focused, panes[0]);
+        render_pane(frame, host_name, remote, *remote_focused, panes[1]);
+    }
+
+    render_footer(frame, app, preview.is_some(), chunks[2]);
+
+    if let Some(prompt) = prompt {
+        render_prompt(frame, prompt, area);
+    }
+}
+
+fn render_pane(frame: &mut Frame, location: &str, pane: &FilePane, focused: bool, area: Rect) {
+    let border_style = if focused { styles::style_accent() } else { styles::style_muted() };
+    let marked = if pane.marked.is_empty() { String::new() } else { format!(" ({} marked)", pane.marked.len()) };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(border_style)
+        .title(format!(" {}: {}{} ", location, pane.path, marked));
+
+    if pane.loading {
+        let loading_msg = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled("Loading...", styles::style_status())),
+        ])
+        .block(block)
+        .alignment(Alignment::Center);
+        frame.render_widget(loading_msg, area);
+        return;
+    }
+
+    let visible_rows = area.height.saturating_sub(2) as usize;
+    let total_entries = pane.entries.len();
+    let selected_index = pane.selected_index;
+    let scroll_offset = if visible_rows == 0 || selected_index < visible_rows / 2 {
+        0
+    } else if selected_index >= total_entries.saturating_sub(visible_rows / 2) {
+        total_entries.saturating_sub(visible_rows)
+    } else {
+        selected_index.saturating_sub(visible_rows / 2)
+    };
+    let end_index = (scroll_offset + visible_rows).min(total_entries);
+
+    let items: Vec<ListItem> = pane.entries[scroll_offset..end_index]
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let actual_index = scroll_offset + i;
+            let is_marked = pane.marked.contains(&entry.name);
+            let marker = match (actual_index == selected_index && focused, is_marked) {
+                (true, true) => "▸*",
+                (true, false) => "▸ ",
+                (false, true) => " *",
+                (false, false) => "  ",
+            };
+            let (icon, style) = if entry.is_dir {
+                ("📁 ", styles::style_accent())
+            } else {
+                ("   ", styles::style_default())
+            };
+            let line_style = if actual_index == selected_index && focused {
+                styles::style_selected()
+            } else if is_marked {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                style
+            };
+            // Half the screen leaves room for size and date only
+            let details = if entry.name == ".." {
+                " ".repeat(29)
+            } else if entry.is_dir {
+                format!("{:>10} {:<16}  ", "", entry.modified_label())
+            } else {
+                format!("{:>10} {:<16}  ", format_size(entry.size), entry.modified_label())
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(marker),
+                Span::styled(details, styles::style_muted()),
+                Span::styled(icon, style),
+                Span::styled(entry.display_name(), line_style),
+            ]))
+        })
+        .collect();
+
+    frame.render_widget(List::new(items).block(block), area);
+}
+
+fn render_footer(frame: &mut Frame, app: &App, previewing: bool, area: Rect) {
+    let running = app.rsync_run.as_ref().filter(|run| run.exit_code.is_none());
+
+    let content = if let Some(ref err) = app.error_message {
+        Line::from(Span::styled(err.as_str(), styles::style_error()))
+    } else if let Some(run) = running {
+        let percent = run.progress.as_ref().map_or(0, |p| p.percent);
+        let queued = if app.file_copies.is_empty() { String::new() } else { format!(", {} more queued", app.file_copies.len()) };
+        Line::from(vec![
+            Span::styled(format!("{} {}% ", run.transfer.tool.name(), percent), styles::style_status()),
+            Span::styled(format!("{} ", format_size(run.bytes())), styles::style_default()),
+            Span::styled(run.current_file.clone().unwrap_or_default(), styles::style_accent()),
+            Span::styled(format!("{}  [Esc] Cancel", queued), styles::style_muted()),
+        ])
+    } else if let Some(ref status) = app.status_message {
+        Line::from(Span::styled(status.as_str(), styles::style_status()))
+    } else if previewing {
+        Line::from(Span::styled("[j/k] Scroll  [g/G] Top/Bottom  [Esc] Close file", styles::style_muted()))
+    } else {
+        Line::from(Span::styled(
+            "[Tab] Pane  [Enter] Open  [Space] Mark  [c] Copy  [d] Download  [v] View  [e] Edit  [r] Rename  [m] Mkdir  [D] Delete  [R] Refresh  [.] Hidden  [o] Sort  [Esc] Back",
+            styles::style_muted(),
+        ))
+    };
+    let footer = Paragraph::new(content).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, area);
+}
+
+fn render_prompt(frame: &mut Frame, prompt: &FilePrompt, area: Rect) {
+    let dialog_width = 60.min(area.width.saturating_sub(4));
+
+    let (title, content) = match prompt {
+        FilePrompt::Rename { name, buffer } => (
+            " Rename ",
+            vec![
+                Line::from(""),
+                Line::from(vec![
+                    Span::styled("  From: ", styles::style_header()),
+                    Span::styled(name.as_str(), styles::style_accent()),
+                ]),
+                Line::from(vec![
+                    Span::styled("  To:   ", styles::style_header()),
+                    Span::styled(buffer.as_str(), styles::style_editing()),
+                    Span::styled("▏", styles::style_accent()),
+                ]),
+                Line::from(""),
+                Line::from(Span::styled("  [Enter] Rename  [Esc] Cancel", styles::style_muted())),
+            ],
+        ),
+        FilePrompt::Mkdir { buffer } => (
+            " New Directory ",
+            vec![
+                Line::from(""),
+                Line::from(vec![
+                    Span::styled("  Name: ", styles::style_header()),
+                    Span::styled(buffer.as_str(), styles::style_editing()),
+                    Span::styled("▏", styles::style_accent()),
+                ]),
+                Line::from(""),
+                Line::from(Span::styled("  [Enter] Create  [Esc] Cancel", styles::style_muted())),
+            ],
+        ),
+        FilePrompt::ConfirmDelete { names } => {
+            let mut lines = vec![
+                Line::from(""),
+                Line::from(Span::styled("  Delete permanently, with directory contents?", styles::style_error())),
+                Line::from(""),
+            ];
+            for name in names.iter().take(8) {
+                lines.push(Line::from(Span::styled(format!("    {}", name), styles::style_default())));
+            }
+            if names.len() > 8 {
+                lines.push(Line::from(Span::styled(format!("    ...and {} more", names.len() - 8), styles::style_muted())));
+            }
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled("  [y] Delete  [any other key] Cancel", styles::style_muted())));
+            (" Confirm Delete ", lines)
+        }
+    };
+
+    let dialog_height = (content.len() as u16 + 2).min(area.height);
+    let x = (area.width.saturating_sub(dialog_width)) / 2;
+    let y = (area.height.saturating_sub(dialog_height)) / 2;
+    let dialog_area = Rect::new(x, y, dialog_width, dialog_height);
+
+    // Clear the area behind the dialog
+    frame.render_widget(Clear, dialog_area);
+
+    let dialog = Paragraph::new(content)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(styles::style_accent()));
+    frame.render_widget(dialog, dialog_area);
+}
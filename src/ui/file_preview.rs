@@ -0,0 +1,82 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::models::{AppMode, FilePreview};
+use super::docker_styles as styles;
+
+/// A file viewed from the script or rsync file browser
+pub fn render(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+
+    let AppMode::FilePreview { preview, return_mode } = &app.mode else {
+        return;
+    };
+    let location = match return_mode.as_ref() {
+        AppMode::FileBrowser { host_index, .. } => app.hosts.get(*host_index).map_or("Remote", |h| h.host.as_str()),
+        AppMode::RsyncFileBrowser { is_remote: true, .. } => "Remote",
+        _ => "Local",
+    };
+    let editable = location != "Local";
+
+    let chunks = Layout::vertical([
+        Constraint::Length(3),  // Header
+        Constraint::Min(0),     // File content
+        Constraint::Length(3),  // Footer
+    ])
+    .split(area);
+
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(" View ", styles::style_header()),
+        Span::styled("│ ", styles::style_muted()),
+        Span::styled(location, styles::style_accent()),
+        Span::styled(" │ ", styles::style_muted()),
+        Span::styled(preview.path.as_str(), styles::style_default()),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, chunks[0]);
+
+    render_pager(frame, preview, chunks[1]);
+
+    let help = if editable {
+        "[j/k] Scroll  [g/G] Top/Bottom  [e] Edit in $EDITOR  [Esc] Back"
+    } else {
+        "[j/k] Scroll  [g/G] Top/Bottom  [Esc] Back"
+    };
+    let (message, style) = if let Some(ref err) = app.error_message {
+        (err.as_str(), styles::style_error())
+    } else if let Some(ref status) = app.status_message {
+        (status.as_str(), styles::style_status())
+    } else {
+        (help, styles::style_muted())
+    };
+    let footer = Paragraph::new(Line::from(Span::styled(message, style)))
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, chunks[2]);
+}
+
+/// The visible lines of a file, with the line range (and whether the file
+/// was cut off) in the title
+pub fn render_pager(frame: &mut Frame, preview: &FilePreview, area: Rect) {
+    let lines = &preview.lines;
+    let visible_height = area.height.saturating_sub(2) as usize;
+    let start = preview.scroll.min(lines.len().saturating_sub(visible_height));
+    let end = (start + visible_height).min(lines.len());
+
+    let visible: Vec<Line> = lines[start..end].iter().map(|l| Line::from(l.as_str())).collect();
+    let cut_off = if preview.truncated { ", cut off" } else { "" };
+    let title = format!(
+        " {}  Lines {}-{} of {}{} ",
+        preview.path,
+        if lines.is_empty() { 0 } else { start + 1 },
+        end,
+        lines.len(),
+        cut_off
+    );
+    let paragraph = Paragraph::new(visible).block(Block::default().borders(Borders::ALL).title(title));
+    frame.render_widget(paragraph, area);
+}
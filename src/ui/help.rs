@@ -41,6 +41,11 @@ pub fn render_help_view(frame: &mut Frame, area: Rect) {
         Line::from("  n             - Create new host"),
         Line::from("  e             - Edit selected host"),
         Line::from("  d             - Delete selected host"),
+        Line::from("  S             - Docker script discovery settings"),
+        Line::from("  r             - Rsync with selected host (sftp or scp where rsync is missing)"),
+        Line::from("  P             - Saved rsync profiles for selected host"),
+        Line::from("  f             - File manager between this machine and selected host"),
+        Line::from("  R             - Transfer between two hosts (R on the source, then on the destination)"),
         Line::from(""),
         Line::from(vec![
             Span::styled("Filtering & Sorting:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
@@ -0,0 +1,158 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Layout},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+
+use crate::app::App;
+use crate::models::{AppMode, ImageUpdateStatus};
+use super::docker_styles as styles;
+
+pub fn render(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let (images, selected_index) = match app.mode.underlying() {
+        AppMode::ImageList { images, selected_index, .. } => (images, *selected_index),
+        _ => return,
+    };
+
+    let chunks = Layout::vertical([
+        Constraint::Length(3),  // Header
+        Constraint::Min(0),     // Image list
+        Constraint::Length(4),  // Footer/status
+    ])
+    .split(area);
+
+    // Header
+    let server_name = app
+        .get_current_docker_host()
+        .map(|s| s.host.as_str())
+        .unwrap_or("Unknown");
+    let dangling_count = images.iter().filter(|i| i.is_dangling()).count();
+    let unused_count = images.iter().filter(|i| i.used_by.is_empty()).count();
+
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(" Images: ", styles::style_default()),
+        Span::styled(server_name, styles::style_header()),
+        Span::styled(" │ ", styles::style_muted()),
+        Span::styled(format!("{} images", images.len()), styles::style_default()),
+        Span::styled(format!(" ({} unused)", unused_count), styles::style_muted()),
+        Span::styled(" │ ", styles::style_muted()),
+        Span::styled(format!("{} dangling", dangling_count), styles::style_paused()),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, chunks[0]);
+
+    // Image table
+    if images.is_empty() {
+        let message = if app.status_message.as_ref().is_some_and(|s| s.contains("...")) {
+            "Loading..."
+        } else {
+            "No images found"
+        };
+        let empty = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled(message, styles::style_header())),
+        ])
+        .block(Block::default().borders(Borders::ALL))
+        .alignment(Alignment::Center);
+        frame.render_widget(empty, chunks[1]);
+    } else {
+        let visible_rows = chunks[1].height.saturating_sub(3) as usize;
+        let total = images.len();
+        let scroll_offset = if visible_rows == 0 || selected_index < visible_rows / 2 {
+            0
+        } else if selected_index >= total.saturating_sub(visible_rows / 2) {
+            total.saturating_sub(visible_rows)
+        } else {
+            selected_index.saturating_sub(visible_rows / 2)
+        };
+        let end_index = (scroll_offset + visible_rows).min(total);
+
+        let header_cells = ["", "Repository", "Tag", "ID", "Size", "Created", "Used by", "Registry"]
+            .iter()
+            .map(|h| Cell::from(*h).style(styles::style_header()));
+        let header_row = Row::new(header_cells).height(1);
+
+        let rows: Vec<Row> = images[scroll_offset..end_index]
+            .iter()
+            .enumerate()
+            .map(|(i, image)| {
+                let actual_index = scroll_offset + i;
+                let marker = if actual_index == selected_index { "▸" } else { " " };
+
+                let used_by_cell = if image.used_by.is_empty() {
+                    let label = if image.is_dangling() { "dangling" } else { "unused" };
+                    Cell::from(label).style(styles::style_paused())
+                } else {
+                    Cell::from(image.used_by.join(", ")).style(styles::style_running())
+                };
+
+                let registry_cell = match &image.update_status {
+                    Some(ImageUpdateStatus::UpToDate) => Cell::from("✓ latest").style(styles::style_running()),
+                    Some(ImageUpdateStatus::UpdateAvailable) => Cell::from("↑ newer").style(styles::style_accent()),
+                    Some(ImageUpdateStatus::Unknown(_)) => Cell::from("? unknown").style(styles::style_muted()),
+                    None => Cell::from("-").style(styles::style_muted()),
+                };
+
+                let row_style = if actual_index == selected_index {
+                    styles::style_selected()
+                } else {
+                    styles::style_default()
+                };
+
+                Row::new(vec![
+                    Cell::from(marker),
+                    Cell::from(image.repository.clone()),
+                    Cell::from(image.tag.clone()),
+                    Cell::from(image.id.clone()),
+                    Cell::from(image.size.clone()),
+                    Cell::from(image.created.clone()),
+                    used_by_cell,
+                    registry_cell,
+                ])
+                .style(row_style)
+            })
+            .collect();
+
+        let title = if total > visible_rows {
+            format!(" Images ({}-{} of {}) ", scroll_offset + 1, end_index, total)
+        } else {
+            format!(" Images ({}) ", total)
+        };
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(2),
+                Constraint::Percentage(24),
+                Constraint::Percentage(10),
+                Constraint::Length(13),
+                Constraint::Length(9),
+                Constraint::Length(11),
+                Constraint::Percentage(22),
+                Constraint::Length(10),
+            ],
+        )
+        .header(header_row)
+        .block(Block::default().borders(Borders::ALL).title(title));
+        frame.render_widget(table, chunks[1]);
+    }
+
+    // Footer
+    let help = "[p]ull [d]elete [P]rune dangling [u]pdate check [R]efresh [j/k] Move [Esc] Back";
+    let content = if let Some(ref err) = app.error_message {
+        vec![
+            Line::from(Span::styled(err.clone(), styles::style_error())),
+            Line::from(Span::styled(help, styles::style_muted())),
+        ]
+    } else if let Some(ref status) = app.status_message {
+        vec![
+            Line::from(Span::styled(status.clone(), styles::style_status())),
+            Line::from(Span::styled(help, styles::style_muted())),
+        ]
+    } else {
+        vec![Line::from(Span::styled(help, styles::style_muted()))]
+    };
+    let footer = Paragraph::new(content).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, chunks[2]);
+}
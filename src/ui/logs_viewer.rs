@@ -98,7 +98,7 @@ pub fn render(frame: &mut Frame, app: &App) {
     frame.render_widget(footer, chunks[2]);
 }
 
-fn colorize_log_line(line: &str) -> Line {
+fn colorize_log_line(line: &str) -> Line<'_> {
     let line_lower = line.to_lowercase();
 
     let style = if line_lower.contains("error") || line_lower.contains("exception") {
@@ -5,6 +5,8 @@ pub mod help;
 pub mod dialogs;
 pub mod rsync;
 pub mod rsync_file_browser;
+pub mod file_manager;
+pub mod file_preview;
 
 // Docker UI modules
 pub mod container_list;
@@ -18,16 +20,34 @@ pub mod process_viewer;
 pub mod env_inspector;
 pub mod script_viewer;
 pub mod script_edit;
+pub mod script_diff;
+pub mod script_run;
+pub mod drift;
+pub mod exec_output;
+pub mod image_list;
+pub mod resource_list;
+pub mod volume_browser;
+pub mod compose;
 
 pub use table::render_table_view;
 pub use editor::{render_editor_view, render_key_selection_view, render_tag_edit_view, render_ssh_flags_selection_view, render_shell_selection_view};
 pub use tag_filter::render_tag_filter_view;
 pub use help::render_help_view;
 pub use dialogs::{render_delete_confirmation, render_search_overlay};
-pub use rsync::render_rsync_view;
+pub use rsync::{render_rsync_view, render_rsync_review, render_rsync_progress, render_rsync_profiles};
 pub use rsync_file_browser::render as render_rsync_file_browser;
+pub use file_manager::render as render_file_manager;
+pub use file_preview::render as render_file_preview;
 pub use container_list::render as render_container_list;
-pub use docker_dialogs::render_docker_confirm;
+pub use docker_dialogs::{
+    render_docker_confirm, render_exec_command_prompt, render_exec_user_prompt, render_create_resource_prompt,
+    render_docker_settings,
+};
+pub use exec_output::render as render_exec_output;
+pub use image_list::render as render_image_list;
+pub use resource_list::{render_volume_list, render_network_list};
+pub use volume_browser::render as render_volume_browser;
+pub use compose::{render_compose_projects, render_compose_output, render_compose_export, render_compose_import};
 pub use logs_viewer::render as render_logs_viewer;
 pub use file_browser::render as render_file_browser;
 pub use stats_viewer::render as render_stats_viewer;
@@ -35,4 +55,7 @@ pub use inspect_viewer::render as render_inspect_viewer;
 pub use process_viewer::render as render_process_viewer;
 pub use env_inspector::render as render_env_inspector;
 pub use script_viewer::render as render_script_viewer;
-pub use script_edit::{render as render_script_edit, render_env_var_dialog};
+pub use script_edit::{render as render_script_edit, render_script_entry_dialog};
+pub use script_diff::{render_script_diff, render_script_backups};
+pub use script_run::{render_script_run_console, render_script_run_history};
+pub use drift::render_drift_report;
@@ -69,9 +69,7 @@ fn render_process_list(frame: &mut Frame, app: &App, area: Rect) {
     let total_processes = processes.len();
 
     // Compute scroll_offset to keep selection visible
-    let scroll_offset = if visible_rows == 0 {
-        0
-    } else if selected_index < visible_rows / 2 {
+    let scroll_offset = if visible_rows == 0 || selected_index < visible_rows / 2 {
         0
     } else if selected_index >= total_processes.saturating_sub(visible_rows / 2) {
         total_processes.saturating_sub(visible_rows)
@@ -0,0 +1,258 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+
+use crate::app::App;
+use crate::models::AppMode;
+use super::docker_styles as styles;
+
+/// Render the host-wide volume list
+pub fn render_volume_list(frame: &mut Frame, app: &App, area: Rect) {
+    let (volumes, selected_index) = match app.mode.underlying() {
+        AppMode::VolumeList { volumes, selected_index, .. } => (volumes, *selected_index),
+        _ => return,
+    };
+
+    let chunks = layout(area);
+    let orphaned_count = volumes.iter().filter(|v| v.is_orphaned()).count();
+    render_header(
+        frame,
+        app,
+        vec![
+            Span::styled(format!("{} volumes", volumes.len()), styles::style_default()),
+            Span::styled(" │ ", styles::style_muted()),
+            Span::styled(format!("{} orphaned", orphaned_count), styles::style_paused()),
+        ],
+        chunks[0],
+    );
+
+    if volumes.is_empty() {
+        render_empty(frame, app, "No volumes found", chunks[1]);
+    } else {
+        let (scroll_offset, end_index) = visible_range(chunks[1], selected_index, volumes.len());
+
+        let rows: Vec<Row> = volumes[scroll_offset..end_index]
+            .iter()
+            .enumerate()
+            .map(|(i, volume)| {
+                let actual_index = scroll_offset + i;
+                let marker = if actual_index == selected_index { "▸" } else { " " };
+
+                let used_by_cell = if volume.is_orphaned() {
+                    Cell::from("orphaned").style(styles::style_paused())
+                } else {
+                    Cell::from(volume.used_by.join(", ")).style(styles::style_running())
+                };
+
+                let row_style = if actual_index == selected_index {
+                    styles::style_selected()
+                } else {
+                    styles::style_default()
+                };
+
+                Row::new(vec![
+                    Cell::from(marker),
+                    Cell::from(volume.short_name()),
+                    Cell::from(volume.driver.clone()),
+                    Cell::from(volume.size.clone().unwrap_or_else(|| "-".to_string())),
+                    used_by_cell,
+                ])
+                .style(row_style)
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(2),
+                Constraint::Percentage(40),
+                Constraint::Length(8),
+                Constraint::Length(10),
+                Constraint::Percentage(40),
+            ],
+        )
+        .header(header_row(&["", "Name", "Driver", "Size", "Used by"]))
+        .block(Block::default().borders(Borders::ALL).title(list_title("Volumes", scroll_offset, end_index, volumes.len(), chunks[1])));
+        frame.render_widget(table, chunks[1]);
+    }
+
+    render_footer(
+        frame,
+        app,
+        "[Enter] Browse [a]dd [d]elete [P]rune [R]efresh [Tab] Networks [j/k] Move [Esc] Back",
+        chunks[2],
+    );
+}
+
+/// Render the host-wide network list
+pub fn render_network_list(frame: &mut Frame, app: &App, area: Rect) {
+    let (networks, selected_index) = match app.mode.underlying() {
+        AppMode::NetworkList { networks, selected_index, .. } => (networks, *selected_index),
+        _ => return,
+    };
+
+    let chunks = layout(area);
+    let unused_count = networks.iter().filter(|n| n.containers.is_empty() && !n.is_builtin()).count();
+    render_header(
+        frame,
+        app,
+        vec![
+            Span::styled(format!("{} networks", networks.len()), styles::style_default()),
+            Span::styled(" │ ", styles::style_muted()),
+            Span::styled(format!("{} unused", unused_count), styles::style_paused()),
+        ],
+        chunks[0],
+    );
+
+    if networks.is_empty() {
+        render_empty(frame, app, "No networks found", chunks[1]);
+    } else {
+        let (scroll_offset, end_index) = visible_range(chunks[1], selected_index, networks.len());
+
+        let rows: Vec<Row> = networks[scroll_offset..end_index]
+            .iter()
+            .enumerate()
+            .map(|(i, network)| {
+                let actual_index = scroll_offset + i;
+                let marker = if actual_index == selected_index { "▸" } else { " " };
+
+                let containers_cell = if network.containers.is_empty() {
+                    Cell::from("-").style(styles::style_muted())
+                } else {
+                    Cell::from(network.containers.join(", ")).style(styles::style_running())
+                };
+
+                let name_cell = if network.is_builtin() {
+                    Cell::from(network.name.clone()).style(styles::style_muted())
+                } else {
+                    Cell::from(network.name.clone())
+                };
+
+                let row_style = if actual_index == selected_index {
+                    styles::style_selected()
+                } else {
+                    styles::style_default()
+                };
+
+                Row::new(vec![
+                    Cell::from(marker),
+                    name_cell,
+                    Cell::from(network.driver.clone()),
+                    Cell::from(network.scope.clone()),
+                    Cell::from(network.subnets.join(", ")),
+                    containers_cell,
+                ])
+                .style(row_style)
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(2),
+                Constraint::Percentage(25),
+                Constraint::Length(8),
+                Constraint::Length(6),
+                Constraint::Percentage(22),
+                Constraint::Percentage(35),
+            ],
+        )
+        .header(header_row(&["", "Name", "Driver", "Scope", "Subnet", "Containers"]))
+        .block(Block::default().borders(Borders::ALL).title(list_title("Networks", scroll_offset, end_index, networks.len(), chunks[1])));
+        frame.render_widget(table, chunks[1]);
+    }
+
+    render_footer(
+        frame,
+        app,
+        "[a]dd [d]elete [P]rune [R]efresh [Tab] Volumes [j/k] Move [Esc] Back",
+        chunks[2],
+    );
+}
+
+fn layout(area: Rect) -> std::rc::Rc<[Rect]> {
+    Layout::vertical([
+        Constraint::Length(3),  // Header
+        Constraint::Min(0),     // List
+        Constraint::Length(4),  // Footer/status
+    ])
+    .split(area)
+}
+
+fn render_header(frame: &mut Frame, app: &App, counts: Vec<Span>, area: Rect) {
+    let server_name = app
+        .get_current_docker_host()
+        .map(|s| s.host.clone())
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    let mut spans = vec![
+        Span::styled(" Docker: ", styles::style_default()),
+        Span::styled(server_name, styles::style_header()),
+        Span::styled(" │ ", styles::style_muted()),
+    ];
+    spans.extend(counts);
+
+    let header = Paragraph::new(Line::from(spans)).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, area);
+}
+
+fn render_empty(frame: &mut Frame, app: &App, message: &str, area: Rect) {
+    let message = if app.status_message.as_ref().is_some_and(|s| s.contains("...")) {
+        "Loading..."
+    } else {
+        message
+    };
+    let empty = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(Span::styled(message, styles::style_header())),
+    ])
+    .block(Block::default().borders(Borders::ALL))
+    .alignment(Alignment::Center);
+    frame.render_widget(empty, area);
+}
+
+/// Scroll window that keeps the selection visible
+fn visible_range(area: Rect, selected: usize, total: usize) -> (usize, usize) {
+    let visible_rows = area.height.saturating_sub(3) as usize;
+    let scroll_offset = if visible_rows == 0 || selected < visible_rows / 2 {
+        0
+    } else if selected >= total.saturating_sub(visible_rows / 2) {
+        total.saturating_sub(visible_rows)
+    } else {
+        selected.saturating_sub(visible_rows / 2)
+    };
+    (scroll_offset, (scroll_offset + visible_rows).min(total))
+}
+
+fn header_row<'a>(titles: &[&'a str]) -> Row<'a> {
+    Row::new(titles.iter().map(|h| Cell::from(*h).style(styles::style_header()))).height(1)
+}
+
+fn list_title(name: &str, scroll_offset: usize, end_index: usize, total: usize, area: Rect) -> String {
+    if total > area.height.saturating_sub(3) as usize {
+        format!(" {} ({}-{} of {}) ", name, scroll_offset + 1, end_index, total)
+    } else {
+        format!(" {} ({}) ", name, total)
+    }
+}
+
+fn render_footer(frame: &mut Frame, app: &App, help: &str, area: Rect) {
+    let content = if let Some(ref err) = app.error_message {
+        vec![
+            Line::from(Span::styled(err.clone(), styles::style_error())),
+            Line::from(Span::styled(help, styles::style_muted())),
+        ]
+    } else if let Some(ref status) = app.status_message {
+        vec![
+            Line::from(Span::styled(status.clone(), styles::style_status())),
+            Line::from(Span::styled(help, styles::style_muted())),
+        ]
+    } else {
+        vec![Line::from(Span::styled(help, styles::style_muted()))]
+    };
+    let footer = Paragraph::new(content).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, area);
+}
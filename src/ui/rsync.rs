@@ -2,12 +2,14 @@ use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Cell, Gauge, Paragraph, Row, Table, Wrap},
     Frame,
 };
 
 use crate::app::App;
 use crate::models::app_state::RsyncField;
+use crate::models::{RsyncChangeKind, RsyncTransfer, TransferTool};
+use crate::ssh::rsync::{format_size, rsync_command_line};
 
 /// Render the rsync mode view
 pub fn render_rsync_view(frame: &mut Frame, app: &App, area: Rect) {
@@ -19,6 +21,11 @@ pub fn render_rsync_view(frame: &mut Frame, app: &App, area: Rect) {
         focused_field,
         editing_mode,
         compress,
+        options,
+        field_buffer,
+        profile,
+        profile_name,
+        dest_host,
         ..
     } = &app.mode
     {
@@ -31,16 +38,22 @@ pub fn render_rsync_view(frame: &mut Frame, app: &App, area: Rect) {
         .split(area);
 
         // Title
-        let title = Paragraph::new("Rsync File Synchronization")
+        let title_text = match profile {
+            Some(name) => format!("Rsync File Synchronization  │  Profile: {}", name),
+            None => "Rsync File Synchronization".to_string(),
+        };
+        let title = Paragraph::new(title_text)
             .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
             .block(Block::default().borders(Borders::ALL));
         frame.render_widget(title, chunks[0]);
 
-        // Form fields area - now just 2 fields (Source and Dest)
+        // Form fields: the two paths, the options, and the command they make
+        let option_rows = RsyncField::OPTIONS.len() as u16 + u16::from(options.delete);
         let field_chunks = Layout::vertical([
-            Constraint::Length(3), // Source path
-            Constraint::Length(3), // Dest path
-            Constraint::Min(0),    // Spacer
+            Constraint::Length(3),               // Source path
+            Constraint::Length(3),               // Dest path
+            Constraint::Length(option_rows + 2), // Options
+            Constraint::Min(3),                  // Command preview
         ])
         .split(chunks[1]);
 
@@ -55,7 +68,11 @@ pub fn render_rsync_view(frame: &mut Frame, app: &App, area: Rect) {
             Style::default()
         };
 
-        let source_bracket = if *sync_to_host { "[local]" } else { "[remote]" };
+        let source_bracket = match dest_host {
+            Some(_) => format!("[{}]", editing_host.host),
+            None if *sync_to_host => "[local]".to_string(),
+            None => "[remote]".to_string(),
+        };
         let source_label = Span::styled(format!("Source {} ", source_bracket), Style::default().fg(Color::White));
         let source_value = Span::raw(source_path.clone());
         let source_hint = if *focused_field == RsyncField::SourcePath && *editing_mode {
@@ -81,7 +98,11 @@ pub fn render_rsync_view(frame: &mut Frame, app: &App, area: Rect) {
             Style::default()
         };
 
-        let dest_bracket = if *sync_to_host { "[remote]" } else { "[local]" };
+        let dest_bracket = match dest_host {
+            Some(dest_host) => format!("[{}]", dest_host.host),
+            None if *sync_to_host => "[remote]".to_string(),
+            None => "[local]".to_string(),
+        };
         let dest_label = Span::styled(format!("Dest {} ", dest_bracket), Style::default().fg(Color::White));
         let dest_value = Span::raw(dest_path.clone());
         let dest_hint = if *focused_field == RsyncField::DestPath && *editing_mode {
@@ -96,8 +117,90 @@ pub fn render_rsync_view(frame: &mut Frame, app: &App, area: Rect) {
             .block(Block::default().borders(Borders::ALL).title(" Destination "));
         frame.render_widget(dest_widget, field_chunks[1]);
 
+        // Options, one row each; the focused one shows its edit buffer
+        let mut option_lines: Vec<Line> = RsyncField::OPTIONS
+            .iter()
+            .map(|field| {
+                let focused = field == focused_field;
+                let editing = focused && *editing_mode;
+                let value = if editing { format!("{}_", field_buffer) } else { field.value(options) };
+                let value_style = if *field == RsyncField::Delete && options.delete {
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+                } else if value.is_empty() || value == "off" {
+                    Style::default().fg(Color::DarkGray)
+                } else {
+                    Style::default()
+                };
+                let line = Line::from(vec![
+                    Span::styled(format!("{:<12}", field.label()), Style::default().fg(Color::White)),
+                    Span::styled(if value.is_empty() { "-".to_string() } else { value }, value_style),
+                ]);
+                if editing {
+                    line.style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
+                } else if focused {
+                    line.style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+                } else {
+                    line
+                }
+            })
+            .collect();
+        if options.delete {
+            option_lines.push(Line::from(Span::styled(
+                "⚠ --delete: files in the destination that are not in the source will be DELETED",
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )));
+        }
+        let options_widget = Paragraph::new(option_lines)
+            .block(Block::default().borders(Borders::ALL).title(" Options (patterns are comma-separated) "));
+        frame.render_widget(options_widget, field_chunks[2]);
+
+        // The command the transfer will run
+        let transfer = RsyncTransfer {
+            host: editing_host.clone(),
+            source: source_path.clone(),
+            dest: dest_path.clone(),
+            to_host: *sync_to_host,
+            compress: *compress,
+            options: options.clone(),
+            skipped: Vec::new(),
+            profile: None,
+            dest_host: dest_host.clone(),
+            relay: false,
+            tool: TransferTool::Rsync,
+        };
+        let preview = match options.validate(dest_path) {
+            Ok(()) => Paragraph::new(rsync_command_line(&transfer)).style(Style::default().fg(Color::Gray)),
+            Err(e) => Paragraph::new(e).style(Style::default().fg(Color::Red)),
+        };
+        let preview_widget = preview
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title(if dest_host.is_some() {
+                " Command (if the hosts cannot reach each other, files stream through this machine) "
+            } else {
+                " Command "
+            }));
+        frame.render_widget(preview_widget, field_chunks[3]);
+
         // Help footer
-        let help_text = if *editing_mode {
+        let help_text = if let Some(name) = profile_name {
+            vec![
+                Line::from(vec![
+                    Span::styled("Save as profile: ", Style::default().fg(Color::Cyan)),
+                    Span::styled(format!("{}_", name), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                ]),
+                Line::from(Span::styled(
+                    "Enter: Save (replaces a profile with the same name)  │  Esc: Cancel",
+                    Style::default().fg(Color::Gray),
+                )),
+            ]
+        } else if *editing_mode && !matches!(focused_field, RsyncField::SourcePath | RsyncField::DestPath) {
+            vec![
+                Line::from(Span::styled(
+                    "Enter: Save  │  Backspace: Delete  │  Esc: Cancel",
+                    Style::default().fg(Color::Gray),
+                )),
+            ]
+        } else if *editing_mode {
             vec![
                 Line::from(Span::styled(
                     "Tab: Complete  │  Enter: Next Field  │  Backspace: Delete  │  Esc: Cancel",
@@ -105,10 +208,10 @@ pub fn render_rsync_view(frame: &mut Frame, app: &App, area: Rect) {
                 )),
             ]
         } else {
-            let direction_text = if *sync_to_host {
-                "Local → Remote"
-            } else {
-                "Remote → Local"
+            let direction_text = match dest_host {
+                Some(dest_host) => format!("{} → {}", editing_host.host, dest_host.host),
+                None if *sync_to_host => "Local → Remote".to_string(),
+                None => "Remote → Local".to_string(),
             };
             let direction_span = Span::styled(
                 format!("[r] Direction: {}", direction_text),
@@ -136,7 +239,7 @@ pub fn render_rsync_view(frame: &mut Frame, app: &App, area: Rect) {
                     compress_span,
                 ]),
                 Line::from(Span::styled(
-                    "k/↑: Up  │  j/↓: Down  │  i/Enter: Edit  │  [b]rowse  │  Space: Execute  │  Esc/q: Back",
+                    "k/↑: Up  │  j/↓: Down  │  i/Enter: Edit/toggle  │  [b]rowse  │  [I]mport .gitignore  │  [s]ave profile  │  Space: Dry run & review  │  Esc/q: Back",
                     Style::default().fg(Color::Gray),
                 )),
             ]
@@ -147,3 +250,329 @@ pub fn render_rsync_view(frame: &mut Frame, app: &App, area: Rect) {
         frame.render_widget(help_widget, chunks[2]);
     }
 }
+
+/// Render a host's saved rsync profiles with how each last ran
+pub fn render_rsync_profiles(frame: &mut Frame, app: &App, area: Rect) {
+    if let crate::models::AppMode::RsyncProfiles { host_index, selected_index, confirm_delete } = &app.mode {
+        let Some(host) = app.hosts.get(*host_index) else {
+            return;
+        };
+        let chunks = Layout::vertical([
+            Constraint::Length(3),  // Title
+            Constraint::Min(0),     // Profiles
+            Constraint::Length(3),  // Help
+        ])
+        .split(area);
+
+        let title = Paragraph::new(format!("Rsync Profiles  │  {}", host.host))
+            .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+            .block(Block::default().borders(Borders::ALL));
+        frame.render_widget(title, chunks[0]);
+
+        let header_cells = ["Name", "Transfer", "Options", "Last run", "Result"]
+            .iter()
+            .map(|h| Cell::from(*h).style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)));
+        let rows: Vec<Row> = host
+            .rsync_profiles
+            .iter()
+            .enumerate()
+            .map(|(i, profile)| {
+                let transfer = if profile.to_host {
+                    format!("{} → {}:{}", profile.source, host.host, profile.dest)
+                } else {
+                    format!("{}:{} → {}", host.host, profile.source, profile.dest)
+                };
+                let mut flags = Vec::new();
+                if profile.compress {
+                    flags.push("-z");
+                }
+                if profile.options.delete {
+                    flags.push("--delete");
+                }
+                if profile.options.checksum {
+                    flags.push("--checksum");
+                }
+                if profile.options.bwlimit.is_some() {
+                    flags.push("--bwlimit");
+                }
+                let options_style = if profile.options.delete {
+                    Style::default().fg(Color::Red)
+                } else {
+                    Style::default().fg(Color::Gray)
+                };
+                let (started, result, result_style) = match &profile.last_run {
+                    None => ("never".to_string(), String::new(), Style::default()),
+                    Some(run) => {
+                        let started = run.started.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string();
+                        match run.exit_code {
+                            Some(0) => (started, format!("ok, {} files, {}", run.files, format_size(run.bytes)), Style::default().fg(Color::Green)),
+                            Some(code) => (started, format!("exit {}", code), Style::default().fg(Color::Red)),
+                            None => (started, "stopped".to_string(), Style::default().fg(Color::Yellow)),
+                        }
+                    }
+                };
+                let row = Row::new(vec![
+                    Cell::from(profile.name.clone()),
+                    Cell::from(transfer),
+                    Cell::from(flags.join(" ")).style(options_style),
+                    Cell::from(started).style(Style::default().fg(Color::Gray)),
+                    Cell::from(result).style(result_style),
+                ]);
+                if i == *selected_index {
+                    row.style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+                } else {
+                    row
+                }
+            })
+            .collect();
+        let empty = rows.is_empty();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(15),
+                Constraint::Percentage(40),
+                Constraint::Percentage(15),
+                Constraint::Length(17),
+                Constraint::Percentage(20),
+            ],
+        )
+        .header(Row::new(header_cells).height(1))
+        .block(Block::default().borders(Borders::ALL).title(format!(" {} profiles ", host.rsync_profiles.len())));
+        if empty {
+            let hint = Paragraph::new("No saved profiles. Press n to set up a transfer, then s in the rsync form to save it.")
+                .style(Style::default().fg(Color::Gray))
+                .block(Block::default().borders(Borders::ALL).title(" 0 profiles "));
+            frame.render_widget(hint, chunks[1]);
+        } else {
+            frame.render_widget(table, chunks[1]);
+        }
+
+        let help = if *confirm_delete {
+            let name = host.rsync_profiles.get(*selected_index).map(|p| p.name.as_str()).unwrap_or_default();
+            Line::from(Span::styled(
+                format!("Delete profile '{}'? y: Delete  │  any other key: Keep", name),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ))
+        } else {
+            Line::from(Span::styled(
+                "Enter: Run  │  Space: Dry run & review  │  e: Edit  │  n: New  │  D: Delete  │  j/k: Move  │  Esc/q: Back",
+                Style::default().fg(Color::Gray),
+            ))
+        };
+        frame.render_widget(Paragraph::new(help).block(Block::default().borders(Borders::ALL)), chunks[2]);
+    }
+}
+
+/// Render the dry-run review: every change the transfer would make, with the
+/// paths the user left out
+pub fn render_rsync_review(frame: &mut Frame, app: &App, area: Rect) {
+    if let crate::models::AppMode::RsyncReview { transfer, changes, selected_index, .. } = &app.mode {
+        let chunks = Layout::vertical([
+            Constraint::Length(3),  // Title
+            Constraint::Min(0),     // Change list
+            Constraint::Length(4),  // Summary/help
+        ])
+        .split(area);
+
+        let (from, to) = endpoints(transfer);
+        let mut title_spans = vec![Span::styled(
+            format!("Dry Run  {} → {}{}", from, to, route_label(transfer)),
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )];
+        if transfer.options.delete {
+            title_spans.push(Span::styled("  --delete ON", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)));
+        }
+        let title = Paragraph::new(Line::from(title_spans))
+            .block(Block::default().borders(Borders::ALL));
+        frame.render_widget(title, chunks[0]);
+
+        // Keep the selection in view
+        let height = chunks[1].height.saturating_sub(2) as usize;
+        let start = selected_index.saturating_sub(height.saturating_sub(1));
+        let lines: Vec<Line> = changes
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(height)
+            .map(|(i, change)| {
+                let color = match change.kind {
+                    RsyncChangeKind::Create => Color::Green,
+                    RsyncChangeKind::Update => Color::Yellow,
+                    RsyncChangeKind::Delete => Color::Red,
+                };
+                let (mark, kind_style, path_style) = if change.excluded {
+                    ("[ ]", Style::default().fg(Color::DarkGray), Style::default().fg(Color::DarkGray).add_modifier(Modifier::CROSSED_OUT))
+                } else {
+                    ("[x]", Style::default().fg(color), Style::default())
+                };
+                let size = if change.is_dir() || change.kind == RsyncChangeKind::Delete {
+                    String::new()
+                } else {
+                    format_size(change.size)
+                };
+                let line = Line::from(vec![
+                    Span::styled(format!("{} ", mark), kind_style),
+                    Span::styled(format!("{:<7}", change.kind.label()), kind_style),
+                    Span::styled(format!("{:>10}  ", size), Style::default().fg(Color::Gray)),
+                    Span::styled(change.path.clone(), path_style),
+                ]);
+                if i == *selected_index {
+                    line.style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+                } else {
+                    line
+                }
+            })
+            .collect();
+        let list = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(format!(" Changes ({}) ", changes.len())));
+        frame.render_widget(list, chunks[1]);
+
+        let included = || changes.iter().filter(|c| !c.excluded);
+        let count = |kind| included().filter(|c| c.kind == kind).count();
+        let bytes: u64 = included().filter(|c| c.kind != RsyncChangeKind::Delete).map(|c| c.size).sum();
+        let excluded = changes.iter().filter(|c| c.excluded).count();
+        let deletes = count(RsyncChangeKind::Delete);
+        let help_text = vec![
+            Line::from(vec![
+                Span::styled(format!("{} create", count(RsyncChangeKind::Create)), Style::default().fg(Color::Green)),
+                Span::raw("  │  "),
+                Span::styled(format!("{} update", count(RsyncChangeKind::Update)), Style::default().fg(Color::Yellow)),
+                Span::raw("  │  "),
+                Span::styled(
+                    format!("{} delete", deletes),
+                    if deletes > 0 { Style::default().fg(Color::Red).add_modifier(Modifier::BOLD) } else { Style::default().fg(Color::Gray) },
+                ),
+                Span::raw("  │  "),
+                Span::styled(format!("{} to send  │  {} excluded", format_size(bytes), excluded), Style::default().fg(Color::Gray)),
+            ]),
+            Line::from(Span::styled(
+                "j/k: Move  │  Space: Exclude/include  │  Enter: Run transfer  │  Esc/q: Back",
+                Style::default().fg(Color::Gray),
+            )),
+        ];
+        let help_widget = Paragraph::new(help_text)
+            .block(Block::default().borders(Borders::ALL).title(" Review "));
+        frame.render_widget(help_widget, chunks[2]);
+    }
+}
+
+/// Render a running transfer's progress, then its summary
+pub fn render_rsync_progress(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(run) = &app.rsync_run else {
+        return;
+    };
+    let transfer = &run.transfer;
+
+    let chunks = Layout::vertical([
+        Constraint::Length(3),  // Title
+        Constraint::Length(3),  // Progress bar
+        Constraint::Min(0),     // Details
+        Constraint::Length(3),  // Help
+    ])
+    .split(area);
+
+    let (from, to) = endpoints(transfer);
+    let title = Paragraph::new(format!("{}  {} → {}{}", transfer.tool.name(), from, to, route_label(transfer)))
+        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(title, chunks[0]);
+
+    let (percent, gauge_color) = match run.exit_code {
+        None => (run.progress.as_ref().map_or(0, |p| p.percent), Color::Cyan),
+        Some(Some(0)) if !run.cancelled => (100, Color::Green),
+        Some(_) => (run.progress.as_ref().map_or(0, |p| p.percent), Color::Red),
+    };
+    let mut gauge = Gauge::default()
+        .block(Block::default().borders(Borders::ALL))
+        .gauge_style(Style::default().fg(gauge_color).bg(Color::Black))
+        .percent(percent.min(100) as u16);
+    if transfer.relay && run.exit_code.is_none() {
+        // tar reports no overall progress
+        gauge = gauge.label(format!("{} files", run.files));
+    }
+    frame.render_widget(gauge, chunks[1]);
+
+    let label = |text: &str| Span::styled(format!("{:<12}", text), Style::default().fg(Color::Gray));
+    let mut lines = Vec::new();
+    match run.exit_code {
+        None => {
+            let elapsed = run.started.elapsed().as_secs();
+            if transfer.relay {
+                lines.push(Line::from(vec![label("Files"), Span::raw(run.files.to_string())]));
+            } else {
+                lines.push(Line::from(vec![
+                    label("Transferred"),
+                    Span::raw(format_size(run.bytes())),
+                ]));
+            }
+            if let Some(progress) = &run.progress {
+                lines.push(Line::from(vec![label("Rate"), Span::raw(progress.rate.clone())]));
+                lines.push(Line::from(vec![label("ETA"), Span::raw(progress.eta.clone())]));
+            }
+            lines.push(Line::from(vec![label("Elapsed"), Span::raw(format!("{}s", elapsed))]));
+            lines.push(Line::from(vec![
+                label("Current"),
+                Span::styled(run.current_file.clone().unwrap_or_default(), Style::default().fg(Color::Yellow)),
+            ]));
+        }
+        Some(exit_code) => {
+            let (result, color) = match exit_code {
+                _ if run.cancelled => ("Cancelled".to_string(), Color::Yellow),
+                Some(0) => ("Completed".to_string(), Color::Green),
+                Some(code) => (format!("Failed (exit {})", code), Color::Red),
+                None => ("Stopped".to_string(), Color::Red),
+            };
+            lines.push(Line::from(vec![
+                label("Result"),
+                Span::styled(result, Style::default().fg(color).add_modifier(Modifier::BOLD)),
+            ]));
+            lines.push(Line::from(vec![label("Files"), Span::raw(run.files.to_string())]));
+            if !transfer.relay {
+                lines.push(Line::from(vec![label("Transferred"), Span::raw(format_size(run.bytes()))]));
+            }
+            lines.push(Line::from(vec![label("Took"), Span::raw(format!("{}s", run.duration_secs))]));
+        }
+    }
+    if !run.errors.is_empty() {
+        lines.push(Line::from(""));
+        // The last errors that fit
+        let room = (chunks[2].height as usize).saturating_sub(lines.len() + 3);
+        for error in run.errors.iter().skip(run.errors.len().saturating_sub(room)) {
+            lines.push(Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red))));
+        }
+    }
+    let details = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(if run.exit_code.is_none() { " Transferring " } else { " Summary " }));
+    frame.render_widget(details, chunks[2]);
+
+    let help = if run.exit_code.is_none() {
+        "c/Esc: Cancel transfer"
+    } else {
+        "Enter/Esc: Back to hosts"
+    };
+    let help_widget = Paragraph::new(Line::from(Span::styled(help, Style::default().fg(Color::Gray))))
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(help_widget, chunks[3]);
+}
+
+/// Where the transfer copies from and to, as `host:path` for remote sides
+fn endpoints(transfer: &RsyncTransfer) -> (String, String) {
+    match &transfer.dest_host {
+        Some(dest_host) => (
+            format!("{}:{}", transfer.host.host, transfer.source),
+            format!("{}:{}", dest_host.host, transfer.dest),
+        ),
+        None if transfer.to_host => (transfer.source.clone(), format!("{}:{}", transfer.host.host, transfer.dest)),
+        None => (format!("{}:{}", transfer.host.host, transfer.source), transfer.dest.clone()),
+    }
+}
+
+/// How a transfer between two hosts travels, or the program standing in for rsync
+fn route_label(transfer: &RsyncTransfer) -> String {
+    match &transfer.dest_host {
+        Some(_) if transfer.relay => "  (through this machine)".to_string(),
+        Some(_) => format!("  (rsync on {})", transfer.host.host),
+        None if transfer.tool != TransferTool::Rsync => format!("  (via {}, rsync unavailable)", transfer.tool.label()),
+        None => String::new(),
+    }
+}
@@ -9,6 +9,7 @@ use ratatui::{
 use crate::app::App;
 use crate::models::AppMode;
 use crate::models::app_state::RsyncField;
+use super::file_browser::{entry_details, listing_label};
 
 pub fn render(frame: &mut Frame, app: &App) {
     let area = frame.area();
@@ -26,18 +27,21 @@ pub fn render(frame: &mut Frame, app: &App) {
 }
 
 fn render_header(frame: &mut Frame, app: &App, area: Rect) {
-    let (current_path, is_remote, target_field) = match &app.mode {
-        AppMode::RsyncFileBrowser { current_path, is_remote, target_field, .. } => {
-            (current_path.clone(), *is_remote, *target_field)
+    let (current_path, location, target_field) = match &app.mode {
+        AppMode::RsyncFileBrowser { current_path, is_remote, target_field, editing_host, dest_host, .. } => {
+            // Name the host when both sides of the transfer are remote
+            let location = match (dest_host, target_field) {
+                (Some(dest_host), RsyncField::DestPath) => dest_host.host.clone(),
+                (Some(_), _) => editing_host.host.clone(),
+                (None, _) if *is_remote => "Remote".to_string(),
+                (None, _) => "Local".to_string(),
+            };
+            (current_path.clone(), location, *target_field)
         }
-        _ => (String::new(), false, RsyncField::SourcePath),
+        _ => (String::new(), "Local".to_string(), RsyncField::SourcePath),
     };
 
-    let location = if is_remote { "Remote" } else { "Local" };
-    let target = match target_field {
-        RsyncField::SourcePath => "Source",
-        RsyncField::DestPath => "Destination",
-    };
+    let target = target_field.label();
 
     let title = Paragraph::new(Line::from(vec![
         Span::styled(" Browse for ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
@@ -46,6 +50,7 @@ fn render_header(frame: &mut Frame, app: &App, area: Rect) {
         Span::styled(location, Style::default().fg(Color::Green)),
         Span::styled(" | ", Style::default().fg(Color::DarkGray)),
         Span::styled(&current_path, Style::default().fg(Color::White)),
+        Span::styled(listing_label(app), Style::default().fg(Color::DarkGray)),
     ]))
     .block(Block::default().borders(Borders::ALL));
 
@@ -87,9 +92,7 @@ fn render_file_list(frame: &mut Frame, app: &App, area: Rect) {
     let total_entries = entries.len();
 
     // Compute scroll_offset to keep selection visible
-    let scroll_offset = if visible_rows == 0 {
-        0
-    } else if selected_index < visible_rows / 2 {
+    let scroll_offset = if visible_rows == 0 || selected_index < visible_rows / 2 {
         0
     } else if selected_index >= total_entries.saturating_sub(visible_rows / 2) {
         total_entries.saturating_sub(visible_rows)
@@ -122,8 +125,9 @@ fn render_file_list(frame: &mut Frame, app: &App, area: Rect) {
 
             ListItem::new(Line::from(vec![
                 Span::raw(marker),
+                Span::styled(entry_details(entry), Style::default().fg(Color::DarkGray)),
                 Span::styled(icon, style),
-                Span::styled(&entry.name, line_style),
+                Span::styled(entry.display_name(), line_style),
             ]))
         })
         .collect();
@@ -142,7 +146,7 @@ fn render_file_list(frame: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
-    let help = "[Enter] Open/Select  [Space] Select Dir  [Esc] Cancel  [j/k] Navigate";
+    let help = "[Enter] Open/Select  [Space] Select Dir  [v] View  [e] Edit  [.] Hidden  [o] Sort  [Esc] Cancel  [j/k] Navigate";
 
     let (message, style) = if let Some(ref err) = app.error_message {
         (err.clone(), Style::default().fg(Color::Red))
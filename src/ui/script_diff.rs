@@ -0,0 +1,276 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::docker;
+use crate::models::{AppMode, SecretWrite};
+use crate::utils::diff::{DiffKind, DiffLine};
+use super::docker_dialogs::lint_lines;
+use super::docker_styles as styles;
+
+const ADDED_BG: Color = Color::Indexed(22);
+const REMOVED_BG: Color = Color::Indexed(52);
+
+const SHELL_KEYWORDS: &[&str] = &[
+    "if", "then", "else", "elif", "fi", "for", "while", "until", "do", "done", "case", "esac",
+    "function", "export", "local", "readonly", "docker", "sudo",
+];
+
+/// Render the diff between the current script and the version about to be written
+pub fn render_script_diff(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+
+    let (script, diff, scroll_offset, lint, secrets, restoring) = match &app.mode {
+        AppMode::ScriptDiff { script, diff, scroll_offset, lint, secrets, return_mode, .. } => (
+            script,
+            diff,
+            *scroll_offset,
+            lint.as_deref(),
+            secrets,
+            matches!(**return_mode, AppMode::ScriptBackups { .. }),
+        ),
+        _ => return,
+    };
+
+    let lint_lines = lint_lines(lint);
+    let chunks = Layout::vertical([
+        Constraint::Length(3),  // Header
+        Constraint::Min(0),     // Content
+        Constraint::Length((lint_lines.len() as u16 + 2).min(10)),  // Script checks
+        Constraint::Length(3),  // Footer
+    ])
+    .split(area);
+
+    let added = diff.iter().filter(|l| l.kind == DiffKind::Added).count();
+    let removed = diff.iter().filter(|l| l.kind == DiffKind::Removed).count();
+
+    let title = if restoring { " Restore Script " } else { " Review Changes " };
+    let mut header_spans = vec![
+        Span::styled(title, styles::style_header()),
+        Span::styled("│ ", styles::style_muted()),
+        Span::styled(&script.path, styles::style_accent()),
+        Span::styled(" │ ", styles::style_muted()),
+        Span::styled(format!("+{}", added), styles::style_running()),
+        Span::raw(" "),
+        Span::styled(format!("-{}", removed), styles::style_stopped()),
+    ];
+    if !secrets.is_empty() {
+        let mut files: Vec<&str> = secrets
+            .iter()
+            .map(|secret| match secret {
+                SecretWrite::EnvFileEntry { path, .. } | SecretWrite::File { path, .. } => path.as_str(),
+            })
+            .collect();
+        files.dedup();
+        header_spans.push(Span::styled(" │ ", styles::style_muted()));
+        header_spans.push(Span::styled(format!("{} secrets to {}", secrets.len(), files.join(", ")), styles::style_paused()));
+    }
+    let header = Paragraph::new(Line::from(header_spans)).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, chunks[0]);
+
+    let content_height = chunks[1].height.saturating_sub(2) as usize;
+    let visible_lines: Vec<Line> = diff
+        .iter()
+        .skip(scroll_offset)
+        .take(content_height)
+        .map(diff_line)
+        .collect();
+
+    let content = Paragraph::new(visible_lines)
+        .block(Block::default().borders(Borders::ALL).title(format!(" {} diff lines ", diff.len())));
+    frame.render_widget(content, chunks[1]);
+
+    let errors = lint.map_or(0, |issues| issues.iter().filter(|i| i.is_error()).count());
+    let (title, border) = match lint {
+        None => (" Checks ".to_string(), styles::style_muted()),
+        Some(issues) if errors > 0 => (format!(" Checks: {} errors, {} warnings ", errors, issues.len() - errors), styles::style_error()),
+        Some(issues) if !issues.is_empty() => (format!(" Checks: {} warnings ", issues.len()), styles::style_paused()),
+        Some(_) => (" Checks ".to_string(), styles::style_running()),
+    };
+    let checks = Paragraph::new(lint_lines)
+        .block(Block::default().borders(Borders::ALL).border_style(border).title(title));
+    frame.render_widget(checks, chunks[2]);
+
+    let action = if restoring { "Restore this version" } else { "Write script" };
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled(format!("[y/Enter] {}  ", action), styles::style_accent()),
+        Span::styled("[j/k] Scroll  [g/G] Top/Bottom  [Ctrl+d/u] Page  [n/Esc] Back", styles::style_muted()),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, chunks[3]);
+}
+
+/// Render the list of backups kept for a script
+pub fn render_script_backups(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+
+    let (script_path, backups, selected_index, loading) = match &app.mode {
+        AppMode::ScriptBackups { script_path, backups, selected_index, loading, .. } => {
+            (script_path, backups, *selected_index, *loading)
+        }
+        _ => return,
+    };
+
+    let chunks = Layout::vertical([
+        Constraint::Length(3),  // Header
+        Constraint::Min(0),     // Content
+        Constraint::Length(3),  // Footer
+    ])
+    .split(area);
+
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(" Script Backups ", styles::style_header()),
+        Span::styled("│ ", styles::style_muted()),
+        Span::styled(script_path, styles::style_accent()),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, chunks[0]);
+
+    let block = Block::default().borders(Borders::ALL).title(format!(" {} backups ", backups.len()));
+    if loading || backups.is_empty() {
+        let message = if loading { "Loading backups..." } else { "No backups yet - one is kept every time the script is saved" };
+        let content = Paragraph::new(Span::styled(message, styles::style_muted())).block(block);
+        frame.render_widget(content, chunks[1]);
+    } else {
+        let items: Vec<ListItem> = backups
+            .iter()
+            .map(|path| {
+                let name = path.rsplit('/').next().unwrap_or(path);
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{:<21}", docker::script_backup_time(path)),
+                        styles::style_status(),
+                    ),
+                    Span::raw(name.to_string()),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(styles::style_selected().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("▶ ");
+        let mut state = ListState::default();
+        state.select(Some(selected_index));
+        frame.render_stateful_widget(list, chunks[1], &mut state);
+    }
+
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled("[Enter] Compare & restore  ", styles::style_accent()),
+        Span::styled("[j/k] Navigate  [Esc] Back", styles::style_muted()),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, chunks[2]);
+}
+
+fn diff_line(line: &DiffLine) -> Line<'_> {
+    match line.kind {
+        DiffKind::FileHeader => Line::from(Span::styled(line.text.as_str(), styles::style_default().add_modifier(Modifier::BOLD))),
+        DiffKind::HunkHeader => Line::from(Span::styled(line.text.as_str(), Style::default().fg(Color::Cyan))),
+        DiffKind::Context => {
+            let mut spans = vec![Span::raw(" ")];
+            spans.extend(highlight_shell(&line.text));
+            Line::from(spans)
+        }
+        DiffKind::Added | DiffKind::Removed => {
+            let (prefix, fg, bg) = if line.kind == DiffKind::Added {
+                ("+", styles::COLOR_RUNNING, ADDED_BG)
+            } else {
+                ("-", styles::COLOR_STOPPED, REMOVED_BG)
+            };
+            let mut spans = vec![Span::styled(prefix, Style::default().fg(fg).bg(bg))];
+            spans.extend(highlight_shell(&line.text).into_iter().map(|span| {
+                let style = if span.style.fg.is_none() { span.style.fg(fg) } else { span.style };
+                span.style(style.bg(bg))
+            }));
+            Line::from(spans)
+        }
+    }
+}
+
+/// Light shell highlighting: comments, quoted strings, variables, flags and keywords
+fn highlight_shell<'a>(text: &'a str) -> Vec<Span<'a>> {
+    let mut spans = Vec::new();
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    let mut plain_start = 0;
+
+    let flush = |spans: &mut Vec<Span<'a>>, from: usize, to: usize| {
+        if to > from {
+            spans.push(Span::raw(&text[from..to]));
+        }
+    };
+
+    while i < bytes.len() {
+        let at_word_start = i == 0 || bytes[i - 1].is_ascii_whitespace();
+        match bytes[i] {
+            b'#' if at_word_start => {
+                flush(&mut spans, plain_start, i);
+                spans.push(Span::styled(&text[i..], styles::style_muted()));
+                return spans;
+            }
+            quote @ (b'"' | b'\'') => {
+                flush(&mut spans, plain_start, i);
+                let mut end = i + 1;
+                while end < bytes.len() && bytes[end] != quote {
+                    if quote == b'"' && bytes[end] == b'\\' {
+                        end += 1;
+                    }
+                    end += 1;
+                }
+                let end = (end + 1).min(bytes.len());
+                spans.push(Span::styled(&text[i..end], Style::default().fg(styles::COLOR_PAUSED)));
+                i = end;
+                plain_start = i;
+                continue;
+            }
+            b'$' => {
+                flush(&mut spans, plain_start, i);
+                let end = if bytes.get(i + 1) == Some(&b'{') {
+                    text[i..].find('}').map(|p| i + p + 1).unwrap_or(bytes.len())
+                } else {
+                    i + 1 + bytes[i + 1..].iter().take_while(|b| b.is_ascii_alphanumeric() || **b == b'_').count()
+                };
+                spans.push(Span::styled(&text[i..end], styles::style_accent()));
+                i = end;
+                plain_start = i;
+                continue;
+            }
+            b'-' if at_word_start => {
+                flush(&mut spans, plain_start, i);
+                let end = word_end(bytes, i);
+                spans.push(Span::styled(&text[i..end], Style::default().fg(Color::Cyan)));
+                i = end;
+                plain_start = i;
+                continue;
+            }
+            c if at_word_start && c.is_ascii_alphabetic() => {
+                let end = word_end(bytes, i);
+                if SHELL_KEYWORDS.contains(&&text[i..end]) {
+                    flush(&mut spans, plain_start, i);
+                    spans.push(Span::styled(&text[i..end], styles::style_default().add_modifier(Modifier::BOLD)));
+                    plain_start = end;
+                }
+                i = end;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    flush(&mut spans, plain_start, bytes.len());
+    spans
+}
+
+/// End of the run of word characters starting at `start` (stops at spaces, `=` and quotes)
+fn word_end(bytes: &[u8], start: usize) -> usize {
+    start
+        + bytes[start..]
+            .iter()
+            .take_while(|b| !b.is_ascii_whitespace() && !matches!(b, b'=' | b'"' | b'\'' | b'$' | b';'))
+            .count()
+}
@@ -2,12 +2,12 @@ use ratatui::{
     layout::{Constraint, Layout, Rect, Alignment},
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, Row, Table, Paragraph, Clear},
+    widgets::{Block, Borders, Cell, Row, Table, Paragraph, Clear, Wrap},
     Frame,
 };
 
 use crate::app::App;
-use crate::models::{AppMode, ScriptSection};
+use crate::models::{AppMode, EnvStorage, EnvVar, ScriptSection};
 use super::docker_styles as styles;
 
 pub fn render(frame: &mut Frame, app: &App, area: Rect) {
@@ -59,12 +59,10 @@ fn render_editor(frame: &mut Frame, app: &App, area: Rect) {
     .split(area);
 
     // Render section tabs
-    let tabs = vec![
-        ("Env Vars", ScriptSection::EnvVars, editing_script.env_vars.len()),
+    let tabs = [("Env Vars", ScriptSection::EnvVars, editing_script.env_vars.len()),
         ("Ports", ScriptSection::Ports, editing_script.ports.len()),
         ("Volumes", ScriptSection::Volumes, editing_script.volumes.len()),
-        ("Network", ScriptSection::Network, 1),
-    ];
+        ("Network", ScriptSection::Network, 1)];
 
     let tab_spans: Vec<Span> = tabs
         .iter()
@@ -87,7 +85,7 @@ fn render_editor(frame: &mut Frame, app: &App, area: Rect) {
 
     // Render section content
     match focused_section {
-        ScriptSection::EnvVars => render_env_vars(frame, editing_script, selected_index, editing_mode, chunks[1]),
+        ScriptSection::EnvVars => render_env_vars(frame, app, editing_script, selected_index, editing_mode, chunks[1]),
         ScriptSection::Ports => render_ports(frame, editing_script, selected_index, chunks[1]),
         ScriptSection::Volumes => render_volumes(frame, editing_script, selected_index, chunks[1]),
         ScriptSection::Network => render_network(frame, editing_script, chunks[1]),
@@ -96,6 +94,7 @@ fn render_editor(frame: &mut Frame, app: &App, area: Rect) {
 
 fn render_env_vars(
     frame: &mut Frame,
+    app: &App,
     script: &crate::models::DeploymentScript,
     selected: usize,
     editing: bool,
@@ -119,9 +118,7 @@ fn render_env_vars(
     let total_vars = script.env_vars.len();
 
     // Compute scroll_offset from selected index to keep selection visible
-    let scroll_offset = if visible_rows == 0 {
-        0
-    } else if selected < visible_rows / 2 {
+    let scroll_offset = if visible_rows == 0 || selected < visible_rows / 2 {
         0
     } else if selected >= total_vars.saturating_sub(visible_rows / 2) {
         total_vars.saturating_sub(visible_rows)
@@ -129,7 +126,7 @@ fn render_env_vars(
         selected.saturating_sub(visible_rows / 2)
     };
 
-    let header_cells = ["", "Key", "Value"]
+    let header_cells = ["", "Key", "Value", "Kept in"]
         .iter()
         .map(|h| Cell::from(*h).style(styles::style_header()));
     let header = Row::new(header_cells).height(1);
@@ -144,11 +141,17 @@ fn render_env_vars(
         .map(|(i, env)| {
             let actual_index = scroll_offset + i;
             let marker = if actual_index == selected { "▸" } else { " " };
-            let value_display = if env.is_secret {
+            let revealed = app.revealed_secrets.contains(&(script.path.clone(), env.key.clone()));
+            let value_display = if env.is_secret && !revealed {
                 "••••••••••••".to_string()
             } else {
                 env.value.clone()
             };
+            let storage_style = match env.storage {
+                EnvStorage::Inline if env.is_secret => styles::style_paused(),
+                EnvStorage::Inline => styles::style_muted(),
+                _ => styles::style_running(),
+            };
 
             let style = if actual_index == selected {
                 if editing {
@@ -164,6 +167,7 @@ fn render_env_vars(
                 Cell::from(marker),
                 Cell::from(env.key.clone()),
                 Cell::from(value_display),
+                Cell::from(env.storage.label()).style(storage_style),
             ])
             .style(style)
         })
@@ -185,7 +189,8 @@ fn render_env_vars(
         [
             Constraint::Length(2),
             Constraint::Percentage(30),
-            Constraint::Percentage(68),
+            Constraint::Percentage(52),
+            Constraint::Length(12),
         ],
     )
     .header(header)
@@ -221,9 +226,7 @@ fn render_ports(
     let visible_rows = area.height.saturating_sub(3) as usize;
     let total_ports = script.ports.len();
 
-    let scroll_offset = if visible_rows == 0 {
-        0
-    } else if selected < visible_rows / 2 {
+    let scroll_offset = if visible_rows == 0 || selected < visible_rows / 2 {
         0
     } else if selected >= total_ports.saturating_sub(visible_rows / 2) {
         total_ports.saturating_sub(visible_rows)
@@ -253,7 +256,7 @@ fn render_ports(
 
             Row::new(vec![
                 Cell::from(marker),
-                Cell::from(port.host_port.to_string()),
+                Cell::from(port.host_address()),
                 Cell::from(port.container_port.to_string()),
                 Cell::from(port.protocol.clone()),
             ])
@@ -308,9 +311,7 @@ fn render_volumes(
     let visible_rows = area.height.saturating_sub(3) as usize;
     let total_volumes = script.volumes.len();
 
-    let scroll_offset = if visible_rows == 0 {
-        0
-    } else if selected < visible_rows / 2 {
+    let scroll_offset = if visible_rows == 0 || selected < visible_rows / 2 {
         0
     } else if selected >= total_volumes.saturating_sub(visible_rows / 2) {
         total_volumes.saturating_sub(visible_rows)
@@ -376,14 +377,20 @@ fn render_volumes(
 
 fn render_network(frame: &mut Frame, script: &crate::models::DeploymentScript, area: Rect) {
     let network_display = script.network.clone().unwrap_or_else(|| "default".to_string());
+    let restart_display = script.restart_policy.as_deref().unwrap_or("no");
 
-    let content = vec![
+    let mut content = vec![
         Line::from(""),
         Line::from(vec![
             Span::styled("  Network: ", styles::style_header()),
             Span::styled(&network_display, styles::style_accent()),
         ]),
         Line::from(""),
+        Line::from(vec![
+            Span::styled("  Restart Policy: ", styles::style_header()),
+            Span::styled(restart_display, styles::style_accent()),
+        ]),
+        Line::from(""),
         Line::from(vec![
             Span::styled("  Repo: ", styles::style_header()),
             Span::styled(&script.repo, styles::style_default()),
@@ -395,7 +402,29 @@ fn render_network(frame: &mut Frame, script: &crate::models::DeploymentScript, a
         ]),
     ];
 
+    // Settings the editor does not change, shown so generated scripts can be checked
+    let labels: Vec<String> = script.labels.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    let health = script.healthcheck.as_ref().map(|h| h.command.clone());
+    let extras = [
+        ("Also connected to", script.extra_networks.join(", ")),
+        ("User", script.user.clone().unwrap_or_default()),
+        ("Entrypoint", script.entrypoint.clone().unwrap_or_default()),
+        ("Command", script.command.join(" ")),
+        ("Memory", script.memory.clone().unwrap_or_default()),
+        ("CPUs", script.cpus.clone().unwrap_or_default()),
+        ("Healthcheck", health.unwrap_or_default()),
+        ("Labels", labels.join(", ")),
+    ];
+    for (label, value) in extras.into_iter().filter(|(_, value)| !value.is_empty()) {
+        content.push(Line::from(""));
+        content.push(Line::from(vec![
+            Span::styled(format!("  {}: ", label), styles::style_header()),
+            Span::styled(value, styles::style_muted()),
+        ]));
+    }
+
     let paragraph = Paragraph::new(content)
+        .wrap(Wrap { trim: false })
         .block(Block::default().borders(Borders::ALL).title(" Network & Settings "));
 
     frame.render_widget(paragraph, area);
@@ -411,10 +440,10 @@ fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
         "[Esc] Cancel  [Enter] Save field"
     } else {
         match focused_section {
-            ScriptSection::EnvVars => "[a]dd [Enter]Edit [d]elete [Tab]Switch section [Ctrl+S]Save [Esc]Back",
-            ScriptSection::Ports => "[a]dd [d]elete [Tab]Switch section [Ctrl+S]Save [Esc]Back",
-            ScriptSection::Volumes => "[a]dd [d]elete [Tab]Switch section [Ctrl+S]Save [Esc]Back",
-            ScriptSection::Network => "[Tab]Switch section [Ctrl+S]Save [Esc]Back",
+            ScriptSection::EnvVars => "[a]dd [Enter]Edit [d]elete [m]ove secret [r]eveal [Tab]Switch section [Ctrl+S]Save [Esc]Back",
+            ScriptSection::Ports => "[a]dd [Enter]Edit [d]elete [Tab]Switch section [Ctrl+S]Save [Esc]Back",
+            ScriptSection::Volumes => "[a]dd [Enter]Edit [d]elete [Tab]Switch section [Ctrl+S]Save [Esc]Back",
+            ScriptSection::Network => "[Enter]Edit [d]Clear [Tab]Switch section [Ctrl+S]Save [Esc]Back",
         }
     };
 
@@ -432,9 +461,9 @@ fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(footer, area);
 }
 
-/// Render an input dialog overlay for adding/editing env vars
-pub fn render_env_var_dialog(frame: &mut Frame, app: &App) {
-    if let AppMode::EnvVarEditor { key_buffer, value_buffer, editing_key, is_new, .. } = &app.mode {
+/// Render an input dialog overlay for adding/editing an entry of a script section
+pub fn render_script_entry_dialog(frame: &mut Frame, app: &App) {
+    if let AppMode::ScriptEntryEditor { section, key_buffer, value_buffer, editing_key, is_new, .. } = &app.mode {
         let area = frame.area();
 
         // Center the dialog
@@ -448,7 +477,16 @@ pub fn render_env_var_dialog(frame: &mut Frame, app: &App) {
         // Clear the area first
         frame.render_widget(Clear, dialog_area);
 
-        let title = if *is_new { " Add Environment Variable " } else { " Edit Environment Variable " };
+        let title = format!(" {} {} ", if *is_new { "Add" } else { "Edit" }, section.entry_name());
+        let (key_label, value_label) = section.entry_fields();
+        let label_width = key_label.len().max(value_label.len()) + 1;
+        let hint = match section {
+            ScriptSection::Volumes => "  Append :ro to the container path for read-only",
+            ScriptSection::Ports => "  Container port may end in /udp or /sctp",
+            ScriptSection::Network => "  Leave a field empty to remove it",
+            ScriptSection::EnvVars if EnvVar::detect_secret(key_buffer) => "  Looks like a secret: [m] in the list moves it out of the script",
+            ScriptSection::EnvVars => "",
+        };
 
         let key_style = if *editing_key { styles::style_editing() } else { styles::style_default() };
         let value_style = if !*editing_key { styles::style_editing() } else { styles::style_default() };
@@ -456,17 +494,17 @@ pub fn render_env_var_dialog(frame: &mut Frame, app: &App) {
         let content = vec![
             Line::from(""),
             Line::from(vec![
-                Span::styled("  Key:   ", styles::style_header()),
+                Span::styled(format!("  {:<width$} ", format!("{}:", key_label), width = label_width), styles::style_header()),
                 Span::styled(key_buffer.as_str(), key_style),
                 if *editing_key { Span::styled("▏", styles::style_accent()) } else { Span::raw("") },
             ]),
             Line::from(""),
             Line::from(vec![
-                Span::styled("  Value: ", styles::style_header()),
+                Span::styled(format!("  {:<width$} ", format!("{}:", value_label), width = label_width), styles::style_header()),
                 Span::styled(value_buffer.as_str(), value_style),
                 if !*editing_key { Span::styled("▏", styles::style_accent()) } else { Span::raw("") },
             ]),
-            Line::from(""),
+            Line::from(Span::styled(hint, styles::style_muted())),
             Line::from(Span::styled("  [Tab] Switch field  [Enter] Save  [Esc] Cancel", styles::style_muted())),
         ];
 
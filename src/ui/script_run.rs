@@ -0,0 +1,183 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+
+use crate::app::App;
+use crate::models::AppMode;
+use crate::ssh::metadata::ScriptRun;
+use super::docker_styles as styles;
+
+/// Render the output of a deployment script run
+pub fn render_script_run_console(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+
+    let (host_index, container_name, run_index, scroll_offset, follow) = match &app.mode {
+        AppMode::ScriptRunConsole { host_index, container_name, run_index, scroll_offset, follow } => {
+            (*host_index, container_name, *run_index, *scroll_offset, *follow)
+        }
+        _ => return,
+    };
+
+    let chunks = Layout::vertical([
+        Constraint::Length(3),  // Header
+        Constraint::Min(0),     // Output
+        Constraint::Length(3),  // Footer
+    ])
+    .split(area);
+
+    let Some((run, elapsed)) = app.script_run_for(host_index, container_name, run_index) else {
+        let empty = Paragraph::new(Span::styled("No script runs recorded", styles::style_muted()))
+            .block(Block::default().borders(Borders::ALL));
+        frame.render_widget(empty, chunks[1]);
+        return;
+    };
+
+    // Header: what ran, and how it went
+    let (state, state_style) = match (elapsed, run.exit_code) {
+        (Some(secs), _) => (format!("Running {}", format_duration(secs)), styles::style_paused()),
+        (None, Some(0)) => (format!("Exit 0 after {}", format_duration(run.duration_secs)), styles::style_running()),
+        (None, Some(code)) => (format!("Failed: exit {} after {}", code, format_duration(run.duration_secs)), styles::style_error()),
+        (None, None) => (format!("Stopped after {}", format_duration(run.duration_secs)), styles::style_stopped()),
+    };
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(" Script Run ", styles::style_header()),
+        Span::styled("│ ", styles::style_muted()),
+        Span::styled(container_name.as_str(), styles::style_accent()),
+        Span::styled(" │ ", styles::style_muted()),
+        Span::styled(run.script_path.as_str(), styles::style_default()),
+        Span::styled(" │ ", styles::style_muted()),
+        Span::styled(state, state_style),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, chunks[0]);
+
+    // Output
+    let height = chunks[1].height.saturating_sub(2) as usize;
+    let total = run.log.len();
+    let start = if follow {
+        total.saturating_sub(height)
+    } else {
+        scroll_offset.min(total.saturating_sub(height))
+    };
+    let lines: Vec<Line> = run.log[start..(start + height).min(total)]
+        .iter()
+        .map(|line| {
+            let style = if line.stderr { styles::style_paused() } else { styles::style_default() };
+            Line::from(Span::styled(line.text.as_str(), style))
+        })
+        .collect();
+
+    let title = format!(
+        " {} {} ",
+        run.started.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+        if follow { "(following)" } else { "" }
+    );
+    let output = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+    frame.render_widget(output, chunks[1]);
+
+    // Footer
+    let mut help = String::from("[j/k] Scroll  [g/G] Top/Bottom  [f]ollow  [h]istory  ");
+    if elapsed.is_some() {
+        help.push_str("[K]ill  ");
+    }
+    help.push_str("[Esc] Back");
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled(help, styles::style_muted()),
+        Span::styled(format!("  Lines {}-{} of {} ", start + 1, (start + height).min(total), total), styles::style_accent()),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, chunks[2]);
+}
+
+/// Render the list of past runs of a container's deployment script
+pub fn render_script_run_history(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+
+    let (host_index, container_name, selected_index) = match &app.mode {
+        AppMode::ScriptRunHistory { host_index, container_name, selected_index } => {
+            (*host_index, container_name, *selected_index)
+        }
+        _ => return,
+    };
+
+    let chunks = Layout::vertical([
+        Constraint::Length(3),  // Header
+        Constraint::Min(0),     // Runs
+        Constraint::Length(3),  // Footer
+    ])
+    .split(area);
+
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(" Script Runs ", styles::style_header()),
+        Span::styled("│ ", styles::style_muted()),
+        Span::styled(container_name.as_str(), styles::style_accent()),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, chunks[0]);
+
+    let runs: Vec<(&ScriptRun, Option<u64>)> = app
+        .script_run_rows(host_index, container_name)
+        .into_iter()
+        .filter_map(|row| app.script_run_for(host_index, container_name, row))
+        .collect();
+
+    let header_cells = ["", "Started", "Result", "Duration", "Script", "Last line"]
+        .iter()
+        .map(|h| Cell::from(*h).style(styles::style_header()));
+    let rows: Vec<Row> = runs
+        .iter()
+        .enumerate()
+        .map(|(i, (run, elapsed))| {
+            let (result, result_style) = match (elapsed, run.exit_code) {
+                (Some(_), _) => ("running".to_string(), styles::style_paused()),
+                (None, Some(0)) => ("ok".to_string(), styles::style_running()),
+                (None, Some(code)) => (format!("exit {}", code), styles::style_error()),
+                (None, None) => ("stopped".to_string(), styles::style_stopped()),
+            };
+            let last_line = run.log.last().map(|l| l.text.clone()).unwrap_or_default();
+            let row_style = if i == selected_index { styles::style_selected() } else { styles::style_default() };
+            Row::new(vec![
+                Cell::from(if i == selected_index { "▸" } else { " " }),
+                Cell::from(run.started.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string()),
+                Cell::from(result).style(result_style),
+                Cell::from(format_duration(elapsed.unwrap_or(run.duration_secs))),
+                Cell::from(run.script_path.clone()).style(styles::style_muted()),
+                Cell::from(last_line).style(styles::style_muted()),
+            ])
+            .style(row_style)
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(2),
+            Constraint::Length(20),
+            Constraint::Length(9),
+            Constraint::Length(9),
+            Constraint::Percentage(30),
+            Constraint::Percentage(50),
+        ],
+    )
+    .header(Row::new(header_cells).height(1))
+    .block(Block::default().borders(Borders::ALL).title(format!(" {} runs ", runs.len())));
+    frame.render_widget(table, chunks[1]);
+
+    let footer = Paragraph::new(Line::from(Span::styled(
+        "[Enter] Show output  [j/k] Navigate  [Esc] Back",
+        styles::style_muted(),
+    )))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, chunks[2]);
+}
+
+fn format_duration(secs: u64) -> String {
+    if secs >= 60 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
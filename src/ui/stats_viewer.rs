@@ -68,7 +68,7 @@ fn render_stats(frame: &mut Frame, app: &App, area: Rect) {
         .block(Block::default().borders(Borders::ALL).title(" CPU Usage "))
         .gauge_style(styles::style_running())
         .percent(cpu_percent.min(100.0) as u16)
-        .label(format!("{}", stats.cpu_percent));
+        .label(stats.cpu_percent.to_string());
     frame.render_widget(cpu_gauge, chunks[1]);
 
     // Memory Usage
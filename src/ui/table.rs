@@ -113,9 +113,7 @@ fn render_host_table(frame: &mut Frame, app: &App, area: Rect) {
     let selected = app.selected_index;
 
     // Compute scroll_offset to keep selection visible
-    let scroll_offset = if visible_rows == 0 {
-        0
-    } else if selected < visible_rows / 2 {
+    let scroll_offset = if visible_rows == 0 || selected < visible_rows / 2 {
         0
     } else if selected >= total_hosts.saturating_sub(visible_rows / 2) {
         total_hosts.saturating_sub(visible_rows)
@@ -172,8 +170,19 @@ fn render_host_table(frame: &mut Frame, app: &App, area: Rect) {
                 host.tags.join(", ")
             };
 
+            // The source picked for a transfer between two hosts
+            let host_cell = if app.rsync_source_host.as_deref() == Some(host.host.as_str()) {
+                Cell::from(format!("{} [source]", host.host)).style(if is_selected {
+                    base_style
+                } else {
+                    Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)
+                })
+            } else {
+                Cell::from(host.host.clone())
+            };
+
             let cells = vec![
-                Cell::from(host.host.clone()),
+                host_cell,
                 Cell::from(host.hostname.clone()),
                 Cell::from(host.user.clone().unwrap_or_else(|| "-".to_string())),
                 Cell::from(host.effective_port().to_string()),
@@ -230,7 +239,11 @@ fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
     let keybindings = vec![
         ("Space", "Connect"),
         ("d", "Docker"),
-        if app.rsync_available { ("r", "Rsync") } else { ("r", "Rsync (disabled)") },
+        ("S", "Docker settings"),
+        if app.rsync_available { ("r", "Rsync") } else { ("r", "Rsync (sftp/scp)") },
+        ("P", "Rsync profiles"),
+        ("f", "Files"),
+        ("R", "Host to host"),
         ("n", "New"),
         ("e", "Edit"),
         ("D", "Delete"),
@@ -247,12 +260,7 @@ fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
             footer_spans.push(Span::raw(" │ "));
         }
 
-        // Grey out rsync keybinding if not available
-        let key_style = if !app.rsync_available && *key == "r" {
-            Style::default().fg(Color::DarkGray)
-        } else {
-            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
-        };
+        let key_style = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
 
         footer_spans.push(Span::styled(*key, key_style));
         footer_spans.push(Span::raw(":"));
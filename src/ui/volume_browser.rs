@@ -0,0 +1,123 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::models::AppMode;
+use super::docker_styles as styles;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+
+    let (volume, current_path, entries, selected_index, loading, preview, preview_scroll) = match &app.mode {
+        AppMode::VolumeBrowser { volume, current_path, entries, selected_index, loading, preview, preview_scroll, .. } => {
+            (volume, current_path, entries, *selected_index, *loading, preview, *preview_scroll)
+        }
+        _ => return,
+    };
+
+    let chunks = Layout::vertical([
+        Constraint::Length(3),  // Header with path
+        Constraint::Min(0),     // Listing or file preview
+        Constraint::Length(3),  // Footer
+    ])
+    .split(area);
+
+    // Header
+    let selected_name = entries.get(selected_index).map(|e| e.name.as_str()).unwrap_or("");
+    let shown_path = match preview {
+        Some(_) if current_path.is_empty() => format!("/{}", selected_name),
+        Some(_) => format!("/{}/{}", current_path, selected_name),
+        None => format!("/{}", current_path),
+    };
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(" Volume: ", styles::style_muted()),
+        Span::styled(volume.as_str(), styles::style_accent()),
+        Span::styled(" │ ", styles::style_muted()),
+        Span::styled(shown_path, styles::style_default()),
+        Span::styled("  (read-only)", styles::style_muted()),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, chunks[0]);
+
+    // Content
+    if let Some(lines) = preview {
+        render_preview(frame, lines, preview_scroll, chunks[1]);
+    } else if loading {
+        let loading_msg = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled("Loading...", styles::style_status())),
+        ])
+        .block(Block::default().borders(Borders::ALL).title(" Files "))
+        .alignment(Alignment::Center);
+        frame.render_widget(loading_msg, chunks[1]);
+    } else {
+        let visible_rows = chunks[1].height.saturating_sub(2) as usize;
+        let total_entries = entries.len();
+        let scroll_offset = if visible_rows == 0 || selected_index < visible_rows / 2 {
+            0
+        } else if selected_index >= total_entries.saturating_sub(visible_rows / 2) {
+            total_entries.saturating_sub(visible_rows)
+        } else {
+            selected_index.saturating_sub(visible_rows / 2)
+        };
+        let end_index = (scroll_offset + visible_rows).min(total_entries);
+
+        let items: Vec<ListItem> = entries[scroll_offset..end_index]
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let actual_index = scroll_offset + i;
+                let marker = if actual_index == selected_index { "▸ " } else { "  " };
+                let (icon, style) = if entry.is_dir {
+                    ("📁 ", styles::style_accent())
+                } else {
+                    ("   ", styles::style_default())
+                };
+                let line_style = if actual_index == selected_index {
+                    styles::style_selected()
+                } else {
+                    style
+                };
+                ListItem::new(Line::from(vec![
+                    Span::raw(marker),
+                    Span::styled(icon, style),
+                    Span::styled(&entry.name, line_style),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(format!(" Files ({}) ", total_entries)));
+        frame.render_widget(list, chunks[1]);
+    }
+
+    // Footer
+    let help = if preview.is_some() {
+        "[j/k] Scroll  [g/G] Top/Bottom  [Esc] Close file"
+    } else {
+        "[Enter] Open  [j/k] Navigate  [Esc] Back"
+    };
+    let (message, style) = if let Some(ref err) = app.error_message {
+        (err.clone(), styles::style_error())
+    } else {
+        (help.to_string(), styles::style_muted())
+    };
+    let footer = Paragraph::new(Line::from(Span::styled(message, style)))
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, chunks[2]);
+}
+
+fn render_preview(frame: &mut Frame, lines: &[String], scroll_offset: usize, area: Rect) {
+    let visible_height = area.height.saturating_sub(2) as usize;
+    let start = scroll_offset.min(lines.len().saturating_sub(visible_height));
+    let end = (start + visible_height).min(lines.len());
+
+    let visible: Vec<Line> = lines[start..end].iter().map(|l| Line::from(l.as_str())).collect();
+    let title = format!(" Lines {}-{} of {} ", if lines.is_empty() { 0 } else { start + 1 }, end, lines.len());
+    let preview = Paragraph::new(visible).block(Block::default().borders(Borders::ALL).title(title));
+    frame.render_widget(preview, area);
+}
@@ -0,0 +1,217 @@
+//! Line-based unified diff, used to review script changes before writing them
+
+/// Lines of context kept around each change
+const CONTEXT: usize = 3;
+
+/// Above this many line pairs the diff falls back to replacing everything
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    FileHeader,
+    HunkHeader,
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub text: String,
+}
+
+impl DiffLine {
+    fn new(kind: DiffKind, text: impl Into<String>) -> Self {
+        Self { kind, text: text.into() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Unified diff of `old` against `new`; empty when they are identical
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let ops = edit_script(&a, &b);
+    if ops.iter().all(|op| *op == Op::Equal) {
+        return Vec::new();
+    }
+
+    let mut out = vec![
+        DiffLine::new(DiffKind::FileHeader, format!("--- {}", old_label)),
+        DiffLine::new(DiffKind::FileHeader, format!("+++ {}", new_label)),
+    ];
+
+    // Position in a and b before each op
+    let mut positions = Vec::with_capacity(ops.len() + 1);
+    let (mut i, mut j) = (0, 0);
+    for op in &ops {
+        positions.push((i, j));
+        match op {
+            Op::Equal => {
+                i += 1;
+                j += 1;
+            }
+            Op::Delete => i += 1,
+            Op::Insert => j += 1,
+        }
+    }
+    positions.push((i, j));
+
+    // Group changes that are close together into hunks
+    let changes: Vec<usize> = (0..ops.len()).filter(|&k| ops[k] != Op::Equal).collect();
+    let mut hunk_start = 0;
+    while hunk_start < changes.len() {
+        let mut hunk_end = hunk_start;
+        while hunk_end + 1 < changes.len() && changes[hunk_end + 1] - changes[hunk_end] <= 2 * CONTEXT + 1 {
+            hunk_end += 1;
+        }
+
+        let from = changes[hunk_start].saturating_sub(CONTEXT);
+        let to = (changes[hunk_end] + CONTEXT + 1).min(ops.len());
+        let (a_start, b_start) = positions[from];
+        let (a_end, b_end) = positions[to];
+        out.push(DiffLine::new(
+            DiffKind::HunkHeader,
+            format!(
+                "@@ -{} +{} @@",
+                hunk_range(a_start, a_end - a_start),
+                hunk_range(b_start, b_end - b_start)
+            ),
+        ));
+
+        for k in from..to {
+            let (i, j) = positions[k];
+            out.push(match ops[k] {
+                Op::Equal => DiffLine::new(DiffKind::Context, a[i]),
+                Op::Delete => DiffLine::new(DiffKind::Removed, a[i]),
+                Op::Insert => DiffLine::new(DiffKind::Added, b[j]),
+            });
+        }
+
+        hunk_start = hunk_end + 1;
+    }
+
+    out
+}
+
+/// `start,len` as written in hunk headers (1-based; an empty range names the line before it)
+fn hunk_range(start: usize, len: usize) -> String {
+    match len {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, len),
+    }
+}
+
+/// Shortest edit script via longest common subsequence, deletions before insertions
+fn edit_script(a: &[&str], b: &[&str]) -> Vec<Op> {
+    // Common prefix and suffix never need the table
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut ops = vec![Op::Equal; prefix];
+    let (n, m) = (a_mid.len(), b_mid.len());
+
+    if n * m > MAX_DIFF_CELLS {
+        ops.extend(std::iter::repeat_n(Op::Delete, n));
+        ops.extend(std::iter::repeat_n(Op::Insert, m));
+    } else {
+        // lcs[i][j] = LCS length of a_mid[i..] and b_mid[j..]
+        let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if a_mid[i] == b_mid[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && a_mid[i] == b_mid[j] {
+                ops.push(Op::Equal);
+                i += 1;
+                j += 1;
+            } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+                ops.push(Op::Delete);
+                i += 1;
+            } else {
+                ops.push(Op::Insert);
+                j += 1;
+            }
+        }
+    }
+
+    ops.extend(std::iter::repeat_n(Op::Equal, suffix));
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(lines: &[DiffLine]) -> String {
+        lines
+            .iter()
+            .map(|line| match line.kind {
+                DiffKind::FileHeader | DiffKind::HunkHeader => line.text.clone(),
+                DiffKind::Context => format!(" {}", line.text),
+                DiffKind::Added => format!("+{}", line.text),
+                DiffKind::Removed => format!("-{}", line.text),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_identical_is_empty() {
+        assert!(unified_diff("a\nb\n", "a\nb\n", "old", "new").is_empty());
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\n15\n";
+        let new = "1\n2\nthree\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n15\n16\n";
+        let expected = "\
+--- a.sh
++++ a.sh (new)
+@@ -1,6 +1,6 @@
+ 1
+ 2
+-3
++three
+ 4
+ 5
+ 6
+@@ -11,5 +11,5 @@
+ 11
+ 12
+ 13
+-14
+ 15
++16";
+        assert_eq!(render(&unified_diff(old, new, "a.sh", "a.sh (new)")), expected);
+    }
+
+    #[test]
+    fn test_new_file() {
+        let diff = unified_diff("", "a\nb\n", "old", "new");
+        assert_eq!(diff[2].text, "@@ -0,0 +1,2 @@");
+        assert_eq!(diff.len(), 5);
+    }
+}
@@ -2,7 +2,7 @@ use anyhow::Result;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
 
 use crate::app::App;
-use crate::models::{AppMode, HostField, ScriptSection};
+use crate::models::{AppMode, ComposeAction, ComposeView, FilePrompt, DockerSettingsField, HostField, ScriptSection};
 
 /// Handle keyboard input based on current app mode (with timeout for non-blocking)
 pub fn handle_input(app: &mut App) -> Result<()> {
@@ -29,8 +29,14 @@ pub fn handle_input(app: &mut App) -> Result<()> {
             }
             AppMode::SelectSshFlags { .. } => handle_ssh_flags_selection_input(app, key)?,
             AppMode::SelectShell { .. } => handle_shell_selection_input(app, key)?,
+            AppMode::DockerSettingsForm { .. } => handle_docker_settings_input(app, key)?,
             AppMode::Rsync { .. } => handle_rsync_input(app, key)?,
             AppMode::RsyncFileBrowser { .. } => handle_rsync_file_browser_input(app, key)?,
+            AppMode::RsyncReview { .. } => handle_rsync_review_input(app, key)?,
+            AppMode::RsyncProgress { .. } => handle_rsync_progress_input(app, key)?,
+            AppMode::RsyncProfiles { .. } => handle_rsync_profiles_input(app, key)?,
+            AppMode::FileManager { .. } => handle_file_manager_input(app, key)?,
+            AppMode::FilePreview { .. } => handle_file_preview_input(app, key)?,
 
             // Docker modes
             AppMode::ContainerList { .. } => handle_container_list_input(app, key)?,
@@ -42,8 +48,24 @@ pub fn handle_input(app: &mut App) -> Result<()> {
             AppMode::EnvInspector { .. } => handle_env_inspector_input(app, key)?,
             AppMode::ScriptViewer { .. } => handle_script_viewer_input(app, key)?,
             AppMode::ScriptEdit { .. } => handle_script_edit_input(app, key)?,
-            AppMode::EnvVarEditor { .. } => handle_env_var_editor_input(app, key)?,
+            AppMode::ScriptEntryEditor { .. } => handle_script_entry_editor_input(app, key)?,
+            AppMode::ScriptDiff { .. } => handle_script_diff_input(app, key)?,
+            AppMode::ScriptBackups { .. } => handle_script_backups_input(app, key)?,
+            AppMode::ScriptRunConsole { .. } => handle_script_run_console_input(app, key)?,
+            AppMode::ScriptRunHistory { .. } => handle_script_run_history_input(app, key)?,
             AppMode::FileBrowser { .. } => handle_file_browser_input(app, key)?,
+            AppMode::ExecUserPrompt { .. } => handle_exec_user_prompt_input(app, key)?,
+            AppMode::ExecCommandPrompt { .. } => handle_exec_command_prompt_input(app, key)?,
+            AppMode::ExecOutput { .. } => handle_exec_output_input(app, key)?,
+            AppMode::ImageList { .. } => handle_image_list_input(app, key)?,
+            AppMode::VolumeList { .. } | AppMode::NetworkList { .. } => handle_resource_list_input(app, key)?,
+            AppMode::VolumeBrowser { .. } => handle_volume_browser_input(app, key)?,
+            AppMode::CreateResourcePrompt { .. } => handle_create_resource_input(app, key)?,
+            AppMode::ComposeProjects { .. } => handle_compose_projects_input(app, key)?,
+            AppMode::ComposeOutput { .. } => handle_compose_output_input(app, key)?,
+            AppMode::ComposeExport { .. } => handle_compose_export_input(app, key)?,
+            AppMode::DriftReport { .. } => handle_drift_report_input(app, key)?,
+            AppMode::ComposeImport { .. } => handle_compose_import_input(app, key)?,
         }
     }
 
@@ -67,17 +89,15 @@ fn handle_table_input(app: &mut App, key: KeyEvent) -> Result<()> {
         KeyCode::Char(' ') | KeyCode::Enter => {
             app.connect_to_selected()?;
         }
-        KeyCode::Char('r') => {
-            if app.rsync_available {
-                app.start_rsync();
-            } else {
-                app.set_error("rsync is not installed on this system");
-            }
-        }
+        KeyCode::Char('r') => app.start_rsync(),
+        KeyCode::Char('R') => app.pick_rsync_host(),
+        KeyCode::Char('P') => app.start_rsync_profiles(),
+        KeyCode::Char('f') => app.start_file_manager(),
         KeyCode::Char('n') => app.start_new_host(),
         KeyCode::Char('e') => app.start_edit_host(),
         KeyCode::Char('D') => app.start_delete_host(),
         KeyCode::Char('d') => app.start_docker_mode(),
+        KeyCode::Char('S') => app.start_docker_settings(),
 
         // Filters
         KeyCode::Char('/') => app.start_search(),
@@ -90,6 +110,7 @@ fn handle_table_input(app: &mut App, key: KeyEvent) -> Result<()> {
         KeyCode::Char('?') => app.show_help(),
 
         // Quit
+        KeyCode::Esc if app.rsync_source_host.is_some() => app.cancel_rsync_host_pick(),
         KeyCode::Char('q') | KeyCode::Esc => app.quit(),
 
         _ => {}
@@ -98,6 +119,61 @@ fn handle_table_input(app: &mut App, key: KeyEvent) -> Result<()> {
     Ok(())
 }
 
+/// Handle input in the Docker settings form
+fn handle_docker_settings_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    if let AppMode::DockerSettingsForm { settings, focused_field, field_buffer, editing_mode, .. } = &mut app.mode {
+        if *editing_mode {
+            match key.code {
+                KeyCode::Enter | KeyCode::Tab => match focused_field.apply(settings, field_buffer) {
+                    Ok(()) => {
+                        *editing_mode = false;
+                        if key.code == KeyCode::Tab {
+                            *focused_field = focused_field.next();
+                            *field_buffer = focused_field.value(settings);
+                        }
+                    }
+                    Err(e) => app.error_message = Some(e),
+                },
+                KeyCode::Esc => {
+                    *field_buffer = focused_field.value(settings);
+                    *editing_mode = false;
+                }
+                KeyCode::Char(c) => field_buffer.push(c),
+                KeyCode::Backspace => {
+                    field_buffer.pop();
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down | KeyCode::Tab => {
+                *focused_field = focused_field.next();
+                *field_buffer = focused_field.value(settings);
+            }
+            KeyCode::Char('k') | KeyCode::Up | KeyCode::BackTab => {
+                *focused_field = focused_field.previous();
+                *field_buffer = focused_field.value(settings);
+            }
+            KeyCode::Enter | KeyCode::Char(' ') if *focused_field == DockerSettingsField::Sudo => {
+                // ask -> always -> never
+                settings.use_sudo = match settings.use_sudo {
+                    None => Some(true),
+                    Some(true) => Some(false),
+                    Some(false) => None,
+                };
+                *field_buffer = focused_field.value(settings);
+            }
+            KeyCode::Enter => *editing_mode = true,
+            KeyCode::Char('s') => app.save_docker_settings()?,
+            KeyCode::Esc | KeyCode::Char('q') => app.mode = AppMode::Table,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
 /// Handle input in edit host view
 fn handle_edit_input(app: &mut App, key: KeyEvent) -> Result<()> {
     if let AppMode::EditHost {
@@ -338,27 +414,21 @@ fn handle_key_selection_input(app: &mut App, key: KeyEvent) -> Result<()> {
     } = &mut app.mode
     {
         match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
-                if *selected_key_index > 0 {
-                    *selected_key_index -= 1;
-                }
+            KeyCode::Up | KeyCode::Char('k') if *selected_key_index > 0 => {
+                *selected_key_index -= 1;
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                if *selected_key_index < available_keys.len().saturating_sub(1) {
-                    *selected_key_index += 1;
-                }
+            KeyCode::Down | KeyCode::Char('j') if *selected_key_index < available_keys.len().saturating_sub(1) => {
+                *selected_key_index += 1;
             }
-            KeyCode::Char(' ') | KeyCode::Enter => {
+            KeyCode::Char(' ') | KeyCode::Enter if *selected_key_index < available_keys.len() => {
                 // Toggle key selection
-                if *selected_key_index < available_keys.len() {
-                    let key = available_keys[*selected_key_index].clone();
-                    let identity_files = editing_host.identity_file.get_or_insert_with(Vec::new);
+                let key = available_keys[*selected_key_index].clone();
+                let identity_files = editing_host.identity_file.get_or_insert_with(Vec::new);
 
-                    if identity_files.contains(&key) {
-                        identity_files.retain(|k| k != &key);
-                    } else {
-                        identity_files.push(key);
-                    }
+                if identity_files.contains(&key) {
+                    identity_files.retain(|k| k != &key);
+                } else {
+                    identity_files.push(key);
                 }
             }
             KeyCode::Esc => {
@@ -423,27 +493,21 @@ fn handle_tag_edit_input(app: &mut App, key: KeyEvent) -> Result<()> {
         } else {
             // SELECTION MODE: toggling tags on/off for this host
             match key.code {
-                KeyCode::Up | KeyCode::Char('k') => {
-                    if !all_available_tags.is_empty() && *selected_tag_index > 0 {
-                        *selected_tag_index -= 1;
-                    }
+                KeyCode::Up | KeyCode::Char('k') if !all_available_tags.is_empty() && *selected_tag_index > 0 => {
+                    *selected_tag_index -= 1;
                 }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    if *selected_tag_index < all_available_tags.len().saturating_sub(1) {
-                        *selected_tag_index += 1;
-                    }
+                KeyCode::Down | KeyCode::Char('j') if *selected_tag_index < all_available_tags.len().saturating_sub(1) => {
+                    *selected_tag_index += 1;
                 }
-                KeyCode::Char(' ') | KeyCode::Enter => {
+                KeyCode::Char(' ') | KeyCode::Enter if *selected_tag_index < all_available_tags.len() => {
                     // Toggle tag on/off for this host
-                    if *selected_tag_index < all_available_tags.len() {
-                        let tag = all_available_tags[*selected_tag_index].clone();
-                        if editing_host.tags.contains(&tag) {
-                            // Remove tag from this host
-                            editing_host.tags.retain(|t| t != &tag);
-                        } else {
-                            // Add tag to this host
-                            editing_host.tags.push(tag);
-                        }
+                    let tag = all_available_tags[*selected_tag_index].clone();
+                    if editing_host.tags.contains(&tag) {
+                        // Remove tag from this host
+                        editing_host.tags.retain(|t| t != &tag);
+                    } else {
+                        // Add tag to this host
+                        editing_host.tags.push(tag);
                     }
                 }
                 KeyCode::Char('a') | KeyCode::Char('n') | KeyCode::Char('i') => {
@@ -538,26 +602,20 @@ fn handle_ssh_flags_selection_input(app: &mut App, key: KeyEvent) -> Result<()>
     } = &mut app.mode
     {
         match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
-                if *selected_flag_index > 0 {
-                    *selected_flag_index -= 1;
-                }
+            KeyCode::Up | KeyCode::Char('k') if *selected_flag_index > 0 => {
+                *selected_flag_index -= 1;
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                if *selected_flag_index < available_flags.len().saturating_sub(1) {
-                    *selected_flag_index += 1;
-                }
+            KeyCode::Down | KeyCode::Char('j') if *selected_flag_index < available_flags.len().saturating_sub(1) => {
+                *selected_flag_index += 1;
             }
-            KeyCode::Char(' ') | KeyCode::Enter => {
+            KeyCode::Char(' ') | KeyCode::Enter if *selected_flag_index < available_flags.len() => {
                 // Toggle flag selection
-                if *selected_flag_index < available_flags.len() {
-                    let flag = available_flags[*selected_flag_index].flag.to_string();
+                let flag = available_flags[*selected_flag_index].flag.to_string();
 
-                    if editing_host.ssh_flags.contains(&flag) {
-                        editing_host.ssh_flags.retain(|f| f != &flag);
-                    } else {
-                        editing_host.ssh_flags.push(flag);
-                    }
+                if editing_host.ssh_flags.contains(&flag) {
+                    editing_host.ssh_flags.retain(|f| f != &flag);
+                } else {
+                    editing_host.ssh_flags.push(flag);
                 }
             }
             KeyCode::Esc => {
@@ -588,27 +646,21 @@ fn handle_shell_selection_input(app: &mut App, key: KeyEvent) -> Result<()> {
     } = &mut app.mode
     {
         match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
-                if *selected_shell_index > 0 {
-                    *selected_shell_index -= 1;
-                }
+            KeyCode::Up | KeyCode::Char('k') if *selected_shell_index > 0 => {
+                *selected_shell_index -= 1;
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                if *selected_shell_index < available_shells.len().saturating_sub(1) {
-                    *selected_shell_index += 1;
-                }
+            KeyCode::Down | KeyCode::Char('j') if *selected_shell_index < available_shells.len().saturating_sub(1) => {
+                *selected_shell_index += 1;
             }
-            KeyCode::Char(' ') | KeyCode::Enter => {
+            KeyCode::Char(' ') | KeyCode::Enter if *selected_shell_index < available_shells.len() => {
                 // Select shell (single selection)
-                if *selected_shell_index < available_shells.len() {
-                    let shell = available_shells[*selected_shell_index].name.to_string();
+                let shell = available_shells[*selected_shell_index].name.to_string();
 
-                    // Toggle - if already selected, deselect it
-                    if editing_host.shell.as_ref() == Some(&shell) {
-                        editing_host.shell = None;
-                    } else {
-                        editing_host.shell = Some(shell);
-                    }
+                // Toggle - if already selected, deselect it
+                if editing_host.shell.as_ref() == Some(&shell) {
+                    editing_host.shell = None;
+                } else {
+                    editing_host.shell = Some(shell);
                 }
             }
             KeyCode::Esc => {
@@ -694,8 +746,6 @@ fn autocomplete_path(partial_path: &str) -> Option<String> {
 
 /// Autocomplete a remote path using SSH to list remote directory
 fn autocomplete_path_remote(host: &crate::models::Host, partial_path: &str) -> Option<String> {
-    use std::process::Command;
-
     // Handle empty path
     if partial_path.is_empty() {
         return None;
@@ -718,30 +768,12 @@ fn autocomplete_path_remote(host: &crate::models::Host, partial_path: &str) -> O
         }
     };
 
-    // Build SSH command
-    let mut cmd = Command::new("ssh");
-
-    // Add SSH options
-    if let Some(ref user) = host.user {
-        cmd.arg(format!("{}@{}", user, host.hostname));
-    } else {
-        cmd.arg(&host.hostname);
-    }
-
-    if let Some(port) = host.port {
-        cmd.arg("-p").arg(port.to_string());
-    }
-
-    if let Some(ref identity_files) = host.identity_file {
-        for file in identity_files {
-            cmd.arg("-i").arg(file);
-        }
-    }
-
-    cmd.arg("-o").arg("StrictHostKeyChecking=no");
+    // Build SSH command, with the same options rsync uses to reach the host
+    let mut cmd = crate::ssh::executor::transport_ssh_command(host);
+    cmd.arg(&host.hostname);
 
     // Execute `ls -1` on the remote directory
-    cmd.arg(format!("ls -1 '{}'", dir));
+    cmd.arg(format!("ls -1 {}", crate::utils::shell::quote_path(&dir)));
 
     // Execute SSH and capture output
     match cmd.output() {
@@ -793,6 +825,9 @@ fn autocomplete_path_remote(host: &crate::models::Host, partial_path: &str) -> O
 
 /// Handle input in rsync mode
 fn handle_rsync_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    use crate::models::app_state::RsyncField;
+
+    let mut error = None;
     if let AppMode::Rsync {
         editing_mode,
         focused_field,
@@ -801,24 +836,57 @@ fn handle_rsync_input(app: &mut App, key: KeyEvent) -> Result<()> {
         sync_to_host,
         editing_host,
         compress,
+        options,
+        field_buffer,
+        profile_name,
+        dest_host,
         ..
     } = &mut app.mode
     {
-        if *editing_mode {
+        // Typing the name to save the form under
+        if let Some(name) = profile_name {
+            match key.code {
+                KeyCode::Char(c) => name.push(c),
+                KeyCode::Backspace => {
+                    name.pop();
+                }
+                KeyCode::Enter => app.save_rsync_profile(),
+                KeyCode::Esc => *profile_name = None,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        let is_path = matches!(focused_field, RsyncField::SourcePath | RsyncField::DestPath);
+        if *editing_mode && !is_path {
+            // Editing an option's text; it is checked when Enter stores it
+            match key.code {
+                KeyCode::Char(c) => field_buffer.push(c),
+                KeyCode::Backspace => {
+                    field_buffer.pop();
+                }
+                KeyCode::Enter => match focused_field.apply(options, field_buffer) {
+                    Ok(()) => *editing_mode = false,
+                    Err(e) => error = Some(e),
+                },
+                KeyCode::Esc => *editing_mode = false,
+                _ => {}
+            }
+        } else if *editing_mode {
             // In editing mode - typing into a field
             match key.code {
                 KeyCode::Char(c) => {
                     match focused_field {
-                        crate::models::app_state::RsyncField::SourcePath => source_path.push(c),
-                        crate::models::app_state::RsyncField::DestPath => dest_path.push(c),
+                        RsyncField::SourcePath => source_path.push(c),
+                        _ => dest_path.push(c),
                     }
                 }
                 KeyCode::Backspace => {
                     match focused_field {
-                        crate::models::app_state::RsyncField::SourcePath => {
+                        RsyncField::SourcePath => {
                             source_path.pop();
                         }
-                        crate::models::app_state::RsyncField::DestPath => {
+                        _ => {
                             dest_path.pop();
                         }
                     }
@@ -826,9 +894,9 @@ fn handle_rsync_input(app: &mut App, key: KeyEvent) -> Result<()> {
                 KeyCode::Tab => {
                     // Autocomplete in path fields (dynamically choose local or remote)
                     match focused_field {
-                        crate::models::app_state::RsyncField::SourcePath => {
+                        RsyncField::SourcePath => {
                             // Source is remote if sync_to_host is false, local if true
-                            if *sync_to_host {
+                            if *sync_to_host && dest_host.is_none() {
                                 // Source is local
                                 if let Some(completed) = autocomplete_path(source_path) {
                                     *source_path = completed;
@@ -840,9 +908,14 @@ fn handle_rsync_input(app: &mut App, key: KeyEvent) -> Result<()> {
                                 }
                             }
                         }
-                        crate::models::app_state::RsyncField::DestPath => {
+                        _ => {
                             // Dest is remote if sync_to_host is true, local if false
-                            if *sync_to_host {
+                            // (on the other host in a transfer between two hosts)
+                            if let Some(dest_host) = dest_host {
+                                if let Some(completed) = autocomplete_path_remote(dest_host, dest_path) {
+                                    *dest_path = completed;
+                                }
+                            } else if *sync_to_host {
                                 // Dest is remote
                                 if let Some(completed) = autocomplete_path_remote(editing_host, dest_path) {
                                     *dest_path = completed;
@@ -857,16 +930,12 @@ fn handle_rsync_input(app: &mut App, key: KeyEvent) -> Result<()> {
                     }
                 }
                 KeyCode::Enter => {
-                    // Move to next field and exit edit mode
+                    // Move to the other path and exit edit mode
                     *editing_mode = false;
-                    match focused_field {
-                        crate::models::app_state::RsyncField::SourcePath => {
-                            *focused_field = crate::models::app_state::RsyncField::DestPath;
-                        }
-                        crate::models::app_state::RsyncField::DestPath => {
-                            *focused_field = crate::models::app_state::RsyncField::SourcePath;
-                        }
-                    }
+                    *focused_field = match focused_field {
+                        RsyncField::SourcePath => RsyncField::DestPath,
+                        _ => RsyncField::SourcePath,
+                    };
                 }
                 KeyCode::Esc => {
                     // Exit edit mode without saving
@@ -877,25 +946,20 @@ fn handle_rsync_input(app: &mut App, key: KeyEvent) -> Result<()> {
         } else {
             // Not in editing mode - navigate between fields or toggle settings
             match key.code {
-                KeyCode::Char('j') | KeyCode::Down => {
-                    *focused_field = match focused_field {
-                        crate::models::app_state::RsyncField::SourcePath => crate::models::app_state::RsyncField::DestPath,
-                        crate::models::app_state::RsyncField::DestPath => crate::models::app_state::RsyncField::SourcePath,
-                    };
-                }
-                KeyCode::Char('k') | KeyCode::Up => {
-                    *focused_field = match focused_field {
-                        crate::models::app_state::RsyncField::SourcePath => crate::models::app_state::RsyncField::DestPath,
-                        crate::models::app_state::RsyncField::DestPath => crate::models::app_state::RsyncField::SourcePath,
-                    };
-                }
+                KeyCode::Char('j') | KeyCode::Down => *focused_field = focused_field.next(),
+                KeyCode::Char('k') | KeyCode::Up => *focused_field = focused_field.previous(),
                 KeyCode::Char('i') | KeyCode::Enter => {
-                    // Enter edit mode
-                    *editing_mode = true;
+                    if focused_field.is_toggle() {
+                        focused_field.toggle(options);
+                    } else {
+                        // Enter edit mode
+                        *field_buffer = focused_field.value(options);
+                        *editing_mode = true;
+                    }
                 }
                 KeyCode::Char('r') => {
                     // Toggle direction
-                    *sync_to_host = !*sync_to_host;
+                    app.toggle_rsync_direction();
                 }
                 KeyCode::Char('z') => {
                     // Toggle compression flag
@@ -905,20 +969,17 @@ fn handle_rsync_input(app: &mut App, key: KeyEvent) -> Result<()> {
                     // Browse for file/directory
                     app.start_rsync_browse();
                 }
+                KeyCode::Char('I') => {
+                    // Exclude what the source's .gitignore ignores
+                    app.import_rsync_gitignore();
+                }
+                KeyCode::Char('s') => {
+                    // Save the form as a profile
+                    app.start_rsync_profile_save();
+                }
                 KeyCode::Char(' ') => {
-                    // Execute rsync - check if paths are filled
-                    if source_path.is_empty() || dest_path.is_empty() {
-                        app.set_error("Both source and destination paths required");
-                    } else {
-                        // Set pending rsync (main loop will handle execution)
-                        app.pending_rsync = Some((
-                            editing_host.clone(),
-                            source_path.clone(),
-                            dest_path.clone(),
-                            *sync_to_host,
-                            *compress,
-                        ));
-                    }
+                    // Dry run first; the real run starts from the review
+                    app.start_rsync_review();
                 }
                 KeyCode::Esc | KeyCode::Char('q') => {
                     // Return to table
@@ -928,7 +989,172 @@ fn handle_rsync_input(app: &mut App, key: KeyEvent) -> Result<()> {
             }
         }
     }
+    if let Some(e) = error {
+        app.set_error(e);
+    }
+
+    Ok(())
+}
 
+/// Handle input in the rsync dry-run review
+fn handle_rsync_review_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    if let AppMode::RsyncReview { changes, selected_index, .. } = &mut app.mode {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down if *selected_index + 1 < changes.len() => *selected_index += 1,
+            KeyCode::Char('k') | KeyCode::Up => *selected_index = selected_index.saturating_sub(1),
+            KeyCode::Char('g') => *selected_index = 0,
+            KeyCode::Char('G') => *selected_index = changes.len().saturating_sub(1),
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                *selected_index = (*selected_index + 10).min(changes.len().saturating_sub(1));
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                *selected_index = selected_index.saturating_sub(10);
+            }
+            KeyCode::Char(' ') | KeyCode::Char('x') => app.toggle_rsync_exclude(),
+            KeyCode::Enter | KeyCode::Char('y') => app.confirm_rsync_review(),
+            KeyCode::Esc | KeyCode::Char('q') => app.cancel_rsync_review(),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Handle input in the list of a host's rsync profiles
+fn handle_rsync_profiles_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    let AppMode::RsyncProfiles { host_index, selected_index, confirm_delete } = &mut app.mode else {
+        return Ok(());
+    };
+    if *confirm_delete {
+        if key.code == KeyCode::Char('y') {
+            app.delete_rsync_profile();
+        } else {
+            *confirm_delete = false;
+        }
+        return Ok(());
+    }
+    let count = app.hosts.get(*host_index).map_or(0, |h| h.rsync_profiles.len());
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down if *selected_index + 1 < count => *selected_index += 1,
+        KeyCode::Char('k') | KeyCode::Up => *selected_index = selected_index.saturating_sub(1),
+        KeyCode::Enter => app.run_rsync_profile(false),
+        KeyCode::Char(' ') => app.run_rsync_profile(true),
+        KeyCode::Char('e') => app.edit_rsync_profile(),
+        KeyCode::Char('n') => app.start_rsync(),
+        KeyCode::Char('D') if count > 0 => *confirm_delete = true,
+        KeyCode::Esc | KeyCode::Char('q') => app.return_to_table(),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle input while a transfer runs, and on its summary
+fn handle_rsync_progress_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    let running = app.rsync_run.as_ref().is_some_and(|run| run.exit_code.is_none());
+    match key.code {
+        KeyCode::Char('c') | KeyCode::Esc if running => app.cancel_rsync_run(),
+        KeyCode::Enter | KeyCode::Esc | KeyCode::Char('q') if !running => app.close_rsync_run(),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle input in the file manager, its prompts and its pager
+fn handle_file_manager_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    let AppMode::FileManager { local, remote, remote_focused, prompt, preview, .. } = &mut app.mode else {
+        return Ok(());
+    };
+
+    if let Some(shown) = preview {
+        let max_scroll = shown.lines.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => shown.scroll = (shown.scroll + 1).min(max_scroll),
+            KeyCode::Char('k') | KeyCode::Up => shown.scroll = shown.scroll.saturating_sub(1),
+            KeyCode::Char('g') => shown.scroll = 0,
+            KeyCode::Char('G') => shown.scroll = max_scroll,
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                shown.scroll = (shown.scroll + 10).min(max_scroll);
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                shown.scroll = shown.scroll.saturating_sub(10);
+            }
+            KeyCode::Esc | KeyCode::Char('q') => *preview = None,
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    if let Some(current) = prompt {
+        match current {
+            FilePrompt::ConfirmDelete { .. } => match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => app.file_manager_submit_prompt(),
+                _ => *prompt = None,
+            },
+            FilePrompt::Rename { buffer, .. } | FilePrompt::Mkdir { buffer } => match key.code {
+                KeyCode::Enter => app.file_manager_submit_prompt(),
+                KeyCode::Esc => *prompt = None,
+                KeyCode::Backspace => {
+                    buffer.pop();
+                }
+                KeyCode::Char(c) => buffer.push(c),
+                _ => {}
+            },
+        }
+        return Ok(());
+    }
+
+    let pane = if *remote_focused { remote } else { local };
+    let last = pane.entries.len().saturating_sub(1);
+    match key.code {
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            pane.selected_index = (pane.selected_index + 10).min(last);
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            pane.selected_index = pane.selected_index.saturating_sub(10);
+        }
+        KeyCode::Char('j') | KeyCode::Down => pane.selected_index = (pane.selected_index + 1).min(last),
+        KeyCode::Char('k') | KeyCode::Up => pane.selected_index = pane.selected_index.saturating_sub(1),
+        KeyCode::Char('g') => pane.selected_index = 0,
+        KeyCode::Char('G') => pane.selected_index = last,
+        KeyCode::Char(' ') => pane.toggle_mark(),
+        KeyCode::Tab | KeyCode::Left | KeyCode::Right => *remote_focused = !*remote_focused,
+        KeyCode::Enter | KeyCode::Char('l') if !pane.loading => app.file_manager_open(),
+        KeyCode::Backspace | KeyCode::Char('h') if !pane.loading => app.file_manager_parent(),
+        KeyCode::Char('c') | KeyCode::F(5) => app.file_manager_copy(),
+        KeyCode::Char('d') => app.file_manager_download(),
+        KeyCode::Char('v') => app.file_manager_view(),
+        KeyCode::Char('e') => app.file_manager_edit(),
+        KeyCode::Char('r') => app.file_manager_start_rename(),
+        KeyCode::Char('m') | KeyCode::F(7) => app.file_manager_start_mkdir(),
+        KeyCode::Char('D') | KeyCode::Delete => app.file_manager_start_delete(),
+        KeyCode::Char('R') => app.file_manager_refresh_all(),
+        KeyCode::Char('.') => app.toggle_hidden_files(),
+        KeyCode::Char('o') => app.cycle_file_sort(),
+        KeyCode::Esc | KeyCode::Char('q') => app.close_file_manager(),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle input in the pager opened from a file browser
+fn handle_file_preview_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    if let AppMode::FilePreview { preview, .. } = &mut app.mode {
+        let max_scroll = preview.lines.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => preview.scroll = (preview.scroll + 1).min(max_scroll),
+            KeyCode::Char('k') | KeyCode::Up => preview.scroll = preview.scroll.saturating_sub(1),
+            KeyCode::Char('g') => preview.scroll = 0,
+            KeyCode::Char('G') => preview.scroll = max_scroll,
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                preview.scroll = (preview.scroll + 10).min(max_scroll);
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                preview.scroll = preview.scroll.saturating_sub(10);
+            }
+            KeyCode::Char('e') => app.edit_previewed_file(),
+            KeyCode::Esc | KeyCode::Char('q') => app.close_file_preview(),
+            _ => {}
+        }
+    }
     Ok(())
 }
 
@@ -954,10 +1180,8 @@ fn handle_rsync_file_browser_input(app: &mut App, key: KeyEvent) -> Result<()> {
         let entries_len = entries.len();
 
         match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                if *selected_index < entries_len.saturating_sub(1) {
-                    *selected_index += 1;
-                }
+            KeyCode::Char('j') | KeyCode::Down if *selected_index < entries_len.saturating_sub(1) => {
+                *selected_index += 1;
             }
             KeyCode::Char('k') | KeyCode::Up => {
                 *selected_index = selected_index.saturating_sub(1);
@@ -974,37 +1198,39 @@ fn handle_rsync_file_browser_input(app: &mut App, key: KeyEvent) -> Result<()> {
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 *selected_index = selected_index.saturating_sub(10);
             }
-            KeyCode::Enter => {
+            KeyCode::Enter if *selected_index < entries.len() => {
                 // Navigate into directory or select file
-                if *selected_index < entries.len() {
-                    let entry = &entries[*selected_index];
-                    if entry.is_dir {
-                        let new_path = if entry.name == ".." {
-                            // Go up one directory
-                            if let Some(parent) = std::path::Path::new(&current_path).parent() {
-                                parent.to_string_lossy().to_string()
-                            } else {
-                                "/".to_string()
-                            }
+                let entry = &entries[*selected_index];
+                if entry.is_dir {
+                    let new_path = if entry.name == ".." {
+                        // Go up one directory
+                        if let Some(parent) = std::path::Path::new(&current_path).parent() {
+                            parent.to_string_lossy().to_string()
                         } else {
-                            // Enter subdirectory
-                            if current_path.ends_with('/') {
-                                format!("{}{}", current_path, entry.name)
-                            } else {
-                                format!("{}/{}", current_path, entry.name)
-                            }
-                        };
-                        app.rsync_navigate_to(new_path);
+                            "/".to_string()
+                        }
                     } else {
-                        // Select the file
-                        app.rsync_select_current_path();
-                    }
+                        // Enter subdirectory
+                        if current_path.ends_with('/') {
+                            format!("{}{}", current_path, entry.name)
+                        } else {
+                            format!("{}/{}", current_path, entry.name)
+                        }
+                    };
+                    app.rsync_navigate_to(new_path);
+                } else {
+                    // Select the file
+                    app.rsync_select_current_path();
                 }
             }
             KeyCode::Char(' ') => {
                 // Select current directory (useful for selecting folders as source/dest)
                 app.rsync_select_current_path();
             }
+            KeyCode::Char('.') => app.toggle_hidden_files(),
+            KeyCode::Char('o') => app.cycle_file_sort(),
+            KeyCode::Char('v') => app.browser_preview_file(),
+            KeyCode::Char('e') => app.browser_edit_file(),
             KeyCode::Esc | KeyCode::Char('q') => {
                 app.rsync_cancel_browse();
             }
@@ -1041,13 +1267,28 @@ fn handle_container_list_input(app: &mut App, key: KeyEvent) -> Result<()> {
         KeyCode::Char('D') => app.view_stats(),
         KeyCode::Char('T') => app.view_processes(),
         KeyCode::Char('I') => app.view_inspect(),
+        KeyCode::Char('i') => app.view_images(),
+        KeyCode::Char('V') => app.view_volumes(),
+        KeyCode::Char('N') => app.view_networks(),
+        KeyCode::Char('O') => app.view_compose_projects(),
+
+        // Shell into container
+        KeyCode::Char('c') => app.open_container_shell(None),
+        KeyCode::Char('C') => app.start_exec_user_prompt(),
+        KeyCode::Char('!') => app.start_exec_command_prompt(),
 
         // Script operations
         KeyCode::Char('n') => app.create_script(),  // NEW: Create new script
+        KeyCode::Char('A') => app.generate_script_from_container(),
         KeyCode::Char('e') => app.edit_script(),
         KeyCode::Char('v') => app.view_script(),
         KeyCode::Char('x') => app.run_script(),
+        KeyCode::Char('H') => app.view_script_runs(),
         KeyCode::Char('b') => app.browse_for_script(),
+        KeyCode::Char('B') => app.view_script_backups(),
+        KeyCode::Char('M') => app.export_compose(),
+        KeyCode::Char('f') => app.view_drift(),
+        KeyCode::Char('F') => app.view_host_drift(),
 
         // Refresh
         KeyCode::Char('R') => app.refresh_containers(),
@@ -1068,6 +1309,13 @@ fn handle_docker_confirm_input(app: &mut App, key: KeyEvent) -> Result<()> {
 
         match key.code {
             KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                if action.has_lint_errors() {
+                    app.set_error("The script checks found errors - press [F] to run it anyway");
+                } else {
+                    app.execute_docker_action(action);
+                }
+            }
+            KeyCode::Char('F') if action.has_lint_errors() => {
                 app.execute_docker_action(action);
             }
             KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
@@ -1079,6 +1327,332 @@ fn handle_docker_confirm_input(app: &mut App, key: KeyEvent) -> Result<()> {
     Ok(())
 }
 
+/// Handle input in the container shell user prompt
+fn handle_exec_user_prompt_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    if let AppMode::ExecUserPrompt { host_index, user_buffer, .. } = &mut app.mode {
+        match key.code {
+            KeyCode::Enter => {
+                let user = user_buffer.trim().to_string();
+                app.open_container_shell(Some(user));
+            }
+            KeyCode::Esc => {
+                app.mode = AppMode::ContainerList { host_index: *host_index };
+            }
+            KeyCode::Char(c) if !c.is_whitespace() => {
+                user_buffer.push(c);
+            }
+            KeyCode::Backspace => {
+                user_buffer.pop();
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Handle input in the one-off container command prompt
+fn handle_exec_command_prompt_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    if let AppMode::ExecCommandPrompt { host_index, command_buffer, history_index, .. } = &mut app.mode {
+        match key.code {
+            KeyCode::Enter => app.submit_exec_command(),
+            KeyCode::Esc => {
+                app.mode = AppMode::ContainerList { host_index: *host_index };
+            }
+            KeyCode::Up => app.exec_history_step(true),
+            KeyCode::Down => app.exec_history_step(false),
+            KeyCode::Char(c) => {
+                command_buffer.push(c);
+                *history_index = None;
+            }
+            KeyCode::Backspace => {
+                command_buffer.pop();
+                *history_index = None;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Handle input in the container command output pager
+fn handle_exec_output_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    if let AppMode::ExecOutput { host_index, scroll_offset, output, .. } = &mut app.mode {
+        let host_index = *host_index;
+        let max_offset = output.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                *scroll_offset = (*scroll_offset + 1).min(max_offset);
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                *scroll_offset = scroll_offset.saturating_sub(1);
+            }
+            KeyCode::Char('g') => *scroll_offset = 0,
+            KeyCode::Char('G') => *scroll_offset = max_offset,
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                *scroll_offset = (*scroll_offset + 10).min(max_offset);
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                *scroll_offset = scroll_offset.saturating_sub(10);
+            }
+            KeyCode::Char('r') => app.rerun_exec_command(),
+            KeyCode::Char('!') => app.start_exec_command_prompt(),
+            KeyCode::Char('.') => app.toggle_hidden_files(),
+            KeyCode::Char('o') => app.cycle_file_sort(),
+            KeyCode::Char('v') => app.browser_preview_file(),
+            KeyCode::Char('e') => app.browser_edit_file(),
+            KeyCode::Esc | KeyCode::Char('q') => {
+                app.mode = AppMode::ContainerList { host_index };
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Handle input in the docker image list
+fn handle_image_list_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    if let AppMode::ImageList { host_index, images, selected_index } = &mut app.mode {
+        let host_index = *host_index;
+        let last = images.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => *selected_index = (*selected_index + 1).min(last),
+            KeyCode::Char('k') | KeyCode::Up => *selected_index = selected_index.saturating_sub(1),
+            KeyCode::Char('g') => *selected_index = 0,
+            KeyCode::Char('G') => *selected_index = last,
+            KeyCode::Char('p') => app.image_pull(),
+            KeyCode::Char('d') => app.image_remove(),
+            KeyCode::Char('P') => app.image_prune(),
+            KeyCode::Char('u') => app.check_image_update(),
+            KeyCode::Char('R') => app.refresh_images(),
+            KeyCode::Esc | KeyCode::Char('q') => {
+                app.mode = AppMode::ContainerList { host_index };
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Handle input in the docker volume and network lists
+fn handle_resource_list_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    let (host_index, selected_index, len) = match &mut app.mode {
+        AppMode::VolumeList { host_index, volumes, selected_index } => (*host_index, selected_index, volumes.len()),
+        AppMode::NetworkList { host_index, networks, selected_index } => (*host_index, selected_index, networks.len()),
+        _ => return Ok(()),
+    };
+    let last = len.saturating_sub(1);
+
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => *selected_index = (*selected_index + 1).min(last),
+        KeyCode::Char('k') | KeyCode::Up => *selected_index = selected_index.saturating_sub(1),
+        KeyCode::Char('g') => *selected_index = 0,
+        KeyCode::Char('G') => *selected_index = last,
+        KeyCode::Char('a') => app.start_create_resource(),
+        KeyCode::Char('d') => {
+            if matches!(app.mode, AppMode::VolumeList { .. }) {
+                app.volume_remove();
+            } else {
+                app.network_remove();
+            }
+        }
+        KeyCode::Char('P') => app.resource_prune(),
+        KeyCode::Char('R') => {
+            if matches!(app.mode, AppMode::VolumeList { .. }) {
+                app.refresh_volumes();
+            } else {
+                app.refresh_networks();
+            }
+        }
+        KeyCode::Enter if matches!(app.mode, AppMode::VolumeList { .. }) => app.browse_volume(),
+        KeyCode::Tab => {
+            if matches!(app.mode, AppMode::VolumeList { .. }) {
+                app.view_networks();
+            } else {
+                app.view_volumes();
+            }
+        }
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.mode = AppMode::ContainerList { host_index };
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle input in the read-only volume browser
+fn handle_volume_browser_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    if let AppMode::VolumeBrowser { entries, selected_index, loading, preview, preview_scroll, .. } = &mut app.mode {
+        if let Some(lines) = preview {
+            let max_scroll = lines.len().saturating_sub(1);
+            match key.code {
+                KeyCode::Char('j') | KeyCode::Down => *preview_scroll = (*preview_scroll + 1).min(max_scroll),
+                KeyCode::Char('k') | KeyCode::Up => *preview_scroll = preview_scroll.saturating_sub(1),
+                KeyCode::Char('g') => *preview_scroll = 0,
+                KeyCode::Char('G') => *preview_scroll = max_scroll,
+                KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    *preview_scroll = (*preview_scroll + 10).min(max_scroll);
+                }
+                KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    *preview_scroll = preview_scroll.saturating_sub(10);
+                }
+                KeyCode::Esc | KeyCode::Char('q') => *preview = None,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        if *loading {
+            if key.code == KeyCode::Esc {
+                app.view_volumes();
+            }
+            return Ok(());
+        }
+
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                *selected_index = (*selected_index + 1).min(entries.len().saturating_sub(1));
+            }
+            KeyCode::Char('k') | KeyCode::Up => *selected_index = selected_index.saturating_sub(1),
+            KeyCode::Char('g') => *selected_index = 0,
+            KeyCode::Char('G') => *selected_index = entries.len().saturating_sub(1),
+            KeyCode::Enter => app.volume_browser_open(),
+            KeyCode::Esc | KeyCode::Char('q') => app.view_volumes(),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Handle input in the new volume/network name prompt
+fn handle_create_resource_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    if let AppMode::CreateResourcePrompt { name_buffer, return_mode, .. } = &mut app.mode {
+        match key.code {
+            KeyCode::Enter => app.submit_create_resource(),
+            KeyCode::Esc => {
+                app.mode = *return_mode.clone();
+            }
+            KeyCode::Char(c) if c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-') => {
+                name_buffer.push(c);
+            }
+            KeyCode::Backspace => {
+                name_buffer.pop();
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Handle input in the compose project list
+fn handle_compose_projects_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    if let AppMode::ComposeProjects { projects, selected_index, .. } = &mut app.mode {
+        let last = projects.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => *selected_index = (*selected_index + 1).min(last),
+            KeyCode::Char('k') | KeyCode::Up => *selected_index = selected_index.saturating_sub(1),
+            KeyCode::Char('g') => *selected_index = 0,
+            KeyCode::Char('G') => *selected_index = last,
+            KeyCode::Char('u') => app.compose_action(ComposeAction::Up),
+            KeyCode::Char('D') => app.compose_action(ComposeAction::Down),
+            KeyCode::Char('p') => app.compose_action(ComposeAction::Pull),
+            KeyCode::Char('r') => app.compose_action(ComposeAction::Restart),
+            KeyCode::Char('l') => app.view_compose_output(ComposeView::Logs),
+            KeyCode::Char('c') => app.view_compose_output(ComposeView::Config),
+            KeyCode::Char('i') => app.import_compose_project(),
+            KeyCode::Char('R') => app.refresh_compose_projects(),
+            KeyCode::Esc | KeyCode::Char('q') => app.compose_go_back(),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Handle input in the compose logs/config viewer
+fn handle_compose_output_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    if let AppMode::ComposeOutput { lines, scroll_offset, .. } = &mut app.mode {
+        let max_offset = lines.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => *scroll_offset = (*scroll_offset + 1).min(max_offset),
+            KeyCode::Char('k') | KeyCode::Up => *scroll_offset = scroll_offset.saturating_sub(1),
+            KeyCode::Char('g') => *scroll_offset = 0,
+            KeyCode::Char('G') => *scroll_offset = max_offset,
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                *scroll_offset = (*scroll_offset + 10).min(max_offset);
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                *scroll_offset = scroll_offset.saturating_sub(10);
+            }
+            KeyCode::Esc | KeyCode::Char('q') => app.view_compose_projects(),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Handle input in the drift report
+fn handle_drift_report_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    let AppMode::DriftReport { host_index, container, .. } = &app.mode else {
+        return Ok(());
+    };
+    let host_index = *host_index;
+    let line_count: usize = app
+        .drift_report_rows(container.as_deref())
+        .iter()
+        .map(|(_, drift)| 1 + drift.map_or(0, <[_]>::len))
+        .sum();
+
+    if let AppMode::DriftReport { scroll_offset, .. } = &mut app.mode {
+        let max_offset = line_count.saturating_sub(1);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => *scroll_offset = (*scroll_offset + 1).min(max_offset),
+            KeyCode::Char('k') | KeyCode::Up => *scroll_offset = scroll_offset.saturating_sub(1),
+            KeyCode::Char('g') => *scroll_offset = 0,
+            KeyCode::Char('G') => *scroll_offset = max_offset,
+            KeyCode::Char('R') => app.refresh_drift_report(),
+            KeyCode::Esc | KeyCode::Char('q') => app.mode = AppMode::ContainerList { host_index },
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Handle input in the compose export preview
+fn handle_compose_export_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    if let AppMode::ComposeExport { host_index, lines, scroll_offset, .. } = &mut app.mode {
+        let max_offset = lines.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => *scroll_offset = (*scroll_offset + 1).min(max_offset),
+            KeyCode::Char('k') | KeyCode::Up => *scroll_offset = scroll_offset.saturating_sub(1),
+            KeyCode::Char('g') => *scroll_offset = 0,
+            KeyCode::Char('G') => *scroll_offset = max_offset,
+            KeyCode::Char('a') => app.toggle_compose_export_scope(),
+            KeyCode::Char('w') => app.write_compose_export(),
+            KeyCode::Esc | KeyCode::Char('q') => {
+                app.mode = AppMode::ContainerList { host_index: *host_index };
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Handle input in the compose service import list
+fn handle_compose_import_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    if let AppMode::ComposeImport { scripts, selected_index, .. } = &mut app.mode {
+        let last = scripts.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => *selected_index = (*selected_index + 1).min(last),
+            KeyCode::Char('k') | KeyCode::Up => *selected_index = selected_index.saturating_sub(1),
+            KeyCode::Char('g') => *selected_index = 0,
+            KeyCode::Char('G') => *selected_index = last,
+            KeyCode::Enter => app.edit_imported_script(),
+            KeyCode::Esc | KeyCode::Char('q') => app.view_compose_projects(),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
 /// Handle input in logs viewer
 fn handle_logs_input(app: &mut App, key: KeyEvent) -> Result<()> {
     if let AppMode::LogsViewer { host_index, scroll_offset, log_buffer, follow_mode, .. } = &mut app.mode {
@@ -1154,10 +1728,8 @@ fn handle_process_input(app: &mut App, key: KeyEvent) -> Result<()> {
         let host_index = *host_index;
         let container_index = *container_index;
         match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                if *selected_index < processes.len().saturating_sub(1) {
-                    *selected_index += 1;
-                }
+            KeyCode::Char('j') | KeyCode::Down if *selected_index < processes.len().saturating_sub(1) => {
+                *selected_index += 1;
             }
             KeyCode::Char('k') | KeyCode::Up => {
                 *selected_index = selected_index.saturating_sub(1);
@@ -1208,9 +1780,7 @@ fn handle_env_inspector_input(app: &mut App, key: KeyEvent) -> Result<()> {
         let host_index = *host_index;
         match key.code {
             KeyCode::Char('j') | KeyCode::Down => {
-                if *selected_index < container_vars.len().saturating_sub(1) {
-                    *selected_index += 1;
-                }
+                *selected_index = (*selected_index + 1).min(container_vars.len().saturating_sub(1));
             }
             KeyCode::Char('k') | KeyCode::Up => {
                 *selected_index = selected_index.saturating_sub(1);
@@ -1245,10 +1815,8 @@ fn handle_script_viewer_input(app: &mut App, key: KeyEvent) -> Result<()> {
     if let AppMode::ScriptViewer { host_index, scroll_offset, script_content, .. } = &mut app.mode {
         let host_index = *host_index;
         match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                if *scroll_offset < script_content.len().saturating_sub(1) {
-                    *scroll_offset += 1;
-                }
+            KeyCode::Char('j') | KeyCode::Down if *scroll_offset < script_content.len().saturating_sub(1) => {
+                *scroll_offset += 1;
             }
             KeyCode::Char('k') | KeyCode::Up => {
                 *scroll_offset = scroll_offset.saturating_sub(1);
@@ -1272,12 +1840,143 @@ fn handle_script_viewer_input(app: &mut App, key: KeyEvent) -> Result<()> {
     Ok(())
 }
 
+/// Handle input while reviewing a script diff before writing it
+fn handle_script_diff_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    if let AppMode::ScriptDiff { diff, scroll_offset, .. } = &mut app.mode {
+        let max_offset = diff.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => *scroll_offset = (*scroll_offset + 1).min(max_offset),
+            KeyCode::Char('k') | KeyCode::Up => *scroll_offset = scroll_offset.saturating_sub(1),
+            KeyCode::Char('g') => *scroll_offset = 0,
+            KeyCode::Char('G') => *scroll_offset = max_offset,
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                *scroll_offset = (*scroll_offset + 10).min(max_offset);
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                *scroll_offset = scroll_offset.saturating_sub(10);
+            }
+            KeyCode::PageDown => *scroll_offset = (*scroll_offset + 10).min(max_offset),
+            KeyCode::PageUp => *scroll_offset = scroll_offset.saturating_sub(10),
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => app.confirm_script_write(),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Char('q') | KeyCode::Esc => app.cancel_script_diff(),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Handle input in the script backup list
+fn handle_script_backups_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    if let AppMode::ScriptBackups { host_index, backups, selected_index, .. } = &mut app.mode {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                *selected_index = (*selected_index + 1).min(backups.len().saturating_sub(1));
+            }
+            KeyCode::Char('k') | KeyCode::Up => *selected_index = selected_index.saturating_sub(1),
+            KeyCode::Char('g') => *selected_index = 0,
+            KeyCode::Char('G') => *selected_index = backups.len().saturating_sub(1),
+            KeyCode::Enter => app.preview_script_restore(),
+            KeyCode::Esc | KeyCode::Char('q') => {
+                app.mode = AppMode::ContainerList { host_index: *host_index };
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Handle input in the script run console
+fn handle_script_run_console_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    let AppMode::ScriptRunConsole { host_index, container_name, run_index, .. } = &app.mode else {
+        return Ok(());
+    };
+    let (host_index, container_name, run_index) = (*host_index, container_name.clone(), *run_index);
+    let (total, live) = app
+        .script_run_for(host_index, &container_name, run_index)
+        .map_or((0, false), |(run, elapsed)| (run.log.len(), elapsed.is_some()));
+
+    match key.code {
+        KeyCode::Char('h') => {
+            app.mode = AppMode::ScriptRunHistory { host_index, container_name, selected_index: 0 };
+        }
+        KeyCode::Char('K') if live => app.cancel_script_run(),
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.mode = AppMode::ContainerList { host_index };
+        }
+        _ => {
+            if let AppMode::ScriptRunConsole { scroll_offset, follow, .. } = &mut app.mode {
+                let max_offset = total.saturating_sub(1);
+                // Scrolling up out of follow mode starts from the bottom
+                let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+                if *follow && (matches!(key.code, KeyCode::Char('k') | KeyCode::Up) || (ctrl && key.code == KeyCode::Char('u'))) {
+                    *scroll_offset = max_offset;
+                }
+                match key.code {
+                    KeyCode::Char('j') | KeyCode::Down => {
+                        *scroll_offset = (*scroll_offset + 1).min(max_offset);
+                        *follow = false;
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        *scroll_offset = scroll_offset.saturating_sub(1);
+                        *follow = false;
+                    }
+                    KeyCode::Char('g') => {
+                        *scroll_offset = 0;
+                        *follow = false;
+                    }
+                    KeyCode::Char('G') => *follow = true,
+                    KeyCode::Char('f') => *follow = !*follow,
+                    KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        *scroll_offset = (*scroll_offset + 10).min(max_offset);
+                        *follow = false;
+                    }
+                    KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        *scroll_offset = scroll_offset.saturating_sub(10);
+                        *follow = false;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Handle input in the script run history
+fn handle_script_run_history_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    let AppMode::ScriptRunHistory { host_index, container_name, .. } = &app.mode else {
+        return Ok(());
+    };
+    let host_index = *host_index;
+    let count = app.script_run_rows(host_index, container_name).len();
+
+    match key.code {
+        KeyCode::Enter => app.open_script_run(),
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.mode = AppMode::ContainerList { host_index };
+        }
+        _ => {
+            if let AppMode::ScriptRunHistory { selected_index, .. } = &mut app.mode {
+                match key.code {
+                    KeyCode::Char('j') | KeyCode::Down => {
+                        *selected_index = (*selected_index + 1).min(count.saturating_sub(1));
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => *selected_index = selected_index.saturating_sub(1),
+                    KeyCode::Char('g') => *selected_index = 0,
+                    KeyCode::Char('G') => *selected_index = count.saturating_sub(1),
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Handle input in script editor
 fn handle_script_edit_input(app: &mut App, key: KeyEvent) -> Result<()> {
-    if let AppMode::ScriptEdit { host_index, focused_section, selected_index, editing_script, .. } = &app.mode {
+    if let AppMode::ScriptEdit { host_index, focused_section, editing_script, .. } = &app.mode {
         let host_index = *host_index;
         let focused_section = *focused_section;
-        let selected_index = *selected_index;
         let env_vars_len = editing_script.env_vars.len();
         let volumes_len = editing_script.volumes.len();
         let ports_len = editing_script.ports.len();
@@ -1317,50 +2016,33 @@ fn handle_script_edit_input(app: &mut App, key: KeyEvent) -> Result<()> {
                 }
             }
 
-            // Add new item
-            KeyCode::Char('a') => {
-                match focused_section {
-                    ScriptSection::EnvVars => app.start_add_env_var(),
-                    _ => {} // TODO: Add volume/port editors
-                }
+            // Add new item (the Network section has a single entry, edited in place)
+            KeyCode::Char('a') if focused_section != ScriptSection::Network => {
+                app.start_script_entry(false);
             }
 
             // Edit existing item
             KeyCode::Enter => {
-                match focused_section {
-                    ScriptSection::EnvVars if env_vars_len > 0 => {
-                        app.start_edit_env_var();
-                    }
-                    _ => {}
+                let has_entry = match focused_section {
+                    ScriptSection::EnvVars => env_vars_len > 0,
+                    ScriptSection::Volumes => volumes_len > 0,
+                    ScriptSection::Ports => ports_len > 0,
+                    ScriptSection::Network => true,
+                };
+                if has_entry {
+                    app.start_script_entry(true);
                 }
             }
 
             // Delete item
             KeyCode::Char('d') => {
-                match focused_section {
-                    ScriptSection::EnvVars if env_vars_len > 0 => {
-                        app.remove_env_var_from_current_script(selected_index);
-                    }
-                    ScriptSection::Volumes if volumes_len > 0 => {
-                        if let AppMode::ScriptEdit { editing_script, selected_index, .. } = &mut app.mode {
-                            if *selected_index < editing_script.volumes.len() {
-                                editing_script.volumes.remove(*selected_index);
-                                *selected_index = (*selected_index).min(editing_script.volumes.len().saturating_sub(1));
-                            }
-                        }
-                    }
-                    ScriptSection::Ports if ports_len > 0 => {
-                        if let AppMode::ScriptEdit { editing_script, selected_index, .. } = &mut app.mode {
-                            if *selected_index < editing_script.ports.len() {
-                                editing_script.ports.remove(*selected_index);
-                                *selected_index = (*selected_index).min(editing_script.ports.len().saturating_sub(1));
-                            }
-                        }
-                    }
-                    _ => {}
-                }
+                app.remove_script_entry();
             }
 
+            // Secrets: move out of the script, show the value
+            KeyCode::Char('m') if focused_section == ScriptSection::EnvVars => app.cycle_env_storage(),
+            KeyCode::Char('r') if focused_section == ScriptSection::EnvVars => app.toggle_secret_reveal(),
+
             // Save script (Ctrl+S)
             KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 app.save_current_script();
@@ -1378,25 +2060,30 @@ fn handle_script_edit_input(app: &mut App, key: KeyEvent) -> Result<()> {
 }
 
 /// Handle input in environment variable editor
-fn handle_env_var_editor_input(app: &mut App, key: KeyEvent) -> Result<()> {
-    if let AppMode::EnvVarEditor { key_buffer, value_buffer, editing_key, .. } = &mut app.mode {
+fn handle_script_entry_editor_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    if let AppMode::ScriptEntryEditor { section, key_buffer, value_buffer, editing_key, .. } = &mut app.mode {
         match key.code {
             KeyCode::Tab => {
                 *editing_key = !*editing_key;
             }
             KeyCode::Enter => {
-                // Save the env var
-                app.save_env_var();
+                // Save the entry
+                app.save_script_entry();
             }
             KeyCode::Esc => {
                 // Cancel and return to script edit
-                app.cancel_env_var_edit();
+                app.cancel_script_entry_edit();
             }
             KeyCode::Char(c) => {
                 if *editing_key {
-                    // Key field: only allow alphanumeric and underscore, convert to uppercase
-                    if c.is_alphanumeric() || c == '_' {
-                        key_buffer.push(c.to_ascii_uppercase());
+                    match section {
+                        // Env var keys: only alphanumeric and underscore, uppercased
+                        ScriptSection::EnvVars if c.is_alphanumeric() || c == '_' => {
+                            key_buffer.push(c.to_ascii_uppercase());
+                        }
+                        ScriptSection::Ports if c.is_ascii_digit() => key_buffer.push(c),
+                        ScriptSection::Volumes | ScriptSection::Network => key_buffer.push(c),
+                        _ => {}
                     }
                 } else {
                     // Value field: allow any character
@@ -1424,20 +2111,15 @@ fn handle_file_browser_input(app: &mut App, key: KeyEvent) -> Result<()> {
 
         if *loading {
             // Don't process input while loading
-            match key.code {
-                KeyCode::Esc => {
-                    app.mode = AppMode::ContainerList { host_index };
-                }
-                _ => {}
+            if key.code == KeyCode::Esc {
+                app.mode = AppMode::ContainerList { host_index };
             }
             return Ok(());
         }
 
         match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                if *selected_index < entries.len().saturating_sub(1) {
-                    *selected_index += 1;
-                }
+            KeyCode::Char('j') | KeyCode::Down if *selected_index < entries.len().saturating_sub(1) => {
+                *selected_index += 1;
             }
             KeyCode::Char('k') | KeyCode::Up => {
                 *selected_index = selected_index.saturating_sub(1);
@@ -1446,57 +2128,39 @@ fn handle_file_browser_input(app: &mut App, key: KeyEvent) -> Result<()> {
             KeyCode::Char('G') => {
                 *selected_index = entries.len().saturating_sub(1);
             }
-            KeyCode::Enter => {
-                if *selected_index < entries.len() {
-                    let entry = &entries[*selected_index];
-                    if entry.is_dir {
-                        // Navigate into directory
-                        let new_path = if entry.name == ".." {
-                            // Go up
-                            let path = std::path::Path::new(&current_path);
-                            path.parent()
-                                .map(|p| p.to_string_lossy().to_string())
-                                .unwrap_or_else(|| current_path.clone())
+            KeyCode::Enter if *selected_index < entries.len() => {
+                let entry = &entries[*selected_index];
+                if entry.is_dir {
+                    // Navigate into directory
+                    let new_path = if entry.name == ".." {
+                        // Go up
+                        let path = std::path::Path::new(&current_path);
+                        path.parent()
+                            .map(|p| p.to_string_lossy().to_string())
+                            .unwrap_or_else(|| current_path.clone())
+                    } else {
+                        format!("{}/{}", current_path.trim_end_matches('/'), entry.name)
+                    };
+
+                    app.file_browser_list(new_path);
+                } else if entry.is_script {
+                    // Select this script for the container
+                    let script_path = format!("{}/{}", current_path.trim_end_matches('/'), entry.name);
+                    if let Some(host) = app.hosts.get(host_index).cloned() {
+                        let base_cmd = format!("cat {}", script_path);
+                        let cmd = if app.use_sudo {
+                            format!("sudo -i {}", base_cmd)
                         } else {
-                            format!("{}/{}", current_path.trim_end_matches('/'), entry.name)
+                            base_cmd
                         };
-
-                        if let Some(host) = app.hosts.get(host_index).cloned() {
-                            // Match dockering's ls -la format
-                            let base_cmd = format!("ls -la {} 2>/dev/null | tail -n +2", new_path);
-                            let cmd = if app.use_sudo {
-                                format!("sudo -i {}", base_cmd)
-                            } else {
-                                base_cmd
-                            };
-                            app.pending_ssh_command = Some(crate::app::PendingSshCommand {
-                                host,
-                                command: cmd,
-                                command_type: crate::app::SshCommandType::ListDirectory { path: new_path.clone() },
-                            });
-                            *current_path = new_path;
-                            *loading = true;
-                            *selected_index = 0;
-                        }
-                    } else if entry.is_script {
-                        // Select this script for the container
-                        let script_path = format!("{}/{}", current_path.trim_end_matches('/'), entry.name);
-                        if let Some(host) = app.hosts.get(host_index).cloned() {
-                            let base_cmd = format!("cat {}", script_path);
-                            let cmd = if app.use_sudo {
-                                format!("sudo -i {}", base_cmd)
-                            } else {
-                                base_cmd
-                            };
-                            app.pending_ssh_command = Some(crate::app::PendingSshCommand {
-                                host,
-                                command: cmd,
-                                command_type: crate::app::SshCommandType::ReadScriptForContainer {
-                                    script_path,
-                                    container_index,
-                                },
-                            });
-                        }
+                        app.pending_ssh_command = Some(crate::app::PendingSshCommand {
+                            host,
+                            command: cmd,
+                            command_type: crate::app::SshCommandType::ReadScriptForContainer {
+                                script_path,
+                                container_index,
+                            },
+                        });
                     }
                 }
             }
@@ -1,3 +1,5 @@
+pub mod diff;
 pub mod input;
 
 pub use input::handle_input;
+pub mod shell;
@@ -0,0 +1,57 @@
+//! Quoting words for the shells commands run in, remote or local. Every
+//! command and script built by sshing quotes through here, so a value reads
+//! back the same way wherever it ends up
+
+/// A word as the shell should see it: left bare when it is made of
+/// characters the shell does not treat specially, single-quoted otherwise
+pub fn quote(word: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "-_./:=@,+%".contains(c);
+    if !word.is_empty() && word.chars().all(plain) {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+/// A path as a shell word. A leading `~` or `$HOME` is left outside the
+/// quotes so the (remote) shell still expands it; the rest is quoted
+pub fn quote_path(path: &str) -> String {
+    for (home, word) in [("~", "~"), ("$HOME", "\"$HOME\"")] {
+        if let Some(rest) = path.strip_prefix(home) {
+            if rest.is_empty() {
+                return word.to_string();
+            }
+            // The slash stays unquoted too, or `~` is not expanded
+            if let Some(rest) = rest.strip_prefix('/') {
+                return format!("{}/{}", word, quote(rest));
+            }
+        }
+    }
+    quote(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("nginx:1.27"), "nginx:1.27");
+        assert_eq!(quote("--exclude=*.log"), "'--exclude=*.log'");
+        assert_eq!(quote("it's here"), "'it'\\''s here'");
+        assert_eq!(quote("$HOME"), "'$HOME'");
+        assert_eq!(quote("a \"b\""), "'a \"b\"'");
+        assert_eq!(quote(""), "''");
+    }
+
+    #[test]
+    fn test_quote_path() {
+        assert_eq!(quote_path("~"), "~");
+        assert_eq!(quote_path("~/my apps/run.sh"), "~/'my apps/run.sh'");
+        assert_eq!(quote_path("$HOME"), "\"$HOME\"");
+        assert_eq!(quote_path("$HOME/clients"), "\"$HOME\"/clients");
+        assert_eq!(quote_path("~bob/x"), "'~bob/x'");
+        assert_eq!(quote_path("$HOMEDIR"), "'$HOMEDIR'");
+        assert_eq!(quote_path("/srv/it's"), "'/srv/it'\\''s'");
+    }
+}
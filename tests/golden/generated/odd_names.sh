@@ -0,0 +1,18 @@
+#!/usr/bin/env bash
+
+# Configuration
+NAME='it'\''s $HOME web'
+REPO='registry.local/app:"v1"'
+
+docker pull "$REPO"
+docker stop "$NAME"
+docker rm "$NAME"
+
+docker create \
+  --net='front end' \
+  --name "$NAME" \
+  --restart=unless-stopped \
+  "$REPO"
+
+docker network connect 'back`end`' "$NAME"
+docker start "$NAME"
@@ -0,0 +1,38 @@
+#!/usr/bin/env bash
+set -e
+
+NAME="billing-api"
+REPO=registry.example.com/billing/api
+VERSION=${VERSION:-2.4.1}
+export DATABASE_URL="postgres://billing@db:5432/billing"
+
+log() {
+    echo "[$(date +%H:%M:%S)] $*"
+}
+
+if docker ps -a --format '{{.Names}}' | grep -q "^${NAME}$"; then
+    log "Removing old container"
+    docker stop "$NAME" && docker rm "$NAME"
+fi
+
+cat > /srv/billing/app.env <<CONF
+WORKERS=4
+# not an option: -e IGNORED=1
+CONF
+
+docker container create \
+  --name="$NAME" \
+  --network=golden-net \
+  --env-file /srv/billing/app.env \
+  --env DATABASE_URL='changed value' \
+  --env=RAILS_ENV=production \
+  -e GOLDEN_ADDED=yes \
+  --volume /srv/billing/uploads:/app/uploads:ro \
+  -v /srv/golden:/golden \
+  --publish 9999:99/udp \
+  --health-cmd "curl -fs http://localhost:3000/health || exit 1" \
+  --restart always \
+  "$REPO:$VERSION"
+
+docker start "$NAME"
+log "Started $NAME"
@@ -0,0 +1,37 @@
+#!/usr/bin/env bash
+set -e
+
+NAME="billing-api"
+REPO=registry.example.com/billing/api
+VERSION=${VERSION:-2.4.1}
+export DATABASE_URL="postgres://billing@db:5432/billing"
+
+log() {
+    echo "[$(date +%H:%M:%S)] $*"
+}
+
+if docker ps -a --format '{{.Names}}' | grep -q "^${NAME}$"; then
+    log "Removing old container"
+    docker stop "$NAME" && docker rm "$NAME"
+fi
+
+cat > /srv/billing/app.env <<CONF
+WORKERS=4
+# not an option: -e IGNORED=1
+CONF
+
+docker container create \
+  --name="$NAME" \
+  --network=billing \
+  --env-file /srv/billing/app.env \
+  --env DATABASE_URL \
+  --env=RAILS_ENV=production \
+  -e SECRET_KEY_BASE="$(cat /srv/billing/secret)" \
+  --volume /srv/billing/uploads:/app/uploads \
+  --publish 3000:3000 \
+  --health-cmd "curl -fs http://localhost:3000/health || exit 1" \
+  --restart unless-stopped \
+  "$REPO:$VERSION"
+
+docker start "$NAME"
+log "Started $NAME"
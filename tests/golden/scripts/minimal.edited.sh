@@ -0,0 +1,2 @@
+#!/bin/sh
+docker run --name minimal -e GOLDEN_ADDED=yes -v /srv/golden:/golden -p 9999:99/udp --network=golden-net --restart=always alpine:3 sleep infinity
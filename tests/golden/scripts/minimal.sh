@@ -0,0 +1,2 @@
+#!/bin/sh
+docker run --name minimal alpine:3 sleep infinity
@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+
+# Configuration
+NAME='acme-web'
+REPO="nginx:1.27-alpine"
+DATA=/srv/acme/web
+
+docker pull $REPO
+docker stop $NAME
+docker rm $NAME
+
+docker create \
+  --net=golden-net \
+  --name $NAME \
+  --restart=always \
+  -p 8443:443 \
+  -p 9999:99/udp \
+  -v $DATA/html:/usr/share/nginx/html \
+  -v $DATA/conf.d:/etc/nginx/conf.d \
+  -v /srv/golden:/golden \
+  -e NGINX_HOST='changed value' \
+  -e NGINX_PORT=80 \
+  -e GOLDEN_ADDED=yes \
+  $REPO
+
+docker start $NAME
@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+
+# Configuration
+NAME='acme-web'
+REPO="nginx:1.27-alpine"
+DATA=/srv/acme/web
+
+docker pull $REPO
+docker stop $NAME
+docker rm $NAME
+
+docker create \
+  --net=acme \
+  --name $NAME \
+  --restart=unless-stopped \
+  -p 8080:80 \
+  -p 8443:443 \
+  -v $DATA/html:/usr/share/nginx/html:ro \
+  -v $DATA/conf.d:/etc/nginx/conf.d \
+  -e NGINX_HOST="www.acme.example" \
+  -e NGINX_PORT=80 \
+  -e TZ='Europe/Berlin' \
+  $REPO
+
+docker start $NAME
@@ -0,0 +1,4 @@
+#!/bin/sh
+# Postgres for the reporting service
+docker rm -f reporting-db 2>/dev/null || true
+docker run -d --name reporting-db --restart always -e POSTGRES_USER='changed value' -e GOLDEN_ADDED=yes -v /srv/reporting/pg:/var/lib/postgresql/data:ro -v /srv/golden:/golden -p 9999:99/udp --network=golden-net postgres:16
@@ -0,0 +1,4 @@
+#!/bin/sh
+# Postgres for the reporting service
+docker rm -f reporting-db 2>/dev/null || true
+docker run -d --name reporting-db --restart always -e POSTGRES_USER=reporting -e POSTGRES_PASSWORD='s3cr3t!' -v /srv/reporting/pg:/var/lib/postgresql/data -p 127.0.0.1:5432:5432 postgres:16
@@ -0,0 +1,15 @@
+#!/bin/bash
+# Game server, tab indented
+NAME=mc
+
+docker run -dit \
+	--name $NAME \
+	--net golden-net \
+	-eEULA='changed value' \
+	-e GOLDEN_ADDED=yes \
+	-v "$PWD/data":/data:z \
+	-v /srv/golden:/golden \
+	-p 25565:25565 \
+	-p 9999:99/udp \
+	--restart=always \
+	itzg/minecraft-server:latest
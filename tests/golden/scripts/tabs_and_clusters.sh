@@ -0,0 +1,13 @@
+#!/bin/bash
+# Game server, tab indented
+NAME=mc
+
+docker run -dit \
+	--name $NAME \
+	--net host \
+	-eEULA=TRUE \
+	-e MEMORY=4G \
+	-v "$PWD/data":/data:ro,z \
+	-p 19132:19132/udp \
+	-p 25565:25565 \
+	itzg/minecraft-server:latest
@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+export NAME=traefik
+TAG="${TRAEFIK_TAG:-v3.1}"
+REPO="traefik:${TAG}"
+
+sudo docker pull "$REPO"
+sudo docker rm -f "$NAME" >/dev/null 2>&1 || true
+
+sudo docker run -d \
+    --name "$NAME" \
+    --network golden-net \
+    --restart=always \
+    --publish 443:443 \
+    -p 9999:99/udp \
+    --mount type=bind,source=/var/run/docker.sock,target=/var/run/docker.sock \
+    --mount type=bind,src=/srv/traefik/acme,dst=/acme \
+    -v /srv/golden:/golden \
+    --label "traefik.enable=true" \
+    --label 'traefik.http.routers.dashboard.rule=Host(`traefik.example.com`)' \
+    --env CF_DNS_API_TOKEN='changed value' \
+    --env GOLDEN_ADDED=yes \
+    "$REPO" \
+    --providers.docker=true \
+    --entrypoints.web.address=:80
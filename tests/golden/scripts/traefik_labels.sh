@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+export NAME=traefik
+TAG="${TRAEFIK_TAG:-v3.1}"
+REPO="traefik:${TAG}"
+
+sudo docker pull "$REPO"
+sudo docker rm -f "$NAME" >/dev/null 2>&1 || true
+
+sudo docker run -d \
+    --name "$NAME" \
+    --network proxy \
+    --restart=always \
+    --publish 80:80 \
+    --publish 443:443 \
+    --mount type=bind,source=/var/run/docker.sock,target=/var/run/docker.sock,readonly \
+    --mount type=bind,src=/srv/traefik/acme,dst=/acme \
+    --label "traefik.enable=true" \
+    --label 'traefik.http.routers.dashboard.rule=Host(`traefik.example.com`)' \
+    --env "CF_DNS_API_TOKEN=${CF_TOKEN:-changeme}" \
+    --env LOG_LEVEL=INFO \
+    "$REPO" \
+    --providers.docker=true \
+    --entrypoints.web.address=:80